@@ -0,0 +1,203 @@
+use crate::common::errors::AppError;
+
+// Text encodings accepted for delimited files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvEncoding {
+    Utf8,
+    Latin1,
+    Windows1252,
+}
+
+impl CsvEncoding {
+    pub fn parse(name: &str) -> Result<Self, AppError> {
+        match name.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" | "utf-8-sig" => Ok(Self::Utf8),
+            "latin1" | "latin-1" | "iso-8859-1" => Ok(Self::Latin1),
+            "windows-1252" | "cp1252" => Ok(Self::Windows1252),
+            other => Err(AppError::InvalidInput(format!(
+                "unsupported encoding: {other} (expected utf-8, latin1 or windows-1252)"
+            ))),
+        }
+    }
+}
+
+pub struct CsvUtil;
+
+impl CsvUtil {
+    // Windows-1252 code points for bytes 0x80..=0x9F; the rest matches Latin-1.
+    const CP1252_HIGH: [char; 32] = [
+        '\u{20AC}', '\u{81}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{8D}',
+        '\u{017D}', '\u{8F}', '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}',
+        '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}',
+        '\u{9D}', '\u{017E}', '\u{0178}',
+    ];
+
+    pub fn decode(bytes: &[u8], encoding: CsvEncoding) -> Result<String, AppError> {
+        match encoding {
+            CsvEncoding::Utf8 => {
+                let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+                String::from_utf8(bytes.to_vec()).map_err(|e| {
+                    AppError::InvalidInput(format!(
+                        "file is not valid utf-8 (byte {}); set encoding",
+                        e.utf8_error().valid_up_to()
+                    ))
+                })
+            }
+            CsvEncoding::Latin1 => Ok(bytes.iter().map(|b| *b as char).collect()),
+            CsvEncoding::Windows1252 => Ok(bytes
+                .iter()
+                .map(|b| match b {
+                    0x80..=0x9F => Self::CP1252_HIGH[(b - 0x80) as usize],
+                    _ => *b as char,
+                })
+                .collect()),
+        }
+    }
+
+    // Reads a one-character option such as the delimiter or quote; "tab" and "\\t" name a tab.
+    pub fn option_char(value: &str, name: &str) -> Result<char, AppError> {
+        if value == "tab" || value == "\\t" {
+            return Ok('\t');
+        }
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c != '\n' && c != '\r' => Ok(c),
+            _ => Err(AppError::InvalidInput(format!(
+                "{name} must be a single character, got {value:?}"
+            ))),
+        }
+    }
+
+    // Picks the most frequent candidate separator on the first line, defaulting to comma.
+    pub fn sniff_delimiter(text: &str) -> char {
+        let first_line = text.lines().next().unwrap_or("");
+        [',', ';', '\t', '|']
+            .into_iter()
+            .map(|d| (d, first_line.matches(d).count()))
+            .filter(|(_, n)| *n > 0)
+            .max_by_key(|(_, n)| *n)
+            .map(|(d, _)| d)
+            .unwrap_or(',')
+    }
+
+    // RFC 4180 reader: quoted fields may contain separators, doubled quotes and line breaks.
+    pub fn parse(text: &str, delimiter: char, quote: char) -> Result<Vec<Vec<String>>, AppError> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut field_started = false;
+        let mut chars = text.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if in_quotes {
+                if ch == quote {
+                    if chars.peek() == Some(&quote) {
+                        field.push(quote);
+                        chars.next();
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    field.push(ch);
+                }
+                continue;
+            }
+            match ch {
+                c if c == quote && field.is_empty() && !field_started => {
+                    in_quotes = true;
+                    field_started = true;
+                }
+                c if c == delimiter => {
+                    row.push(std::mem::take(&mut field));
+                    field_started = false;
+                }
+                '\r' | '\n' => {
+                    if ch == '\r' && chars.peek() == Some(&'\n') {
+                        chars.next();
+                    }
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                    field_started = false;
+                }
+                _ => field.push(ch),
+            }
+        }
+
+        if in_quotes {
+            return Err(AppError::InvalidInput(
+                "unterminated quoted field in delimited file".to_string(),
+            ));
+        }
+        if field_started || !field.is_empty() || !row.is_empty() {
+            row.push(field);
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+}
+
+// Field quoting policy for written CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvQuoting {
+    // Only fields containing the delimiter, the quote or a line break.
+    Minimal,
+    All,
+    // Every field that is not a number.
+    NonNumeric,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CsvWriter {
+    pub delimiter: char,
+    pub quote: char,
+    pub quoting: CsvQuoting,
+    pub line_ending: &'static str,
+}
+
+impl Default for CsvWriter {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            quoting: CsvQuoting::Minimal,
+            line_ending: "\n",
+        }
+    }
+}
+
+impl CsvWriter {
+    // Appends one record and its line ending; each field is (text, is_numeric).
+    pub fn write_record<'a>(
+        &self,
+        out: &mut String,
+        fields: impl IntoIterator<Item = (&'a str, bool)>,
+    ) {
+        for (i, (field, numeric)) in fields.into_iter().enumerate() {
+            if i > 0 {
+                out.push(self.delimiter);
+            }
+            let needs_quotes = match self.quoting {
+                CsvQuoting::All => true,
+                CsvQuoting::NonNumeric => !numeric,
+                CsvQuoting::Minimal => field
+                    .chars()
+                    .any(|c| c == self.delimiter || c == self.quote || c == '\n' || c == '\r'),
+            };
+            if needs_quotes {
+                out.push(self.quote);
+                for c in field.chars() {
+                    if c == self.quote {
+                        out.push(self.quote);
+                    }
+                    out.push(c);
+                }
+                out.push(self.quote);
+            } else {
+                out.push_str(field);
+            }
+        }
+        out.push_str(self.line_ending);
+    }
+}
@@ -4,28 +4,33 @@ use std::path::{Path, PathBuf};
 pub struct FsUtil;
 
 impl FsUtil {
-    // Normalizes relative paths and enforces the .ods extension contract.
+    // Normalizes relative paths and enforces the .ods (zip) / .fods (flat XML) extension contract.
     pub fn resolve_ods_path(path: &str) -> Result<PathBuf, AppError> {
-        if path.trim().is_empty() {
-            return Err(AppError::InvalidPath("path is empty".to_string()));
-        }
-        let input = Path::new(path);
-        let abs = if input.is_absolute() {
-            input.to_path_buf()
-        } else {
-            std::env::current_dir()?.join(input)
-        };
+        let abs = Self::resolve_path(path)?;
         let ext = abs
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or_default()
             .to_ascii_lowercase();
-        if ext != "ods" {
+        if ext != "ods" && ext != "fods" {
             return Err(AppError::InvalidPath(format!(
-                "expected .ods extension: {}",
+                "expected .ods extension (or .fods for flat XML): {}",
                 abs.display()
             )));
         }
         Ok(abs)
     }
+
+    // Resolves companion files (CSV, JSON, ...) relative to the working directory.
+    pub fn resolve_path(path: &str) -> Result<PathBuf, AppError> {
+        if path.trim().is_empty() {
+            return Err(AppError::InvalidPath("path is empty".to_string()));
+        }
+        let input = Path::new(path);
+        if input.is_absolute() {
+            Ok(input.to_path_buf())
+        } else {
+            Ok(std::env::current_dir()?.join(input))
+        }
+    }
 }
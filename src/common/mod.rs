@@ -1,3 +1,5 @@
+pub mod csv;
 pub mod errors;
 pub mod fs;
 pub mod json;
+pub mod time;
@@ -0,0 +1,103 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct TimeUtil;
+
+impl TimeUtil {
+    // 1899-12-30 relative to 1970-01-01, the day zero used by ODF and Excel serial dates.
+    const SERIAL_EPOCH_DAYS: i64 = -25_569;
+
+    // ISO 8601 UTC timestamp as stored in meta.xml (for example: 2026-03-01T10:15:00Z).
+    pub fn now_iso8601() -> String {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Self::iso8601_from_unix(secs)
+    }
+
+    pub fn iso8601_from_unix(secs: i64) -> String {
+        let days = secs.div_euclid(86_400);
+        let rem = secs.rem_euclid(86_400);
+        let (y, m, d) = Self::civil_from_days(days);
+        format!(
+            "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+            rem / 3600,
+            (rem % 3600) / 60,
+            rem % 60
+        )
+    }
+
+    // Days since 1970-01-01 to proleptic Gregorian (year, month, day).
+    pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let y = yoe + era * 400 + i64::from(m <= 2);
+        (y, m, d)
+    }
+
+    // Proleptic Gregorian (year, month, day) to days since 1970-01-01.
+    pub fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+        let y = if m <= 2 { y - 1 } else { y };
+        let era = y.div_euclid(400);
+        let yoe = y.rem_euclid(400);
+        let mp = i64::from(if m > 2 { m - 3 } else { m + 9 });
+        let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    // Spreadsheet serial date (days since 1899-12-30, fraction = time of day) to ISO 8601.
+    pub fn iso8601_from_serial(serial: f64) -> String {
+        let total_secs = (serial * 86_400.0).round() as i64;
+        let days = total_secs.div_euclid(86_400) + Self::SERIAL_EPOCH_DAYS;
+        let rem = total_secs.rem_euclid(86_400);
+        let (y, m, d) = Self::civil_from_days(days);
+        if rem == 0 {
+            format!("{y:04}-{m:02}-{d:02}")
+        } else {
+            format!(
+                "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}",
+                rem / 3600,
+                (rem % 3600) / 60,
+                rem % 60
+            )
+        }
+    }
+
+    // Inverse of iso8601_from_serial; accepts YYYY-MM-DD with an optional THH:MM[:SS[.fff]] part.
+    pub fn serial_from_iso8601(iso: &str) -> Option<f64> {
+        let (date, time) = match iso.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (iso, None),
+        };
+        let mut parts = date.splitn(3, '-');
+        let y: i64 = parts.next()?.parse().ok()?;
+        let m: u32 = parts.next()?.parse().ok()?;
+        let d: u32 = parts.next()?.parse().ok()?;
+        if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+            return None;
+        }
+        let mut secs = 0.0;
+        if let Some(time) = time {
+            let time = time.trim_end_matches('Z');
+            let mut parts = time.splitn(3, ':');
+            let h: f64 = parts.next()?.parse().ok()?;
+            let min: f64 = parts.next()?.parse().ok()?;
+            let s: f64 = parts
+                .next()
+                .map(str::parse)
+                .transpose()
+                .ok()?
+                .unwrap_or(0.0);
+            secs = h * 3600.0 + min * 60.0 + s;
+        }
+        let days = Self::days_from_civil(y, m, d) - Self::SERIAL_EPOCH_DAYS;
+        Some(days as f64 + secs / 86_400.0)
+    }
+}
@@ -2,3 +2,4 @@ pub mod common;
 pub mod mcp;
 pub mod ods;
 pub mod tools;
+pub mod xlsx;
@@ -27,8 +27,12 @@ impl Dispatcher {
         let arguments = payload.get("arguments").cloned().unwrap_or(Value::Null);
 
         let result = Self::dispatch_direct_tool(name, arguments)?;
-        let text = serde_json::to_string_pretty(&result)
-            .unwrap_or_else(|_| "{\"error\":\"failed to render tool result\"}".to_string());
+        // Tools that render text (markdown/csv/html) expose it directly; the rest show their JSON.
+        let text = match result.get("rendered").and_then(Value::as_str) {
+            Some(rendered) => rendered.to_string(),
+            None => serde_json::to_string_pretty(&result)
+                .unwrap_or_else(|_| "{\"error\":\"failed to render tool result\"}".to_string()),
+        };
 
         Ok(json!({
             "content": [
@@ -48,12 +52,35 @@ impl Dispatcher {
             "get_sheets" => tools::get_sheets::handle(args),
             "get_sheet_content" => tools::get_sheet_content::handle(args),
             "set_cell_value" => tools::set_cell_value::handle(args),
+            "set_cell_formula" => tools::set_cell_formula::handle(args),
             "duplicate_sheet" => tools::duplicate_sheet::handle(args),
             "delete_sheet" => tools::delete_sheet::handle(args),
             "add_sheet" => tools::add_sheet::handle(args),
             "rename_sheet" => tools::rename_sheet::handle(args),
+            "move_sheet" => tools::move_sheet::handle(args),
             "get_cell_value" => tools::get_cell_value::handle(args),
             "set_range_values" => tools::set_range_values::handle(args),
+            "set_sheet_visibility" => tools::set_sheet_visibility::handle(args),
+            "set_sheet_tab_color" => tools::set_sheet_tab_color::handle(args),
+            "set_sheet_protection" => tools::set_sheet_protection::handle(args),
+            "get_document_properties" => tools::get_document_properties::handle(args),
+            "set_document_properties" => tools::set_document_properties::handle(args),
+            "import_csv" => tools::import_csv::handle(args),
+            "export_csv" => tools::export_csv::handle(args),
+            "convert_workbook" => tools::convert_workbook::handle(args),
+            "export_json" => tools::export_json::handle(args),
+            "import_json" => tools::import_json::handle(args),
+            "find_cells" => tools::find_cells::handle(args),
+            "replace_in_cells" => tools::replace_in_cells::handle(args),
+            "recalculate" => tools::recalculate::handle(args),
+            "evaluate_formula" => tools::evaluate_formula::handle(args),
+            "trace_precedents" => tools::trace_precedents::handle(args),
+            "trace_dependents" => tools::trace_dependents::handle(args),
+            "check_workbook" => tools::check_workbook::handle(args),
+            "validate_ods" => tools::validate_ods::handle(args),
+            "insert_image" => tools::insert_image::handle(args),
+            "list_images" => tools::list_images::handle(args),
+            "delete_image" => tools::delete_image::handle(args),
             _ => Err(AppError::InvalidInput(format!(
                 "unknown method/tool: {tool_name}"
             ))),
@@ -74,152 +101,550 @@ impl Dispatcher {
     }
 
     fn tools_list_result() -> Value {
-        json!({
-            "tools": [
-                {
-                    "name": "create_ods",
-                    "description": "Create a valid ODS file with an initial sheet.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "overwrite": { "type": "boolean", "default": false },
-                            "initial_sheet_name": { "type": "string", "default": "Hoja1" }
-                        },
-                        "required": ["path"]
-                    }
-                },
-                {
-                    "name": "get_sheets",
-                    "description": "Return sheet names in workbook order.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" }
-                        },
-                        "required": ["path"]
-                    }
-                },
-                {
-                    "name": "get_sheet_content",
-                    "description": "Return a sheet as a 2D matrix.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "sheet": Self::sheet_selector_schema(),
-                            "mode": { "type": "string", "enum": ["matrix"] },
-                            "max_rows": { "type": "integer" },
-                            "max_cols": { "type": "integer" },
-                            "include_empty_trailing": { "type": "boolean" }
-                        },
-                        "required": ["path", "sheet"]
-                    }
-                },
-                {
-                    "name": "set_cell_value",
-                    "description": "Set a single cell value by A1 address.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "sheet": Self::sheet_selector_schema(),
-                            "cell": { "type": "string" },
-                            "value": {
+        // One json! per tool: a single literal for the whole list exceeds the macro recursion limit.
+        let tools = vec![
+            json!({
+                "name": "create_ods",
+                "description": "Create a valid ODS file with an initial sheet.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "overwrite": { "type": "boolean", "default": false },
+                        "initial_sheet_name": { "type": "string", "default": "Hoja1" }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "get_sheets",
+                "description": "Return sheet names in workbook order plus, per sheet, visibility, tab colour, protection, used range, non-empty cell count and whether it has formulas, merges or comments.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "get_sheet_content",
+                "description": "Return a sheet as a 2D matrix, as records keyed by a header row, as a list of non-empty cells, or rendered as Markdown/CSV/HTML text. Large sheets are read page by page with offsets or next_cursor.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "mode": { "type": "string", "enum": ["matrix", "records", "cells", "markdown", "csv", "html"] },
+                        "max_rows": { "type": "integer" },
+                        "max_cols": { "type": "integer" },
+                        "include_empty_trailing": { "type": "boolean" },
+                        "header_row": { "type": "integer", "minimum": 1 },
+                        "cell_offset": { "type": "integer", "minimum": 0 },
+                        "max_cells": { "type": "integer", "minimum": 1 },
+                        "offset_row": { "type": "integer", "minimum": 0 },
+                        "offset_col": { "type": "integer", "minimum": 0 },
+                        "cursor": { "type": "string" },
+                        "range": { "type": "string" },
+                        "formula_syntax": { "type": "string", "enum": ["odf", "excel"], "default": "odf" }
+                    },
+                    "required": ["path", "sheet"]
+                }
+            }),
+            json!({
+                "name": "set_cell_value",
+                "description": "Set a single cell value by A1 address.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "cell": { "type": "string" },
+                        "value": {
+                            "type": "object",
+                            "properties": {
+                                "type": { "type": "string", "enum": ["string", "number", "boolean", "empty"] },
+                                "data": {}
+                            },
+                            "required": ["type"]
+                        }
+                    },
+                    "required": ["path", "sheet", "cell", "value"]
+                }
+            }),
+            json!({
+                "name": "set_cell_formula",
+                "description": "Store a formula in a cell, keeping its style, and cache its result when it can be computed. Accepts Excel syntax (=SUM(A1:A10), =Sheet2!B3, comma separators) or OpenFormula (of:=SUM([.A1:.A10])); detected automatically unless formula_syntax is given.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "cell": { "type": "string" },
+                        "formula": { "type": "string" },
+                        "formula_syntax": { "type": "string", "enum": ["odf", "excel"] }
+                    },
+                    "required": ["path", "sheet", "cell", "formula"]
+                }
+            }),
+            json!({
+                "name": "duplicate_sheet",
+                "description": "Duplicate a sheet and insert it after source or at a position.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "source_sheet": Self::sheet_selector_schema(),
+                        "new_sheet_name": { "type": "string" },
+                        "position": Self::sheet_position_schema()
+                    },
+                    "required": ["path", "source_sheet", "new_sheet_name"]
+                }
+            }),
+            json!({
+                "name": "add_sheet",
+                "description": "Add an empty sheet at start, end, an index or next to another sheet.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet_name": { "type": "string" },
+                        "position": Self::sheet_position_schema()
+                    },
+                    "required": ["path", "sheet_name"]
+                }
+            }),
+            json!({
+                "name": "delete_sheet",
+                "description": "Delete an existing sheet by name or index.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema()
+                    },
+                    "required": ["path", "sheet"]
+                }
+            }),
+            json!({
+                "name": "move_sheet",
+                "description": "Move a sheet to an index or before/after another sheet.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "position": Self::sheet_position_schema()
+                    },
+                    "required": ["path", "sheet", "position"]
+                }
+            }),
+            json!({
+                "name": "rename_sheet",
+                "description": "Rename an existing sheet by name or index.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "new_sheet_name": { "type": "string" }
+                    },
+                    "required": ["path", "sheet", "new_sheet_name"]
+                }
+            }),
+            json!({
+                "name": "get_cell_value",
+                "description": "Read one typed cell value.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "cell": { "type": "string" }
+                    },
+                    "required": ["path", "sheet", "cell"]
+                }
+            }),
+            json!({
+                "name": "set_range_values",
+                "description": "Write a matrix from a start cell.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "start_cell": { "type": "string" },
+                        "data": {
+                            "type": "array",
+                            "items": {
+                                "type": "array",
+                                "items": { "type": "string" }
+                            }
+                        }
+                    },
+                    "required": ["path", "sheet", "start_cell", "data"]
+                }
+            }),
+            json!({
+                "name": "set_sheet_visibility",
+                "description": "Hide or show a sheet.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "visible": { "type": "boolean" }
+                    },
+                    "required": ["path", "sheet", "visible"]
+                }
+            }),
+            json!({
+                "name": "set_sheet_tab_color",
+                "description": "Set a sheet tab colour (#RRGGBB); null clears it.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "color": { "type": ["string", "null"] }
+                    },
+                    "required": ["path", "sheet"]
+                }
+            }),
+            json!({
+                "name": "set_sheet_protection",
+                "description": "Protect or unprotect a sheet, optionally with a password or precomputed hash.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "protected": { "type": "boolean" },
+                        "password": { "type": "string" },
+                        "password_hash": { "type": "string" },
+                        "digest_algorithm": { "type": "string" }
+                    },
+                    "required": ["path", "sheet", "protected"]
+                }
+            }),
+            json!({
+                "name": "get_document_properties",
+                "description": "Read document metadata from meta.xml.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "set_document_properties",
+                "description": "Update document metadata in meta.xml. Empty strings remove a field.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "title": { "type": "string" },
+                        "subject": { "type": "string" },
+                        "description": { "type": "string" },
+                        "keywords": { "type": "array", "items": { "type": "string" } },
+                        "initial_creator": { "type": "string" },
+                        "creator": { "type": "string" },
+                        "creation_date": { "type": "string" },
+                        "user_defined": {
+                            "type": "array",
+                            "items": {
                                 "type": "object",
                                 "properties": {
-                                    "type": { "type": "string", "enum": ["string", "number", "boolean", "empty"] },
-                                    "data": {}
+                                    "name": { "type": "string" },
+                                    "value": { "type": ["string", "number", "boolean"] },
+                                    "type": { "type": "string", "enum": ["string", "float", "boolean", "date", "time"] }
                                 },
-                                "required": ["type"]
+                                "required": ["name", "value"]
                             }
                         },
-                        "required": ["path", "sheet", "cell", "value"]
-                    }
-                },
-                {
-                    "name": "duplicate_sheet",
-                    "description": "Duplicate a sheet and insert it after source.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "source_sheet": Self::sheet_selector_schema(),
-                            "new_sheet_name": { "type": "string" }
-                        },
-                        "required": ["path", "source_sheet", "new_sheet_name"]
-                    }
-                },
-                {
-                    "name": "add_sheet",
-                    "description": "Add an empty sheet at start or end.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "sheet_name": { "type": "string" },
-                            "position": { "type": "string", "enum": ["start", "end"] }
+                        "remove_user_defined": { "type": "array", "items": { "type": "string" } }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "import_csv",
+                "description": "Import a CSV/TSV file into a sheet from a start cell, inferring number and boolean columns and keeping existing cell styles.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "csv_path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "create_sheet": { "type": "boolean" },
+                        "start_cell": { "type": "string" },
+                        "delimiter": { "type": "string" },
+                        "quote": { "type": "string" },
+                        "encoding": { "type": "string", "enum": ["utf-8", "latin1", "windows-1252"] },
+                        "has_header": { "type": "boolean" },
+                        "decimal_separator": { "type": "string", "enum": [".", ","] },
+                        "infer_types": { "type": "boolean" },
+                        "clear_target": { "type": "boolean" }
+                    },
+                    "required": ["path", "csv_path", "sheet"]
+                }
+            }),
+            json!({
+                "name": "export_csv",
+                "description": "Write a sheet or range to a local CSV file (raw typed values or displayed text). Without range, exports A1 to the last used cell.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "csv_path": { "type": "string" },
+                        "range": { "type": "string" },
+                        "overwrite": { "type": "boolean" },
+                        "delimiter": { "type": "string" },
+                        "quote": { "type": "string" },
+                        "quoting": { "type": "string", "enum": ["minimal", "all", "non_numeric"] },
+                        "line_ending": { "type": "string", "enum": ["lf", "crlf"] },
+                        "values": { "type": "string", "enum": ["raw", "displayed"] }
+                    },
+                    "required": ["path", "sheet", "csv_path"]
+                }
+            }),
+            json!({
+                "name": "convert_workbook",
+                "description": "Convert a workbook between .ods and .xlsx (direction from the file extensions). Carries sheet names, values, dates, formulas, merges and basic cell styles.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "source": { "type": "string" },
+                        "target": { "type": "string" },
+                        "overwrite": { "type": "boolean" }
+                    },
+                    "required": ["source", "target"]
+                }
+            }),
+            json!({
+                "name": "export_json",
+                "description": "Serialise the whole workbook (all sheets, typed values, formulas, merges, comments and basic styles) to the mcp-ods/workbook JSON schema. Writes json_path when given, otherwise returns the document inline.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "json_path": { "type": "string" },
+                        "overwrite": { "type": "boolean" },
+                        "pretty": { "type": "boolean" }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "import_json",
+                "description": "Build a new .ods from an mcp-ods/workbook JSON document, read from json_path or passed inline as workbook.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "json_path": { "type": "string" },
+                        "workbook": { "type": "object" },
+                        "overwrite": { "type": "boolean" }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "find_cells",
+                "description": "Search one sheet or all sheets for a text, number or regex and return the matching cell addresses with their values. Can look in values, formulas and/or comments; case-insensitive substring match by default.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": {
+                            "oneOf": [
+                                { "type": "object", "properties": { "name": { "type": "string" } }, "required": ["name"] },
+                                { "type": "object", "properties": { "index": { "type": "integer", "minimum": 0 } }, "required": ["index"] }
+                            ]
                         },
-                        "required": ["path", "sheet_name"]
-                    }
-                },
-                {
-                    "name": "delete_sheet",
-                    "description": "Delete an existing sheet by name or index.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "sheet": Self::sheet_selector_schema()
+                        "query": { "type": ["string", "number"] },
+                        "match_type": { "type": "string", "enum": ["text", "number", "regex"] },
+                        "case_sensitive": { "type": "boolean" },
+                        "whole_cell": { "type": "boolean" },
+                        "look_in": {
+                            "type": "array",
+                            "items": { "type": "string", "enum": ["values", "formulas", "comments"] }
                         },
-                        "required": ["path", "sheet"]
-                    }
-                },
-                {
-                    "name": "rename_sheet",
-                    "description": "Rename an existing sheet by name or index.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "sheet": Self::sheet_selector_schema(),
-                            "new_sheet_name": { "type": "string" }
+                        "max_results": { "type": "integer", "minimum": 0 },
+                        "formula_syntax": { "type": "string", "enum": ["odf", "excel"], "default": "odf" }
+                    },
+                    "required": ["path", "query"]
+                }
+            }),
+            json!({
+                "name": "replace_in_cells",
+                "description": "Replace text in string cells (and optionally formulas) of one sheet, a range or the whole workbook, keeping cell styles. Plain text by default; with regex, $1 or ${name} in the replacement expand capture groups. dry_run returns the planned changes without saving.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "range": { "type": "string", "description": "A1 range such as A1:C10; requires sheet" },
+                        "find": { "type": "string" },
+                        "replace": { "type": "string" },
+                        "regex": { "type": "boolean", "default": false },
+                        "case_sensitive": { "type": "boolean", "default": false },
+                        "whole_cell": { "type": "boolean", "default": false },
+                        "include_formulas": { "type": "boolean", "default": false },
+                        "dry_run": { "type": "boolean", "default": false },
+                        "max_results": { "type": "integer", "minimum": 0, "default": 100 }
+                    },
+                    "required": ["path", "find", "replace"]
+                }
+            }),
+            json!({
+                "name": "recalculate",
+                "description": "Evaluate every formula in dependency order and refresh the cached results stored in the file, keeping formulas and styles. Supports arithmetic, comparisons, references and SUM, AVERAGE, MIN, MAX, COUNT, COUNTA, IF, AND, OR, ROUND, CONCATENATE, VLOOKUP, SUMIF, COUNTIF, IFERROR, TODAY. Circular references, unsupported functions and their dependents are reported in issues and keep their previous value.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "evaluate_formula",
+                "description": "Evaluate a formula against the current workbook contents and return the typed result without modifying the file. Accepts OpenFormula (SUM([$Ventas.B2:.B500])) or Excel syntax (SUM(Ventas!B2:B500)); auto-detected by default. Unqualified references point at `sheet` (default: first sheet). Formula cells contribute their cached results.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "formula": { "type": "string" },
+                        "sheet": {
+                            "oneOf": [
+                                { "type": "object", "properties": { "name": { "type": "string" } }, "required": ["name"] },
+                                { "type": "object", "properties": { "index": { "type": "integer", "minimum": 0 } }, "required": ["index"] }
+                            ]
                         },
-                        "required": ["path", "sheet", "new_sheet_name"]
-                    }
-                },
+                        "formula_syntax": { "type": "string", "enum": ["auto", "odf", "excel"], "default": "auto" }
+                    },
+                    "required": ["path", "formula"]
+                }
+            }),
+            json!({
+                "name": "trace_precedents",
+                "description": "Return the tree of cells and ranges feeding into a cell: the references of its formula, followed through the formulas they hold, across sheets. Ranges list only the formula cells inside them. Stops at max_depth levels and max_nodes nodes; cycles are marked circular.",
+                "inputSchema": Self::trace_schema()
+            }),
+            json!({
+                "name": "trace_dependents",
+                "description": "Return the tree of formula cells fed by a cell, directly or through other formulas, across sheets. Stops at max_depth levels and max_nodes nodes; cycles are marked circular.",
+                "inputSchema": Self::trace_schema()
+            }),
+            json!({
+                "name": "check_workbook",
+                "description": "Scan the workbook for common problems without modifying it: duplicate sheet names, references to missing sheets or deleted cells, formulas with cached #REF!/#NAME? or other errors, numbers stored as text, formulas out of line with their column, overlapping merged areas and numeric cells whose displayed text disagrees with the stored value. Returns issues with severity (error|warning), code, sheet and cell.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "max_issues": { "type": "integer", "minimum": 0, "default": 200 }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "validate_ods",
+                "description": "Check the package structure without modifying it: mimetype stored uncompressed as the first entry, every part listed in META-INF/manifest.xml with the right media type, well-formed XML parts, unique table names and valid repeat/span attributes within the sheet size. Saves run the same XML and table checks on the parts they write and refuse to write broken ones.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" }
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "insert_image",
+                "description": "Insert a local PNG, JPEG or SVG image into a sheet. The file is stored under Pictures/ and listed in the manifest (embedded inline in .fods files), and a picture frame is anchored to a cell or, without cell, to the page. Sizes are ODF lengths such as \"4cm\" (a bare number means pixels) and are written in cm; omitted ones come from the image at 96 dpi, keeping the aspect ratio when only one is given. x/y offset the frame from the cell corner or the page origin.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema(),
+                        "source": { "type": "string" },
+                        "cell": { "type": "string" },
+                        "width": { "type": "string" },
+                        "height": { "type": "string" },
+                        "x": { "type": "string", "default": "0cm" },
+                        "y": { "type": "string", "default": "0cm" },
+                        "name": { "type": "string" }
+                    },
+                    "required": ["path", "sheet", "source"]
+                }
+            }),
+            json!({
+                "name": "list_images",
+                "description": "List picture frames with sheet, anchor (cell in A1 form or \"page\"), name, package part, media type, size and position. Charts and other embedded objects are not included.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema()
+                    },
+                    "required": ["path"]
+                }
+            }),
+            json!({
+                "name": "delete_image",
+                "description": "Delete picture frames by name, optionally on one sheet only. Pictures/ parts no longer used by another frame or by styles are removed from the package and the manifest.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "name": { "type": "string" },
+                        "sheet": Self::sheet_selector_schema()
+                    },
+                    "required": ["path", "name"]
+                }
+            }),
+        ];
+        json!({ "tools": tools })
+    }
+
+    fn trace_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "sheet": Self::sheet_selector_schema(),
+                "cell": { "type": "string" },
+                "max_depth": { "type": "integer", "minimum": 0, "default": 5 },
+                "max_nodes": { "type": "integer", "minimum": 1, "default": 200 },
+                "formula_syntax": { "type": "string", "enum": ["odf", "excel"], "default": "odf" }
+            },
+            "required": ["path", "sheet", "cell"]
+        })
+    }
+
+    fn sheet_position_schema() -> Value {
+        json!({
+            "oneOf": [
+                { "type": "string", "enum": ["start", "end"] },
+                { "type": "integer", "minimum": 0 },
                 {
-                    "name": "get_cell_value",
-                    "description": "Read one typed cell value.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "sheet": Self::sheet_selector_schema(),
-                            "cell": { "type": "string" }
-                        },
-                        "required": ["path", "sheet", "cell"]
-                    }
+                    "type": "object",
+                    "properties": {
+                        "before": Self::sheet_selector_schema()
+                    },
+                    "required": ["before"]
                 },
                 {
-                    "name": "set_range_values",
-                    "description": "Write a matrix from a start cell.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "path": { "type": "string" },
-                            "sheet": Self::sheet_selector_schema(),
-                            "start_cell": { "type": "string" },
-                            "data": {
-                                "type": "array",
-                                "items": {
-                                    "type": "array",
-                                    "items": { "type": "string" }
-                                }
-                            }
-                        },
-                        "required": ["path", "sheet", "start_cell", "data"]
-                    }
+                    "type": "object",
+                    "properties": {
+                        "after": Self::sheet_selector_schema()
+                    },
+                    "required": ["after"]
                 }
             ]
         })
@@ -49,6 +49,27 @@ impl CellAddress {
         })
     }
 
+    // Parses "B2:F40" (either corner order) or a single cell into (top-left, bottom-right).
+    pub fn parse_range(input: &str) -> Result<(Self, Self), AppError> {
+        let (start, end) = match input.split_once(':') {
+            Some((start, end)) => (Self::parse(start.trim())?, Self::parse(end.trim())?),
+            None => {
+                let cell = Self::parse(input.trim())?;
+                (cell, cell)
+            }
+        };
+        Ok((
+            Self {
+                row: start.row.min(end.row),
+                col: start.col.min(end.col),
+            },
+            Self {
+                row: start.row.max(end.row),
+                col: start.col.max(end.col),
+            },
+        ))
+    }
+
     pub fn to_a1(self) -> String {
         // Converts zero-based column index back to base-26 spreadsheet letters.
         let mut col = self.col + 1;
@@ -360,7 +360,7 @@ impl ContentXml {
         let bytes = writer.into_inner().into_inner();
         String::from_utf8(bytes).map_err(|e| AppError::XmlParseError(e.to_string()))
     }
-    fn write_value_cell(
+    pub(super) fn write_value_cell(
         writer: &mut Writer<Cursor<Vec<u8>>>,
         value: &CellValue,
         existing: Option<&BytesStart<'_>>,
@@ -419,6 +419,25 @@ impl ContentXml {
                     .write_event(Event::End(BytesEnd::new("table:table-cell")))
                     .map_err(|e| AppError::XmlParseError(e.to_string()))?;
             }
+            CellValue::Date(v) => {
+                cell.push_attribute(("office:value-type", "date"));
+                cell.push_attribute(("office:date-value", v.as_str()));
+                writer
+                    .write_event(Event::Start(cell))
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                writer
+                    .write_event(Event::Start(BytesStart::new("text:p")))
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                writer
+                    .write_event(Event::Text(BytesText::new(v)))
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                writer
+                    .write_event(Event::End(BytesEnd::new("text:p")))
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                writer
+                    .write_event(Event::End(BytesEnd::new("table:table-cell")))
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            }
             CellValue::Boolean(v) => {
                 let s = if *v { "true" } else { "false" };
                 cell.push_attribute(("office:value-type", "boolean"));
@@ -448,7 +467,10 @@ impl ContentXml {
         Ok(())
     }
 
-    fn clone_cell_with_repeat(src: &BytesStart<'_>, repeat: usize) -> BytesStart<'static> {
+    pub(super) fn clone_cell_with_repeat(
+        src: &BytesStart<'_>,
+        repeat: usize,
+    ) -> BytesStart<'static> {
         let mut out = BytesStart::new("table:table-cell");
         for attr in src.attributes().flatten() {
             if Self::is_local_name_bytes(attr.key.as_ref(), b"number-columns-repeated") {
@@ -463,7 +485,7 @@ impl ContentXml {
         out
     }
 
-    fn default_gap_cell(repeat: usize) -> BytesStart<'static> {
+    pub(super) fn default_gap_cell(repeat: usize) -> BytesStart<'static> {
         let mut gap = BytesStart::new("table:table-cell");
         if repeat > 1 {
             let cols_text = repeat.to_string();
@@ -472,7 +494,10 @@ impl ContentXml {
         gap
     }
 
-    fn clone_row_with_repeat(src: &BytesStart<'_>, repeat: Option<usize>) -> BytesStart<'static> {
+    pub(super) fn clone_row_with_repeat(
+        src: &BytesStart<'_>,
+        repeat: Option<usize>,
+    ) -> BytesStart<'static> {
         let mut out = BytesStart::new("table:table-row");
         for attr in src.attributes().flatten() {
             if Self::is_local_name_bytes(attr.key.as_ref(), b"number-rows-repeated") {
@@ -692,7 +717,7 @@ impl ContentXml {
         Ok(wrote)
     }
 
-    fn attr_repeat_owned(e: &BytesStart<'_>, key: &[u8]) -> usize {
+    pub(super) fn attr_repeat_owned(e: &BytesStart<'_>, key: &[u8]) -> usize {
         for attr in e.attributes().flatten() {
             if Self::is_local_name_bytes(attr.key.as_ref(), key) {
                 if let Ok(v) = std::str::from_utf8(attr.value.as_ref()) {
@@ -0,0 +1,293 @@
+use super::sparse_cells::CellRun;
+use super::*;
+
+// What a search predicate sees for one XML cell run, however many times it repeats.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchCell<'a> {
+    pub value_type: &'a str,
+    pub value: &'a CellValue,
+    pub formula: Option<&'a str>,
+    // Text as displayed by Calc (paragraphs joined with newlines).
+    pub text: &'a str,
+    // Comment paragraphs joined with newlines.
+    pub comment: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellMatch {
+    pub sheet_index: usize,
+    pub sheet: String,
+    pub row: usize,
+    pub col: usize,
+    // office:value-type as stored, or "empty" for a cell that only carries a comment.
+    pub value_type: String,
+    pub value: CellValue,
+    pub formula: Option<String>,
+    pub text: String,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CellMatches {
+    pub matches: Vec<CellMatch>,
+    // Every matching cell, including those beyond the returned limit.
+    pub total: usize,
+}
+
+// Matching run of the open row, replicated for each repeated row.
+struct MatchTemplate {
+    col: usize,
+    repeat: usize,
+    value_type: String,
+    value: CellValue,
+    formula: Option<String>,
+    text: String,
+    comment: Option<String>,
+}
+
+// Comment captured from office:annotation; dc:creator and dc:date are not part of the text.
+#[derive(Default)]
+struct CommentScan {
+    text: String,
+    paragraphs: usize,
+    in_metadata: bool,
+}
+
+impl ContentXml {
+    pub fn find_cells_raw(
+        original_content: &str,
+        sheet_index: Option<usize>,
+        limit: usize,
+        mut predicate: impl FnMut(&SearchCell<'_>) -> bool,
+    ) -> Result<CellMatches, AppError> {
+        // One streaming pass: the predicate runs once per cell run and repeated rows/cells
+        // multiply its verdict, so a column repeated a million times costs a single check.
+        let mut reader = Reader::from_str(original_content);
+        reader.config_mut().trim_text(false);
+
+        let mut result = CellMatches::default();
+        let mut table_idx = 0usize;
+        let mut table_depth = 0usize;
+        let mut sheet_name = String::new();
+        let mut in_target = false;
+        let mut found = sheet_index.is_none();
+        let mut current_row = 0usize;
+        let mut row_repeat = 1usize;
+        let mut current_col = 0usize;
+        let mut row_matches: Vec<MatchTemplate> = Vec::new();
+        let mut cell: Option<CellRun> = None;
+        let mut comment: Option<CommentScan> = None;
+        let mut cell_comment: Option<String> = None;
+        let mut in_text_p = false;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        in_target = sheet_index.is_none_or(|i| i == table_idx);
+                        found |= in_target;
+                        sheet_name = Self::table_name_attr(&e, reader.decoder());
+                        table_idx += 1;
+                        current_row = 0;
+                    }
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    found |= sheet_index == Some(table_idx);
+                    table_idx += 1;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 && in_target && sheet_index.is_some() {
+                        break;
+                    }
+                }
+                _ if !in_target || table_depth != 1 => {
+                    if matches!(event, Event::Eof) {
+                        break;
+                    }
+                }
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
+                    row_repeat = Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                    current_col = 0;
+                    row_matches.clear();
+                }
+                Event::Empty(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
+                    current_row += Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
+                    Self::emit_matches(
+                        &mut result,
+                        &row_matches,
+                        (table_idx - 1, &sheet_name),
+                        current_row,
+                        row_repeat,
+                        limit,
+                    );
+                    current_row += row_repeat;
+                    row_repeat = 1;
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if comment.is_none()
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                {
+                    current_col +=
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                }
+                Event::Empty(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") => {
+                    let run = Self::cell_run_from_attrs(&e, current_col, reader.decoder());
+                    current_col += run.repeat;
+                    row_matches.extend(Self::match_run(run, None, &mut predicate));
+                }
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") => {
+                    let run = Self::cell_run_from_attrs(&e, current_col, reader.decoder());
+                    current_col += run.repeat;
+                    cell = Some(run);
+                    cell_comment = None;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") => {
+                    if let Some(run) = cell.take() {
+                        row_matches.extend(Self::match_run(
+                            run,
+                            cell_comment.take(),
+                            &mut predicate,
+                        ));
+                    }
+                    in_text_p = false;
+                }
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    comment = Some(CommentScan::default());
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    cell_comment = comment.take().map(|c| c.text);
+                    in_text_p = false;
+                }
+                Event::Start(e) if comment.is_some() => {
+                    let name = e.name();
+                    if let Some(scan) = comment.as_mut() {
+                        if Self::is_local_name_bytes(name.as_ref(), b"p") {
+                            Self::start_paragraph(&mut scan.text, &mut scan.paragraphs);
+                            in_text_p = true;
+                        } else if Self::is_local_name_bytes(name.as_ref(), b"creator")
+                            || Self::is_local_name_bytes(name.as_ref(), b"date")
+                        {
+                            scan.in_metadata = true;
+                        }
+                    }
+                }
+                Event::End(_) if comment.as_ref().is_some_and(|c| c.in_metadata) => {
+                    if let Some(scan) = comment.as_mut() {
+                        scan.in_metadata = false;
+                    }
+                }
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"p") => {
+                    if let Some(run) = cell.as_mut() {
+                        Self::start_paragraph(&mut run.text, &mut run.paragraphs);
+                        in_text_p = true;
+                    }
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"p") => {
+                    in_text_p = false;
+                }
+                Event::Empty(e) if in_text_p => match comment.as_mut() {
+                    Some(scan) => Self::push_inline_text(&mut scan.text, &e),
+                    None => {
+                        if let Some(run) = cell.as_mut() {
+                            Self::push_inline_text(&mut run.text, &e);
+                        }
+                    }
+                },
+                Event::Text(t) if in_text_p => {
+                    let text = t
+                        .unescape()
+                        .map_err(|x| AppError::XmlParseError(x.to_string()))?;
+                    match comment.as_mut() {
+                        Some(scan) => scan.text.push_str(&text),
+                        None => {
+                            if let Some(run) = cell.as_mut() {
+                                run.text.push_str(&text);
+                            }
+                        }
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if !found {
+            let index = sheet_index.unwrap_or_default();
+            return Err(AppError::SheetNotFound(format!("index {index}")));
+        }
+        Ok(result)
+    }
+
+    fn match_run(
+        run: CellRun,
+        comment: Option<String>,
+        predicate: &mut impl FnMut(&SearchCell<'_>) -> bool,
+    ) -> Option<MatchTemplate> {
+        let (value_type, value) = match run.typed_value() {
+            Some(typed) => typed,
+            None if comment.is_some() => ("empty".to_string(), CellValue::Empty),
+            None => return None,
+        };
+        let candidate = SearchCell {
+            value_type: &value_type,
+            value: &value,
+            formula: run.formula.as_deref(),
+            text: &run.text,
+            comment: comment.as_deref(),
+        };
+        if !predicate(&candidate) {
+            return None;
+        }
+        Some(MatchTemplate {
+            col: run.col,
+            repeat: run.repeat,
+            value_type,
+            value,
+            formula: run.formula,
+            text: run.text,
+            comment,
+        })
+    }
+
+    fn emit_matches(
+        result: &mut CellMatches,
+        row_matches: &[MatchTemplate],
+        (sheet_index, sheet): (usize, &str),
+        first_row: usize,
+        row_repeat: usize,
+        limit: usize,
+    ) {
+        let per_row: usize = row_matches.iter().map(|m| m.repeat).sum();
+        result.total += per_row * row_repeat;
+        'rows: for copy in 0..row_repeat {
+            for template in row_matches {
+                for offset in 0..template.repeat {
+                    if result.matches.len() >= limit {
+                        break 'rows;
+                    }
+                    result.matches.push(CellMatch {
+                        sheet_index,
+                        sheet: sheet.to_string(),
+                        row: first_row + copy,
+                        col: template.col + offset,
+                        value_type: template.value_type.clone(),
+                        value: template.value.clone(),
+                        formula: template.formula.clone(),
+                        text: template.text.clone(),
+                        comment: template.comment.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use super::range_edit::RowItem;
+use super::*;
+use crate::common::time::TimeUtil;
+use std::collections::HashMap;
+
+// New cached result of a formula cell and the text to show for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResult {
+    pub value: CellValue,
+    pub text: String,
+}
+
+impl ContentXml {
+    pub fn write_formula_results_raw(
+        original_content: &str,
+        results: &HashMap<(usize, usize, usize), CachedResult>,
+    ) -> Result<String, AppError> {
+        Self::rewrite_formula_cells(original_content, results, None)
+    }
+
+    // Stores `formula` in one cell together with its cached result, keeping the cell style.
+    // The target is split out of any repeated run first, so only that cell changes.
+    pub fn set_cell_formula_raw(
+        original_content: &str,
+        sheet_index: usize,
+        target_row: usize,
+        target_col: usize,
+        formula: &str,
+        result: &CachedResult,
+    ) -> Result<String, AppError> {
+        let isolated = Self::set_cell_value_preserving_styles_raw(
+            original_content,
+            sheet_index,
+            target_row,
+            target_col,
+            &CellValue::Empty,
+        )?;
+        let key = (sheet_index, target_row, target_col);
+        let results = HashMap::from([(key, result.clone())]);
+        Self::rewrite_formula_cells(&isolated, &results, Some((key, formula)))
+    }
+
+    fn rewrite_formula_cells(
+        original_content: &str,
+        results: &HashMap<(usize, usize, usize), CachedResult>,
+        new_formula: Option<((usize, usize, usize), &str)>,
+    ) -> Result<String, AppError> {
+        // Results are keyed by (sheet index, row, column) of the first cell of a run; a
+        // repeated formula cell holds the same formula, so every copy takes the same result.
+        // Only value attributes and paragraphs change: table:formula, styles, validation and
+        // comments stay as they are.
+        let mut reader = Reader::from_str(original_content);
+        reader.config_mut().trim_text(false);
+        let mut writer = Writer::new(Cursor::new(Vec::new()));
+
+        let mut table_count = 0usize;
+        let mut table_depth = 0usize;
+        let mut current_row = 0usize;
+        let mut capture: Option<(BytesStart<'static>, Vec<Event<'static>>, usize)> = None;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+
+            if let Some((_, inner, depth)) = capture.as_mut() {
+                match &event {
+                    Event::Start(_) => *depth += 1,
+                    Event::End(_) if *depth == 0 => {
+                        let (row_start, inner, _) =
+                            capture.take().unwrap_or_else(|| unreachable!());
+                        let repeat = Self::attr_repeat_owned(&row_start, b"number-rows-repeated");
+                        Self::write_result_row(
+                            &mut writer,
+                            &row_start,
+                            &inner,
+                            (table_count - 1, current_row),
+                            results,
+                            new_formula,
+                        )?;
+                        current_row += repeat;
+                        continue;
+                    }
+                    Event::End(_) => *depth -= 1,
+                    Event::Eof => {
+                        return Err(AppError::XmlParseError(
+                            "unterminated table-row in content.xml".to_string(),
+                        ))
+                    }
+                    _ => {}
+                }
+                inner.push(event.into_owned());
+                continue;
+            }
+
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        table_count += 1;
+                        current_row = 0;
+                    }
+                    writer
+                        .write_event(Event::Start(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    table_count += 1;
+                    writer
+                        .write_event(Event::Empty(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                    writer
+                        .write_event(Event::End(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Start(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    capture = Some((e.into_owned(), Vec::new(), 0));
+                }
+                Event::Empty(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    current_row += Self::attr_repeat_owned(&e, b"number-rows-repeated");
+                    writer
+                        .write_event(Event::Empty(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Eof => break,
+                other => writer
+                    .write_event(other.into_owned())
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?,
+            }
+        }
+
+        let bytes = writer.into_inner().into_inner();
+        String::from_utf8(bytes).map_err(|e| AppError::XmlParseError(e.to_string()))
+    }
+
+    fn write_result_row(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        row_start: &BytesStart<'static>,
+        inner: &[Event<'static>],
+        (sheet_index, row): (usize, usize),
+        results: &HashMap<(usize, usize, usize), CachedResult>,
+        new_formula: Option<((usize, usize, usize), &str)>,
+    ) -> Result<(), AppError> {
+        let repeat = Self::attr_repeat_owned(row_start, b"number-rows-repeated");
+        let items = Self::split_row_items(inner);
+        let mut col = 0usize;
+        let mut planned = Vec::with_capacity(items.len());
+        for item in &items {
+            match item {
+                RowItem::Cell {
+                    covered, repeat, ..
+                } => {
+                    let key = (sheet_index, row, col);
+                    planned.push(if *covered {
+                        None
+                    } else {
+                        results.get(&key).map(|result| {
+                            let formula = new_formula
+                                .filter(|(target, _)| *target == key)
+                                .map(|(_, formula)| formula);
+                            (result, formula)
+                        })
+                    });
+                    col += repeat;
+                }
+                RowItem::Other(_) => planned.push(None),
+            }
+        }
+        if planned.iter().all(Option::is_none) {
+            return Self::write_row_copy(writer, row_start, inner, Some(repeat));
+        }
+
+        writer
+            .write_event(Event::Start(Self::clone_row_with_repeat(
+                row_start,
+                Some(repeat),
+            )))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        for (item, result) in items.iter().zip(planned) {
+            match (item, result) {
+                (RowItem::Other(event), _) => writer
+                    .write_event(event.clone())
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?,
+                (
+                    RowItem::Cell {
+                        start,
+                        body,
+                        covered,
+                        repeat,
+                    },
+                    None,
+                ) => Self::write_cell_copy(writer, start, body.as_deref(), *repeat, *covered)?,
+                (RowItem::Cell { start, body, .. }, Some((result, formula))) => {
+                    let (cell, text) = Self::result_cell_start(start, result, formula);
+                    Self::write_cell_with_text(writer, cell, body.as_deref(), &text)?;
+                }
+            }
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("table:table-row")))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        Ok(())
+    }
+
+    // Start tag carrying the new value and the text to show. Numbers keep a float, percentage
+    // or currency type (and a date/time type when the cell was formatted as one). An empty
+    // result leaves the cell without a value type; `formula` replaces table:formula when set.
+    fn result_cell_start(
+        start: &BytesStart<'static>,
+        result: &CachedResult,
+        formula: Option<&str>,
+    ) -> (BytesStart<'static>, String) {
+        let existing_type = Self::attr_value(start, b"value-type").unwrap_or_default();
+        let mut text = result.text.clone();
+        let mut value_attrs: Vec<(&str, String)> = Vec::new();
+        match &result.value {
+            CellValue::Number(n) => match existing_type.as_str() {
+                "percentage" | "currency" => {
+                    value_attrs.push(("office:value-type", existing_type.clone()));
+                    value_attrs.push(("office:value", n.to_string()));
+                }
+                "date" => {
+                    let iso = TimeUtil::iso8601_from_serial(*n);
+                    text = iso.clone();
+                    value_attrs.push(("office:value-type", "date".to_string()));
+                    value_attrs.push(("office:date-value", iso));
+                }
+                "time" => {
+                    let secs = (n * 86_400.0).round() as i64;
+                    value_attrs.push(("office:value-type", "time".to_string()));
+                    value_attrs.push((
+                        "office:time-value",
+                        format!(
+                            "PT{:02}H{:02}M{:02}S",
+                            secs / 3600,
+                            (secs % 3600) / 60,
+                            secs % 60
+                        ),
+                    ));
+                }
+                _ => {
+                    value_attrs.push(("office:value-type", "float".to_string()));
+                    value_attrs.push(("office:value", n.to_string()));
+                }
+            },
+            CellValue::Boolean(b) => {
+                value_attrs.push(("office:value-type", "boolean".to_string()));
+                value_attrs.push(("office:boolean-value", b.to_string()));
+            }
+            CellValue::Date(iso) => {
+                value_attrs.push(("office:value-type", "date".to_string()));
+                value_attrs.push(("office:date-value", iso.clone()));
+            }
+            CellValue::String(_) => {
+                value_attrs.push(("office:value-type", "string".to_string()));
+            }
+            CellValue::Empty => {}
+        }
+        let keeps_currency = value_attrs
+            .first()
+            .is_some_and(|(_, t)| t.as_str() == "currency");
+
+        let mut cell = BytesStart::new("table:table-cell");
+        for attr in start.attributes().flatten() {
+            let key = attr.key.as_ref();
+            let is_value = [
+                b"value-type".as_slice(),
+                b"value",
+                b"boolean-value",
+                b"date-value",
+                b"time-value",
+                b"string-value",
+            ]
+            .iter()
+            .any(|name| Self::is_local_name_bytes(key, name));
+            if is_value
+                || (!keeps_currency && Self::is_local_name_bytes(key, b"currency"))
+                || (formula.is_some() && Self::is_local_name_bytes(key, b"formula"))
+            {
+                continue;
+            }
+            cell.push_attribute(attr);
+        }
+        if let Some(formula) = formula {
+            cell.push_attribute(("table:formula", formula));
+        }
+        for (key, value) in &value_attrs {
+            cell.push_attribute((*key, value.as_str()));
+        }
+        (cell.into_owned(), text)
+    }
+}
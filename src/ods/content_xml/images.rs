@@ -0,0 +1,625 @@
+use super::range_edit::RowItem;
+use super::*;
+
+// Picture frame (draw:frame holding a draw:image) found in a sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageFrame {
+    pub sheet_index: usize,
+    pub sheet: String,
+    // Anchor cell as (row, col); None for frames anchored to the page (table:shapes).
+    pub cell: Option<(usize, usize)>,
+    pub name: Option<String>,
+    // Package part holding the picture; None when it is embedded as office:binary-data.
+    pub href: Option<String>,
+    pub media_type: Option<String>,
+    pub width: Option<String>,
+    pub height: Option<String>,
+    pub x: Option<String>,
+    pub y: Option<String>,
+    pub z_index: Option<i64>,
+    // Byte range of the whole draw:frame element in content.xml.
+    span: (usize, usize),
+}
+
+// Picture of a new frame: a package part, or base64 data for flat documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageData {
+    Part(String),
+    Inline(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewImageFrame {
+    pub name: String,
+    pub data: ImageData,
+    pub media_type: String,
+    pub width: String,
+    pub height: String,
+    pub x: String,
+    pub y: String,
+}
+
+const FRAME_NAMESPACES: [(&str, &str); 3] = [
+    ("draw", "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"),
+    (
+        "svg",
+        "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0",
+    ),
+    ("xlink", "http://www.w3.org/1999/xlink"),
+];
+
+// Children a table keeps ahead of table:shapes; the first of these ends the search.
+const AFTER_SHAPES: [&str; 6] = [
+    "<table:table-column",
+    "<table:table-header-columns",
+    "<table:table-row",
+    "<table:table-header-rows",
+    "<text:soft-page-break",
+    "</table:table>",
+];
+
+impl ContentXml {
+    pub fn images_raw(original_content: &str) -> Result<Vec<ImageFrame>, AppError> {
+        let mut reader = Reader::from_str(original_content);
+        reader.config_mut().trim_text(false);
+
+        let mut result = Vec::new();
+        let mut sheet_count = 0usize;
+        let mut sheet = String::new();
+        let mut table_depth = 0usize;
+        let mut current_row = 0usize;
+        let mut row_repeat = 1usize;
+        let mut current_col = 0usize;
+        let mut open_cell: Option<usize> = None;
+        // Outermost frame being read, its nesting depth, whether it holds a picture and whether
+        // it holds an object (charts carry a draw:image too, as their replacement picture).
+        let mut frame: Option<(ImageFrame, usize, bool, bool)> = None;
+
+        loop {
+            let before = reader.buffer_position() as usize;
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            let after = reader.buffer_position() as usize;
+
+            if let Some((current, depth, has_image, has_object)) = frame.as_mut() {
+                match &event {
+                    Event::Start(e) | Event::Empty(e)
+                        if Self::is_local_name_bytes(e.name().as_ref(), b"image")
+                            && !*has_image =>
+                    {
+                        *has_image = true;
+                        current.href = Self::attr_value(e, b"href");
+                        current.media_type = Self::attr_value(e, b"mime-type");
+                        if matches!(event, Event::Start(_)) {
+                            *depth += 1;
+                        }
+                    }
+                    Event::Start(e) | Event::Empty(e)
+                        if Self::is_local_name_bytes(e.name().as_ref(), b"object")
+                            || Self::is_local_name_bytes(e.name().as_ref(), b"object-ole") =>
+                    {
+                        *has_object = true;
+                        if matches!(event, Event::Start(_)) {
+                            *depth += 1;
+                        }
+                    }
+                    Event::Start(_) => *depth += 1,
+                    Event::End(_) if *depth == 0 => {
+                        let (mut done, _, has_image, has_object) =
+                            frame.take().unwrap_or_else(|| unreachable!());
+                        if has_image && !has_object {
+                            done.span.1 = after;
+                            result.push(done);
+                        }
+                    }
+                    Event::End(_) => *depth -= 1,
+                    Event::Eof => {
+                        return Err(AppError::XmlParseError(
+                            "unterminated draw:frame in content.xml".to_string(),
+                        ))
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        sheet_count += 1;
+                        sheet = Self::table_name_attr(&e, reader.decoder());
+                        current_row = 0;
+                    }
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    sheet_count += 1;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                }
+                Event::Start(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    row_repeat = Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                    current_col = 0;
+                }
+                Event::Empty(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    current_row += Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                }
+                Event::End(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    current_row += row_repeat;
+                    row_repeat = 1;
+                }
+                Event::Start(e)
+                    if table_depth == 1
+                        && (Self::is_local_name_bytes(e.name().as_ref(), b"table-cell")
+                            || Self::is_local_name_bytes(
+                                e.name().as_ref(),
+                                b"covered-table-cell",
+                            )) =>
+                {
+                    open_cell = Some(current_col);
+                    current_col +=
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                }
+                Event::Empty(e)
+                    if table_depth == 1
+                        && (Self::is_local_name_bytes(e.name().as_ref(), b"table-cell")
+                            || Self::is_local_name_bytes(
+                                e.name().as_ref(),
+                                b"covered-table-cell",
+                            )) =>
+                {
+                    current_col +=
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                }
+                Event::End(e)
+                    if table_depth == 1
+                        && (Self::is_local_name_bytes(e.name().as_ref(), b"table-cell")
+                            || Self::is_local_name_bytes(
+                                e.name().as_ref(),
+                                b"covered-table-cell",
+                            )) =>
+                {
+                    open_cell = None;
+                }
+                Event::Start(e)
+                    if table_depth >= 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"frame") =>
+                {
+                    frame = Some((
+                        ImageFrame {
+                            sheet_index: sheet_count - 1,
+                            sheet: sheet.clone(),
+                            cell: open_cell.map(|col| (current_row, col)),
+                            name: Self::attr_value(&e, b"name"),
+                            href: None,
+                            media_type: None,
+                            width: Self::attr_value(&e, b"width"),
+                            height: Self::attr_value(&e, b"height"),
+                            x: Self::attr_value(&e, b"x"),
+                            y: Self::attr_value(&e, b"y"),
+                            z_index: Self::attr_value(&e, b"z-index")
+                                .and_then(|z| z.trim().parse().ok()),
+                            span: (before, before),
+                        },
+                        0,
+                        false,
+                        false,
+                    ));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+
+    // Adds a picture frame to one sheet, anchored to a cell or (cell None) to the page.
+    // Cell-anchored x/y are offsets inside the cell; the cell value and style are kept.
+    pub fn insert_image_frame_raw(
+        original_content: &str,
+        sheet_index: usize,
+        cell: Option<(usize, usize)>,
+        frame: &NewImageFrame,
+    ) -> Result<String, AppError> {
+        let z_index = Self::images_raw(original_content)?
+            .iter()
+            .filter_map(|f| f.z_index)
+            .max()
+            .map_or(0, |z| z + 1);
+        let frame_xml = Self::image_frame_xml(frame, z_index);
+
+        let mut content = original_content.to_string();
+        for (prefix, uri) in FRAME_NAMESPACES {
+            content = Self::ensure_root_namespace(&content, prefix, uri)?;
+        }
+        let Some((row, col)) = cell else {
+            return Self::insert_page_frame(&content, sheet_index, &frame_xml);
+        };
+        if let Some(updated) = Self::insert_cell_frame(&content, sheet_index, row, col, &frame_xml)?
+        {
+            return Ok(updated);
+        }
+        // The cell lies past the rows and cells written so far: extend the grid, then retry.
+        let padded = Self::set_cell_value_preserving_styles_raw(
+            &content,
+            sheet_index,
+            row,
+            col,
+            &CellValue::Empty,
+        )?;
+        Self::insert_cell_frame(&padded, sheet_index, row, col, &frame_xml)?.ok_or_else(|| {
+            AppError::InvalidOdsFormat(format!("cell ({row}, {col}) could not be written"))
+        })
+    }
+
+    // Cuts the given frames out of content.xml, dropping a table:shapes left empty.
+    pub fn remove_image_frames_raw(
+        original_content: &str,
+        frames: &[&ImageFrame],
+    ) -> Result<String, AppError> {
+        let mut spans: Vec<(usize, usize)> = frames.iter().map(|f| f.span).collect();
+        spans.sort_unstable();
+        spans.dedup();
+        let mut content = original_content.to_string();
+        for (start, end) in spans.into_iter().rev() {
+            if end > content.len() || !content.is_char_boundary(start) {
+                return Err(AppError::InvalidInput(
+                    "image frame does not belong to this content.xml".to_string(),
+                ));
+            }
+            content.replace_range(start..end, "");
+        }
+
+        let mut pos = 0usize;
+        while let Some(found) = content[pos..].find("<table:shapes>") {
+            let start = pos + found;
+            let inner = start + "<table:shapes>".len();
+            let rest = &content[inner..];
+            if rest.trim_start().starts_with("</table:shapes>") {
+                let close = inner + rest.find("</table:shapes>").unwrap_or_default();
+                content.replace_range(start..close + "</table:shapes>".len(), "");
+            }
+            pos = start + 1;
+        }
+        Ok(content)
+    }
+
+    fn image_frame_xml(frame: &NewImageFrame, z_index: i64) -> String {
+        let (href, inline) = match &frame.data {
+            ImageData::Part(href) => (
+                format!(
+                    " xlink:href=\"{}\" xlink:type=\"simple\" xlink:show=\"embed\" xlink:actuate=\"onLoad\"",
+                    Self::escape_xml_attr(href)
+                ),
+                String::new(),
+            ),
+            ImageData::Inline(base64) => (
+                String::new(),
+                format!("<office:binary-data>{base64}</office:binary-data>"),
+            ),
+        };
+        format!(
+            "<draw:frame draw:z-index=\"{z_index}\" draw:name=\"{}\" svg:width=\"{}\" svg:height=\"{}\" svg:x=\"{}\" svg:y=\"{}\"><draw:image{href} draw:mime-type=\"{}\">{inline}<text:p/></draw:image></draw:frame>",
+            Self::escape_xml_attr(&frame.name),
+            Self::escape_xml_attr(&frame.width),
+            Self::escape_xml_attr(&frame.height),
+            Self::escape_xml_attr(&frame.x),
+            Self::escape_xml_attr(&frame.y),
+            Self::escape_xml_attr(&frame.media_type),
+        )
+    }
+
+    fn insert_page_frame(
+        content: &str,
+        sheet_index: usize,
+        frame_xml: &str,
+    ) -> Result<String, AppError> {
+        let tables = Self::find_table_blocks(content)?;
+        let table = tables
+            .get(sheet_index)
+            .ok_or_else(|| AppError::SheetNotFound(format!("index {sheet_index}")))?;
+        let start_tag_end = Self::find_tag_end(content, table.start)?;
+        let start_tag = &content[table.start..start_tag_end];
+        if let Some(open_tag) = start_tag.trim_end().strip_suffix("/>") {
+            return Ok(format!(
+                "{}{}><table:shapes>{frame_xml}</table:shapes></table:table>{}",
+                &content[..table.start],
+                open_tag.trim_end(),
+                &content[table.end..]
+            ));
+        }
+
+        let body = &content[start_tag_end..table.end];
+        let (offset, insert) = match body.find("</table:shapes>") {
+            Some(close) => (close, frame_xml.to_string()),
+            None => {
+                let first_after = AFTER_SHAPES
+                    .iter()
+                    .filter_map(|tag| body.find(tag))
+                    .min()
+                    .unwrap_or(body.len());
+                (
+                    first_after,
+                    format!("<table:shapes>{frame_xml}</table:shapes>"),
+                )
+            }
+        };
+        let at = start_tag_end + offset;
+        Ok(format!("{}{insert}{}", &content[..at], &content[at..]))
+    }
+
+    // Splits the target cell out of any repeated run and writes the frame inside it. Returns
+    // None when the sheet has no cell at that position yet.
+    fn insert_cell_frame(
+        content: &str,
+        sheet_index: usize,
+        target_row: usize,
+        target_col: usize,
+        frame_xml: &str,
+    ) -> Result<Option<String>, AppError> {
+        let mut reader = Reader::from_str(content);
+        reader.config_mut().trim_text(false);
+        let mut writer = Writer::new(Cursor::new(Vec::new()));
+
+        let mut current_sheet = 0usize;
+        let mut table_depth = 0usize;
+        let mut in_target_sheet = false;
+        let mut sheet_found = false;
+        let mut written = false;
+        let mut current_row = 0usize;
+        let mut capture: Option<(BytesStart<'static>, Vec<Event<'static>>, usize)> = None;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+
+            if let Some((_, inner, depth)) = capture.as_mut() {
+                match &event {
+                    Event::Start(_) => *depth += 1,
+                    Event::End(_) if *depth == 0 => {
+                        let (row_start, inner, _) =
+                            capture.take().unwrap_or_else(|| unreachable!());
+                        let repeat = Self::attr_repeat_owned(&row_start, b"number-rows-repeated");
+                        if (current_row..current_row + repeat).contains(&target_row) {
+                            written = Self::write_frame_row_run(
+                                &mut writer,
+                                &row_start,
+                                &inner,
+                                (current_row, repeat),
+                                (target_row, target_col),
+                                frame_xml,
+                            )?;
+                        } else {
+                            Self::write_row_copy(&mut writer, &row_start, &inner, Some(repeat))?;
+                        }
+                        current_row += repeat;
+                        continue;
+                    }
+                    Event::End(_) => *depth -= 1,
+                    Event::Eof => {
+                        return Err(AppError::XmlParseError(
+                            "unterminated table-row in content.xml".to_string(),
+                        ))
+                    }
+                    _ => {}
+                }
+                inner.push(event.into_owned());
+                continue;
+            }
+
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        in_target_sheet = current_sheet == sheet_index;
+                        sheet_found |= in_target_sheet;
+                        current_sheet += 1;
+                        current_row = 0;
+                    }
+                    writer
+                        .write_event(Event::Start(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    sheet_found |= current_sheet == sheet_index;
+                    current_sheet += 1;
+                    writer
+                        .write_event(Event::Empty(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 {
+                        in_target_sheet = false;
+                    }
+                    writer
+                        .write_event(Event::End(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Start(e)
+                    if in_target_sheet
+                        && !written
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    capture = Some((e.into_owned(), Vec::new(), 0));
+                }
+                Event::Empty(e)
+                    if in_target_sheet
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    current_row += Self::attr_repeat_owned(&e, b"number-rows-repeated");
+                    writer
+                        .write_event(Event::Empty(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Eof => break,
+                other => writer
+                    .write_event(other.into_owned())
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?,
+            }
+        }
+
+        if !sheet_found {
+            return Err(AppError::SheetNotFound(format!("index {sheet_index}")));
+        }
+        if !written {
+            return Ok(None);
+        }
+        let bytes = writer.into_inner().into_inner();
+        String::from_utf8(bytes)
+            .map(Some)
+            .map_err(|e| AppError::XmlParseError(e.to_string()))
+    }
+
+    // Writes a run of rows containing the target row; returns whether the target cell was
+    // found in it.
+    fn write_frame_row_run(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        row_start: &BytesStart<'static>,
+        inner: &[Event<'static>],
+        (first_row, repeat): (usize, usize),
+        (target_row, target_col): (usize, usize),
+        frame_xml: &str,
+    ) -> Result<bool, AppError> {
+        if target_row > first_row {
+            Self::write_row_copy(writer, row_start, inner, Some(target_row - first_row))?;
+        }
+        writer
+            .write_event(Event::Start(Self::clone_row_with_repeat(row_start, None)))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        let mut col = 0usize;
+        let mut found = false;
+        for item in Self::split_row_items(inner) {
+            match item {
+                RowItem::Other(event) => writer
+                    .write_event(event)
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?,
+                RowItem::Cell {
+                    start,
+                    body,
+                    covered,
+                    repeat,
+                } if (col..col + repeat).contains(&target_col) => {
+                    let body = body.as_deref();
+                    if target_col > col {
+                        Self::write_cell_copy(writer, &start, body, target_col - col, covered)?;
+                    }
+                    Self::write_frame_cell(writer, &start, body, covered, frame_xml)?;
+                    let after = col + repeat - target_col - 1;
+                    if after > 0 {
+                        Self::write_cell_copy(writer, &start, body, after, covered)?;
+                    }
+                    found = true;
+                    col += repeat;
+                }
+                RowItem::Cell {
+                    start,
+                    body,
+                    covered,
+                    repeat,
+                } => {
+                    Self::write_cell_copy(writer, &start, body.as_deref(), repeat, covered)?;
+                    col += repeat;
+                }
+            }
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("table:table-row")))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        let rows_after = first_row + repeat - target_row - 1;
+        if rows_after > 0 {
+            Self::write_row_copy(writer, row_start, inner, Some(rows_after))?;
+        }
+        Ok(found)
+    }
+
+    // The frame goes after a leading comment (office:annotation) and before the paragraphs.
+    fn write_frame_cell(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        start: &BytesStart<'static>,
+        body: Option<&[Event<'static>]>,
+        covered: bool,
+        frame_xml: &str,
+    ) -> Result<(), AppError> {
+        let name = if covered {
+            "table:covered-table-cell"
+        } else {
+            "table:table-cell"
+        };
+        let mut cell = BytesStart::new(name);
+        cell.extend_attributes(
+            Self::clone_cell_with_repeat(start, 1)
+                .attributes()
+                .flatten(),
+        );
+        let body = body.unwrap_or_default();
+
+        let mut split = 0usize;
+        let mut depth = 0usize;
+        for (i, event) in body.iter().enumerate() {
+            match event {
+                Event::Start(e) if depth == 0 => {
+                    if !Self::is_local_name_bytes(e.name().as_ref(), b"annotation") {
+                        break;
+                    }
+                    depth = 1;
+                }
+                Event::Empty(e) if depth == 0 => {
+                    if !Self::is_local_name_bytes(e.name().as_ref(), b"annotation") {
+                        break;
+                    }
+                    split = i + 1;
+                }
+                Event::Start(_) => depth += 1,
+                Event::End(_) => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        split = i + 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        writer
+            .write_event(Event::Start(cell))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        for event in &body[..split] {
+            writer
+                .write_event(event.clone())
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        writer
+            .write_event(Event::Text(BytesText::from_escaped(frame_xml)))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        for event in &body[split..] {
+            writer
+                .write_event(event.clone())
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new(name)))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -1,6 +1,116 @@
 use super::*;
 
 impl ContentXml {
+    pub fn merged_ranges_raw(
+        original_content: &str,
+        sheet_index: usize,
+    ) -> Result<Vec<MergedRange>, AppError> {
+        // Collects spanned anchors of one sheet; covered cells only advance the column cursor.
+        let mut reader = Reader::from_str(original_content);
+        reader.config_mut().trim_text(false);
+
+        let mut ranges = Vec::new();
+        let mut current_sheet: usize = 0;
+        let mut table_depth: usize = 0;
+        let mut in_target_sheet = false;
+        let mut current_row: usize = 0;
+        let mut current_row_repeat: usize = 1;
+        let mut current_col: usize = 0;
+        // Spans found in the open row as (col, rows, cols); replicated for repeated rows.
+        let mut row_spans: Vec<(usize, usize, usize)> = Vec::new();
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        in_target_sheet = current_sheet == sheet_index;
+                        current_sheet += 1;
+                    }
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    if current_sheet == sheet_index {
+                        break;
+                    }
+                    current_sheet += 1;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 && in_target_sheet {
+                        break;
+                    }
+                }
+                Event::Start(e)
+                    if in_target_sheet
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    current_row_repeat =
+                        Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                    current_col = 0;
+                    row_spans.clear();
+                }
+                Event::Empty(e)
+                    if in_target_sheet
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    current_row += Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                }
+                Event::End(e)
+                    if in_target_sheet
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    for rep in 0..current_row_repeat {
+                        ranges.extend(row_spans.iter().map(|(col, rows, cols)| MergedRange {
+                            row: current_row + rep,
+                            col: *col,
+                            rows: *rows,
+                            cols: *cols,
+                        }));
+                    }
+                    current_row += current_row_repeat;
+                    current_row_repeat = 1;
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if in_target_sheet
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") =>
+                {
+                    let col_repeat =
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    let rows = Self::attr_repeat(&e, b"number-rows-spanned", reader.decoder());
+                    let cols = Self::attr_repeat(&e, b"number-columns-spanned", reader.decoder());
+                    if rows > 1 || cols > 1 {
+                        for rep in 0..col_repeat {
+                            row_spans.push((current_col + rep, rows, cols));
+                        }
+                    }
+                    current_col += col_repeat;
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if in_target_sheet
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                {
+                    current_col +=
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(ranges)
+    }
+
     pub fn resolve_merged_anchor_raw(
         original_content: &str,
         sheet_index: usize,
@@ -34,97 +144,103 @@ impl ContentXml {
                     }
                     in_target_sheet = false;
                 }
-                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
-                    if in_target_sheet {
-                        break;
-                    }
+                Event::End(e)
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"table")
+                        && in_target_sheet =>
+                {
+                    break;
                 }
-                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
-                    if in_target_sheet {
-                        current_row_repeat =
-                            Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
-                        in_row = true;
-                        current_col = 0;
-                    }
+                Event::Start(e)
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"table-row")
+                        && in_target_sheet =>
+                {
+                    current_row_repeat =
+                        Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                    in_row = true;
+                    current_col = 0;
                 }
-                Event::Empty(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
-                    if in_target_sheet {
-                        let repeat =
-                            Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
-                        if target_row >= current_row && target_row < current_row + repeat {
-                            return Ok((target_row, target_col));
-                        }
-                        current_row += repeat;
+                Event::Empty(e)
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"table-row")
+                        && in_target_sheet =>
+                {
+                    let repeat = Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                    if target_row >= current_row && target_row < current_row + repeat {
+                        return Ok((target_row, target_col));
                     }
+                    current_row += repeat;
                 }
-                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
-                    if in_target_sheet && in_row {
-                        current_row += current_row_repeat;
-                        current_row_repeat = 1;
-                        in_row = false;
-                    }
+                Event::End(e)
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"table-row")
+                        && in_target_sheet
+                        && in_row =>
+                {
+                    current_row += current_row_repeat;
+                    current_row_repeat = 1;
+                    in_row = false;
                 }
-                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") => {
-                    if in_target_sheet && in_row {
-                        let col_repeat =
-                            Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
-                        let row_span =
-                            Self::attr_repeat(&e, b"number-rows-spanned", reader.decoder());
-                        let col_span =
-                            Self::attr_repeat(&e, b"number-columns-spanned", reader.decoder());
-                        if let Some(anchor_col) = Self::find_spanned_anchor_col(
-                            current_row,
-                            current_col,
-                            row_span,
-                            col_span,
-                            col_repeat,
-                            target_row,
-                            target_col,
-                        ) {
-                            return Ok((current_row, anchor_col));
-                        }
-                        current_col += col_repeat * col_span;
+                Event::Start(e)
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell")
+                        && in_target_sheet
+                        && in_row =>
+                {
+                    let col_repeat =
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    let row_span = Self::attr_repeat(&e, b"number-rows-spanned", reader.decoder());
+                    let col_span =
+                        Self::attr_repeat(&e, b"number-columns-spanned", reader.decoder());
+                    if let Some(anchor_col) = Self::find_spanned_anchor_col(
+                        current_row,
+                        current_col,
+                        row_span,
+                        col_span,
+                        col_repeat,
+                        target_row,
+                        target_col,
+                    ) {
+                        return Ok((current_row, anchor_col));
                     }
+                    current_col += col_repeat * col_span;
                 }
-                Event::Empty(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") => {
-                    if in_target_sheet && in_row {
-                        let col_repeat =
-                            Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
-                        let row_span =
-                            Self::attr_repeat(&e, b"number-rows-spanned", reader.decoder());
-                        let col_span =
-                            Self::attr_repeat(&e, b"number-columns-spanned", reader.decoder());
-                        if let Some(anchor_col) = Self::find_spanned_anchor_col(
-                            current_row,
-                            current_col,
-                            row_span,
-                            col_span,
-                            col_repeat,
-                            target_row,
-                            target_col,
-                        ) {
-                            return Ok((current_row, anchor_col));
-                        }
-                        current_col += col_repeat * col_span;
+                Event::Empty(e)
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell")
+                        && in_target_sheet
+                        && in_row =>
+                {
+                    let col_repeat =
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    let row_span = Self::attr_repeat(&e, b"number-rows-spanned", reader.decoder());
+                    let col_span =
+                        Self::attr_repeat(&e, b"number-columns-spanned", reader.decoder());
+                    if let Some(anchor_col) = Self::find_spanned_anchor_col(
+                        current_row,
+                        current_col,
+                        row_span,
+                        col_span,
+                        col_repeat,
+                        target_row,
+                        target_col,
+                    ) {
+                        return Ok((current_row, anchor_col));
                     }
+                    current_col += col_repeat * col_span;
                 }
                 Event::Start(e)
-                    if Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell")
+                        && in_target_sheet
+                        && in_row =>
                 {
-                    if in_target_sheet && in_row {
-                        let repeat =
-                            Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
-                        current_col += repeat;
-                    }
+                    let repeat =
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    current_col += repeat;
                 }
                 Event::Empty(e)
-                    if Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell")
+                        && in_target_sheet
+                        && in_row =>
                 {
-                    if in_target_sheet && in_row {
-                        let repeat =
-                            Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
-                        current_col += repeat;
-                    }
+                    let repeat =
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    current_col += repeat;
                 }
                 Event::Eof => break,
                 _ => {}
@@ -1,13 +1,31 @@
 use crate::common::errors::AppError;
-use crate::ods::sheet_model::{Cell, CellValue, Sheet, Workbook};
+use crate::ods::sheet_model::{Cell, CellComment, CellStyle, CellValue, Sheet, Workbook};
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::{Reader, Writer};
 use std::io::Cursor;
 
+pub use crate::ods::sheet_model::MergedRange;
+
+pub use cell_search::{CellMatch, CellMatches, SearchCell};
+pub use formula_results::CachedResult;
+pub use images::{ImageData, ImageFrame, NewImageFrame};
+pub use sheet_attrs::{SheetAttributes, SheetProtection};
+pub use sheet_stats::SheetStats;
+pub use sparse_cells::{SheetWindow, SparseCell, SparseCellPage};
+pub use text_replace::{ReplaceOutcome, ReplaceScope, ReplacedField, TextReplacement};
+
 pub struct ContentXml;
 mod cell_edit;
+mod cell_search;
+mod formula_results;
+mod images;
 mod merged_anchor;
+mod range_edit;
+mod sheet_attrs;
+mod sheet_stats;
+mod sparse_cells;
 mod table_blocks;
+mod text_replace;
 mod workbook_xml;
 
 impl ContentXml {
@@ -0,0 +1,451 @@
+use super::*;
+use crate::ods::cell_address::CellAddress;
+
+// One child of a captured row: a cell with its body (None when self-closing) or any other node.
+pub(super) enum RowItem {
+    Cell {
+        start: BytesStart<'static>,
+        body: Option<Vec<Event<'static>>>,
+        covered: bool,
+        repeat: usize,
+    },
+    Other(Event<'static>),
+}
+
+impl ContentXml {
+    pub fn set_range_values_preserving_styles_raw(
+        original_content: &str,
+        sheet_index: usize,
+        start_row: usize,
+        start_col: usize,
+        values: &[Vec<CellValue>],
+    ) -> Result<String, AppError> {
+        // Writes a block of rows in a single pass; repeated rows and cells are split only
+        // where the block touches them, so untouched formatting runs stay compressed.
+        let mut reader = Reader::from_str(original_content);
+        reader.config_mut().trim_text(false);
+        let mut writer = Writer::new(Cursor::new(Vec::new()));
+
+        let mut current_sheet: usize = 0;
+        let mut table_depth: usize = 0;
+        let mut in_target_sheet = false;
+        let mut sheet_found = false;
+        let mut current_row: usize = 0;
+        // Open row of the target sheet as (start tag, inner events, nesting depth).
+        let mut capture: Option<(BytesStart<'static>, Vec<Event<'static>>, usize)> = None;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+
+            if let Some((_, inner, depth)) = capture.as_mut() {
+                match &event {
+                    Event::Start(_) => *depth += 1,
+                    Event::End(_) if *depth == 0 => {
+                        let (row_start, inner, _) =
+                            capture.take().unwrap_or_else(|| unreachable!());
+                        let repeat = Self::attr_repeat_owned(&row_start, b"number-rows-repeated");
+                        Self::write_row_run(
+                            &mut writer,
+                            &row_start,
+                            &inner,
+                            current_row,
+                            repeat,
+                            start_row,
+                            start_col,
+                            values,
+                        )?;
+                        current_row += repeat;
+                        continue;
+                    }
+                    Event::End(_) => *depth -= 1,
+                    Event::Eof => {
+                        return Err(AppError::XmlParseError(
+                            "unterminated table-row in content.xml".to_string(),
+                        ))
+                    }
+                    _ => {}
+                }
+                inner.push(event.into_owned());
+                continue;
+            }
+
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        in_target_sheet = current_sheet == sheet_index;
+                        sheet_found |= in_target_sheet;
+                        current_sheet += 1;
+                    }
+                    writer
+                        .write_event(Event::Start(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    if current_sheet == sheet_index {
+                        sheet_found = true;
+                        let end_name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                        writer
+                            .write_event(Event::Start(e.into_owned()))
+                            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                        Self::append_block_rows(&mut writer, 0, start_row, start_col, values)?;
+                        writer
+                            .write_event(Event::End(BytesEnd::new(end_name)))
+                            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                    } else {
+                        writer
+                            .write_event(Event::Empty(e.into_owned()))
+                            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                    }
+                    current_sheet += 1;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 && in_target_sheet {
+                        Self::append_block_rows(
+                            &mut writer,
+                            current_row,
+                            start_row,
+                            start_col,
+                            values,
+                        )?;
+                        in_target_sheet = false;
+                    }
+                    writer
+                        .write_event(Event::End(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Start(e)
+                    if in_target_sheet
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    capture = Some((e.into_owned(), Vec::new(), 0));
+                }
+                Event::Empty(e)
+                    if in_target_sheet
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    let row_start = e.into_owned();
+                    let repeat = Self::attr_repeat_owned(&row_start, b"number-rows-repeated");
+                    Self::write_row_run(
+                        &mut writer,
+                        &row_start,
+                        &[],
+                        current_row,
+                        repeat,
+                        start_row,
+                        start_col,
+                        values,
+                    )?;
+                    current_row += repeat;
+                }
+                Event::Eof => break,
+                other => writer
+                    .write_event(other.into_owned())
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?,
+            }
+        }
+
+        if !sheet_found {
+            return Err(AppError::SheetNotFound(format!("index {sheet_index}")));
+        }
+
+        let bytes = writer.into_inner().into_inner();
+        String::from_utf8(bytes).map_err(|e| AppError::XmlParseError(e.to_string()))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn write_row_run(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        row_start: &BytesStart<'static>,
+        inner: &[Event<'static>],
+        first_row: usize,
+        repeat: usize,
+        start_row: usize,
+        start_col: usize,
+        values: &[Vec<CellValue>],
+    ) -> Result<(), AppError> {
+        // A run of `repeat` identical rows becomes: untouched rows before the block, one row
+        // per block row it covers, and untouched rows after.
+        let block_end = start_row + values.len();
+        let run_end = first_row + repeat;
+        if run_end <= start_row || first_row >= block_end {
+            return Self::write_row_copy(writer, row_start, inner, Some(repeat));
+        }
+
+        if start_row > first_row {
+            Self::write_row_copy(writer, row_start, inner, Some(start_row - first_row))?;
+        }
+        for row in first_row.max(start_row)..run_end.min(block_end) {
+            writer
+                .write_event(Event::Start(Self::clone_row_with_repeat(row_start, None)))
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            Self::write_row_cells(writer, inner, row, start_col, &values[row - start_row])?;
+            writer
+                .write_event(Event::End(BytesEnd::new("table:table-row")))
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        if run_end > block_end {
+            Self::write_row_copy(writer, row_start, inner, Some(run_end - block_end))?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn write_row_copy(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        row_start: &BytesStart<'static>,
+        inner: &[Event<'static>],
+        repeat: Option<usize>,
+    ) -> Result<(), AppError> {
+        let start = Self::clone_row_with_repeat(row_start, repeat);
+        if inner.is_empty() {
+            return writer
+                .write_event(Event::Empty(start))
+                .map(|_| ())
+                .map_err(|e| AppError::XmlParseError(e.to_string()));
+        }
+        writer
+            .write_event(Event::Start(start))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        for event in inner {
+            writer
+                .write_event(event.clone())
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("table:table-row")))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn write_row_cells(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        inner: &[Event<'static>],
+        row: usize,
+        start_col: usize,
+        row_values: &[CellValue],
+    ) -> Result<(), AppError> {
+        let end_col = start_col + row_values.len();
+        let mut current_col: usize = 0;
+
+        for item in Self::split_row_items(inner) {
+            let RowItem::Cell {
+                start,
+                body,
+                covered,
+                repeat,
+            } = item
+            else {
+                if let RowItem::Other(event) = item {
+                    writer
+                        .write_event(event)
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                continue;
+            };
+
+            let run_end = current_col + repeat;
+            if run_end <= start_col || current_col >= end_col {
+                Self::write_cell_copy(writer, &start, body.as_deref(), repeat, covered)?;
+                current_col = run_end;
+                continue;
+            }
+            if covered {
+                return Err(AppError::InvalidInput(format!(
+                    "range overlaps merged cell at {}",
+                    CellAddress {
+                        row,
+                        col: current_col.max(start_col)
+                    }
+                    .to_a1()
+                )));
+            }
+
+            if start_col > current_col {
+                Self::write_cell_copy(
+                    writer,
+                    &start,
+                    body.as_deref(),
+                    start_col - current_col,
+                    false,
+                )?;
+            }
+            let existing = Self::strip_value_attrs(&start);
+            for col in current_col.max(start_col)..run_end.min(end_col) {
+                Self::write_value_cell(writer, &row_values[col - start_col], Some(&existing))?;
+            }
+            if run_end > end_col {
+                Self::write_cell_copy(writer, &start, body.as_deref(), run_end - end_col, false)?;
+            }
+            current_col = run_end;
+        }
+
+        // Cells past the end of the stored row are appended after a default gap.
+        if current_col < end_col {
+            if start_col > current_col {
+                writer
+                    .write_event(Event::Empty(Self::default_gap_cell(
+                        start_col - current_col,
+                    )))
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            }
+            for col in current_col.max(start_col)..end_col {
+                Self::write_value_cell(writer, &row_values[col - start_col], None)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn append_block_rows(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        current_row: usize,
+        start_row: usize,
+        start_col: usize,
+        values: &[Vec<CellValue>],
+    ) -> Result<(), AppError> {
+        let block_end = start_row + values.len();
+        if current_row >= block_end {
+            return Ok(());
+        }
+        if start_row > current_row {
+            let mut gap = BytesStart::new("table:table-row");
+            let rows_text = (start_row - current_row).to_string();
+            gap.push_attribute(("table:number-rows-repeated", rows_text.as_str()));
+            writer
+                .write_event(Event::Empty(gap))
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        for row in current_row.max(start_row)..block_end {
+            writer
+                .write_event(Event::Start(BytesStart::new("table:table-row")))
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            Self::write_row_cells(writer, &[], row, start_col, &values[row - start_row])?;
+            writer
+                .write_event(Event::End(BytesEnd::new("table:table-row")))
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn split_row_items(inner: &[Event<'static>]) -> Vec<RowItem> {
+        let mut items = Vec::new();
+        let mut open: Option<(BytesStart<'static>, Vec<Event<'static>>, bool, usize)> = None;
+
+        for event in inner {
+            if let Some((_, body, _, depth)) = open.as_mut() {
+                match event {
+                    Event::Start(_) => *depth += 1,
+                    Event::End(_) if *depth == 0 => {
+                        let (start, body, covered, _) =
+                            open.take().unwrap_or_else(|| unreachable!());
+                        let repeat = Self::attr_repeat_owned(&start, b"number-columns-repeated");
+                        items.push(RowItem::Cell {
+                            start,
+                            body: Some(body),
+                            covered,
+                            repeat,
+                        });
+                        continue;
+                    }
+                    Event::End(_) => *depth -= 1,
+                    _ => {}
+                }
+                body.push(event.clone());
+                continue;
+            }
+
+            match event {
+                Event::Start(e) | Event::Empty(e)
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell")
+                        || Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                {
+                    let covered =
+                        Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell");
+                    if matches!(event, Event::Start(_)) {
+                        open = Some((e.clone(), Vec::new(), covered, 0));
+                    } else {
+                        let repeat = Self::attr_repeat_owned(e, b"number-columns-repeated");
+                        items.push(RowItem::Cell {
+                            start: e.clone(),
+                            body: None,
+                            covered,
+                            repeat,
+                        });
+                    }
+                }
+                other => items.push(RowItem::Other(other.clone())),
+            }
+        }
+        items
+    }
+
+    pub(super) fn write_cell_copy(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        start: &BytesStart<'static>,
+        body: Option<&[Event<'static>]>,
+        repeat: usize,
+        covered: bool,
+    ) -> Result<(), AppError> {
+        let name = if covered {
+            "table:covered-table-cell"
+        } else {
+            "table:table-cell"
+        };
+        let mut cell = BytesStart::new(name);
+        cell.extend_attributes(
+            Self::clone_cell_with_repeat(start, repeat)
+                .attributes()
+                .flatten(),
+        );
+        let Some(body) = body else {
+            return writer
+                .write_event(Event::Empty(cell))
+                .map(|_| ())
+                .map_err(|e| AppError::XmlParseError(e.to_string()));
+        };
+        writer
+            .write_event(Event::Start(cell))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        for event in body {
+            writer
+                .write_event(event.clone())
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new(name)))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn strip_value_attrs(start: &BytesStart<'_>) -> BytesStart<'static> {
+        // Keeps style, span and validation attributes; drops anything describing the old value.
+        const VALUE_ATTRS: [&[u8]; 8] = [
+            b"formula",
+            b"string-value",
+            b"date-value",
+            b"time-value",
+            b"currency",
+            b"value-type",
+            b"value",
+            b"boolean-value",
+        ];
+        let mut out = BytesStart::new("table:table-cell");
+        for attr in start.attributes().flatten() {
+            if VALUE_ATTRS
+                .iter()
+                .any(|key| Self::is_local_name_bytes(attr.key.as_ref(), key))
+            {
+                continue;
+            }
+            out.push_attribute(attr);
+        }
+        out
+    }
+}
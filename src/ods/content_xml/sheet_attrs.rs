@@ -0,0 +1,402 @@
+use super::table_blocks::TableBlock;
+use super::*;
+
+const STYLE_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:style:1.0";
+const TABLEOOO_NS: &str = "http://openoffice.org/2009/table";
+const SHA256_DIGEST: &str = "http://www.w3.org/2000/09/xmldsig#sha256";
+
+// Sheet-level attributes that live outside the cell grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetAttributes {
+    pub name: String,
+    pub style_name: Option<String>,
+    pub visible: bool,
+    pub tab_color: Option<String>,
+    pub protected: bool,
+    pub has_password: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetProtection {
+    pub key: Option<String>,
+    pub digest_algorithm: Option<String>,
+}
+
+impl SheetProtection {
+    // Calc stores base64(SHA-256(UTF-8 password)) for ODF 1.2+ sheet protection.
+    pub fn from_password(password: &str) -> Self {
+        use base64::Engine;
+        use sha2::{Digest, Sha256};
+        let digest = Sha256::digest(password.as_bytes());
+        Self {
+            key: Some(base64::engine::general_purpose::STANDARD.encode(digest)),
+            digest_algorithm: Some(SHA256_DIGEST.to_string()),
+        }
+    }
+}
+
+impl ContentXml {
+    pub fn sheet_attributes_raw(original_content: &str) -> Result<Vec<SheetAttributes>, AppError> {
+        let tables = Self::find_table_blocks(original_content)?;
+        let mut result = Vec::with_capacity(tables.len());
+        for table in &tables {
+            let start_tag = Self::table_start_tag(original_content, table)?;
+            let style_name = Self::extract_attr_value(start_tag, "table:style-name");
+            let props_tag = style_name
+                .as_deref()
+                .and_then(|name| Self::find_table_style_block(original_content, name))
+                .and_then(|(start, end)| {
+                    Self::find_table_properties_tag(&original_content[start..end])
+                        .map(|(s, e)| original_content[start + s..start + e].to_string())
+                });
+            let visible = props_tag
+                .as_deref()
+                .and_then(|tag| Self::extract_attr_value(tag, "table:display"))
+                .map(|v| v != "false")
+                .unwrap_or(true);
+            let tab_color = props_tag
+                .as_deref()
+                .and_then(|tag| Self::extract_attr_value(tag, "tableooo:tab-color"));
+
+            result.push(SheetAttributes {
+                name: table.name.clone(),
+                style_name,
+                visible,
+                tab_color,
+                protected: Self::extract_attr_value(start_tag, "table:protected").as_deref()
+                    == Some("true"),
+                has_password: Self::extract_attr_value(start_tag, "table:protection-key")
+                    .is_some_and(|k| !k.is_empty()),
+            });
+        }
+        Ok(result)
+    }
+
+    pub fn set_sheet_visibility_raw(
+        original_content: &str,
+        sheet_index: usize,
+        visible: bool,
+    ) -> Result<String, AppError> {
+        let value = if visible { "true" } else { "false" };
+        Self::update_table_properties(original_content, sheet_index, "table:display", Some(value))
+    }
+
+    pub fn set_sheet_tab_color_raw(
+        original_content: &str,
+        sheet_index: usize,
+        color: Option<&str>,
+    ) -> Result<String, AppError> {
+        let content = if color.is_some() {
+            Self::ensure_root_namespace(original_content, "tableooo", TABLEOOO_NS)?
+        } else {
+            original_content.to_string()
+        };
+        Self::update_table_properties(&content, sheet_index, "tableooo:tab-color", color)
+    }
+
+    // `None` removes protection; a protection without key protects with no password.
+    pub fn set_sheet_protection_raw(
+        original_content: &str,
+        sheet_index: usize,
+        protection: Option<&SheetProtection>,
+    ) -> Result<String, AppError> {
+        let tables = Self::find_table_blocks(original_content)?;
+        let table = tables
+            .get(sheet_index)
+            .ok_or_else(|| AppError::SheetNotFound(sheet_index.to_string()))?;
+        let start_tag = Self::table_start_tag(original_content, table)?;
+        let tag_end = table.start + start_tag.len();
+
+        let (protected, key, algorithm) = match protection {
+            Some(p) => (
+                Some("true"),
+                p.key.as_deref(),
+                p.key
+                    .as_ref()
+                    .map(|_| p.digest_algorithm.as_deref().unwrap_or(SHA256_DIGEST)),
+            ),
+            None => (None, None, None),
+        };
+        let mut new_tag = Self::set_tag_attr(start_tag, "table:protected", protected);
+        new_tag = Self::set_tag_attr(&new_tag, "table:protection-key", key);
+        new_tag = Self::set_tag_attr(&new_tag, "table:protection-key-digest-algorithm", algorithm);
+
+        // Calc adds loext:table-protection with selection flags; drop it on unprotect.
+        let mut body = original_content[tag_end..table.end].to_string();
+        if protection.is_none() {
+            if let Some(pos) = body.find("<loext:table-protection") {
+                let end = Self::find_tag_end(&body, pos)?;
+                body.replace_range(pos..end, "");
+            }
+        }
+
+        let mut out = String::with_capacity(original_content.len() + 128);
+        out.push_str(&original_content[..table.start]);
+        out.push_str(&new_tag);
+        out.push_str(&body);
+        out.push_str(&original_content[table.end..]);
+        Ok(out)
+    }
+
+    fn update_table_properties(
+        original_content: &str,
+        sheet_index: usize,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<String, AppError> {
+        let tables = Self::find_table_blocks(original_content)?;
+        let table = tables
+            .get(sheet_index)
+            .ok_or_else(|| AppError::SheetNotFound(sheet_index.to_string()))?;
+        let start_tag = Self::table_start_tag(original_content, table)?;
+        let style_name = Self::extract_attr_value(start_tag, "table:style-name");
+        let existing = style_name
+            .as_deref()
+            .and_then(|name| Self::find_table_style_block(original_content, name));
+
+        let shared = match style_name.as_deref() {
+            Some(name) => {
+                let mut users = 0usize;
+                for t in &tables {
+                    let tag = Self::table_start_tag(original_content, t)?;
+                    if Self::extract_attr_value(tag, "table:style-name").as_deref() == Some(name) {
+                        users += 1;
+                    }
+                }
+                users > 1
+            }
+            None => false,
+        };
+
+        if let (Some((style_start, style_end)), false) = (existing, shared) {
+            // The style belongs to this sheet only, so it can be edited in place.
+            let updated = Self::set_table_properties_attr(
+                &original_content[style_start..style_end],
+                key,
+                value,
+            )?;
+            let mut out = String::with_capacity(original_content.len() + 64);
+            out.push_str(&original_content[..style_start]);
+            out.push_str(&updated);
+            out.push_str(&original_content[style_end..]);
+            return Ok(out);
+        }
+
+        // Shared or missing style: give this sheet its own copy so siblings are unaffected.
+        let new_name = Self::next_table_style_name(original_content);
+        let base_block = match existing {
+            Some((start, end)) => {
+                Self::set_tag_attr_in_first_tag(&original_content[start..end], "style:name", &new_name)?
+            }
+            None => format!(
+                "<style:style style:name=\"{}\" style:family=\"table\"><style:table-properties table:display=\"true\"/></style:style>",
+                Self::escape_xml_attr(&new_name)
+            ),
+        };
+        let new_block = Self::set_table_properties_attr(&base_block, key, value)?;
+        let new_table_tag = Self::set_tag_attr(start_tag, "table:style-name", Some(&new_name));
+
+        let mut with_table = String::with_capacity(original_content.len() + 64);
+        with_table.push_str(&original_content[..table.start]);
+        with_table.push_str(&new_table_tag);
+        with_table.push_str(&original_content[table.start + start_tag.len()..]);
+
+        let with_styles = Self::ensure_root_namespace(&with_table, "style", STYLE_NS)?;
+        Self::append_automatic_style(&with_styles, &new_block)
+    }
+
+    fn set_table_properties_attr(
+        style_block: &str,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<String, AppError> {
+        if let Some((start, end)) = Self::find_table_properties_tag(style_block) {
+            let tag = Self::set_tag_attr(&style_block[start..end], key, value);
+            let mut out = String::with_capacity(style_block.len() + 32);
+            out.push_str(&style_block[..start]);
+            out.push_str(&tag);
+            out.push_str(&style_block[end..]);
+            return Ok(out);
+        }
+
+        let Some(value) = value else {
+            return Ok(style_block.to_string());
+        };
+        let props = format!(
+            "<style:table-properties {key}=\"{}\"/>",
+            Self::escape_xml_attr(value)
+        );
+        let tag_end = Self::find_tag_end(style_block, 0)?;
+        let start_tag = &style_block[..tag_end];
+        if start_tag.trim_end().ends_with("/>") {
+            let open = start_tag.trim_end().trim_end_matches("/>").trim_end();
+            return Ok(format!(
+                "{open}>{props}</style:style>{}",
+                &style_block[tag_end..]
+            ));
+        }
+        Ok(format!("{start_tag}{props}{}", &style_block[tag_end..]))
+    }
+
+    fn find_table_style_block(content: &str, style_name: &str) -> Option<(usize, usize)> {
+        let styles_start = content.find("<office:automatic-styles")?;
+        let styles_end = content[styles_start..]
+            .find("</office:automatic-styles>")
+            .map(|i| styles_start + i)?;
+        let mut pos = styles_start;
+        while let Some(rel) = content[pos..styles_end].find("<style:style") {
+            let start = pos + rel;
+            let tag_end = Self::find_tag_end(content, start).ok()?;
+            let tag = &content[start..tag_end];
+            let matches = Self::extract_attr_value(tag, "style:name").as_deref()
+                == Some(style_name)
+                && Self::extract_attr_value(tag, "style:family").as_deref() == Some("table");
+            let end = if tag.trim_end().ends_with("/>") {
+                tag_end
+            } else {
+                content[tag_end..styles_end]
+                    .find("</style:style>")
+                    .map(|i| tag_end + i + "</style:style>".len())?
+            };
+            if matches {
+                return Some((start, end));
+            }
+            pos = end;
+        }
+        None
+    }
+
+    fn find_table_properties_tag(style_block: &str) -> Option<(usize, usize)> {
+        let start = style_block.find("<style:table-properties")?;
+        let end = Self::find_tag_end(style_block, start).ok()?;
+        Some((start, end))
+    }
+
+    fn next_table_style_name(content: &str) -> String {
+        let mut max = 0usize;
+        let mut pos = 0usize;
+        while let Some(rel) = content[pos..].find("style:name=\"ta") {
+            let start = pos + rel + "style:name=\"ta".len();
+            let digits: String = content[start..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if let Ok(n) = digits.parse::<usize>() {
+                max = max.max(n);
+            }
+            pos = start;
+        }
+        format!("ta{}", max + 1)
+    }
+
+    fn append_automatic_style(content: &str, style_block: &str) -> Result<String, AppError> {
+        if let Some(close) = content.find("</office:automatic-styles>") {
+            let mut out = String::with_capacity(content.len() + style_block.len());
+            out.push_str(&content[..close]);
+            out.push_str(style_block);
+            out.push_str(&content[close..]);
+            return Ok(out);
+        }
+        if let Some(start) = content.find("<office:automatic-styles") {
+            // Self-closing <office:automatic-styles/>.
+            let end = Self::find_tag_end(content, start)?;
+            return Ok(format!(
+                "{}<office:automatic-styles>{style_block}</office:automatic-styles>{}",
+                &content[..start],
+                &content[end..]
+            ));
+        }
+        let body = content.find("<office:body").ok_or_else(|| {
+            AppError::InvalidOdsFormat("office:body not found in content.xml".to_string())
+        })?;
+        Ok(format!(
+            "{}<office:automatic-styles>{style_block}</office:automatic-styles>{}",
+            &content[..body],
+            &content[body..]
+        ))
+    }
+
+    pub(super) fn ensure_root_namespace(
+        content: &str,
+        prefix: &str,
+        uri: &str,
+    ) -> Result<String, AppError> {
+        let root_start = content.find("<office:document-content").ok_or_else(|| {
+            AppError::InvalidOdsFormat("office:document-content not found".to_string())
+        })?;
+        let root_end = Self::find_tag_end(content, root_start)?;
+        let root = &content[root_start..root_end];
+        let key = format!("xmlns:{prefix}");
+        if Self::extract_attr_value(root, &key).is_some() {
+            return Ok(content.to_string());
+        }
+        let new_root = Self::set_tag_attr(root, &key, Some(uri));
+        Ok(format!(
+            "{}{new_root}{}",
+            &content[..root_start],
+            &content[root_end..]
+        ))
+    }
+
+    fn set_tag_attr_in_first_tag(block: &str, key: &str, value: &str) -> Result<String, AppError> {
+        let tag_end = Self::find_tag_end(block, 0)?;
+        let tag = Self::set_tag_attr(&block[..tag_end], key, Some(value));
+        Ok(format!("{tag}{}", &block[tag_end..]))
+    }
+
+    // Replaces, inserts (`Some`) or removes (`None`) one attribute of a start tag.
+    pub(super) fn set_tag_attr(tag: &str, key: &str, value: Option<&str>) -> String {
+        let Some(value) = value else {
+            return Self::remove_tag_attr(tag, key);
+        };
+        let escaped = Self::escape_xml_attr(value);
+        if let Some((value_start, value_end)) = Self::find_attr_value_range(tag, key) {
+            return format!("{}{escaped}{}", &tag[..value_start], &tag[value_end..]);
+        }
+
+        let trimmed_len = tag.trim_end().len();
+        let close_len = if tag[..trimmed_len].ends_with("/>") {
+            2
+        } else {
+            1
+        };
+        let insert_at = tag[..trimmed_len - close_len].trim_end().len();
+        format!(
+            "{} {key}=\"{escaped}\"{}",
+            &tag[..insert_at],
+            &tag[insert_at..]
+        )
+    }
+
+    fn find_attr_value_range(tag: &str, key: &str) -> Option<(usize, usize)> {
+        let pattern = format!("{key}=\"");
+        let bytes = tag.as_bytes();
+        let mut from = 0usize;
+        while let Some(rel) = tag[from..].find(&pattern) {
+            let attr_start = from + rel;
+            if attr_start > 0 && bytes[attr_start - 1].is_ascii_whitespace() {
+                let value_start = attr_start + pattern.len();
+                let end_rel = tag[value_start..].find('"')?;
+                return Some((value_start, value_start + end_rel));
+            }
+            from = attr_start + pattern.len();
+        }
+        None
+    }
+
+    fn remove_tag_attr(tag: &str, key: &str) -> String {
+        match Self::find_attr_value_range(tag, key) {
+            Some((value_start, value_end)) => {
+                let attr_start = value_start - key.len() - 2;
+                let ws_start = tag[..attr_start].trim_end().len();
+                format!("{}{}", &tag[..ws_start], &tag[value_end + 1..])
+            }
+            None => tag.to_string(),
+        }
+    }
+
+    fn table_start_tag<'a>(content: &'a str, table: &TableBlock) -> Result<&'a str, AppError> {
+        let end = Self::find_tag_end(content, table.start)?;
+        Ok(&content[table.start..end])
+    }
+}
@@ -0,0 +1,239 @@
+use super::*;
+use crate::ods::cell_address::CellAddress;
+
+// Per-sheet summary computed in one streaming pass over content.xml.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SheetStats {
+    pub name: String,
+    // Zero-based inclusive bounds of non-empty cells: (first_row, first_col, last_row, last_col).
+    pub used_bounds: Option<(usize, usize, usize, usize)>,
+    pub non_empty_cells: usize,
+    pub has_formulas: bool,
+    pub has_merges: bool,
+    pub has_comments: bool,
+}
+
+struct CellScan {
+    col: usize,
+    repeat: usize,
+    has_value: bool,
+    has_text: bool,
+}
+
+impl SheetStats {
+    pub fn used_range_a1(&self) -> Option<String> {
+        self.used_bounds.map(|(r0, c0, r1, c1)| {
+            format!(
+                "{}:{}",
+                CellAddress { row: r0, col: c0 }.to_a1(),
+                CellAddress { row: r1, col: c1 }.to_a1()
+            )
+        })
+    }
+
+    fn include(&mut self, first_row: usize, rows: usize, first_col: usize, cols: usize) {
+        let last_row = first_row + rows - 1;
+        let last_col = first_col + cols - 1;
+        self.non_empty_cells += rows * cols;
+        self.used_bounds = Some(match self.used_bounds {
+            Some((r0, c0, r1, c1)) => (
+                r0.min(first_row),
+                c0.min(first_col),
+                r1.max(last_row),
+                c1.max(last_col),
+            ),
+            None => (first_row, first_col, last_row, last_col),
+        });
+    }
+}
+
+impl ContentXml {
+    pub fn sheet_stats_raw(original_content: &str) -> Result<Vec<SheetStats>, AppError> {
+        let mut reader = Reader::from_str(original_content);
+        reader.config_mut().trim_text(false);
+
+        let mut result: Vec<SheetStats> = Vec::new();
+        let mut current: Option<SheetStats> = None;
+        let mut table_depth = 0usize;
+        let mut current_row = 0usize;
+        let mut row_repeat = 1usize;
+        let mut current_col = 0usize;
+        // Non-empty cells of the open row as (first_col, repeat); applied once row repeat is known.
+        let mut row_cells: Vec<(usize, usize)> = Vec::new();
+        let mut cell: Option<CellScan> = None;
+        let mut annotation_depth = 0usize;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        current = Some(SheetStats {
+                            name: Self::table_name_attr(&e, reader.decoder()),
+                            ..Default::default()
+                        });
+                        current_row = 0;
+                    }
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    result.push(SheetStats {
+                        name: Self::table_name_attr(&e, reader.decoder()),
+                        ..Default::default()
+                    });
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 {
+                        if let Some(stats) = current.take() {
+                            result.push(stats);
+                        }
+                    }
+                }
+                Event::Start(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    row_repeat = Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                    current_col = 0;
+                    row_cells.clear();
+                }
+                Event::Empty(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    current_row += Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                }
+                Event::End(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    if let Some(stats) = current.as_mut() {
+                        for (col, repeat) in row_cells.drain(..) {
+                            stats.include(current_row, row_repeat, col, repeat);
+                        }
+                    }
+                    current_row += row_repeat;
+                    row_repeat = 1;
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if table_depth == 1
+                        && annotation_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                {
+                    current_col +=
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                }
+                Event::Empty(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") =>
+                {
+                    let repeat =
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    let has_value = Self::scan_cell_attrs(&e, current.as_mut());
+                    if has_value {
+                        row_cells.push((current_col, repeat));
+                    }
+                    current_col += repeat;
+                }
+                Event::Start(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") =>
+                {
+                    let repeat =
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    let has_value = Self::scan_cell_attrs(&e, current.as_mut());
+                    cell = Some(CellScan {
+                        col: current_col,
+                        repeat,
+                        has_value,
+                        has_text: false,
+                    });
+                    current_col += repeat;
+                }
+                Event::End(e)
+                    if table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") =>
+                {
+                    if let Some(scan) = cell.take() {
+                        if scan.has_value || scan.has_text {
+                            row_cells.push((scan.col, scan.repeat));
+                        }
+                    }
+                }
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    annotation_depth += 1;
+                    if let Some(stats) = current.as_mut() {
+                        stats.has_comments = true;
+                    }
+                }
+                Event::Empty(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    if let Some(stats) = current.as_mut() {
+                        stats.has_comments = true;
+                    }
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    annotation_depth = annotation_depth.saturating_sub(1);
+                }
+                Event::Text(t) if annotation_depth == 0 => {
+                    if let Some(scan) = cell.as_mut() {
+                        if !t.as_ref().iter().all(|b| b.is_ascii_whitespace()) {
+                            scan.has_text = true;
+                        }
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+
+    // Flags formulas and merges on the sheet; returns whether the cell carries a typed value.
+    fn scan_cell_attrs(e: &BytesStart<'_>, stats: Option<&mut SheetStats>) -> bool {
+        let mut has_value = false;
+        let mut has_formula = false;
+        let mut has_merge = false;
+        for attr in e.attributes().flatten() {
+            let key = attr.key.as_ref();
+            if Self::is_local_name_bytes(key, b"value-type") {
+                // Strings only count when they carry text, which the caller checks separately.
+                has_value = attr.value.as_ref() != b"string";
+            } else if Self::is_local_name_bytes(key, b"formula") {
+                has_formula = true;
+            } else if Self::is_local_name_bytes(key, b"number-columns-spanned")
+                || Self::is_local_name_bytes(key, b"number-rows-spanned")
+            {
+                has_merge |= std::str::from_utf8(attr.value.as_ref())
+                    .ok()
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .is_some_and(|n| n > 1);
+            }
+        }
+        if let Some(stats) = stats {
+            stats.has_formulas |= has_formula;
+            stats.has_merges |= has_merge;
+        }
+        has_value || has_formula
+    }
+
+    pub(super) fn table_name_attr(
+        e: &BytesStart<'_>,
+        decoder: quick_xml::encoding::Decoder,
+    ) -> String {
+        for attr in e.attributes().flatten() {
+            if Self::is_local_name_bytes(attr.key.as_ref(), b"name") {
+                if let Ok(v) = attr.decode_and_unescape_value(decoder) {
+                    return v.into_owned();
+                }
+            }
+        }
+        "Sheet1".to_string()
+    }
+}
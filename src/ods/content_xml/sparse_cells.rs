@@ -0,0 +1,461 @@
+use super::*;
+use std::ops::Range;
+
+// Non-empty cell found while streaming a sheet; repeated runs are expanded lazily per page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseCell {
+    pub row: usize,
+    pub col: usize,
+    // office:value-type as stored (float, percentage, currency, date, time, boolean, string).
+    pub value_type: String,
+    pub value: CellValue,
+    pub formula: Option<String>,
+    // Text as displayed by Calc (paragraphs joined with newlines); may be empty for values.
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SparseCellPage {
+    pub cells: Vec<SparseCell>,
+    pub total: usize,
+}
+
+// Non-empty cells of a rectangular window of a sheet, with the size of the whole sheet.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SheetWindow {
+    pub cells: Vec<SparseCell>,
+    // Rows and columns the table spans, empty repeated ones included.
+    pub grid_rows: usize,
+    pub grid_cols: usize,
+    // Rows and columns up to the last cell holding a value.
+    pub used_rows: usize,
+    pub used_cols: usize,
+}
+
+// Cell run of the open row: one XML cell plus its number-columns-repeated.
+pub(super) struct CellRun {
+    pub(super) col: usize,
+    pub(super) repeat: usize,
+    pub(super) value_type: Option<String>,
+    pub(super) value: Option<String>,
+    pub(super) formula: Option<String>,
+    pub(super) text: String,
+    pub(super) paragraphs: usize,
+}
+
+// Non-empty cell run kept for the open row and replicated for each repeated row.
+struct CellTemplate {
+    col: usize,
+    repeat: usize,
+    value_type: String,
+    value: CellValue,
+    formula: Option<String>,
+    text: String,
+}
+
+impl CellRun {
+    // Declared type and typed value; None when the run holds nothing worth reporting.
+    pub(super) fn typed_value(&self) -> Option<(String, CellValue)> {
+        let has_text = self.paragraphs > 0 && !self.text.is_empty();
+        if self.value_type.is_none() && !has_text && self.formula.is_none() {
+            return None;
+        }
+        let value_type = self
+            .value_type
+            .clone()
+            .unwrap_or_else(|| "string".to_string());
+        let value = match value_type.as_str() {
+            "float" | "percentage" | "currency" => self
+                .value
+                .as_deref()
+                .and_then(|v| v.parse::<f64>().ok())
+                .map(CellValue::Number)
+                .unwrap_or(CellValue::Empty),
+            "boolean" => self
+                .value
+                .as_deref()
+                .map(|v| CellValue::Boolean(v.eq_ignore_ascii_case("true")))
+                .unwrap_or(CellValue::Empty),
+            "date" | "time" => self
+                .value
+                .clone()
+                .map(CellValue::String)
+                .unwrap_or(CellValue::Empty),
+            _ => {
+                if has_text {
+                    CellValue::String(self.text.clone())
+                } else {
+                    self.value
+                        .clone()
+                        .map(CellValue::String)
+                        .unwrap_or(CellValue::Empty)
+                }
+            }
+        };
+        // String cells written with no text (e.g. "" via set_range_values) count as empty.
+        if value == CellValue::Empty && self.formula.is_none() {
+            return None;
+        }
+        Some((value_type, value))
+    }
+
+    fn into_template(self) -> Option<CellTemplate> {
+        let (value_type, value) = self.typed_value()?;
+        Some(CellTemplate {
+            col: self.col,
+            repeat: self.repeat,
+            value_type,
+            value,
+            formula: self.formula,
+            text: self.text,
+        })
+    }
+}
+
+impl ContentXml {
+    pub fn sheet_cells_raw(
+        original_content: &str,
+        sheet_index: usize,
+        skip: usize,
+        limit: usize,
+    ) -> Result<SparseCellPage, AppError> {
+        // Streams one sheet and returns non-empty cells in row-major order, never padding the
+        // grid: repeated rows/cells are counted arithmetically and only the requested page is built.
+        let mut page = SparseCellPage::default();
+        let window_end = skip.saturating_add(limit);
+        Self::scan_row_groups(
+            original_content,
+            sheet_index,
+            &mut |first_row, repeat, cells| {
+                Self::emit_row_group(&mut page, cells, first_row, repeat, skip, window_end);
+                true
+            },
+        )?;
+        Ok(page)
+    }
+
+    pub fn sheet_window_raw(
+        original_content: &str,
+        sheet_index: usize,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<SheetWindow, AppError> {
+        // Builds only the cells inside rows x cols; the sizes of the rest are counted as it streams.
+        let mut window = SheetWindow::default();
+        let (grid_rows, grid_cols) = Self::scan_row_groups(
+            original_content,
+            sheet_index,
+            &mut |first_row, repeat, cells| {
+                if let Some(last) = cells
+                    .iter()
+                    .filter(|c| c.value != CellValue::Empty)
+                    .map(|c| c.col + c.repeat)
+                    .max()
+                {
+                    window.used_rows = first_row + repeat;
+                    window.used_cols = window.used_cols.max(last);
+                }
+                for row in rows.start.max(first_row)..rows.end.min(first_row + repeat) {
+                    for template in cells {
+                        let from = template.col.max(cols.start);
+                        let to = (template.col + template.repeat).min(cols.end);
+                        window
+                            .cells
+                            .extend((from..to).map(|col| template.to_cell(row, col)));
+                    }
+                }
+                true
+            },
+        )?;
+        window.grid_rows = grid_rows;
+        window.grid_cols = grid_cols;
+        Ok(window)
+    }
+
+    // Calls `visit(row, cells)` for each row with values in `cols`, top to bottom, until it
+    // returns false; later rows are not read.
+    pub fn visit_sheet_rows_raw(
+        original_content: &str,
+        sheet_index: usize,
+        cols: Range<usize>,
+        visit: &mut dyn FnMut(usize, Vec<SparseCell>) -> bool,
+    ) -> Result<(), AppError> {
+        Self::scan_row_groups(
+            original_content,
+            sheet_index,
+            &mut |first_row, repeat, cells| {
+                let in_cols: Vec<&CellTemplate> = cells
+                    .iter()
+                    .filter(|c| c.value != CellValue::Empty)
+                    .filter(|c| c.col < cols.end && c.col + c.repeat > cols.start)
+                    .collect();
+                if in_cols.is_empty() {
+                    return true;
+                }
+                (first_row..first_row + repeat).all(|row| {
+                    let row_cells = in_cols
+                        .iter()
+                        .flat_map(|template| {
+                            let from = template.col.max(cols.start);
+                            let to = (template.col + template.repeat).min(cols.end);
+                            (from..to).map(move |col| template.to_cell(row, col))
+                        })
+                        .collect();
+                    visit(row, row_cells)
+                })
+            },
+        )?;
+        Ok(())
+    }
+
+    // Streams the rows of one sheet, calling `row_group(first_row, repeat, cells)` for each row,
+    // or run of repeated rows, holding non-empty cells; scanning stops when it returns false.
+    // Returns the rows and columns the table spans so far, empty repeated ones included.
+    fn scan_row_groups(
+        original_content: &str,
+        sheet_index: usize,
+        row_group: &mut dyn FnMut(usize, usize, &[CellTemplate]) -> bool,
+    ) -> Result<(usize, usize), AppError> {
+        let mut reader = Reader::from_str(original_content);
+        reader.config_mut().trim_text(false);
+
+        let mut grid_cols = 0usize;
+        let mut table_idx = 0usize;
+        let mut table_depth = 0usize;
+        let mut in_target = false;
+        let mut found = false;
+        let mut current_row = 0usize;
+        let mut row_repeat = 1usize;
+        let mut current_col = 0usize;
+        let mut row_cells: Vec<CellTemplate> = Vec::new();
+        let mut cell: Option<CellRun> = None;
+        let mut in_text_p = false;
+        let mut annotation_depth = 0usize;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        in_target = table_idx == sheet_index;
+                        found |= in_target;
+                        table_idx += 1;
+                        current_row = 0;
+                    }
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    found |= table_idx == sheet_index;
+                    table_idx += 1;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 && in_target {
+                        break;
+                    }
+                }
+                _ if !in_target || table_depth != 1 => {
+                    if matches!(event, Event::Eof) {
+                        break;
+                    }
+                }
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
+                    row_repeat = Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                    current_col = 0;
+                    row_cells.clear();
+                }
+                Event::Empty(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
+                    current_row += Self::attr_repeat(&e, b"number-rows-repeated", reader.decoder());
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-row") => {
+                    grid_cols = grid_cols.max(current_col);
+                    let keep_going =
+                        row_cells.is_empty() || row_group(current_row, row_repeat, &row_cells);
+                    current_row += row_repeat;
+                    row_repeat = 1;
+                    if !keep_going {
+                        break;
+                    }
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if annotation_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                {
+                    current_col +=
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                }
+                Event::Empty(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") => {
+                    let run = Self::cell_run_from_attrs(&e, current_col, reader.decoder());
+                    current_col += run.repeat;
+                    row_cells.extend(run.into_template());
+                }
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") => {
+                    let run = Self::cell_run_from_attrs(&e, current_col, reader.decoder());
+                    current_col += run.repeat;
+                    cell = Some(run);
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") => {
+                    if let Some(run) = cell.take() {
+                        row_cells.extend(run.into_template());
+                    }
+                    in_text_p = false;
+                }
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    annotation_depth += 1;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    annotation_depth = annotation_depth.saturating_sub(1);
+                }
+                Event::Start(e)
+                    if annotation_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"p") =>
+                {
+                    if let Some(run) = cell.as_mut() {
+                        Self::start_paragraph(&mut run.text, &mut run.paragraphs);
+                        in_text_p = true;
+                    }
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"p") => {
+                    in_text_p = false;
+                }
+                Event::Empty(e) if in_text_p && annotation_depth == 0 => {
+                    if let Some(run) = cell.as_mut() {
+                        Self::push_inline_text(&mut run.text, &e);
+                    }
+                }
+                Event::Text(t) if in_text_p && annotation_depth == 0 => {
+                    if let Some(run) = cell.as_mut() {
+                        let text = t
+                            .unescape()
+                            .map_err(|x| AppError::XmlParseError(x.to_string()))?;
+                        run.text.push_str(&text);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if !found {
+            return Err(AppError::SheetNotFound(format!("index {sheet_index}")));
+        }
+        Ok((current_row, grid_cols))
+    }
+
+    // Opens a text:p of a cell or comment; paragraphs are joined with a newline, as Calc
+    // shows them.
+    pub(super) fn start_paragraph(text: &mut String, paragraphs: &mut usize) {
+        if *paragraphs > 0 {
+            text.push('\n');
+        }
+        *paragraphs += 1;
+    }
+
+    // Appends what an empty element inside a paragraph stands for: text:s, text:tab or
+    // text:line-break. Other elements add nothing.
+    pub(super) fn push_inline_text(text: &mut String, e: &BytesStart<'_>) {
+        let name = e.name();
+        if Self::is_local_name_bytes(name.as_ref(), b"s") {
+            text.push_str(&" ".repeat(Self::attr_repeat_owned(e, b"c")));
+        } else if Self::is_local_name_bytes(name.as_ref(), b"tab") {
+            text.push('\t');
+        } else if Self::is_local_name_bytes(name.as_ref(), b"line-break") {
+            text.push('\n');
+        }
+    }
+
+    pub(super) fn cell_run_from_attrs(
+        e: &BytesStart<'_>,
+        col: usize,
+        decoder: quick_xml::encoding::Decoder,
+    ) -> CellRun {
+        let mut run = CellRun {
+            col,
+            repeat: Self::attr_repeat(e, b"number-columns-repeated", decoder),
+            value_type: None,
+            value: None,
+            formula: None,
+            text: String::new(),
+            paragraphs: 0,
+        };
+        let mut string_value = None;
+        for attr in e.attributes().flatten() {
+            let key = attr.key.as_ref();
+            let Ok(decoded) = attr.decode_and_unescape_value(decoder) else {
+                continue;
+            };
+            if Self::is_local_name_bytes(key, b"value-type") {
+                run.value_type = Some(decoded.into_owned());
+            } else if Self::is_local_name_bytes(key, b"formula") {
+                run.formula = Some(decoded.into_owned());
+            } else if Self::is_local_name_bytes(key, b"value")
+                || Self::is_local_name_bytes(key, b"boolean-value")
+                || Self::is_local_name_bytes(key, b"date-value")
+                || Self::is_local_name_bytes(key, b"time-value")
+            {
+                run.value = Some(decoded.into_owned());
+            } else if Self::is_local_name_bytes(key, b"string-value") {
+                string_value = Some(decoded.into_owned());
+            }
+        }
+        if run.value.is_none() {
+            run.value = string_value;
+        }
+        run
+    }
+
+    fn emit_row_group(
+        page: &mut SparseCellPage,
+        row_cells: &[CellTemplate],
+        first_row: usize,
+        row_repeat: usize,
+        skip: usize,
+        window_end: usize,
+    ) {
+        let per_row: usize = row_cells.iter().map(|c| c.repeat).sum();
+        if per_row == 0 {
+            return;
+        }
+        let group_start = page.total;
+        page.total += per_row * row_repeat;
+        if page.total <= skip || group_start >= window_end {
+            return;
+        }
+
+        // Jump straight to the first repeated row that overlaps the requested window.
+        let first_copy = skip.saturating_sub(group_start) / per_row;
+        for copy in first_copy..row_repeat {
+            let mut index = group_start + copy * per_row;
+            if index >= window_end {
+                break;
+            }
+            for template in row_cells {
+                let from = skip.saturating_sub(index).min(template.repeat);
+                let to = window_end.saturating_sub(index).min(template.repeat);
+                for offset in from..to {
+                    page.cells
+                        .push(template.to_cell(first_row + copy, template.col + offset));
+                }
+                index += template.repeat;
+            }
+        }
+    }
+}
+
+impl CellTemplate {
+    fn to_cell(&self, row: usize, col: usize) -> SparseCell {
+        SparseCell {
+            row,
+            col,
+            value_type: self.value_type.clone(),
+            value: self.value.clone(),
+            formula: self.formula.clone(),
+            text: self.text.clone(),
+        }
+    }
+}
@@ -11,6 +11,23 @@ impl ContentXml {
         source_name: Option<&str>,
         source_index: Option<usize>,
         new_sheet_name: &str,
+    ) -> Result<String, AppError> {
+        Self::duplicate_sheet_at_preserving_styles_raw(
+            original_content,
+            source_name,
+            source_index,
+            new_sheet_name,
+            None,
+        )
+    }
+
+    // `insert_index` counts existing sheets; `None` places the copy right after source.
+    pub fn duplicate_sheet_at_preserving_styles_raw(
+        original_content: &str,
+        source_name: Option<&str>,
+        source_index: Option<usize>,
+        new_sheet_name: &str,
+        insert_index: Option<usize>,
     ) -> Result<String, AppError> {
         let tables = Self::find_table_blocks(original_content)?;
         if tables.is_empty() {
@@ -32,11 +49,15 @@ impl ContentXml {
         let source = &tables[table_pos];
         let source_xml = &original_content[source.start..source.end];
         let cloned = Self::rename_first_table_name(source_xml, new_sheet_name)?;
+        let insert_at = match insert_index {
+            Some(index) => Self::table_insert_offset(&tables, index)?,
+            None => source.end,
+        };
 
         let mut out = String::with_capacity(original_content.len() + cloned.len() + 8);
-        out.push_str(&original_content[..source.end]);
+        out.push_str(&original_content[..insert_at]);
         out.push_str(&cloned);
-        out.push_str(&original_content[source.end..]);
+        out.push_str(&original_content[insert_at..]);
         Ok(out)
     }
 
@@ -108,6 +129,25 @@ impl ContentXml {
         original_content: &str,
         sheet_name: &str,
         position: &str,
+    ) -> Result<String, AppError> {
+        let tables = Self::find_table_blocks(original_content)?;
+        let insert_index = if position.eq_ignore_ascii_case("start") {
+            0
+        } else if position.eq_ignore_ascii_case("end") {
+            tables.len()
+        } else {
+            return Err(AppError::InvalidInput(format!(
+                "unknown sheet position: {position} (expected start or end)"
+            )));
+        };
+        Self::add_sheet_at_preserving_styles_raw(original_content, sheet_name, insert_index)
+    }
+
+    // `insert_index` is the index the new sheet will have (0..=sheet count).
+    pub fn add_sheet_at_preserving_styles_raw(
+        original_content: &str,
+        sheet_name: &str,
+        insert_index: usize,
     ) -> Result<String, AppError> {
         let tables = Self::find_table_blocks(original_content)?;
         if tables.is_empty() {
@@ -121,12 +161,7 @@ impl ContentXml {
 
         let escaped_name = Self::escape_xml_attr(sheet_name);
         let new_table = format!("<table:table table:name=\"{escaped_name}\"/>");
-
-        let insert_at = if position.eq_ignore_ascii_case("start") {
-            tables[0].start
-        } else {
-            tables[tables.len() - 1].end
-        };
+        let insert_at = Self::table_insert_offset(&tables, insert_index)?;
 
         let mut out = String::with_capacity(original_content.len() + new_table.len() + 8);
         out.push_str(&original_content[..insert_at]);
@@ -135,6 +170,52 @@ impl ContentXml {
         Ok(out)
     }
 
+    // Moves one table block so that it ends up at `target_index` in the final order.
+    pub fn move_sheet_preserving_styles_raw(
+        original_content: &str,
+        source_name: Option<&str>,
+        source_index: Option<usize>,
+        target_index: usize,
+    ) -> Result<String, AppError> {
+        let tables = Self::find_table_blocks(original_content)?;
+        if tables.is_empty() {
+            return Err(AppError::InvalidOdsFormat(
+                "no table:table blocks found".to_string(),
+            ));
+        }
+        if target_index >= tables.len() {
+            return Err(AppError::InvalidInput(format!(
+                "target index {target_index} out of range (sheet count {})",
+                tables.len()
+            )));
+        }
+
+        let table_pos = Self::resolve_table_index(
+            &tables,
+            source_name,
+            source_index,
+            "missing source sheet selector",
+        )?;
+        if table_pos == target_index {
+            return Ok(original_content.to_string());
+        }
+
+        let source = &tables[table_pos];
+        let block = &original_content[source.start..source.end];
+        let mut without = String::with_capacity(original_content.len());
+        without.push_str(&original_content[..source.start]);
+        without.push_str(&original_content[source.end..]);
+
+        let remaining = Self::find_table_blocks(&without)?;
+        let insert_at = Self::table_insert_offset(&remaining, target_index)?;
+
+        let mut out = String::with_capacity(original_content.len());
+        out.push_str(&without[..insert_at]);
+        out.push_str(block);
+        out.push_str(&without[insert_at..]);
+        Ok(out)
+    }
+
     pub fn rename_first_sheet_name_raw(
         original_content: &str,
         new_sheet_name: &str,
@@ -153,7 +234,7 @@ impl ContentXml {
         Ok(out)
     }
 
-    fn find_table_blocks(content: &str) -> Result<Vec<TableBlock>, AppError> {
+    pub(super) fn find_table_blocks(content: &str) -> Result<Vec<TableBlock>, AppError> {
         let mut result = Vec::new();
         let mut pos = 0usize;
         while let Some(start) = Self::find_next_table_open(content, pos) {
@@ -175,7 +256,20 @@ impl ContentXml {
         Ok(result)
     }
 
-    fn resolve_table_index(
+    fn table_insert_offset(tables: &[TableBlock], insert_index: usize) -> Result<usize, AppError> {
+        if insert_index > tables.len() {
+            return Err(AppError::InvalidInput(format!(
+                "sheet position {insert_index} out of range (sheet count {})",
+                tables.len()
+            )));
+        }
+        Ok(match tables.get(insert_index) {
+            Some(table) => table.start,
+            None => tables[tables.len() - 1].end,
+        })
+    }
+
+    pub(super) fn resolve_table_index(
         tables: &[TableBlock],
         source_name: Option<&str>,
         source_index: Option<usize>,
@@ -196,7 +290,7 @@ impl ContentXml {
         Err(AppError::InvalidInput(missing_selector_message.to_string()))
     }
 
-    fn find_tag_end(content: &str, tag_start: usize) -> Result<usize, AppError> {
+    pub(super) fn find_tag_end(content: &str, tag_start: usize) -> Result<usize, AppError> {
         let bytes = content.as_bytes();
         let mut i = tag_start;
         let mut in_quote = false;
@@ -254,7 +348,7 @@ impl ContentXml {
             .or_else(|| Self::extract_attr_value(start_tag, "name"))
     }
 
-    fn extract_attr_value(tag: &str, key: &str) -> Option<String> {
+    pub(super) fn extract_attr_value(tag: &str, key: &str) -> Option<String> {
         let pattern = format!("{key}=\"");
         let bytes = tag.as_bytes();
         let mut from = 0usize;
@@ -319,7 +413,7 @@ impl ContentXml {
         Ok(out)
     }
 
-    fn escape_xml_attr(value: &str) -> String {
+    pub(super) fn escape_xml_attr(value: &str) -> String {
         value
             .replace('&', "&amp;")
             .replace('"', "&quot;")
@@ -347,8 +441,8 @@ impl ContentXml {
     }
 }
 
-struct TableBlock {
-    start: usize,
-    end: usize,
-    name: String,
+pub(super) struct TableBlock {
+    pub(super) start: usize,
+    pub(super) end: usize,
+    pub(super) name: String,
 }
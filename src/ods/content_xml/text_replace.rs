@@ -0,0 +1,563 @@
+use super::range_edit::RowItem;
+use super::*;
+
+// Where replace_in_cells_raw may touch cells. Rows and columns are zero-based and inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplaceScope {
+    pub sheet_index: Option<usize>,
+    pub rows: (usize, usize),
+    pub cols: (usize, usize),
+    pub values: bool,
+    pub formulas: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplacedField {
+    Value,
+    Formula,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextReplacement {
+    pub sheet_index: usize,
+    pub sheet: String,
+    pub row: usize,
+    pub col: usize,
+    pub field: ReplacedField,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReplaceOutcome {
+    pub content: String,
+    // First `limit` replacements in document order; `total` counts all of them.
+    pub replacements: Vec<TextReplacement>,
+    pub total: usize,
+}
+
+// New text and/or formula for one cell run, applied to its columns inside the scope.
+struct CellEdit {
+    text: Option<(String, String)>,
+    formula: Option<(String, String)>,
+}
+
+struct ReplaceState<'a, F> {
+    scope: &'a ReplaceScope,
+    replace: F,
+    limit: usize,
+    sheet_index: usize,
+    sheet: String,
+    outcome: ReplaceOutcome,
+}
+
+impl ContentXml {
+    pub fn replace_in_cells_raw(
+        original_content: &str,
+        scope: &ReplaceScope,
+        limit: usize,
+        replace: impl FnMut(&str) -> Option<String>,
+    ) -> Result<ReplaceOutcome, AppError> {
+        // String cells get their paragraphs rewritten and formulas their expression; every
+        // other attribute (styles, spans, validation) and the cell comment are kept. Repeated
+        // rows and cells are split only where the scope boundary cuts through a changed run.
+        let mut reader = Reader::from_str(original_content);
+        reader.config_mut().trim_text(false);
+        let mut writer = Writer::new(Cursor::new(Vec::new()));
+        let mut state = ReplaceState {
+            scope,
+            replace,
+            limit,
+            sheet_index: 0,
+            sheet: String::new(),
+            outcome: ReplaceOutcome::default(),
+        };
+
+        let mut table_count = 0usize;
+        let mut table_depth = 0usize;
+        let mut in_scope = false;
+        let mut found = scope.sheet_index.is_none();
+        let mut current_row = 0usize;
+        let mut capture: Option<(BytesStart<'static>, Vec<Event<'static>>, usize)> = None;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+
+            if let Some((_, inner, depth)) = capture.as_mut() {
+                match &event {
+                    Event::Start(_) => *depth += 1,
+                    Event::End(_) if *depth == 0 => {
+                        let (row_start, inner, _) =
+                            capture.take().unwrap_or_else(|| unreachable!());
+                        let repeat = Self::attr_repeat_owned(&row_start, b"number-rows-repeated");
+                        Self::replace_in_row_run(
+                            &mut writer,
+                            &mut state,
+                            &row_start,
+                            &inner,
+                            current_row,
+                            repeat,
+                        )?;
+                        current_row += repeat;
+                        continue;
+                    }
+                    Event::End(_) => *depth -= 1,
+                    Event::Eof => {
+                        return Err(AppError::XmlParseError(
+                            "unterminated table-row in content.xml".to_string(),
+                        ))
+                    }
+                    _ => {}
+                }
+                inner.push(event.into_owned());
+                continue;
+            }
+
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        in_scope = scope.sheet_index.is_none_or(|i| i == table_count);
+                        found |= in_scope;
+                        state.sheet_index = table_count;
+                        state.sheet = Self::table_name_attr(&e, reader.decoder());
+                        table_count += 1;
+                        current_row = 0;
+                    }
+                    writer
+                        .write_event(Event::Start(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Empty(e)
+                    if table_depth == 0
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table") =>
+                {
+                    found |= scope.sheet_index == Some(table_count);
+                    table_count += 1;
+                    writer
+                        .write_event(Event::Empty(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 {
+                        in_scope = false;
+                    }
+                    writer
+                        .write_event(Event::End(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Start(e)
+                    if in_scope
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    capture = Some((e.into_owned(), Vec::new(), 0));
+                }
+                Event::Empty(e)
+                    if in_scope
+                        && table_depth == 1
+                        && Self::is_local_name_bytes(e.name().as_ref(), b"table-row") =>
+                {
+                    current_row += Self::attr_repeat_owned(&e, b"number-rows-repeated");
+                    writer
+                        .write_event(Event::Empty(e.into_owned()))
+                        .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+                }
+                Event::Eof => break,
+                other => writer
+                    .write_event(other.into_owned())
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?,
+            }
+        }
+
+        if !found {
+            let index = scope.sheet_index.unwrap_or_default();
+            return Err(AppError::SheetNotFound(format!("index {index}")));
+        }
+        let bytes = writer.into_inner().into_inner();
+        state.outcome.content =
+            String::from_utf8(bytes).map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        Ok(state.outcome)
+    }
+
+    fn replace_in_row_run<F: FnMut(&str) -> Option<String>>(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        state: &mut ReplaceState<'_, F>,
+        row_start: &BytesStart<'static>,
+        inner: &[Event<'static>],
+        first_row: usize,
+        repeat: usize,
+    ) -> Result<(), AppError> {
+        let (scope_first, scope_last) = state.scope.rows;
+        let from = first_row.max(scope_first);
+        let to = (first_row + repeat - 1).min(scope_last);
+        let items = Self::split_row_items(inner);
+        // Every row of a repeated run is identical, so edits are planned once for the run.
+        let edits = if from <= to {
+            Self::plan_row_edits(state, &items)
+        } else {
+            Vec::new()
+        };
+        if edits.iter().all(Option::is_none) {
+            return Self::write_row_copy(writer, row_start, inner, Some(repeat));
+        }
+
+        for row in from..=to {
+            for (item, edit) in items.iter().zip(&edits) {
+                let (RowItem::Cell { .. }, Some((cols, edit))) = (item, edit) else {
+                    continue;
+                };
+                for col in cols.0..=cols.1 {
+                    Self::record_replacements(state, row, col, edit);
+                }
+            }
+        }
+
+        if from > first_row {
+            Self::write_row_copy(writer, row_start, inner, Some(from - first_row))?;
+        }
+        writer
+            .write_event(Event::Start(Self::clone_row_with_repeat(
+                row_start,
+                Some(to - from + 1),
+            )))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        let mut col = 0usize;
+        for (item, edit) in items.iter().zip(&edits) {
+            match item {
+                RowItem::Other(event) => writer
+                    .write_event(event.clone())
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?,
+                RowItem::Cell {
+                    start,
+                    body,
+                    covered,
+                    repeat,
+                } => {
+                    let run_end = col + repeat;
+                    match edit {
+                        Some(((first, last), edit)) => {
+                            if *first > col {
+                                Self::write_cell_copy(
+                                    writer,
+                                    start,
+                                    body.as_deref(),
+                                    first - col,
+                                    false,
+                                )?;
+                            }
+                            Self::write_edited_cell(
+                                writer,
+                                start,
+                                body.as_deref(),
+                                last - first + 1,
+                                edit,
+                            )?;
+                            if run_end > last + 1 {
+                                Self::write_cell_copy(
+                                    writer,
+                                    start,
+                                    body.as_deref(),
+                                    run_end - last - 1,
+                                    false,
+                                )?;
+                            }
+                        }
+                        None => Self::write_cell_copy(
+                            writer,
+                            start,
+                            body.as_deref(),
+                            *repeat,
+                            *covered,
+                        )?,
+                    }
+                    col = run_end;
+                }
+            }
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("table:table-row")))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        if first_row + repeat > to + 1 {
+            Self::write_row_copy(writer, row_start, inner, Some(first_row + repeat - to - 1))?;
+        }
+        Ok(())
+    }
+
+    // Per row item: the columns (inclusive) to rewrite and the new text/formula, if any.
+    fn plan_row_edits<F: FnMut(&str) -> Option<String>>(
+        state: &mut ReplaceState<'_, F>,
+        items: &[RowItem],
+    ) -> Vec<Option<((usize, usize), CellEdit)>> {
+        let (scope_first, scope_last) = state.scope.cols;
+        let mut col = 0usize;
+        let mut edits = Vec::with_capacity(items.len());
+        for item in items {
+            let RowItem::Cell {
+                start,
+                body,
+                covered,
+                repeat,
+            } = item
+            else {
+                edits.push(None);
+                continue;
+            };
+            let from = col.max(scope_first);
+            let to = (col + repeat - 1).min(scope_last);
+            col += repeat;
+            if *covered || from > to {
+                edits.push(None);
+                continue;
+            }
+
+            let formula = Self::attr_value(start, b"formula");
+            let formula_edit =
+                formula
+                    .as_ref()
+                    .filter(|_| state.scope.formulas)
+                    .and_then(|formula| {
+                        let (namespace, expression) = Self::split_formula_namespace(formula);
+                        (state.replace)(expression)
+                            .filter(|new| new != expression)
+                            .map(|new| (formula.clone(), format!("{namespace}{new}")))
+                    });
+            // Formula results are recomputed by Calc, so only literal strings are rewritten.
+            let is_string = Self::attr_value(start, b"value-type").is_none_or(|t| t == "string");
+            let text_edit = if state.scope.values && is_string && formula.is_none() {
+                body.as_deref()
+                    .and_then(Self::cell_paragraph_text)
+                    .and_then(|text| {
+                        (state.replace)(&text)
+                            .filter(|new| *new != text)
+                            .map(|new| (text, new))
+                    })
+            } else {
+                None
+            };
+            if text_edit.is_none() && formula_edit.is_none() {
+                edits.push(None);
+                continue;
+            }
+            edits.push(Some((
+                (from, to),
+                CellEdit {
+                    text: text_edit,
+                    formula: formula_edit,
+                },
+            )));
+        }
+        edits
+    }
+
+    fn record_replacements<F>(
+        state: &mut ReplaceState<'_, F>,
+        row: usize,
+        col: usize,
+        edit: &CellEdit,
+    ) {
+        let fields = [
+            (ReplacedField::Value, &edit.text),
+            (ReplacedField::Formula, &edit.formula),
+        ];
+        for (field, change) in fields {
+            let Some((before, after)) = change else {
+                continue;
+            };
+            state.outcome.total += 1;
+            if state.outcome.replacements.len() < state.limit {
+                state.outcome.replacements.push(TextReplacement {
+                    sheet_index: state.sheet_index,
+                    sheet: state.sheet.clone(),
+                    row,
+                    col,
+                    field,
+                    before: before.clone(),
+                    after: after.clone(),
+                });
+            }
+        }
+    }
+
+    fn write_edited_cell(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        start: &BytesStart<'static>,
+        body: Option<&[Event<'static>]>,
+        repeat: usize,
+        edit: &CellEdit,
+    ) -> Result<(), AppError> {
+        let mut cell = BytesStart::new("table:table-cell");
+        for attr in Self::clone_cell_with_repeat(start, repeat)
+            .attributes()
+            .flatten()
+        {
+            let key = attr.key.as_ref();
+            if edit.formula.is_some() && Self::is_local_name_bytes(key, b"formula") {
+                continue;
+            }
+            // A stored string-value would shadow the rewritten paragraphs.
+            if edit.text.is_some() && Self::is_local_name_bytes(key, b"string-value") {
+                continue;
+            }
+            cell.push_attribute(attr);
+        }
+        if let Some((_, formula)) = &edit.formula {
+            cell.push_attribute(("table:formula", formula.as_str()));
+        }
+
+        let Some((_, text)) = &edit.text else {
+            return Self::write_cell_copy_body(writer, cell, body);
+        };
+        Self::write_cell_with_text(writer, cell, body, text)
+    }
+
+    fn write_cell_copy_body(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        cell: BytesStart<'_>,
+        body: Option<&[Event<'static>]>,
+    ) -> Result<(), AppError> {
+        let Some(body) = body else {
+            return writer
+                .write_event(Event::Empty(cell))
+                .map(|_| ())
+                .map_err(|e| AppError::XmlParseError(e.to_string()));
+        };
+        writer
+            .write_event(Event::Start(cell))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        for event in body {
+            writer
+                .write_event(event.clone())
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("table:table-cell")))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        Ok(())
+    }
+
+    // Writes `cell` with its top-level paragraphs replaced by `text` (one per line);
+    // annotations and anything else in the body stay where they were. Inline formatting
+    // inside the old paragraphs is not kept.
+    pub(super) fn write_cell_with_text(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        cell: BytesStart<'_>,
+        body: Option<&[Event<'static>]>,
+        text: &str,
+    ) -> Result<(), AppError> {
+        writer
+            .write_event(Event::Start(cell))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        let mut depth = 0usize;
+        let mut skipping = false;
+        let mut written = false;
+        for event in body.unwrap_or_default() {
+            let is_paragraph = matches!(
+                event,
+                Event::Start(e) | Event::Empty(e) if depth == 0 && Self::is_local_name_bytes(e.name().as_ref(), b"p")
+            );
+            if is_paragraph {
+                if !written {
+                    Self::write_paragraphs(writer, text)?;
+                    written = true;
+                }
+                skipping = matches!(event, Event::Start(_));
+                if skipping {
+                    depth += 1;
+                }
+                continue;
+            }
+            match event {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => {
+                    depth = depth.saturating_sub(1);
+                    if skipping && depth == 0 {
+                        skipping = false;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            if !skipping {
+                writer
+                    .write_event(event.clone())
+                    .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            }
+        }
+        if !written {
+            Self::write_paragraphs(writer, text)?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("table:table-cell")))
+            .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn write_paragraphs(writer: &mut Writer<Cursor<Vec<u8>>>, text: &str) -> Result<(), AppError> {
+        for line in text.split('\n') {
+            writer
+                .write_event(Event::Start(BytesStart::new("text:p")))
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            writer
+                .write_event(Event::Text(BytesText::new(line)))
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            writer
+                .write_event(Event::End(BytesEnd::new("text:p")))
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    // Displayed text of a cell body (paragraphs joined with '\n'), ignoring annotations.
+    // None when the cell has no paragraph at all.
+    fn cell_paragraph_text(body: &[Event<'static>]) -> Option<String> {
+        let mut text = String::new();
+        let mut paragraphs = 0usize;
+        let mut annotation_depth = 0usize;
+        let mut in_paragraph = false;
+        for event in body {
+            match event {
+                Event::Start(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    annotation_depth += 1;
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") => {
+                    annotation_depth = annotation_depth.saturating_sub(1);
+                }
+                _ if annotation_depth > 0 => {}
+                Event::Start(e) | Event::Empty(e)
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"p") =>
+                {
+                    Self::start_paragraph(&mut text, &mut paragraphs);
+                    in_paragraph = matches!(event, Event::Start(_));
+                }
+                Event::End(e) if Self::is_local_name_bytes(e.name().as_ref(), b"p") => {
+                    in_paragraph = false;
+                }
+                Event::Empty(e) if in_paragraph => Self::push_inline_text(&mut text, e),
+                Event::Text(t) if in_paragraph => {
+                    text.push_str(&t.unescape().ok()?);
+                }
+                _ => {}
+            }
+        }
+        (paragraphs > 0).then_some(text)
+    }
+
+    // "of:=SUM(...)" splits into ("of:", "=SUM(...)"); formulas without a namespace stay whole.
+    pub(super) fn split_formula_namespace(formula: &str) -> (&str, &str) {
+        match formula.find(':') {
+            Some(pos) if !formula[..pos].contains('=') => formula.split_at(pos + 1),
+            _ => ("", formula),
+        }
+    }
+
+    pub(super) fn attr_value(e: &BytesStart<'_>, key: &[u8]) -> Option<String> {
+        e.attributes()
+            .flatten()
+            .find(|a| Self::is_local_name_bytes(a.key.as_ref(), key))
+            .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::HashMap;
 
 impl ContentXml {
     pub fn parse(content: &str) -> Result<Workbook, AppError> {
@@ -9,13 +10,37 @@ impl ContentXml {
 
         let mut current_sheet: Option<Sheet> = None;
         let mut current_row: Option<Vec<Cell>> = None;
-        let mut current_cell_value = CellValue::Empty;
+        let mut current_cell = Cell::empty();
         let mut row_repeat = 1usize;
         let mut cell_repeat = 1usize;
         let mut in_text_p = false;
+        let mut in_covered_cell = false;
+        // Automatic cell styles precede the body, so they are known before any cell refers to them.
+        let mut styles: HashMap<String, CellStyle> = HashMap::new();
+        let mut current_style: Option<(String, CellStyle)> = None;
+        // Inside office:annotation the paragraphs belong to the comment, not to the cell.
+        let mut comment: Option<CellComment> = None;
+        let mut comment_field: Option<&'static [u8]> = None;
+        let mut comment_paragraphs = 0usize;
 
         loop {
             match reader.read_event() {
+                Ok(Event::Start(e)) if Self::is_local_name_bytes(e.name().as_ref(), b"style") => {
+                    current_style = Self::cell_style_name(&e, reader.decoder())
+                        .map(|name| (name, CellStyle::default()));
+                }
+                Ok(Event::End(e)) if Self::is_local_name_bytes(e.name().as_ref(), b"style") => {
+                    if let Some((name, style)) = current_style.take() {
+                        styles.insert(name, style);
+                    }
+                }
+                Ok(Event::Start(e) | Event::Empty(e))
+                    if current_style.is_some() && Self::is_style_properties(e.name().as_ref()) =>
+                {
+                    if let Some((_, style)) = current_style.as_mut() {
+                        Self::read_style_properties(&e, reader.decoder(), style);
+                    }
+                }
                 Ok(Event::Start(e)) if Self::is_local_name_bytes(e.name().as_ref(), b"table") => {
                     let mut name = "Sheet1".to_string();
                     for attr in e.attributes().flatten() {
@@ -72,23 +97,99 @@ impl ContentXml {
                 Ok(Event::Empty(e))
                     if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") =>
                 {
-                    if let Some(row) = current_row.as_mut() {
-                        let value = Self::value_from_attrs(&e, reader.decoder());
+                    if let (Some(sheet), Some(row)) = (current_sheet.as_mut(), current_row.as_mut())
+                    {
+                        let cell = Self::cell_from_attrs(&e, reader.decoder(), &styles);
+                        Self::record_span(&e, reader.decoder(), sheet, row.len());
                         let repeat =
                             Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
-                        for _ in 0..repeat {
-                            row.push(Cell {
-                                value: value.clone(),
-                            });
-                        }
+                        row.extend(std::iter::repeat_n(cell, repeat));
                     }
                 }
                 Ok(Event::Start(e))
                     if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") =>
                 {
-                    current_cell_value = Self::value_from_attrs(&e, reader.decoder());
+                    current_cell = Self::cell_from_attrs(&e, reader.decoder(), &styles);
+                    cell_repeat =
+                        Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    if let (Some(sheet), Some(row)) = (current_sheet.as_mut(), current_row.as_ref())
+                    {
+                        Self::record_span(&e, reader.decoder(), sheet, row.len());
+                    }
+                }
+                Ok(Event::Empty(e))
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                {
+                    // Covered cells keep their grid slot so cells after a merge stay in place.
+                    if let Some(row) = current_row.as_mut() {
+                        let repeat =
+                            Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                        row.extend(std::iter::repeat_n(Cell::empty(), repeat));
+                    }
+                }
+                Ok(Event::Start(e))
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                {
+                    current_cell = Cell::empty();
                     cell_repeat =
                         Self::attr_repeat(&e, b"number-columns-repeated", reader.decoder());
+                    in_covered_cell = true;
+                }
+                Ok(Event::End(e))
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"covered-table-cell") =>
+                {
+                    if let Some(row) = current_row.as_mut() {
+                        row.extend(std::iter::repeat_n(Cell::empty(), cell_repeat));
+                    }
+                    current_cell = Cell::empty();
+                    cell_repeat = 1;
+                    in_text_p = false;
+                    in_covered_cell = false;
+                }
+                Ok(Event::Start(e))
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") =>
+                {
+                    comment = Some(CellComment::default());
+                    comment_paragraphs = 0;
+                }
+                Ok(Event::End(e))
+                    if Self::is_local_name_bytes(e.name().as_ref(), b"annotation") =>
+                {
+                    current_cell.comment = comment.take();
+                    in_text_p = false;
+                }
+                Ok(Event::Start(e)) if comment.is_some() => {
+                    let name = e.name();
+                    if Self::is_local_name_bytes(name.as_ref(), b"creator") {
+                        comment_field = Some(b"creator");
+                    } else if Self::is_local_name_bytes(name.as_ref(), b"date") {
+                        comment_field = Some(b"date");
+                    } else if Self::is_local_name_bytes(name.as_ref(), b"p") {
+                        if let Some(comment) = comment.as_mut().filter(|_| comment_paragraphs > 0) {
+                            comment.text.push('\n');
+                        }
+                        comment_paragraphs += 1;
+                        in_text_p = true;
+                    }
+                }
+                Ok(Event::End(_)) if comment.is_some() && comment_field.is_some() => {
+                    comment_field = None;
+                }
+                Ok(Event::Text(text))
+                    if comment.is_some() && (in_text_p || comment_field.is_some()) =>
+                {
+                    let t = text
+                        .unescape()
+                        .map_err(|x| AppError::XmlParseError(x.to_string()))?;
+                    if let Some(comment) = comment.as_mut() {
+                        match comment_field {
+                            Some(b"creator") => {
+                                comment.author.get_or_insert_with(String::new).push_str(&t)
+                            }
+                            Some(_) => comment.date.get_or_insert_with(String::new).push_str(&t),
+                            None => comment.text.push_str(&t),
+                        }
+                    }
                 }
                 Ok(Event::Start(e)) if Self::is_local_name_bytes(e.name().as_ref(), b"p") => {
                     in_text_p = true;
@@ -96,29 +197,25 @@ impl ContentXml {
                 Ok(Event::End(e)) if Self::is_local_name_bytes(e.name().as_ref(), b"p") => {
                     in_text_p = false;
                 }
-                Ok(Event::Text(text)) if in_text_p => {
+                Ok(Event::Text(text)) if in_text_p && !in_covered_cell => {
                     let t = text
                         .unescape()
                         .map_err(|x| AppError::XmlParseError(x.to_string()))?
                         .into_owned();
-                    match &mut current_cell_value {
+                    match &mut current_cell.value {
                         CellValue::String(existing) => existing.push_str(&t),
-                        CellValue::Empty => current_cell_value = CellValue::String(t),
-                        CellValue::Number(_) | CellValue::Boolean(_) => {}
+                        CellValue::Empty => current_cell.value = CellValue::String(t),
+                        CellValue::Number(_) | CellValue::Boolean(_) | CellValue::Date(_) => {}
                     }
                 }
                 Ok(Event::End(e))
                     if Self::is_local_name_bytes(e.name().as_ref(), b"table-cell") =>
                 {
                     // Expandimos celdas repetidas para exponer siempre una matriz explícita.
+                    let cell = std::mem::replace(&mut current_cell, Cell::empty());
                     if let Some(row) = current_row.as_mut() {
-                        for _ in 0..cell_repeat {
-                            row.push(Cell {
-                                value: current_cell_value.clone(),
-                            });
-                        }
+                        row.extend(std::iter::repeat_n(cell, cell_repeat));
                     }
-                    current_cell_value = CellValue::Empty;
                     cell_repeat = 1;
                     in_text_p = false;
                 }
@@ -141,6 +238,15 @@ impl ContentXml {
             "xmlns:office",
             "urn:oasis:names:tc:opendocument:xmlns:office:1.0",
         ));
+        root.push_attribute((
+            "xmlns:style",
+            "urn:oasis:names:tc:opendocument:xmlns:style:1.0",
+        ));
+        root.push_attribute((
+            "xmlns:fo",
+            "urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0",
+        ));
+        root.push_attribute(("xmlns:dc", "http://purl.org/dc/elements/1.1/"));
         root.push_attribute((
             "xmlns:table",
             "urn:oasis:names:tc:opendocument:xmlns:table:1.0",
@@ -149,6 +255,7 @@ impl ContentXml {
             "xmlns:text",
             "urn:oasis:names:tc:opendocument:xmlns:text:1.0",
         ));
+        root.push_attribute(("xmlns:of", "urn:oasis:names:tc:opendocument:xmlns:of:1.2"));
         root.push_attribute((
             "xmlns:calcext",
             "urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0",
@@ -156,6 +263,23 @@ impl ContentXml {
         root.push_attribute(("office:version", "1.2"));
         writer.write_event(Event::Start(root))?;
 
+        // Every distinct non-default style becomes one automatic style (ce1, ce2, ...).
+        let mut style_names: HashMap<&CellStyle, String> = HashMap::new();
+        let mut ordered_styles: Vec<&CellStyle> = Vec::new();
+        for cell in workbook.sheets.iter().flat_map(|s| s.rows.iter().flatten()) {
+            if !cell.style.is_default() && !style_names.contains_key(&cell.style) {
+                ordered_styles.push(&cell.style);
+                style_names.insert(&cell.style, format!("ce{}", ordered_styles.len()));
+            }
+        }
+        if !ordered_styles.is_empty() {
+            writer.write_event(Event::Start(BytesStart::new("office:automatic-styles")))?;
+            for style in &ordered_styles {
+                Self::write_cell_style(&mut writer, &style_names[style], style)?;
+            }
+            writer.write_event(Event::End(BytesEnd::new("office:automatic-styles")))?;
+        }
+
         writer.write_event(Event::Start(BytesStart::new("office:body")))?;
         writer.write_event(Event::Start(BytesStart::new("office:spreadsheet")))?;
 
@@ -164,41 +288,49 @@ impl ContentXml {
             table.push_attribute(("table:name", sheet.name.as_str()));
             writer.write_event(Event::Start(table))?;
 
-            for row in &sheet.rows {
-                writer.write_event(Event::Start(BytesStart::new("table:table-row")))?;
-                for cell in row {
-                    let mut cell_tag = BytesStart::new("table:table-cell");
-                    let maybe_text = match &cell.value {
-                        CellValue::String(v) => {
-                            cell_tag.push_attribute(("office:value-type", "string"));
-                            Some(v.clone())
-                        }
-                        CellValue::Number(v) => {
-                            let n = v.to_string();
-                            cell_tag.push_attribute(("office:value-type", "float"));
-                            cell_tag.push_attribute(("office:value", n.as_str()));
-                            Some(n)
+            // Runs of blank rows and cells are written once with a repeat count, so sparse
+            // sheets stay small.
+            let mut r = 0;
+            while r < sheet.rows.len() {
+                let row = &sheet.rows[r];
+                let mut repeat = 1;
+                if Self::is_blank_row(sheet, r) {
+                    while sheet.rows.get(r + repeat).is_some_and(|next| {
+                        next.len() == row.len() && Self::is_blank_row(sheet, r + repeat)
+                    }) {
+                        repeat += 1;
+                    }
+                }
+                let mut row_tag = BytesStart::new("table:table-row");
+                let rows_repeated = repeat.to_string();
+                if repeat > 1 {
+                    row_tag.push_attribute(("table:number-rows-repeated", rows_repeated.as_str()));
+                }
+                writer.write_event(Event::Start(row_tag))?;
+                let mut c = 0;
+                while c < row.len() {
+                    if Self::is_blank_cell(sheet, r, c) {
+                        let mut run = 1;
+                        while c + run < row.len() && Self::is_blank_cell(sheet, r, c + run) {
+                            run += 1;
                         }
-                        CellValue::Boolean(v) => {
-                            let b = if *v { "true" } else { "false" };
-                            cell_tag.push_attribute(("office:value-type", "boolean"));
-                            cell_tag.push_attribute(("office:boolean-value", b));
-                            Some(b.to_string())
+                        let mut cell_tag = BytesStart::new("table:table-cell");
+                        let cols_repeated = run.to_string();
+                        if run > 1 {
+                            cell_tag.push_attribute((
+                                "table:number-columns-repeated",
+                                cols_repeated.as_str(),
+                            ));
                         }
-                        CellValue::Empty => None,
-                    };
-
-                    if let Some(text) = maybe_text {
-                        writer.write_event(Event::Start(cell_tag))?;
-                        writer.write_event(Event::Start(BytesStart::new("text:p")))?;
-                        writer.write_event(Event::Text(BytesText::new(&text)))?;
-                        writer.write_event(Event::End(BytesEnd::new("text:p")))?;
-                        writer.write_event(Event::End(BytesEnd::new("table:table-cell")))?;
-                    } else {
                         writer.write_event(Event::Empty(cell_tag))?;
+                        c += run;
+                        continue;
                     }
+                    Self::write_cell(&mut writer, sheet, r, c, &style_names)?;
+                    c += 1;
                 }
                 writer.write_event(Event::End(BytesEnd::new("table:table-row")))?;
+                r += repeat;
             }
 
             writer.write_event(Event::End(BytesEnd::new("table:table")))?;
@@ -212,11 +344,267 @@ impl ContentXml {
         String::from_utf8(bytes).map_err(|e| AppError::XmlParseError(e.to_string()))
     }
 
+    fn write_cell(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        sheet: &Sheet,
+        r: usize,
+        c: usize,
+        style_names: &HashMap<&CellStyle, String>,
+    ) -> Result<(), AppError> {
+        let cell = &sheet.rows[r][c];
+        let merge = sheet.merges.iter().find(|m| m.contains(r, c));
+        let tag_name = match merge {
+            Some(m) if (m.row, m.col) != (r, c) => "table:covered-table-cell",
+            _ => "table:table-cell",
+        };
+        let mut cell_tag = BytesStart::new(tag_name);
+        if let Some(name) = style_names.get(&cell.style) {
+            cell_tag.push_attribute(("table:style-name", name.as_str()));
+        }
+        if let Some(m) = merge.filter(|m| (m.row, m.col) == (r, c)) {
+            let cols = m.cols.to_string();
+            let rows = m.rows.to_string();
+            cell_tag.push_attribute(("table:number-columns-spanned", cols.as_str()));
+            cell_tag.push_attribute(("table:number-rows-spanned", rows.as_str()));
+        }
+        if let Some(formula) = &cell.formula {
+            cell_tag.push_attribute(("table:formula", formula.as_str()));
+        }
+        let maybe_text = match &cell.value {
+            CellValue::String(v) => {
+                cell_tag.push_attribute(("office:value-type", "string"));
+                Some(v.clone())
+            }
+            CellValue::Number(v) => {
+                let n = v.to_string();
+                cell_tag.push_attribute(("office:value-type", "float"));
+                cell_tag.push_attribute(("office:value", n.as_str()));
+                Some(n)
+            }
+            CellValue::Boolean(v) => {
+                let b = if *v { "true" } else { "false" };
+                cell_tag.push_attribute(("office:value-type", "boolean"));
+                cell_tag.push_attribute(("office:boolean-value", b));
+                Some(b.to_string())
+            }
+            CellValue::Date(v) => {
+                cell_tag.push_attribute(("office:value-type", "date"));
+                cell_tag.push_attribute(("office:date-value", v.as_str()));
+                Some(v.clone())
+            }
+            CellValue::Empty => None,
+        };
+
+        if maybe_text.is_none() && cell.comment.is_none() {
+            writer.write_event(Event::Empty(cell_tag))?;
+            return Ok(());
+        }
+        writer.write_event(Event::Start(cell_tag))?;
+        if let Some(comment) = &cell.comment {
+            Self::write_comment(writer, comment)?;
+        }
+        if let Some(text) = maybe_text {
+            writer.write_event(Event::Start(BytesStart::new("text:p")))?;
+            writer.write_event(Event::Text(BytesText::new(&text)))?;
+            writer.write_event(Event::End(BytesEnd::new("text:p")))?;
+        }
+        writer.write_event(Event::End(BytesEnd::new(tag_name)))?;
+        Ok(())
+    }
+
+    // Empty, unstyled and outside any merge: such cells can share one repeated element.
+    fn is_blank_cell(sheet: &Sheet, r: usize, c: usize) -> bool {
+        let cell = &sheet.rows[r][c];
+        cell.value == CellValue::Empty
+            && cell.formula.is_none()
+            && cell.comment.is_none()
+            && cell.style.is_default()
+            && !sheet.merges.iter().any(|m| m.contains(r, c))
+    }
+
+    fn is_blank_row(sheet: &Sheet, r: usize) -> bool {
+        (0..sheet.rows[r].len()).all(|c| Self::is_blank_cell(sheet, r, c))
+    }
+
+    // office:annotation must precede the cell's own paragraphs.
+    fn write_comment(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        comment: &CellComment,
+    ) -> Result<(), AppError> {
+        writer.write_event(Event::Start(BytesStart::new("office:annotation")))?;
+        for (tag, value) in [("dc:creator", &comment.author), ("dc:date", &comment.date)] {
+            if let Some(value) = value {
+                writer.write_event(Event::Start(BytesStart::new(tag)))?;
+                writer.write_event(Event::Text(BytesText::new(value)))?;
+                writer.write_event(Event::End(BytesEnd::new(tag)))?;
+            }
+        }
+        for line in comment.text.split('\n') {
+            writer.write_event(Event::Start(BytesStart::new("text:p")))?;
+            writer.write_event(Event::Text(BytesText::new(line)))?;
+            writer.write_event(Event::End(BytesEnd::new("text:p")))?;
+        }
+        writer.write_event(Event::End(BytesEnd::new("office:annotation")))?;
+        Ok(())
+    }
+
+    fn write_cell_style(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        name: &str,
+        style: &CellStyle,
+    ) -> Result<(), AppError> {
+        let mut tag = BytesStart::new("style:style");
+        tag.push_attribute(("style:name", name));
+        tag.push_attribute(("style:family", "table-cell"));
+        tag.push_attribute(("style:parent-style-name", "Default"));
+        writer.write_event(Event::Start(tag))?;
+
+        if let Some(color) = &style.background_color {
+            let mut props = BytesStart::new("style:table-cell-properties");
+            props.push_attribute(("fo:background-color", color.as_str()));
+            writer.write_event(Event::Empty(props))?;
+        }
+        if let Some(align) = &style.horizontal_align {
+            let odf_align = match align.as_str() {
+                "left" => "start",
+                "right" => "end",
+                other => other,
+            };
+            let mut props = BytesStart::new("style:paragraph-properties");
+            props.push_attribute(("fo:text-align", odf_align));
+            writer.write_event(Event::Empty(props))?;
+        }
+        if style.bold || style.italic || style.underline || style.font_color.is_some() {
+            let mut props = BytesStart::new("style:text-properties");
+            if style.bold {
+                props.push_attribute(("fo:font-weight", "bold"));
+            }
+            if style.italic {
+                props.push_attribute(("fo:font-style", "italic"));
+            }
+            if style.underline {
+                props.push_attribute(("style:text-underline-style", "solid"));
+                props.push_attribute(("style:text-underline-width", "auto"));
+                props.push_attribute(("style:text-underline-color", "font-color"));
+            }
+            if let Some(color) = &style.font_color {
+                props.push_attribute(("fo:color", color.as_str()));
+            }
+            writer.write_event(Event::Empty(props))?;
+        }
+
+        writer.write_event(Event::End(BytesEnd::new("style:style")))?;
+        Ok(())
+    }
+
+    // Name of a style:style element when it formats table cells.
+    fn cell_style_name(
+        e: &BytesStart<'_>,
+        decoder: quick_xml::encoding::Decoder,
+    ) -> Option<String> {
+        let mut name = None;
+        let mut is_cell_family = false;
+        for attr in e.attributes().flatten() {
+            let key = attr.key.as_ref();
+            let Ok(value) = attr.decode_and_unescape_value(decoder) else {
+                continue;
+            };
+            if Self::is_local_name_bytes(key, b"name") {
+                name = Some(value.to_string());
+            } else if Self::is_local_name_bytes(key, b"family") {
+                is_cell_family = value == "table-cell";
+            }
+        }
+        name.filter(|_| is_cell_family)
+    }
+
+    fn is_style_properties(name: &[u8]) -> bool {
+        [
+            b"text-properties".as_slice(),
+            b"paragraph-properties",
+            b"table-cell-properties",
+        ]
+        .iter()
+        .any(|local| Self::is_local_name_bytes(name, local))
+    }
+
+    fn read_style_properties(
+        e: &BytesStart<'_>,
+        decoder: quick_xml::encoding::Decoder,
+        style: &mut CellStyle,
+    ) {
+        let is_cell_props = Self::is_local_name_bytes(e.name().as_ref(), b"table-cell-properties");
+        for attr in e.attributes().flatten() {
+            let key = attr.key.as_ref();
+            let Ok(value) = attr.decode_and_unescape_value(decoder) else {
+                continue;
+            };
+            if Self::is_local_name_bytes(key, b"font-weight") {
+                style.bold = value == "bold" || value.parse::<u32>().is_ok_and(|w| w >= 600);
+            } else if Self::is_local_name_bytes(key, b"font-style") {
+                style.italic = value == "italic" || value == "oblique";
+            } else if Self::is_local_name_bytes(key, b"text-underline-style") {
+                style.underline = value != "none";
+            } else if Self::is_local_name_bytes(key, b"color") {
+                style.font_color = Some(value.to_string());
+            } else if is_cell_props && Self::is_local_name_bytes(key, b"background-color") {
+                style.background_color = Some(value.to_string()).filter(|c| c != "transparent");
+            } else if Self::is_local_name_bytes(key, b"text-align") {
+                style.horizontal_align = match value.as_ref() {
+                    "start" | "left" => Some("left".to_string()),
+                    "center" => Some("center".to_string()),
+                    "end" | "right" => Some("right".to_string()),
+                    _ => None,
+                };
+            }
+        }
+    }
+
+    // Records a merge anchored at the current grid slot when the cell spans more than itself.
+    fn record_span(
+        e: &BytesStart<'_>,
+        decoder: quick_xml::encoding::Decoder,
+        sheet: &mut Sheet,
+        col: usize,
+    ) {
+        let cols = Self::attr_repeat(e, b"number-columns-spanned", decoder);
+        let rows = Self::attr_repeat(e, b"number-rows-spanned", decoder);
+        if cols > 1 || rows > 1 {
+            sheet.merges.push(MergedRange {
+                row: sheet.rows.len(),
+                col,
+                rows,
+                cols,
+            });
+        }
+    }
+
+    fn cell_from_attrs(
+        e: &BytesStart<'_>,
+        decoder: quick_xml::encoding::Decoder,
+        styles: &HashMap<String, CellStyle>,
+    ) -> Cell {
+        let mut cell = Cell::with_value(Self::value_from_attrs(e, decoder));
+        for attr in e.attributes().flatten() {
+            let key = attr.key.as_ref();
+            let Ok(value) = attr.decode_and_unescape_value(decoder) else {
+                continue;
+            };
+            if Self::is_local_name_bytes(key, b"formula") {
+                cell.formula = Some(value.to_string());
+            } else if Self::is_local_name_bytes(key, b"style-name") {
+                cell.style = styles.get(value.as_ref()).cloned().unwrap_or_default();
+            }
+        }
+        cell
+    }
+
     fn value_from_attrs(e: &BytesStart<'_>, decoder: quick_xml::encoding::Decoder) -> CellValue {
         // Value type is represented by attributes; text is optional for numbers/booleans.
         let mut value_type: Option<String> = None;
         let mut value: Option<String> = None;
         let mut boolean_value: Option<String> = None;
+        let mut date_value: Option<String> = None;
 
         for attr in e.attributes().flatten() {
             let key = attr.key.as_ref();
@@ -230,6 +618,8 @@ impl ContentXml {
                 value = Some(decoded);
             } else if Self::is_local_name_bytes(key, b"boolean-value") {
                 boolean_value = Some(decoded);
+            } else if Self::is_local_name_bytes(key, b"date-value") {
+                date_value = Some(decoded);
             }
         }
 
@@ -242,6 +632,7 @@ impl ContentXml {
                 .map(|v| v.eq_ignore_ascii_case("true"))
                 .map(CellValue::Boolean)
                 .unwrap_or(CellValue::Empty),
+            Some("date") => date_value.map(CellValue::Date).unwrap_or(CellValue::Empty),
             Some("string") => value.map(CellValue::String).unwrap_or(CellValue::Empty),
             _ => CellValue::Empty,
         }
@@ -0,0 +1,431 @@
+use crate::common::errors::AppError;
+use crate::ods::ods_templates::OdsTemplates;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+// Flat OpenDocument (.fods): one office:document holding what a zip package splits into
+// content.xml, styles.xml, meta.xml and settings.xml. Tools keep working on content.xml
+// and meta.xml; this module maps those views in and out of the single document.
+pub struct FlatOds;
+
+// Top-level element kept verbatim: local name plus its full source text.
+#[derive(Debug, Clone)]
+struct Part {
+    local_name: String,
+    raw: String,
+}
+
+#[derive(Debug)]
+struct Root {
+    prefix: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Part>,
+}
+
+impl FlatOds {
+    // Canonical order of office:document children (ODF 1.2, 3.1.3.1).
+    const ORDER: [&'static str; 8] = [
+        "meta",
+        "settings",
+        "scripts",
+        "font-face-decls",
+        "styles",
+        "automatic-styles",
+        "master-styles",
+        "body",
+    ];
+    // Children that belong to content.xml in the zip layout.
+    const CONTENT_PARTS: [&'static str; 4] =
+        ["scripts", "font-face-decls", "automatic-styles", "body"];
+
+    pub fn content_xml(document: &str) -> Result<String, AppError> {
+        let root = Self::document_root(document)?;
+        Ok(Self::wrap(&root, "document-content", |name| {
+            Self::CONTENT_PARTS.contains(&name)
+        }))
+    }
+
+    pub fn meta_xml(document: &str) -> Result<Option<String>, AppError> {
+        let root = Self::document_root(document)?;
+        if !root.children.iter().any(|p| p.local_name == "meta") {
+            return Ok(None);
+        }
+        Ok(Some(Self::wrap(&root, "document-meta", |name| {
+            name == "meta"
+        })))
+    }
+
+    // Splices a content.xml document back in; automatic styles that master pages still
+    // use (page layouts, header/footer styles) survive even if content.xml dropped them.
+    pub fn replace_content(document: &str, content_xml: &str) -> Result<String, AppError> {
+        let mut root = Self::document_root(document)?;
+        let content = Self::parse_root(content_xml)?;
+        Self::merge_namespaces(&mut root, &content);
+
+        let master_refs: Vec<String> = root
+            .children
+            .iter()
+            .filter(|p| p.local_name == "master-styles")
+            .flat_map(|p| Self::referenced_style_names(&p.raw))
+            .collect();
+        let old_auto = root
+            .children
+            .iter()
+            .find(|p| p.local_name == "automatic-styles")
+            .cloned();
+        let new_auto = content
+            .children
+            .iter()
+            .find(|p| p.local_name == "automatic-styles")
+            .cloned();
+        let kept: Vec<Part> = match &old_auto {
+            Some(old) => Self::children_of(&old.raw)?
+                .into_iter()
+                .filter(|p| Self::style_name(&p.raw).is_some_and(|n| master_refs.contains(&n)))
+                .collect(),
+            None => Vec::new(),
+        };
+        let mut auto_children = match &new_auto {
+            Some(new) => Self::children_of(&new.raw)?,
+            None => Vec::new(),
+        };
+        for part in kept {
+            let name = Self::style_name(&part.raw);
+            if !auto_children
+                .iter()
+                .any(|p| Self::style_name(&p.raw) == name)
+            {
+                auto_children.push(part);
+            }
+        }
+
+        root.children.retain(|p| {
+            !Self::CONTENT_PARTS.contains(&p.local_name.as_str())
+                || (p.local_name == "font-face-decls"
+                    && !content
+                        .children
+                        .iter()
+                        .any(|c| c.local_name == "font-face-decls"))
+                || (p.local_name == "scripts"
+                    && !content.children.iter().any(|c| c.local_name == "scripts"))
+        });
+        for part in &content.children {
+            if part.local_name == "automatic-styles"
+                || !Self::CONTENT_PARTS.contains(&part.local_name.as_str())
+            {
+                continue;
+            }
+            root.children.push(part.clone());
+        }
+        if !auto_children.is_empty() {
+            root.children.push(Self::element(
+                &root.prefix,
+                "automatic-styles",
+                &auto_children,
+            ));
+        }
+        Ok(Self::render(&root))
+    }
+
+    pub fn replace_meta(document: &str, meta_xml: &str) -> Result<String, AppError> {
+        let mut root = Self::document_root(document)?;
+        let meta = Self::parse_root(meta_xml)?;
+        Self::merge_namespaces(&mut root, &meta);
+        root.children.retain(|p| p.local_name != "meta");
+        root.children
+            .extend(meta.children.into_iter().filter(|p| p.local_name == "meta"));
+        Ok(Self::render(&root))
+    }
+
+    // Builds a flat document from the four XML streams of a zip package.
+    pub fn from_parts(
+        content_xml: &str,
+        styles_xml: &str,
+        meta_xml: &str,
+        settings_xml: &str,
+    ) -> Result<String, AppError> {
+        let content = Self::parse_root(content_xml)?;
+        let styles = Self::parse_root(styles_xml)?;
+        let meta = Self::parse_root(meta_xml)?;
+        let settings = Self::parse_root(settings_xml)?;
+
+        let mut root = Root {
+            prefix: content.prefix.clone(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        };
+        for part in [&content, &styles, &meta, &settings] {
+            Self::merge_namespaces(&mut root, part);
+        }
+        root.attrs.extend(
+            content
+                .attrs
+                .iter()
+                .filter(|(k, _)| !k.starts_with("xmlns"))
+                .cloned(),
+        );
+        root.attrs.push((
+            format!("{}:mimetype", root.prefix),
+            OdsTemplates::mimetype().to_string(),
+        ));
+
+        let pick = |from: &Root, name: &str| {
+            from.children
+                .iter()
+                .filter(|p| p.local_name == name)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        root.children.extend(pick(&meta, "meta"));
+        root.children.extend(pick(&settings, "settings"));
+        root.children.extend(pick(&content, "scripts"));
+        let fonts = pick(&content, "font-face-decls");
+        root.children.extend(if fonts.is_empty() {
+            pick(&styles, "font-face-decls")
+        } else {
+            fonts
+        });
+        root.children.extend(pick(&styles, "styles"));
+
+        // Both packages carry automatic styles; content.xml wins on a name clash.
+        let mut auto_children = Vec::new();
+        for part in pick(&content, "automatic-styles")
+            .iter()
+            .chain(pick(&styles, "automatic-styles").iter())
+        {
+            for child in Self::children_of(&part.raw)? {
+                let name = Self::style_name(&child.raw);
+                if name.is_none()
+                    || !auto_children
+                        .iter()
+                        .any(|p: &Part| Self::style_name(&p.raw) == name)
+                {
+                    auto_children.push(child);
+                }
+            }
+        }
+        if !auto_children.is_empty() {
+            root.children.push(Self::element(
+                &root.prefix,
+                "automatic-styles",
+                &auto_children,
+            ));
+        }
+        root.children.extend(pick(&styles, "master-styles"));
+        root.children.extend(pick(&content, "body"));
+        Ok(Self::render(&root))
+    }
+
+    fn document_root(document: &str) -> Result<Root, AppError> {
+        let root = Self::parse_root(document)?;
+        let mimetype = root
+            .attrs
+            .iter()
+            .find(|(k, _)| Self::local_name(k) == "mimetype")
+            .map(|(_, v)| v.as_str());
+        if mimetype != Some(OdsTemplates::mimetype()) {
+            return Err(AppError::InvalidOdsFormat(
+                "flat document is not a spreadsheet (office:mimetype)".to_string(),
+            ));
+        }
+        Ok(root)
+    }
+
+    // Root prefix, attributes (unescaped) and top-level children as raw text.
+    fn parse_root(xml: &str) -> Result<Root, AppError> {
+        let mut reader = Reader::from_str(xml);
+        let mut root: Option<Root> = None;
+        let mut depth = 0usize;
+        let mut child_start = 0usize;
+        loop {
+            let before = reader.buffer_position() as usize;
+            match reader.read_event()? {
+                Event::Start(e) => {
+                    depth += 1;
+                    if depth == 1 {
+                        root = Some(Self::root_from(&e)?);
+                    } else if depth == 2 {
+                        child_start = before;
+                    }
+                }
+                Event::Empty(e) => {
+                    if depth == 0 {
+                        root = Some(Self::root_from(&e)?);
+                    } else if depth == 1 {
+                        if let Some(root) = root.as_mut() {
+                            root.children.push(Part {
+                                local_name: Self::local_name(&String::from_utf8_lossy(
+                                    e.name().as_ref(),
+                                ))
+                                .to_string(),
+                                raw: xml[before..reader.buffer_position() as usize].to_string(),
+                            });
+                        }
+                    }
+                }
+                Event::End(e) => {
+                    if depth == 2 {
+                        if let Some(root) = root.as_mut() {
+                            root.children.push(Part {
+                                local_name: Self::local_name(&String::from_utf8_lossy(
+                                    e.name().as_ref(),
+                                ))
+                                .to_string(),
+                                raw: xml[child_start..reader.buffer_position() as usize]
+                                    .to_string(),
+                            });
+                        }
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        root.ok_or_else(|| AppError::XmlParseError("document has no root element".to_string()))
+    }
+
+    fn root_from(e: &quick_xml::events::BytesStart<'_>) -> Result<Root, AppError> {
+        let qname = String::from_utf8_lossy(e.name().as_ref()).to_string();
+        let prefix = qname
+            .split_once(':')
+            .map(|(p, _)| p.to_string())
+            .unwrap_or_else(|| "office".to_string());
+        let mut attrs = Vec::new();
+        for attr in e.attributes() {
+            let attr = attr.map_err(|x| AppError::XmlParseError(x.to_string()))?;
+            attrs.push((
+                String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+                attr.unescape_value()
+                    .map_err(|x| AppError::XmlParseError(x.to_string()))?
+                    .to_string(),
+            ));
+        }
+        Ok(Root {
+            prefix,
+            attrs,
+            children: Vec::new(),
+        })
+    }
+
+    fn children_of(raw: &str) -> Result<Vec<Part>, AppError> {
+        Ok(Self::parse_root(raw)?.children)
+    }
+
+    // Adds namespace declarations of `from` that `root` lacks.
+    fn merge_namespaces(root: &mut Root, from: &Root) {
+        for (key, value) in &from.attrs {
+            if key.starts_with("xmlns") && !root.attrs.iter().any(|(k, _)| k == key) {
+                root.attrs.push((key.clone(), value.clone()));
+            }
+        }
+    }
+
+    // Names referenced from master pages: style-name, page-layout-name and friends.
+    fn referenced_style_names(raw: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut rest = raw;
+        while let Some(pos) = rest.find("-name=\"") {
+            let after = &rest[pos + "-name=\"".len()..];
+            let end = after.find('"').unwrap_or(after.len());
+            names.push(after[..end].to_string());
+            rest = &after[end..];
+        }
+        names
+    }
+
+    fn style_name(raw: &str) -> Option<String> {
+        let mut reader = Reader::from_str(raw);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e) | Event::Empty(e)) => {
+                    return e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.as_ref() == b"style:name")
+                        .and_then(|a| a.unescape_value().ok().map(|v| v.to_string()));
+                }
+                Ok(Event::Eof) | Err(_) => return None,
+                Ok(_) => {}
+            }
+        }
+    }
+
+    fn element(prefix: &str, local_name: &str, children: &[Part]) -> Part {
+        let mut raw = format!("<{prefix}:{local_name}>");
+        for child in children {
+            raw.push_str(&child.raw);
+        }
+        raw.push_str(&format!("</{prefix}:{local_name}>"));
+        Part {
+            local_name: local_name.to_string(),
+            raw,
+        }
+    }
+
+    // Sub-document rooted at <prefix:root_name> with the selected children, e.g. content.xml.
+    fn wrap(root: &Root, root_name: &str, keep: impl Fn(&str) -> bool) -> String {
+        let view = Root {
+            prefix: root.prefix.clone(),
+            attrs: root
+                .attrs
+                .iter()
+                .filter(|(k, _)| Self::local_name(k) != "mimetype")
+                .cloned()
+                .collect(),
+            children: root
+                .children
+                .iter()
+                .filter(|p| keep(&p.local_name))
+                .cloned()
+                .collect(),
+        };
+        Self::render_as(&view, root_name)
+    }
+
+    fn render(root: &Root) -> String {
+        let mut ordered: Vec<&Part> = Vec::new();
+        for name in Self::ORDER {
+            ordered.extend(root.children.iter().filter(|p| p.local_name == name));
+        }
+        // Anything unknown keeps its place ahead of the body.
+        let unknown: Vec<&Part> = root
+            .children
+            .iter()
+            .filter(|p| !Self::ORDER.contains(&p.local_name.as_str()))
+            .collect();
+        let body_at = ordered
+            .iter()
+            .position(|p| p.local_name == "body")
+            .unwrap_or(ordered.len());
+        for (i, part) in unknown.into_iter().enumerate() {
+            ordered.insert(body_at + i, part);
+        }
+        let view = Root {
+            prefix: root.prefix.clone(),
+            attrs: root.attrs.clone(),
+            children: ordered.into_iter().cloned().collect(),
+        };
+        Self::render_as(&view, "document")
+    }
+
+    fn render_as(root: &Root, root_name: &str) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!("<{}:{root_name}", root.prefix));
+        for (key, value) in &root.attrs {
+            out.push_str(&format!(" {key}=\"{}\"", escape(value.as_str())));
+        }
+        out.push('>');
+        for part in &root.children {
+            out.push('\n');
+            out.push_str(&part.raw);
+        }
+        out.push_str(&format!("\n</{}:{root_name}>\n", root.prefix));
+        out
+    }
+
+    fn local_name(qname: &str) -> &str {
+        qname.rsplit(':').next().unwrap_or(qname)
+    }
+}
@@ -0,0 +1,166 @@
+use super::*;
+
+// Result of evaluating a sub-expression: ranges stay whole until a function or operator
+// decides how to read them.
+pub(super) enum Operand {
+    Value(FormulaValue),
+    Range(RangeValues),
+}
+
+pub(super) struct Evaluator<'a> {
+    source: &'a mut dyn FnMut(&RangeRef) -> Result<RangeValues, FormulaError>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub(super) fn new(
+        source: &'a mut dyn FnMut(&RangeRef) -> Result<RangeValues, FormulaError>,
+    ) -> Self {
+        Self { source }
+    }
+
+    // Final value of a formula: a single-cell range yields its cell, a larger one #VALUE!.
+    pub(super) fn value(&mut self, expr: &Expr) -> FormulaValue {
+        match self.operand(expr) {
+            Operand::Value(value) => value,
+            Operand::Range(range) => Self::range_scalar(&range),
+        }
+    }
+
+    pub(super) fn operand(&mut self, expr: &Expr) -> Operand {
+        match expr {
+            Expr::Ref(range) => match (self.source)(range) {
+                Ok(values) => Operand::Range(values),
+                Err(error) => Operand::Value(FormulaValue::Error(error)),
+            },
+            other => Operand::Value(self.scalar(other)),
+        }
+    }
+
+    fn scalar(&mut self, expr: &Expr) -> FormulaValue {
+        match expr {
+            Expr::Number(n) => FormulaValue::Number(*n),
+            Expr::Text(text) => FormulaValue::Text(text.clone()),
+            Expr::Boolean(b) => FormulaValue::Boolean(*b),
+            Expr::Error(e) => FormulaValue::Error(*e),
+            Expr::Name(_) => FormulaValue::Error(FormulaError::Name),
+            Expr::Missing => FormulaValue::Empty,
+            Expr::Ref(_) => self.value(expr),
+            Expr::Negate(inner) => {
+                let value = self.value(inner);
+                Self::number_result(Self::to_number(&value).map(|n| -n))
+            }
+            Expr::Percent(inner) => {
+                let value = self.value(inner);
+                Self::number_result(Self::to_number(&value).map(|n| n / 100.0))
+            }
+            Expr::Binary(op, left, right) => {
+                let left = self.value(left);
+                let right = self.value(right);
+                Self::binary(*op, &left, &right)
+            }
+            Expr::Call(name, args) => self.call(name, args),
+        }
+    }
+
+    fn binary(op: BinaryOp, left: &FormulaValue, right: &FormulaValue) -> FormulaValue {
+        if let FormulaValue::Error(e) = left {
+            return FormulaValue::Error(*e);
+        }
+        if let FormulaValue::Error(e) = right {
+            return FormulaValue::Error(*e);
+        }
+        let ordering = |matches: fn(Ordering) -> bool| {
+            FormulaValue::Boolean(matches(Self::compare(left, right)))
+        };
+        match op {
+            BinaryOp::Concat => {
+                FormulaValue::Text(format!("{}{}", left.display_text(), right.display_text()))
+            }
+            BinaryOp::Eq => ordering(|o| o == Ordering::Equal),
+            BinaryOp::Ne => ordering(|o| o != Ordering::Equal),
+            BinaryOp::Lt => ordering(|o| o == Ordering::Less),
+            BinaryOp::Le => ordering(|o| o != Ordering::Greater),
+            BinaryOp::Gt => ordering(|o| o == Ordering::Greater),
+            BinaryOp::Ge => ordering(|o| o != Ordering::Less),
+            _ => {
+                let (a, b) = match (Self::to_number(left), Self::to_number(right)) {
+                    (Ok(a), Ok(b)) => (a, b),
+                    (Err(e), _) | (_, Err(e)) => return FormulaValue::Error(e),
+                };
+                let result = match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    BinaryOp::Div if b == 0.0 => return FormulaValue::Error(FormulaError::Div0),
+                    BinaryOp::Div => a / b,
+                    _ => a.powf(b),
+                };
+                Self::number_result(Ok(result))
+            }
+        }
+    }
+
+    // Calc ordering across types: numbers < text < booleans; text compares case-insensitively
+    // and an empty cell counts as 0, "" or FALSE depending on the other side.
+    pub(super) fn compare(left: &FormulaValue, right: &FormulaValue) -> Ordering {
+        use FormulaValue as V;
+        let rank = |v: &FormulaValue| match v {
+            V::Number(_) | V::Empty => 0,
+            V::Text(_) => 1,
+            V::Boolean(_) => 2,
+            V::Error(_) => 3,
+        };
+        match (left, right) {
+            (V::Empty, V::Empty) => Ordering::Equal,
+            (V::Empty, V::Text(t)) => "".cmp(t.as_str()),
+            (V::Text(t), V::Empty) => t.as_str().cmp(""),
+            (V::Empty, V::Boolean(b)) => false.cmp(b),
+            (V::Boolean(b), V::Empty) => b.cmp(&false),
+            (V::Number(a), V::Empty) => a.total_cmp(&0.0),
+            (V::Empty, V::Number(b)) => 0.0f64.total_cmp(b),
+            (V::Number(a), V::Number(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            (V::Text(a), V::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (V::Boolean(a), V::Boolean(b)) => a.cmp(b),
+            _ => rank(left).cmp(&rank(right)),
+        }
+    }
+
+    pub(super) fn to_number(value: &FormulaValue) -> Result<f64, FormulaError> {
+        match value {
+            FormulaValue::Number(n) => Ok(*n),
+            FormulaValue::Boolean(b) => Ok(if *b { 1.0 } else { 0.0 }),
+            FormulaValue::Empty => Ok(0.0),
+            FormulaValue::Text(text) => text.trim().parse().map_err(|_| FormulaError::Value),
+            FormulaValue::Error(e) => Err(*e),
+        }
+    }
+
+    pub(super) fn to_bool(value: &FormulaValue) -> Result<bool, FormulaError> {
+        match value {
+            FormulaValue::Boolean(b) => Ok(*b),
+            FormulaValue::Number(n) => Ok(*n != 0.0),
+            FormulaValue::Empty => Ok(false),
+            FormulaValue::Text(text) if text.eq_ignore_ascii_case("true") => Ok(true),
+            FormulaValue::Text(text) if text.eq_ignore_ascii_case("false") => Ok(false),
+            FormulaValue::Text(_) => Err(FormulaError::Value),
+            FormulaValue::Error(e) => Err(*e),
+        }
+    }
+
+    // Infinite or NaN results are #NUM!, as in Calc.
+    pub(super) fn number_result(result: Result<f64, FormulaError>) -> FormulaValue {
+        match result {
+            Ok(n) if n.is_finite() => FormulaValue::Number(if n == 0.0 { 0.0 } else { n }),
+            Ok(_) => FormulaValue::Error(FormulaError::Num),
+            Err(e) => FormulaValue::Error(e),
+        }
+    }
+
+    fn range_scalar(range: &RangeValues) -> FormulaValue {
+        if range.rows == 1 && range.cols == 1 {
+            range.get(0, 0).clone()
+        } else {
+            FormulaValue::Error(FormulaError::Value)
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use super::evaluator::{Evaluator, Operand};
+use super::*;
+
+// SUMIF/COUNTIF condition such as 5, ">=10", "<>x" or "ab*".
+struct Criterion {
+    op: BinaryOp,
+    operand: FormulaValue,
+}
+
+// Functions `call` implements; anything else evaluates to #NAME?.
+pub(super) const SUPPORTED_FUNCTIONS: &[&str] = &[
+    "AND",
+    "AVERAGE",
+    "CONCATENATE",
+    "COUNT",
+    "COUNTA",
+    "COUNTIF",
+    "FALSE",
+    "IF",
+    "IFERROR",
+    "MAX",
+    "MIN",
+    "OR",
+    "ROUND",
+    "SUM",
+    "SUMIF",
+    "TODAY",
+    "TRUE",
+    "VLOOKUP",
+];
+
+impl Evaluator<'_> {
+    // Unknown functions evaluate to #NAME?, wrong argument counts to #VALUE!.
+    pub(super) fn call(&mut self, name: &str, args: &[Expr]) -> FormulaValue {
+        let arity = |min: usize, max: usize| (min..=max).contains(&args.len());
+        match name {
+            "SUM" => Self::number_result(self.numbers(args).map(|n| n.iter().sum())),
+            "AVERAGE" => Self::number_result(self.numbers(args).and_then(|n| {
+                if n.is_empty() {
+                    Err(FormulaError::Div0)
+                } else {
+                    Ok(n.iter().sum::<f64>() / n.len() as f64)
+                }
+            })),
+            "MIN" => Self::number_result(
+                self.numbers(args)
+                    .map(|n| n.into_iter().reduce(f64::min).unwrap_or(0.0)),
+            ),
+            "MAX" => Self::number_result(
+                self.numbers(args)
+                    .map(|n| n.into_iter().reduce(f64::max).unwrap_or(0.0)),
+            ),
+            "COUNT" => FormulaValue::Number(self.count(args, false) as f64),
+            "COUNTA" => FormulaValue::Number(self.count(args, true) as f64),
+            "IF" if arity(1, 3) => {
+                let condition = self.value(&args[0]);
+                match Self::to_bool(&condition) {
+                    Ok(true) => match args.get(1) {
+                        Some(expr) => self.value(expr),
+                        None => FormulaValue::Boolean(true),
+                    },
+                    Ok(false) => match args.get(2) {
+                        Some(expr) => self.value(expr),
+                        None => FormulaValue::Boolean(false),
+                    },
+                    Err(e) => FormulaValue::Error(e),
+                }
+            }
+            "AND" | "OR" if !args.is_empty() => match self.booleans(args) {
+                Ok(values) if values.is_empty() => FormulaValue::Error(FormulaError::Value),
+                Ok(values) if name == "AND" => FormulaValue::Boolean(values.iter().all(|b| *b)),
+                Ok(values) => FormulaValue::Boolean(values.iter().any(|b| *b)),
+                Err(e) => FormulaValue::Error(e),
+            },
+            "ROUND" if arity(1, 2) => {
+                let value = self.value(&args[0]);
+                let digits = match args.get(1) {
+                    Some(expr) => self.value(expr),
+                    None => FormulaValue::Number(0.0),
+                };
+                Self::number_result(
+                    Self::to_number(&value)
+                        .and_then(|n| Ok((n, Self::to_number(&digits)?.trunc() as i32)))
+                        .map(|(n, digits)| Self::round_half_away(n, digits)),
+                )
+            }
+            "CONCATENATE" => {
+                let mut text = String::new();
+                for arg in args {
+                    match self.value(arg) {
+                        FormulaValue::Error(e) => return FormulaValue::Error(e),
+                        value => text.push_str(&value.display_text()),
+                    }
+                }
+                FormulaValue::Text(text)
+            }
+            "VLOOKUP" if arity(3, 4) => self.vlookup(args),
+            "SUMIF" if arity(2, 3) => self.sum_if(args, false),
+            "COUNTIF" if arity(2, 2) => self.sum_if(args, true),
+            "IFERROR" if arity(2, 2) => match self.value(&args[0]) {
+                FormulaValue::Error(_) => self.value(&args[1]),
+                value => value,
+            },
+            "TRUE" | "FALSE" if args.is_empty() => FormulaValue::Boolean(name == "TRUE"),
+            "TODAY" if args.is_empty() => {
+                let days = TimeUtil::serial_from_iso8601(&TimeUtil::now_iso8601()[..10]);
+                days.map(FormulaValue::Number)
+                    .unwrap_or(FormulaValue::Error(FormulaError::Value))
+            }
+            "IF" | "AND" | "OR" | "ROUND" | "VLOOKUP" | "SUMIF" | "COUNTIF" | "IFERROR"
+            | "TRUE" | "FALSE" | "TODAY" => FormulaValue::Error(FormulaError::Value),
+            _ => FormulaValue::Error(FormulaError::Name),
+        }
+    }
+
+    // Numbers for SUM-like functions: ranges contribute only their numeric cells, direct
+    // arguments are coerced ("3" and TRUE count). The first error wins.
+    fn numbers(&mut self, args: &[Expr]) -> Result<Vec<f64>, FormulaError> {
+        let mut numbers = Vec::new();
+        for arg in args {
+            match self.operand(arg) {
+                Operand::Range(range) => {
+                    for value in range.stored() {
+                        match value {
+                            FormulaValue::Number(n) => numbers.push(*n),
+                            FormulaValue::Error(e) => return Err(*e),
+                            _ => {}
+                        }
+                    }
+                }
+                Operand::Value(FormulaValue::Empty) => {}
+                Operand::Value(value) => numbers.push(Self::to_number(&value)?),
+            }
+        }
+        Ok(numbers)
+    }
+
+    fn count(&mut self, args: &[Expr], any_value: bool) -> usize {
+        let counts = |value: &FormulaValue| {
+            if any_value {
+                *value != FormulaValue::Empty
+            } else {
+                matches!(value, FormulaValue::Number(_))
+            }
+        };
+        let mut total = 0;
+        for arg in args {
+            match self.operand(arg) {
+                Operand::Range(range) => total += range.stored().filter(|v| counts(v)).count(),
+                Operand::Value(FormulaValue::Empty) => {}
+                Operand::Value(value) if any_value => total += usize::from(counts(&value)),
+                Operand::Value(value) => {
+                    let numeric =
+                        !matches!(value, FormulaValue::Error(_)) && Self::to_number(&value).is_ok();
+                    total += usize::from(numeric);
+                }
+            }
+        }
+        total
+    }
+
+    // Logical values for AND/OR: ranges skip text and empty cells.
+    fn booleans(&mut self, args: &[Expr]) -> Result<Vec<bool>, FormulaError> {
+        let mut values = Vec::new();
+        for arg in args {
+            match self.operand(arg) {
+                Operand::Range(range) => {
+                    for value in range.stored() {
+                        match value {
+                            FormulaValue::Number(_) | FormulaValue::Boolean(_) => {
+                                values.push(Self::to_bool(value)?)
+                            }
+                            FormulaValue::Error(e) => return Err(*e),
+                            _ => {}
+                        }
+                    }
+                }
+                Operand::Value(FormulaValue::Empty) => {}
+                Operand::Value(value) => values.push(Self::to_bool(&value)?),
+            }
+        }
+        Ok(values)
+    }
+
+    // Half away from zero on the value cut to 15 significant digits, so 2.675 gives 2.68
+    // even though its binary form is 2.67499999...
+    fn round_half_away(n: f64, digits: i32) -> f64 {
+        let factor = 10f64.powi(digits.abs());
+        let scaled = if digits >= 0 { n * factor } else { n / factor };
+        let scaled: f64 = format!("{scaled:.14e}").parse().unwrap_or(scaled);
+        if digits >= 0 {
+            scaled.round() / factor
+        } else {
+            scaled.round() * factor
+        }
+    }
+
+    fn vlookup(&mut self, args: &[Expr]) -> FormulaValue {
+        let needle = self.value(&args[0]);
+        if let FormulaValue::Error(e) = needle {
+            return FormulaValue::Error(e);
+        }
+        let table = match self.operand(&args[1]) {
+            Operand::Range(range) => range,
+            Operand::Value(FormulaValue::Error(e)) => return FormulaValue::Error(e),
+            Operand::Value(_) => return FormulaValue::Error(FormulaError::Value),
+        };
+        let column = match Self::to_number(&self.value(&args[2])) {
+            Ok(n) if n >= 1.0 => n.trunc() as usize - 1,
+            Ok(_) => return FormulaValue::Error(FormulaError::Value),
+            Err(e) => return FormulaValue::Error(e),
+        };
+        if column >= table.cols {
+            return FormulaValue::Error(FormulaError::Ref);
+        }
+        let sorted = match args.get(3).map(|expr| self.value(expr)) {
+            None | Some(FormulaValue::Empty) => true,
+            Some(value) => match Self::to_bool(&value) {
+                Ok(sorted) => sorted,
+                Err(e) => return FormulaValue::Error(e),
+            },
+        };
+
+        let same_kind = |value: &FormulaValue| {
+            matches!(
+                (value, &needle),
+                (FormulaValue::Number(_), FormulaValue::Number(_))
+                    | (FormulaValue::Text(_), FormulaValue::Text(_))
+                    | (FormulaValue::Boolean(_), FormulaValue::Boolean(_))
+            )
+        };
+        let mut found = None;
+        for row in 0..table.rows.min(table.values.len()) {
+            let key = table.get(row, 0);
+            if !same_kind(key) {
+                continue;
+            }
+            let ordering = Self::compare(key, &needle);
+            if sorted {
+                // Approximate match: last key not greater than the needle in a sorted column.
+                if ordering == Ordering::Greater {
+                    break;
+                }
+                found = Some(row);
+            } else if ordering == Ordering::Equal {
+                found = Some(row);
+                break;
+            }
+        }
+        match found {
+            Some(row) => table.get(row, column).clone(),
+            None => FormulaValue::Error(FormulaError::Na),
+        }
+    }
+
+    // SUMIF(range; criterion[; sum_range]) and COUNTIF(range; criterion). The sum range is
+    // read cell by cell at the same offsets as the tested range.
+    fn sum_if(&mut self, args: &[Expr], count_only: bool) -> FormulaValue {
+        let tested = match self.operand(&args[0]) {
+            Operand::Range(range) => range,
+            Operand::Value(FormulaValue::Error(e)) => return FormulaValue::Error(e),
+            Operand::Value(_) => return FormulaValue::Error(FormulaError::Value),
+        };
+        let criterion = match self.value(&args[1]) {
+            FormulaValue::Error(e) => return FormulaValue::Error(e),
+            value => Criterion::parse(&value),
+        };
+        if count_only {
+            let mut count = tested.stored().filter(|v| criterion.matches(v)).count();
+            if criterion.matches(&FormulaValue::Empty) {
+                count += tested.implicit_empty();
+            }
+            return FormulaValue::Number(count as f64);
+        }
+        let summed = match args.get(2) {
+            Some(expr) => match self.operand(expr) {
+                Operand::Range(range) => range,
+                Operand::Value(FormulaValue::Error(e)) => return FormulaValue::Error(e),
+                Operand::Value(_) => return FormulaValue::Error(FormulaError::Value),
+            },
+            None => tested.clone(),
+        };
+        let mut total = 0.0;
+        for (r, row) in tested.values.iter().enumerate().take(tested.rows) {
+            for (c, value) in row.iter().enumerate().take(tested.cols) {
+                if !criterion.matches(value) {
+                    continue;
+                }
+                match summed.get(r, c) {
+                    FormulaValue::Number(n) => total += n,
+                    FormulaValue::Error(e) => return FormulaValue::Error(*e),
+                    _ => {}
+                }
+            }
+        }
+        Self::number_result(Ok(total))
+    }
+}
+
+impl Criterion {
+    fn parse(value: &FormulaValue) -> Self {
+        let FormulaValue::Text(text) = value else {
+            return Criterion {
+                op: BinaryOp::Eq,
+                operand: value.clone(),
+            };
+        };
+        let (op, rest) = [
+            ("<>", BinaryOp::Ne),
+            ("<=", BinaryOp::Le),
+            (">=", BinaryOp::Ge),
+            ("<", BinaryOp::Lt),
+            (">", BinaryOp::Gt),
+            ("=", BinaryOp::Eq),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| text.strip_prefix(prefix).map(|rest| (op, rest)))
+        .unwrap_or((BinaryOp::Eq, text.as_str()));
+        let operand = if let Ok(n) = rest.trim().parse::<f64>() {
+            FormulaValue::Number(n)
+        } else if rest.eq_ignore_ascii_case("true") || rest.eq_ignore_ascii_case("false") {
+            FormulaValue::Boolean(rest.eq_ignore_ascii_case("true"))
+        } else {
+            FormulaValue::Text(rest.to_string())
+        };
+        Criterion { op, operand }
+    }
+
+    fn matches(&self, value: &FormulaValue) -> bool {
+        if matches!(value, FormulaValue::Error(_)) {
+            return false;
+        }
+        let equal = match (&self.operand, value) {
+            (FormulaValue::Text(pattern), FormulaValue::Text(text)) => {
+                Self::wildcard_match(&pattern.to_lowercase(), &text.to_lowercase())
+            }
+            (FormulaValue::Text(pattern), FormulaValue::Empty) => pattern.is_empty(),
+            (FormulaValue::Empty, FormulaValue::Empty) => true,
+            (FormulaValue::Number(a), FormulaValue::Number(b)) => a == b,
+            (FormulaValue::Boolean(a), FormulaValue::Boolean(b)) => a == b,
+            _ => false,
+        };
+        match self.op {
+            BinaryOp::Eq => equal,
+            BinaryOp::Ne => !equal,
+            _ => {
+                // Ordered comparisons only look at cells of the criterion's own type.
+                let comparable = matches!(
+                    (&self.operand, value),
+                    (FormulaValue::Number(_), FormulaValue::Number(_))
+                        | (FormulaValue::Text(_), FormulaValue::Text(_))
+                        | (FormulaValue::Boolean(_), FormulaValue::Boolean(_))
+                );
+                let ordering = Evaluator::compare(value, &self.operand);
+                comparable
+                    && match self.op {
+                        BinaryOp::Lt => ordering == Ordering::Less,
+                        BinaryOp::Le => ordering != Ordering::Greater,
+                        BinaryOp::Gt => ordering == Ordering::Greater,
+                        _ => ordering != Ordering::Less,
+                    }
+            }
+        }
+    }
+
+    // '*' matches any run of characters and '?' a single one; '~' escapes either.
+    fn wildcard_match(pattern: &str, text: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        let (mut p, mut t) = (0, 0);
+        let mut backtrack: Option<(usize, usize)> = None;
+        while t < text.len() {
+            match pattern.get(p) {
+                Some('*') => {
+                    backtrack = Some((p, t));
+                    p += 1;
+                    continue;
+                }
+                Some('~')
+                    if matches!(pattern.get(p + 1), Some('*' | '?' | '~'))
+                        && pattern[p + 1] == text[t] =>
+                {
+                    p += 2;
+                    t += 1;
+                    continue;
+                }
+                Some('?') => {
+                    p += 1;
+                    t += 1;
+                    continue;
+                }
+                Some(&c) if c == text[t] => {
+                    p += 1;
+                    t += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            match backtrack {
+                Some((star, start)) => {
+                    p = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            }
+        }
+        pattern[p..].iter().all(|c| *c == '*')
+    }
+}
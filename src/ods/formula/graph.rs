@@ -0,0 +1,153 @@
+use super::*;
+use std::collections::BTreeMap;
+
+// Formula cells of a workbook and the ranges each one reads, resolved to sheet indexes.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReferenceGraph {
+    // In sheet/row/column order.
+    pub cells: Vec<FormulaCell>,
+    by_sheet: Vec<BTreeMap<(usize, usize), usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormulaCell {
+    pub sheet_index: usize,
+    pub row: usize,
+    pub col: usize,
+    pub text: String,
+    // Parser message when the formula could not be parsed; it then reads nothing.
+    pub parsed: Result<Formula, String>,
+    pub references: Vec<GraphRange>,
+}
+
+// Range read by a formula. `sheet_index` is None when the named sheet does not exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphRange {
+    pub sheet_index: Option<usize>,
+    pub sheet_name: String,
+    pub start: CellAddress,
+    pub end: CellAddress,
+}
+
+impl ReferenceGraph {
+    pub fn build(workbook: &Workbook) -> Self {
+        let mut graph = ReferenceGraph {
+            cells: Vec::new(),
+            by_sheet: vec![BTreeMap::new(); workbook.sheets.len()],
+        };
+        for (sheet_index, sheet) in workbook.sheets.iter().enumerate() {
+            for (row, cells) in sheet.rows.iter().enumerate() {
+                for (col, cell) in cells.iter().enumerate() {
+                    let Some(text) = &cell.formula else {
+                        continue;
+                    };
+                    let parsed = Formula::parse(text).map_err(|e| e.to_string());
+                    let references = parsed
+                        .as_ref()
+                        .map(Formula::references)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|range| {
+                            let index = Formula::resolve_sheet(workbook, sheet_index, &range);
+                            GraphRange {
+                                sheet_index: index,
+                                sheet_name: index
+                                    .map(|i| workbook.sheets[i].name.clone())
+                                    .or(range.sheet)
+                                    .unwrap_or_default(),
+                                start: range.start,
+                                end: range.end,
+                            }
+                        })
+                        .collect();
+                    graph.by_sheet[sheet_index].insert((row, col), graph.cells.len());
+                    graph.cells.push(FormulaCell {
+                        sheet_index,
+                        row,
+                        col,
+                        text: text.clone(),
+                        parsed,
+                        references,
+                    });
+                }
+            }
+        }
+        graph
+    }
+
+    pub fn formula_at(&self, sheet_index: usize, row: usize, col: usize) -> Option<usize> {
+        self.by_sheet.get(sheet_index)?.get(&(row, col)).copied()
+    }
+
+    // Formula cells inside `range`, in row/column order.
+    pub fn formulas_in(&self, range: &GraphRange) -> Vec<usize> {
+        let Some(cells) = range.sheet_index.and_then(|i| self.by_sheet.get(i)) else {
+            return Vec::new();
+        };
+        let span = (range.start.row, 0)..=(range.end.row, usize::MAX);
+        cells
+            .range(span)
+            .filter(|(&(_, col), _)| (range.start.col..=range.end.col).contains(&col))
+            .map(|(_, &index)| index)
+            .collect()
+    }
+
+    // Formula cells that read the given cell, in sheet/row/column order.
+    pub fn dependents_of(&self, sheet_index: usize, row: usize, col: usize) -> Vec<usize> {
+        (0..self.cells.len())
+            .filter(|&index| {
+                self.cells[index]
+                    .references
+                    .iter()
+                    .any(|range| range.contains(sheet_index, row, col))
+            })
+            .collect()
+    }
+
+    // Formula cells each formula cell reads, by index; sorted and without duplicates.
+    pub fn precedents(&self) -> Vec<Vec<usize>> {
+        self.cells
+            .iter()
+            .map(|cell| {
+                let mut precedents: Vec<usize> = cell
+                    .references
+                    .iter()
+                    .flat_map(|range| self.formulas_in(range))
+                    .collect();
+                precedents.sort_unstable();
+                precedents.dedup();
+                precedents
+            })
+            .collect()
+    }
+}
+
+impl GraphRange {
+    pub fn is_single_cell(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, sheet_index: usize, row: usize, col: usize) -> bool {
+        self.sheet_index == Some(sheet_index)
+            && (self.start.row..=self.end.row).contains(&row)
+            && (self.start.col..=self.end.col).contains(&col)
+    }
+
+    // "B2", "B2:D9", "A:B" or "1:3", without the sheet.
+    pub fn to_a1(&self) -> String {
+        let (start, end) = (self.start.to_a1(), self.end.to_a1());
+        let letters = |a1: &str| {
+            a1.trim_end_matches(|c: char| c.is_ascii_digit())
+                .to_string()
+        };
+        if self.start.row == 0 && self.end.row == RangeRef::LAST_ROW {
+            format!("{}:{}", letters(&start), letters(&end))
+        } else if self.start.col == 0 && self.end.col == RangeRef::LAST_COL {
+            format!("{}:{}", self.start.row + 1, self.end.row + 1)
+        } else if self.is_single_cell() {
+            start
+        } else {
+            format!("{start}:{end}")
+        }
+    }
+}
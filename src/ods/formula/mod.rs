@@ -0,0 +1,324 @@
+use crate::common::errors::AppError;
+use crate::common::time::TimeUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::sheet_model::{CellValue, Workbook};
+use std::cmp::Ordering;
+
+mod evaluator;
+mod functions;
+mod graph;
+mod parser;
+mod recalc;
+mod syntax;
+
+pub use graph::{FormulaCell, GraphRange, ReferenceGraph};
+pub use recalc::{RecalcIssue, RecalcIssueKind, RecalcOutcome, RecalcResult};
+pub use syntax::{FormulaDialect, FormulaSyntax};
+
+// Parsed OpenFormula expression, as stored in table:formula (of:=SUM([.A1:.B2];[$Data.C3])).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    expr: Expr,
+}
+
+// Cell or range reference; `sheet` is None for the sheet holding the formula. Whole columns
+// and rows ([.A:.B], [.1:.3]) run to the last row or column of the sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeRef {
+    pub sheet: Option<String>,
+    pub start: CellAddress,
+    pub end: CellAddress,
+}
+
+// Spreadsheet error values, written the way Calc shows them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormulaError {
+    Div0,
+    Na,
+    Name,
+    Null,
+    Num,
+    Ref,
+    Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormulaValue {
+    Number(f64),
+    Text(String),
+    Boolean(bool),
+    Error(FormulaError),
+    Empty,
+}
+
+// Values of a referenced range, row-major. Rows may be shorter than `cols` and the list may
+// stop before `rows` when the source knows the rest is empty (e.g. a whole column).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RangeValues {
+    pub rows: usize,
+    pub cols: usize,
+    pub values: Vec<Vec<FormulaValue>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Text(String),
+    Boolean(bool),
+    Error(FormulaError),
+    Ref(RangeRef),
+    // Named expression; not supported, evaluates to #NAME?.
+    Name(String),
+    // Omitted function argument, as in IF([.A1];;1).
+    Missing,
+    Negate(Box<Expr>),
+    Percent(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Concat,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Formula {
+    // Every cell or range the formula reads, in source order.
+    pub fn references(&self) -> Vec<RangeRef> {
+        let mut refs = Vec::new();
+        Self::collect_references(&self.expr, &mut refs);
+        refs
+    }
+
+    // Function and named-expression names the evaluator cannot compute, without duplicates.
+    pub fn unsupported_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        Self::collect_unsupported(&self.expr, &mut names);
+        names
+    }
+
+    // Evaluates against `source`, which resolves each reference to its current values and
+    // returns #REF! for unknown sheets. A formula that only points at an empty cell yields 0.
+    pub fn evaluate(
+        &self,
+        source: &mut dyn FnMut(&RangeRef) -> Result<RangeValues, FormulaError>,
+    ) -> FormulaValue {
+        match evaluator::Evaluator::new(source).value(&self.expr) {
+            FormulaValue::Empty => FormulaValue::Number(0.0),
+            value => value,
+        }
+    }
+
+    // Evaluates with the values stored in `workbook`; formula cells contribute their cached
+    // result, so stale caches are read as-is.
+    pub fn evaluate_in_workbook(&self, workbook: &Workbook, sheet_index: usize) -> FormulaValue {
+        self.evaluate(&mut |range| Self::workbook_range(workbook, sheet_index, range))
+    }
+
+    fn workbook_range(
+        workbook: &Workbook,
+        sheet_index: usize,
+        range: &RangeRef,
+    ) -> Result<RangeValues, FormulaError> {
+        let sheet = Self::resolve_sheet(workbook, sheet_index, range)
+            .and_then(|index| workbook.sheets.get(index))
+            .ok_or(FormulaError::Ref)?;
+        // Whole columns and rows keep their full extent, so A:A spans every sheet row.
+        let rows = range.end.row - range.start.row + 1;
+        let cols = range.end.col - range.start.col + 1;
+        // Only the used part of the sheet is materialised; the rest reads as empty.
+        let values = sheet
+            .rows
+            .iter()
+            .skip(range.start.row)
+            .take(rows)
+            .map(|row| {
+                row.iter()
+                    .skip(range.start.col)
+                    .take(cols)
+                    .map(|cell| {
+                        let value = FormulaValue::from_cell_value(&cell.value);
+                        match (&cell.formula, &value) {
+                            (Some(_), FormulaValue::Text(text)) => FormulaError::from_code(text)
+                                .map(FormulaValue::Error)
+                                .unwrap_or(value),
+                            _ => value,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(RangeValues { rows, cols, values })
+    }
+
+    // Sheet a reference points at; names match exactly first, then ignoring ASCII case.
+    fn resolve_sheet(workbook: &Workbook, current: usize, range: &RangeRef) -> Option<usize> {
+        match &range.sheet {
+            None => Some(current),
+            Some(name) => workbook.sheet_index_by_name(name).or_else(|| {
+                workbook
+                    .sheets
+                    .iter()
+                    .position(|s| s.name.eq_ignore_ascii_case(name))
+            }),
+        }
+    }
+
+    fn collect_unsupported(expr: &Expr, names: &mut Vec<String>) {
+        match expr {
+            Expr::Name(name) if !names.contains(name) => names.push(name.clone()),
+            Expr::Call(name, args) => {
+                if !functions::SUPPORTED_FUNCTIONS.contains(&name.as_str()) && !names.contains(name)
+                {
+                    names.push(name.clone());
+                }
+                for arg in args {
+                    Self::collect_unsupported(arg, names);
+                }
+            }
+            Expr::Negate(inner) | Expr::Percent(inner) => Self::collect_unsupported(inner, names),
+            Expr::Binary(_, left, right) => {
+                Self::collect_unsupported(left, names);
+                Self::collect_unsupported(right, names);
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_references(expr: &Expr, refs: &mut Vec<RangeRef>) {
+        match expr {
+            Expr::Ref(range) => refs.push(range.clone()),
+            Expr::Negate(inner) | Expr::Percent(inner) => Self::collect_references(inner, refs),
+            Expr::Binary(_, left, right) => {
+                Self::collect_references(left, refs);
+                Self::collect_references(right, refs);
+            }
+            Expr::Call(_, args) => {
+                for arg in args {
+                    Self::collect_references(arg, refs);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl RangeRef {
+    // Last row and column of a sheet, as Calc and Excel size them.
+    pub const LAST_ROW: usize = 1_048_575;
+    pub const LAST_COL: usize = 16_383;
+
+    pub fn is_whole_columns(&self) -> bool {
+        self.start.row == 0 && self.end.row == Self::LAST_ROW
+    }
+
+    pub fn is_whole_rows(&self) -> bool {
+        self.start.col == 0 && self.end.col == Self::LAST_COL
+    }
+}
+
+impl FormulaError {
+    pub fn code(self) -> &'static str {
+        match self {
+            FormulaError::Div0 => "#DIV/0!",
+            FormulaError::Na => "#N/A",
+            FormulaError::Name => "#NAME?",
+            FormulaError::Null => "#NULL!",
+            FormulaError::Num => "#NUM!",
+            FormulaError::Ref => "#REF!",
+            FormulaError::Value => "#VALUE!",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        [
+            FormulaError::Div0,
+            FormulaError::Na,
+            FormulaError::Name,
+            FormulaError::Null,
+            FormulaError::Num,
+            FormulaError::Ref,
+            FormulaError::Value,
+        ]
+        .into_iter()
+        .find(|e| e.code().eq_ignore_ascii_case(code))
+    }
+}
+
+impl FormulaValue {
+    // Dates become serial numbers, as Calc computes with them.
+    pub fn from_cell_value(value: &CellValue) -> Self {
+        match value {
+            CellValue::String(text) => FormulaValue::Text(text.clone()),
+            CellValue::Number(n) => FormulaValue::Number(*n),
+            CellValue::Boolean(b) => FormulaValue::Boolean(*b),
+            CellValue::Date(iso) => TimeUtil::serial_from_iso8601(iso)
+                .map(FormulaValue::Number)
+                .unwrap_or_else(|| FormulaValue::Text(iso.clone())),
+            CellValue::Empty => FormulaValue::Empty,
+        }
+    }
+
+    // Errors are kept as their display text ("#DIV/0!").
+    pub fn to_cell_value(&self) -> CellValue {
+        match self {
+            FormulaValue::Number(n) => CellValue::Number(*n),
+            FormulaValue::Text(text) => CellValue::String(text.clone()),
+            FormulaValue::Boolean(b) => CellValue::Boolean(*b),
+            FormulaValue::Error(e) => CellValue::String(e.code().to_string()),
+            FormulaValue::Empty => CellValue::Empty,
+        }
+    }
+
+    // Text as Calc would show it with the default format; numbers keep 15 significant digits.
+    pub fn display_text(&self) -> String {
+        match self {
+            FormulaValue::Number(n) => Self::number_text(*n),
+            FormulaValue::Text(text) => text.clone(),
+            FormulaValue::Boolean(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+            FormulaValue::Error(e) => e.code().to_string(),
+            FormulaValue::Empty => String::new(),
+        }
+    }
+
+    fn number_text(n: f64) -> String {
+        let rounded: f64 = format!("{n:.14e}").parse().unwrap_or(n);
+        if rounded == 0.0 {
+            return "0".to_string();
+        }
+        format!("{rounded}")
+    }
+}
+
+impl RangeValues {
+    pub fn get(&self, row: usize, col: usize) -> &FormulaValue {
+        self.values
+            .get(row)
+            .and_then(|r| r.get(col))
+            .unwrap_or(&FormulaValue::Empty)
+    }
+
+    // Cells of the range that are not stored in `values`, always empty.
+    fn implicit_empty(&self) -> usize {
+        let stored: usize = self.values.iter().map(|r| r.len().min(self.cols)).sum();
+        self.rows * self.cols - stored
+    }
+
+    fn stored(&self) -> impl Iterator<Item = &FormulaValue> {
+        self.values
+            .iter()
+            .take(self.rows)
+            .flat_map(|r| r.iter().take(self.cols))
+    }
+}
@@ -0,0 +1,440 @@
+use super::syntax::CellRef;
+use super::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Ref(RangeRef),
+    Error(FormulaError),
+    // Function name when `call` is set (directly followed by '('), otherwise TRUE/FALSE or a name.
+    Ident { name: String, call: bool },
+    Op(&'static str),
+    Open,
+    Close,
+    Separator,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Formula {
+    // Accepts the stored form ("of:=SUM([.A1:.A3])") or the bare expression ("=SUM([.A1:.A3])",
+    // "SUM([.A1:.A3])"). Other namespaces, such as msoxl:, are rejected.
+    pub fn parse(formula: &str) -> Result<Self, AppError> {
+        let body = match formula.split_once(':') {
+            Some((namespace, rest)) if Self::is_namespace(namespace) => {
+                if namespace != "of" {
+                    return Err(AppError::InvalidInput(format!(
+                        "unsupported formula namespace: {namespace}"
+                    )));
+                }
+                rest
+            }
+            _ => formula,
+        };
+        let body = body.trim_start();
+        let body = body.strip_prefix('=').unwrap_or(body);
+        let tokens = Self::tokenize(body)
+            .map_err(|e| AppError::InvalidInput(format!("invalid formula: {e}")))?;
+        if tokens.is_empty() {
+            return Err(AppError::InvalidInput("formula is empty".to_string()));
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser
+            .comparison()
+            .and_then(|expr| match parser.peek() {
+                None => Ok(expr),
+                Some(token) => Err(format!("unexpected {}", Parser::describe(token))),
+            })
+            .map_err(|e| AppError::InvalidInput(format!("invalid formula: {e}")))?;
+        Ok(Formula { expr })
+    }
+
+    fn is_namespace(prefix: &str) -> bool {
+        !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_alphanumeric())
+    }
+
+    fn tokenize(body: &str) -> Result<Vec<Token>, String> {
+        let chars: Vec<char> = body.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                _ if c.is_whitespace() => i += 1,
+                '"' => {
+                    let mut text = String::new();
+                    let mut j = i + 1;
+                    loop {
+                        match chars.get(j) {
+                            None => return Err("unterminated string".to_string()),
+                            Some('"') if chars.get(j + 1) == Some(&'"') => {
+                                text.push('"');
+                                j += 2;
+                            }
+                            Some('"') => break,
+                            Some(&other) => {
+                                text.push(other);
+                                j += 1;
+                            }
+                        }
+                    }
+                    tokens.push(Token::Text(text));
+                    i = j + 1;
+                }
+                '[' => {
+                    let mut j = i + 1;
+                    let mut in_quotes = false;
+                    while j < chars.len() && (in_quotes || chars[j] != ']') {
+                        if chars[j] == '\'' {
+                            in_quotes = !in_quotes;
+                        }
+                        j += 1;
+                    }
+                    if j == chars.len() {
+                        return Err("unterminated reference".to_string());
+                    }
+                    let inner: String = chars[i + 1..j].iter().collect();
+                    tokens.push(Self::reference_token(&inner)?);
+                    i = j + 1;
+                }
+                '#' => {
+                    let rest: String = chars[i..].iter().collect();
+                    let error = [
+                        FormulaError::Div0,
+                        FormulaError::Na,
+                        FormulaError::Name,
+                        FormulaError::Null,
+                        FormulaError::Num,
+                        FormulaError::Ref,
+                        FormulaError::Value,
+                    ]
+                    .into_iter()
+                    .find(|e| rest.to_ascii_uppercase().starts_with(e.code()))
+                    .ok_or_else(|| format!("unknown error value at {rest}"))?;
+                    tokens.push(Token::Error(error));
+                    i += error.code().chars().count();
+                }
+                _ if c.is_ascii_digit() || (c == '.' && Self::digit_at(&chars, i + 1)) => {
+                    let mut j = i;
+                    while Self::digit_at(&chars, j) || chars.get(j) == Some(&'.') {
+                        j += 1;
+                    }
+                    if matches!(chars.get(j), Some('e' | 'E')) {
+                        let exp = if matches!(chars.get(j + 1), Some('+' | '-')) {
+                            j + 2
+                        } else {
+                            j + 1
+                        };
+                        if Self::digit_at(&chars, exp) {
+                            j = exp;
+                            while Self::digit_at(&chars, j) {
+                                j += 1;
+                            }
+                        }
+                    }
+                    let text: String = chars[i..j].iter().collect();
+                    let number = text
+                        .parse::<f64>()
+                        .map_err(|_| format!("invalid number {text}"))?;
+                    tokens.push(Token::Number(number));
+                    i = j;
+                }
+                _ if c.is_alphabetic() || c == '_' => {
+                    let mut j = i;
+                    while j < chars.len()
+                        && (chars[j].is_alphanumeric() || matches!(chars[j], '_' | '.'))
+                    {
+                        j += 1;
+                    }
+                    let name: String = chars[i..j].iter().collect();
+                    let mut next = j;
+                    while chars.get(next).is_some_and(|c| c.is_whitespace()) {
+                        next += 1;
+                    }
+                    tokens.push(Token::Ident {
+                        name,
+                        call: chars.get(next) == Some(&'('),
+                    });
+                    i = j;
+                }
+                '(' => {
+                    tokens.push(Token::Open);
+                    i += 1;
+                }
+                ')' => {
+                    tokens.push(Token::Close);
+                    i += 1;
+                }
+                ';' => {
+                    tokens.push(Token::Separator);
+                    i += 1;
+                }
+                _ => {
+                    let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+                    let op = ["<>", "<=", ">="]
+                        .into_iter()
+                        .find(|op| two == *op)
+                        .or_else(|| {
+                            ["+", "-", "*", "/", "^", "&", "%", "=", "<", ">"]
+                                .into_iter()
+                                .find(|op| op.starts_with(c))
+                        })
+                        .ok_or_else(|| format!("unexpected character '{c}'"))?;
+                    tokens.push(Token::Op(op));
+                    i += op.len();
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    // ".A1", ".$A$1:.B2", "$Data.A1", "$'My data'.A1:.B3" or whole columns and rows (".A:.B",
+    // ".1:.3"); a deleted target ("#REF!") stays an error.
+    fn reference_token(inner: &str) -> Result<Token, String> {
+        if inner.to_ascii_uppercase().contains("#REF!") {
+            return Ok(Token::Error(FormulaError::Ref));
+        }
+        let mut parts = Vec::new();
+        let mut in_quotes = false;
+        let mut last = 0;
+        for (i, c) in inner.char_indices() {
+            if c == '\'' {
+                in_quotes = !in_quotes;
+            } else if c == ':' && !in_quotes {
+                parts.push(&inner[last..i]);
+                last = i + 1;
+            }
+        }
+        parts.push(&inner[last..]);
+        if parts.len() > 2 {
+            return Err(format!("invalid reference [{inner}]"));
+        }
+
+        let mut sheet = None;
+        let mut cells = Vec::new();
+        for (index, part) in parts.iter().enumerate() {
+            let mut dot = None;
+            let mut in_quotes = false;
+            for (i, c) in part.char_indices() {
+                if c == '\'' {
+                    in_quotes = !in_quotes;
+                } else if c == '.' && !in_quotes {
+                    dot = Some(i);
+                }
+            }
+            let (sheet_part, cell_part) = match dot {
+                Some(dot) => (&part[..dot], &part[dot + 1..]),
+                None => ("", *part),
+            };
+            let sheet_part = sheet_part.trim_start_matches('$');
+            if index == 0 && !sheet_part.is_empty() {
+                sheet = Some(match sheet_part.strip_prefix('\'') {
+                    Some(quoted) => quoted
+                        .strip_suffix('\'')
+                        .ok_or_else(|| format!("invalid sheet name in [{inner}]"))?
+                        .replace("''", "'"),
+                    None => sheet_part.to_string(),
+                });
+            }
+            let cell = FormulaSyntax::parse_cell(cell_part)
+                .ok_or_else(|| format!("invalid reference [{inner}]"))?;
+            cells.push(cell);
+        }
+        let first = cells[0];
+        let second = cells.get(1).copied().unwrap_or(first);
+        if !first.same_shape(second) || (cells.len() == 1 && !first.is_cell()) {
+            return Err(format!("invalid reference [{inner}]"));
+        }
+        // A missing row or column spans the whole sheet in that direction.
+        let low = |cell: CellRef| CellAddress {
+            row: cell.row.unwrap_or(0),
+            col: cell.col.unwrap_or(0),
+        };
+        let high = |cell: CellRef| CellAddress {
+            row: cell.row.unwrap_or(RangeRef::LAST_ROW),
+            col: cell.col.unwrap_or(RangeRef::LAST_COL),
+        };
+        Ok(Token::Ref(RangeRef {
+            sheet,
+            start: CellAddress {
+                row: low(first).row.min(low(second).row),
+                col: low(first).col.min(low(second).col),
+            },
+            end: CellAddress {
+                row: high(first).row.max(high(second).row),
+                col: high(first).col.max(high(second).col),
+            },
+        }))
+    }
+
+    fn digit_at(chars: &[char], i: usize) -> bool {
+        chars.get(i).is_some_and(|c| c.is_ascii_digit())
+    }
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_op(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    // Precedence, lowest first: comparison, &, + -, * /, ^, prefix -, postfix %.
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let mut left = self.concat()?;
+        while let Some(op) = self.eat_op(&["=", "<>", "<", "<=", ">", ">="]) {
+            let op = match op {
+                "=" => BinaryOp::Eq,
+                "<>" => BinaryOp::Ne,
+                "<" => BinaryOp::Lt,
+                "<=" => BinaryOp::Le,
+                ">" => BinaryOp::Gt,
+                _ => BinaryOp::Ge,
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.concat()?));
+        }
+        Ok(left)
+    }
+
+    fn concat(&mut self) -> Result<Expr, String> {
+        let mut left = self.additive()?;
+        while self.eat_op(&["&"]).is_some() {
+            left = Expr::Binary(BinaryOp::Concat, Box::new(left), Box::new(self.additive()?));
+        }
+        Ok(left)
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.multiplicative()?;
+        while let Some(op) = self.eat_op(&["+", "-"]) {
+            let op = if op == "+" {
+                BinaryOp::Add
+            } else {
+                BinaryOp::Sub
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+        Ok(left)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut left = self.power()?;
+        while let Some(op) = self.eat_op(&["*", "/"]) {
+            let op = if op == "*" {
+                BinaryOp::Mul
+            } else {
+                BinaryOp::Div
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.power()?));
+        }
+        Ok(left)
+    }
+
+    fn power(&mut self) -> Result<Expr, String> {
+        let mut left = self.prefix()?;
+        while self.eat_op(&["^"]).is_some() {
+            left = Expr::Binary(BinaryOp::Pow, Box::new(left), Box::new(self.prefix()?));
+        }
+        Ok(left)
+    }
+
+    fn prefix(&mut self) -> Result<Expr, String> {
+        match self.eat_op(&["-", "+"]) {
+            Some("-") => Ok(Expr::Negate(Box::new(self.prefix()?))),
+            Some(_) => self.prefix(),
+            None => self.postfix(),
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+        while self.eat_op(&["%"]).is_some() {
+            expr = Expr::Percent(Box::new(expr));
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Text(text)) => Ok(Expr::Text(text)),
+            Some(Token::Ref(range)) => Ok(Expr::Ref(range)),
+            Some(Token::Error(error)) => Ok(Expr::Error(error)),
+            Some(Token::Open) => {
+                let expr = self.comparison()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("missing ')'".to_string()),
+                }
+            }
+            Some(Token::Ident { name, call: true }) => {
+                self.pos += 1;
+                let args = self.arguments()?;
+                Ok(Expr::Call(name.to_ascii_uppercase(), args))
+            }
+            Some(Token::Ident { name, call: false }) => {
+                Ok(match name.to_ascii_uppercase().as_str() {
+                    "TRUE" => Expr::Boolean(true),
+                    "FALSE" => Expr::Boolean(false),
+                    _ => Expr::Name(name),
+                })
+            }
+            Some(token) => Err(format!("unexpected {}", Self::describe(&token))),
+            None => Err("unexpected end of formula".to_string()),
+        }
+    }
+
+    // Arguments after the opening parenthesis; empty slots become Expr::Missing.
+    fn arguments(&mut self) -> Result<Vec<Expr>, String> {
+        let mut args = Vec::new();
+        if self.peek() == Some(&Token::Close) {
+            self.pos += 1;
+            return Ok(args);
+        }
+        loop {
+            let arg = match self.peek() {
+                Some(Token::Separator | Token::Close) => Expr::Missing,
+                _ => self.comparison()?,
+            };
+            args.push(arg);
+            match self.next() {
+                Some(Token::Separator) => continue,
+                Some(Token::Close) => return Ok(args),
+                _ => return Err("missing ')' after function arguments".to_string()),
+            }
+        }
+    }
+
+    fn describe(token: &Token) -> String {
+        match token {
+            Token::Number(n) => format!("number {n}"),
+            Token::Text(text) => format!("text \"{text}\""),
+            Token::Ref(_) => "reference".to_string(),
+            Token::Error(e) => e.code().to_string(),
+            Token::Ident { name, .. } => format!("'{name}'"),
+            Token::Op(op) => format!("'{op}'"),
+            Token::Open => "'('".to_string(),
+            Token::Close => "')'".to_string(),
+            Token::Separator => "';'".to_string(),
+        }
+    }
+}
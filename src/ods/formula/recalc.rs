@@ -0,0 +1,206 @@
+use super::*;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecalcResult {
+    pub sheet_index: usize,
+    pub row: usize,
+    pub col: usize,
+    // Cached value before recalculation.
+    pub previous: FormulaValue,
+    pub value: FormulaValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecalcIssueKind {
+    ParseError,
+    UnsupportedFunction,
+    CircularReference,
+    // Reads a cell that could not be recalculated for one of the other reasons.
+    DependsOnUnresolved,
+}
+
+// Formula cell left with its cached value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecalcIssue {
+    pub sheet_index: usize,
+    pub row: usize,
+    pub col: usize,
+    pub formula: String,
+    pub kind: RecalcIssueKind,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RecalcOutcome {
+    // Every recalculated formula cell, in sheet/row/column order.
+    pub results: Vec<RecalcResult>,
+    pub issues: Vec<RecalcIssue>,
+}
+
+impl Formula {
+    // Evaluates every formula of the workbook after the formula cells it reads, so chains
+    // settle in one pass. Cycles, unparsable formulas and unsupported functions are reported
+    // and, like everything that depends on them, keep their cached value.
+    pub fn recalculate(workbook: &Workbook) -> RecalcOutcome {
+        let graph = ReferenceGraph::build(workbook);
+        let nodes = &graph.cells;
+        let precedents = graph.precedents();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+        for (index, sources) in precedents.iter().enumerate() {
+            for &source in sources {
+                dependents[source].push(index);
+            }
+        }
+
+        // Kahn's algorithm; whatever is left afterwards sits on or behind a cycle.
+        let mut pending: Vec<usize> = precedents.iter().map(Vec::len).collect();
+        let mut queue: VecDeque<usize> = (0..nodes.len()).filter(|&i| pending[i] == 0).collect();
+        let mut order = Vec::with_capacity(nodes.len());
+        while let Some(index) = queue.pop_front() {
+            order.push(index);
+            for &next in &dependents[index] {
+                pending[next] -= 1;
+                if pending[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+        let mut blocked: Vec<bool> = pending.iter().map(|&p| p > 0).collect();
+        // Peel blocked cells that feed no other blocked cell: they only sit behind a cycle.
+        let mut feeds: Vec<usize> = (0..nodes.len())
+            .map(|i| dependents[i].iter().filter(|&&d| blocked[d]).count())
+            .collect();
+        let mut behind_cycle = vec![false; nodes.len()];
+        let mut peel: Vec<usize> = (0..nodes.len())
+            .filter(|&i| blocked[i] && feeds[i] == 0)
+            .collect();
+        while let Some(index) = peel.pop() {
+            behind_cycle[index] = true;
+            blocked[index] = false;
+            for &source in &precedents[index] {
+                if blocked[source] {
+                    feeds[source] -= 1;
+                    if feeds[source] == 0 {
+                        peel.push(source);
+                    }
+                }
+            }
+        }
+
+        let mut values = workbook.clone();
+        let mut resolved = vec![false; nodes.len()];
+        let mut outcome = RecalcOutcome::default();
+        for &index in &order {
+            let node = &nodes[index];
+            let issue = |kind, detail: String| RecalcIssue {
+                sheet_index: node.sheet_index,
+                row: node.row,
+                col: node.col,
+                formula: node.text.clone(),
+                kind,
+                detail,
+            };
+            let formula = match &node.parsed {
+                Ok(formula) => formula,
+                Err(e) => {
+                    outcome
+                        .issues
+                        .push(issue(RecalcIssueKind::ParseError, e.clone()));
+                    continue;
+                }
+            };
+            let unsupported = formula.unsupported_names();
+            if !unsupported.is_empty() {
+                outcome.issues.push(issue(
+                    RecalcIssueKind::UnsupportedFunction,
+                    unsupported.join(", "),
+                ));
+                continue;
+            }
+            if let Some(&source) = precedents[index].iter().find(|&&p| !resolved[p]) {
+                outcome.issues.push(issue(
+                    RecalcIssueKind::DependsOnUnresolved,
+                    Self::cell_label(workbook, &nodes[source]),
+                ));
+                continue;
+            }
+
+            let value = formula.evaluate_in_workbook(&values, node.sheet_index);
+            let cell = &mut values.sheets[node.sheet_index].rows[node.row][node.col];
+            let previous = FormulaValue::from_cell_value(&cell.value);
+            cell.value = value.to_cell_value();
+            resolved[index] = true;
+            outcome.results.push(RecalcResult {
+                sheet_index: node.sheet_index,
+                row: node.row,
+                col: node.col,
+                previous: match previous {
+                    FormulaValue::Text(text) => FormulaError::from_code(&text)
+                        .map(FormulaValue::Error)
+                        .unwrap_or(FormulaValue::Text(text)),
+                    other => other,
+                },
+                value,
+            });
+        }
+        for (index, node) in nodes.iter().enumerate() {
+            let kind = if blocked[index] {
+                RecalcIssueKind::CircularReference
+            } else if behind_cycle[index] {
+                RecalcIssueKind::DependsOnUnresolved
+            } else {
+                continue;
+            };
+            let detail = if kind == RecalcIssueKind::CircularReference {
+                "formula is part of a circular reference".to_string()
+            } else {
+                precedents[index]
+                    .iter()
+                    .find(|&&p| blocked[p] || behind_cycle[p])
+                    .map(|&p| Self::cell_label(workbook, &nodes[p]))
+                    .unwrap_or_default()
+            };
+            outcome.issues.push(RecalcIssue {
+                sheet_index: node.sheet_index,
+                row: node.row,
+                col: node.col,
+                formula: node.text.clone(),
+                kind,
+                detail,
+            });
+        }
+
+        outcome
+            .results
+            .sort_by_key(|r| (r.sheet_index, r.row, r.col));
+        outcome
+            .issues
+            .sort_by_key(|i| (i.sheet_index, i.row, i.col));
+        outcome
+    }
+
+    // "Hoja1.B3", used in issue details to point at the blocking cell.
+    fn cell_label(workbook: &Workbook, node: &FormulaCell) -> String {
+        let address = CellAddress {
+            row: node.row,
+            col: node.col,
+        };
+        format!(
+            "{}.{}",
+            workbook.sheets[node.sheet_index].name,
+            address.to_a1()
+        )
+    }
+}
+
+impl RecalcIssueKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RecalcIssueKind::ParseError => "parse_error",
+            RecalcIssueKind::UnsupportedFunction => "unsupported_function",
+            RecalcIssueKind::CircularReference => "circular_reference",
+            RecalcIssueKind::DependsOnUnresolved => "depends_on_unresolved",
+        }
+    }
+}
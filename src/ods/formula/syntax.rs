@@ -0,0 +1,654 @@
+use super::*;
+use serde::Deserialize;
+
+// Converts formulas between the OpenFormula text stored in content.xml
+// (of:=SUM([.A1:.B2];[$Data.C3])) and the A1 syntax used by Excel and XLSX (SUM(A1:B2,Data!C3)).
+pub struct FormulaSyntax;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormulaDialect {
+    #[default]
+    Odf,
+    Excel,
+}
+
+// Functions whose names differ between the dialects, as (Excel, OpenFormula). Excel's newer
+// functions are stored by Calc under the COM.MICROSOFT namespace, legacy statistics under LEGACY.
+const FUNCTION_NAMES: &[(&str, &str)] = &[
+    ("AGGREGATE", "COM.MICROSOFT.AGGREGATE"),
+    ("CEILING.MATH", "COM.MICROSOFT.CEILING.MATH"),
+    ("CEILING.PRECISE", "COM.MICROSOFT.CEILING.PRECISE"),
+    ("CHIDIST", "LEGACY.CHIDIST"),
+    ("CHIINV", "LEGACY.CHIINV"),
+    ("CHITEST", "LEGACY.CHITEST"),
+    ("CONCAT", "COM.MICROSOFT.CONCAT"),
+    ("ERF.PRECISE", "COM.MICROSOFT.ERF.PRECISE"),
+    ("ERFC.PRECISE", "COM.MICROSOFT.ERFC.PRECISE"),
+    ("FDIST", "LEGACY.FDIST"),
+    ("FILTER", "COM.MICROSOFT.FILTER"),
+    ("FINV", "LEGACY.FINV"),
+    ("FLOOR.MATH", "COM.MICROSOFT.FLOOR.MATH"),
+    ("FLOOR.PRECISE", "COM.MICROSOFT.FLOOR.PRECISE"),
+    ("GAMMALN.PRECISE", "COM.MICROSOFT.GAMMALN.PRECISE"),
+    ("IFS", "COM.MICROSOFT.IFS"),
+    ("ISO.CEILING", "COM.MICROSOFT.ISO.CEILING"),
+    ("LET", "COM.MICROSOFT.LET"),
+    ("MAXIFS", "COM.MICROSOFT.MAXIFS"),
+    ("MINIFS", "COM.MICROSOFT.MINIFS"),
+    ("MODE.MULT", "COM.MICROSOFT.MODE.MULT"),
+    ("MODE.SNGL", "COM.MICROSOFT.MODE.SNGL"),
+    ("NETWORKDAYS.INTL", "COM.MICROSOFT.NETWORKDAYS.INTL"),
+    ("NORM.DIST", "COM.MICROSOFT.NORM.DIST"),
+    ("NORM.INV", "COM.MICROSOFT.NORM.INV"),
+    ("NORM.S.DIST", "COM.MICROSOFT.NORM.S.DIST"),
+    ("NORM.S.INV", "COM.MICROSOFT.NORM.S.INV"),
+    ("NORMSDIST", "LEGACY.NORMSDIST"),
+    ("NORMSINV", "LEGACY.NORMSINV"),
+    ("PERCENTILE.EXC", "COM.MICROSOFT.PERCENTILE.EXC"),
+    ("PERCENTILE.INC", "COM.MICROSOFT.PERCENTILE.INC"),
+    ("QUARTILE.EXC", "COM.MICROSOFT.QUARTILE.EXC"),
+    ("QUARTILE.INC", "COM.MICROSOFT.QUARTILE.INC"),
+    ("RANDARRAY", "COM.MICROSOFT.RANDARRAY"),
+    ("RANK.AVG", "COM.MICROSOFT.RANK.AVG"),
+    ("RANK.EQ", "COM.MICROSOFT.RANK.EQ"),
+    ("SEQUENCE", "COM.MICROSOFT.SEQUENCE"),
+    ("SORT", "COM.MICROSOFT.SORT"),
+    ("SORTBY", "COM.MICROSOFT.SORTBY"),
+    ("STDEV.P", "COM.MICROSOFT.STDEV.P"),
+    ("STDEV.S", "COM.MICROSOFT.STDEV.S"),
+    ("SWITCH", "COM.MICROSOFT.SWITCH"),
+    ("TDIST", "LEGACY.TDIST"),
+    ("TEXTJOIN", "COM.MICROSOFT.TEXTJOIN"),
+    ("TINV", "LEGACY.TINV"),
+    ("UNIQUE", "COM.MICROSOFT.UNIQUE"),
+    ("VAR.P", "COM.MICROSOFT.VAR.P"),
+    ("VAR.S", "COM.MICROSOFT.VAR.S"),
+    ("XLOOKUP", "COM.MICROSOFT.XLOOKUP"),
+    ("XMATCH", "COM.MICROSOFT.XMATCH"),
+];
+
+// One end of a reference; whole-column and whole-row ends ("A" in A:B, "1" in 1:3) leave
+// the other coordinate out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct CellRef {
+    pub(super) col: Option<usize>,
+    pub(super) row: Option<usize>,
+    col_abs: bool,
+    row_abs: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // String literal including its quotes.
+    Str(String),
+    Ref {
+        sheet: Option<String>,
+        start: CellRef,
+        end: Option<CellRef>,
+    },
+    Word(String),
+    Char(char),
+}
+
+impl FormulaSyntax {
+    // "of:" prefix or a bracketed reference outside strings means OpenFormula; anything else,
+    // including plain arithmetic that reads the same in both, is treated as Excel.
+    pub fn detect(formula: &str) -> FormulaDialect {
+        let formula = formula.trim_start();
+        let mut in_string = false;
+        let bracketed = formula.chars().any(|c| {
+            if c == '"' {
+                in_string = !in_string;
+            }
+            c == '[' && !in_string
+        });
+        if formula.starts_with("of:") || bracketed {
+            FormulaDialect::Odf
+        } else {
+            FormulaDialect::Excel
+        }
+    }
+
+    // Stored form ("of:=...") of a formula written in `dialect`, detected when None.
+    pub fn to_odf(formula: &str, dialect: Option<FormulaDialect>) -> String {
+        let formula = formula.trim();
+        match dialect.unwrap_or_else(|| Self::detect(formula)) {
+            FormulaDialect::Excel => Self::excel_to_odf(formula),
+            FormulaDialect::Odf if formula.starts_with("of:") => formula.to_string(),
+            FormulaDialect::Odf => {
+                format!("of:={}", formula.strip_prefix('=').unwrap_or(formula))
+            }
+        }
+    }
+
+    // Stored formula as shown to callers: unchanged for ODF, "=SUM(A1:B2)" for Excel.
+    pub fn from_odf(formula: &str, dialect: FormulaDialect) -> String {
+        match dialect {
+            FormulaDialect::Odf => formula.to_string(),
+            FormulaDialect::Excel => format!("={}", Self::odf_to_excel(formula)),
+        }
+    }
+
+    pub fn odf_to_excel(formula: &str) -> String {
+        let body = formula.strip_prefix("of:").unwrap_or(formula);
+        let body = body.strip_prefix('=').unwrap_or(body);
+        let chars: Vec<char> = body.chars().collect();
+        let mut out = String::new();
+        let mut in_array = false;
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '"' => {
+                    let end = Self::string_end(&chars, i);
+                    out.extend(&chars[i..end]);
+                    i = end;
+                    continue;
+                }
+                '[' => {
+                    let end = Self::bracket_end(&chars, i);
+                    let close = if chars[end - 1] == ']' { end - 1 } else { end };
+                    let inner: String = chars[i + 1..close].iter().collect();
+                    out.push_str(&Self::odf_reference_to_excel(&inner));
+                    i = end;
+                    continue;
+                }
+                '{' => {
+                    in_array = true;
+                    out.push('{');
+                }
+                '}' => {
+                    in_array = false;
+                    out.push('}');
+                }
+                ';' => out.push(','),
+                '|' if in_array => out.push(';'),
+                c if c.is_alphabetic() || c == '_' => {
+                    let end = Self::word_end(&chars, i);
+                    let word: String = chars[i..end].iter().collect();
+                    if chars.get(end) == Some(&'(') {
+                        out.push_str(&Self::excel_function_name(&word));
+                    } else {
+                        out.push_str(&word);
+                    }
+                    i = end;
+                    continue;
+                }
+                c => out.push(c),
+            }
+            i += 1;
+        }
+        out
+    }
+
+    pub fn excel_to_odf(formula: &str) -> String {
+        let body = formula.strip_prefix('=').unwrap_or(formula);
+        let mut out = String::from("of:=");
+        let mut in_array = false;
+        let mut tokens = Self::tokenize_excel(body).into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                Token::Word(s) if tokens.peek() == Some(&Token::Char('(')) => {
+                    out.push_str(&Self::odf_function_name(&s));
+                }
+                Token::Str(s) | Token::Word(s) => out.push_str(&s),
+                Token::Ref { sheet, start, end } => {
+                    out.push('[');
+                    if let Some(sheet) = &sheet {
+                        out.push('$');
+                        out.push_str(&Self::quote_sheet(sheet, false));
+                    }
+                    out.push('.');
+                    out.push_str(&Self::cell_text(start));
+                    if let Some(end) = end {
+                        out.push_str(":.");
+                        out.push_str(&Self::cell_text(end));
+                    }
+                    out.push(']');
+                }
+                Token::Char('{') => {
+                    in_array = true;
+                    out.push('{');
+                }
+                Token::Char('}') => {
+                    in_array = false;
+                    out.push('}');
+                }
+                Token::Char(',') => out.push(';'),
+                Token::Char(';') if in_array => out.push('|'),
+                Token::Char(c) => out.push(c),
+            }
+        }
+        out
+    }
+
+    // Stored formula with relative references written as offsets from (row, col), R1C1 style:
+    // [.B3] in C5 becomes [.R[-2]C[-1]]. Copies of one formula down a column share this form.
+    pub fn relative_form(formula: &str, row: usize, col: usize) -> String {
+        let chars: Vec<char> = formula.chars().collect();
+        let mut out = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '"' => {
+                    let end = Self::string_end(&chars, i);
+                    out.extend(&chars[i..end]);
+                    i = end;
+                }
+                '[' => {
+                    let end = Self::bracket_end(&chars, i);
+                    let close = if chars[end - 1] == ']' { end - 1 } else { end };
+                    let inner: String = chars[i + 1..close].iter().collect();
+                    let parts: Vec<String> = Self::split_outside_quotes(&inner, ':')
+                        .into_iter()
+                        .map(|part| {
+                            let (sheet, cell) =
+                                Self::rsplit_outside_quotes(part, '.').unwrap_or(("", part));
+                            match Self::parse_cell(cell) {
+                                Some(cell) => {
+                                    format!("{sheet}.{}", Self::offset_text(cell, row, col))
+                                }
+                                None => part.to_string(),
+                            }
+                        })
+                        .collect();
+                    out.push('[');
+                    out.push_str(&parts.join(":"));
+                    out.push(']');
+                    i = end;
+                }
+                c => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        }
+        out
+    }
+
+    fn offset_text(cell: CellRef, row: usize, col: usize) -> String {
+        let row_text = match cell.row {
+            None => String::new(),
+            Some(r) if cell.row_abs => format!("R{}", r + 1),
+            Some(r) => format!("R[{}]", r as i64 - row as i64),
+        };
+        let col_text = match cell.col {
+            None => String::new(),
+            Some(c) if cell.col_abs => format!("C{}", c + 1),
+            Some(c) => format!("C[{}]", c as i64 - col as i64),
+        };
+        row_text + &col_text
+    }
+
+    // Moves relative references of an A1 formula by (rows, cols), as XLSX shared formulas require.
+    pub fn shift_excel(formula: &str, rows: i64, cols: i64) -> String {
+        let move_by = |index: usize, by: i64, abs: bool| {
+            if abs {
+                index
+            } else {
+                (index as i64 + by).max(0) as usize
+            }
+        };
+        let shift = |cell: CellRef| CellRef {
+            row: cell.row.map(|row| move_by(row, rows, cell.row_abs)),
+            col: cell.col.map(|col| move_by(col, cols, cell.col_abs)),
+            ..cell
+        };
+        let mut out = String::new();
+        for token in Self::tokenize_excel(formula) {
+            match token {
+                Token::Str(s) | Token::Word(s) => out.push_str(&s),
+                Token::Ref { sheet, start, end } => {
+                    if let Some(sheet) = &sheet {
+                        out.push_str(&Self::quote_sheet(sheet, true));
+                        out.push('!');
+                    }
+                    out.push_str(&Self::cell_text(shift(start)));
+                    if let Some(end) = end {
+                        out.push(':');
+                        out.push_str(&Self::cell_text(shift(end)));
+                    }
+                }
+                Token::Char(c) => out.push(c),
+            }
+        }
+        out
+    }
+
+    // Function names are case-insensitive in Excel and upper case in ODF; XLSX files may
+    // carry the _xlfn./_xlws. markers Excel adds to newer functions.
+    fn odf_function_name(name: &str) -> String {
+        let upper = name.to_uppercase();
+        let bare = upper
+            .strip_prefix("_XLFN.")
+            .or_else(|| upper.strip_prefix("_XLWS."))
+            .unwrap_or(&upper);
+        FUNCTION_NAMES
+            .iter()
+            .find(|(excel, _)| *excel == bare)
+            .map(|(_, odf)| odf.to_string())
+            .unwrap_or_else(|| bare.to_string())
+    }
+
+    fn excel_function_name(name: &str) -> String {
+        FUNCTION_NAMES
+            .iter()
+            .find(|(_, odf)| odf.eq_ignore_ascii_case(name))
+            .map(|(excel, _)| excel.to_string())
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    // ".A1:.B2", "$Data.A1" or "$'My data'.$A$1:.B2" to the A1 form.
+    fn odf_reference_to_excel(inner: &str) -> String {
+        let parts = Self::split_outside_quotes(inner, ':');
+        let mut out = String::new();
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                out.push(':');
+            }
+            match Self::rsplit_outside_quotes(part, '.') {
+                Some((sheet, cell)) => {
+                    let sheet = sheet.trim_start_matches('$');
+                    if i == 0 && !sheet.is_empty() {
+                        out.push_str(&Self::quote_sheet(&Self::unquote(sheet), true));
+                        out.push('!');
+                    }
+                    out.push_str(cell);
+                }
+                None => out.push_str(part),
+            }
+        }
+        out
+    }
+
+    fn tokenize_excel(body: &str) -> Vec<Token> {
+        let chars: Vec<char> = body.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c == '"' {
+                let end = Self::string_end(&chars, i);
+                tokens.push(Token::Str(chars[i..end].iter().collect()));
+                i = end;
+            } else if c == '\'' {
+                // Quoted sheet name; only meaningful when a reference follows the '!'.
+                let end = Self::quoted_end(&chars, i, '\'');
+                let name: String = chars[i..end].iter().collect();
+                if chars.get(end) == Some(&'!') {
+                    if let Some((token, next)) =
+                        Self::read_reference(&chars, end + 1, Some(Self::unquote(&name)))
+                    {
+                        tokens.push(token);
+                        i = next;
+                        continue;
+                    }
+                }
+                tokens.push(Token::Word(name));
+                i = end;
+            } else if c.is_ascii_digit() || (c == '.' && Self::is_digit_at(&chars, i + 1)) {
+                // Whole rows ("1:3") start like a number.
+                if let Some((token, next)) = Self::read_reference(&chars, i, None) {
+                    tokens.push(token);
+                    i = next;
+                    continue;
+                }
+                let mut end = i;
+                while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.') {
+                    end += 1;
+                }
+                if end < chars.len() && matches!(chars[end], 'e' | 'E') {
+                    let exp = if matches!(chars.get(end + 1), Some('+' | '-')) {
+                        end + 2
+                    } else {
+                        end + 1
+                    };
+                    if Self::is_digit_at(&chars, exp) {
+                        end = exp;
+                        while Self::is_digit_at(&chars, end) {
+                            end += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Word(chars[i..end].iter().collect()));
+                i = end;
+            } else if c.is_alphabetic() || c == '_' || c == '$' {
+                let end = Self::word_end(&chars, i);
+                let word: String = chars[i..end].iter().collect();
+                if chars.get(end) == Some(&'!') {
+                    if let Some((token, next)) =
+                        Self::read_reference(&chars, end + 1, Some(word.clone()))
+                    {
+                        tokens.push(token);
+                        i = next;
+                        continue;
+                    }
+                }
+                if chars.get(end) != Some(&'(') {
+                    if let Some((token, next)) = Self::read_reference(&chars, i, None) {
+                        tokens.push(token);
+                        i = next;
+                        continue;
+                    }
+                }
+                tokens.push(Token::Word(word));
+                i = end;
+            } else {
+                tokens.push(Token::Char(c));
+                i += 1;
+            }
+        }
+        tokens
+    }
+
+    // Reads "A1", "A1:B2", "A:B" or "1:3" starting at `start`; returns the token and the
+    // index after it.
+    fn read_reference(
+        chars: &[char],
+        start: usize,
+        sheet: Option<String>,
+    ) -> Option<(Token, usize)> {
+        let end = Self::word_end(chars, start);
+        let first = Self::parse_cell(&chars[start..end].iter().collect::<String>())?;
+        if chars.get(end) == Some(&'(') {
+            return None;
+        }
+        let mut next = end;
+        let mut second = None;
+        if chars.get(end) == Some(&':') {
+            let end2 = Self::word_end(chars, end + 1);
+            if let Some(cell) = Self::parse_cell(&chars[end + 1..end2].iter().collect::<String>())
+                .filter(|cell| cell.same_shape(first))
+            {
+                second = Some(cell);
+                next = end2;
+            }
+        }
+        // A lone column or row ("A", "12") is a name or a number, not a reference.
+        if second.is_none() && !first.is_cell() {
+            return None;
+        }
+        Some((
+            Token::Ref {
+                sheet,
+                start: first,
+                end: second,
+            },
+            next,
+        ))
+    }
+
+    // "B2", "$B$2", or a whole-column or whole-row end such as "$B" or "2".
+    pub(super) fn parse_cell(text: &str) -> Option<CellRef> {
+        let dollar = text.starts_with('$');
+        let rest = text.strip_prefix('$').unwrap_or(text);
+        let split = rest
+            .find(|c: char| c == '$' || c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (letters, digits) = rest.split_at(split);
+        let row_abs = digits.starts_with('$') || (letters.is_empty() && dollar);
+        let digits = digits.strip_prefix('$').unwrap_or(digits);
+        if letters.len() > 3
+            || !letters.chars().all(|c| c.is_ascii_alphabetic())
+            || !digits.chars().all(|c| c.is_ascii_digit())
+            || (digits.is_empty() && (letters.is_empty() || row_abs))
+        {
+            return None;
+        }
+        let col = match letters {
+            "" => None,
+            letters => {
+                let number = letters.chars().fold(0, |acc, c| {
+                    acc * 26 + (c.to_ascii_uppercase() as u8 - b'A') as usize + 1
+                });
+                if number > RangeRef::LAST_COL + 1 {
+                    return None;
+                }
+                Some(number - 1)
+            }
+        };
+        let row = match digits {
+            "" => None,
+            digits => {
+                let number: usize = digits.parse().ok()?;
+                if number == 0 || number > RangeRef::LAST_ROW + 1 {
+                    return None;
+                }
+                Some(number - 1)
+            }
+        };
+        Some(CellRef {
+            col,
+            row,
+            col_abs: dollar && col.is_some(),
+            row_abs,
+        })
+    }
+
+    fn cell_text(cell: CellRef) -> String {
+        let mut text = String::new();
+        if let Some(col) = cell.col {
+            let a1 = CellAddress { row: 0, col }.to_a1();
+            if cell.col_abs {
+                text.push('$');
+            }
+            text.push_str(a1.trim_end_matches(|c: char| c.is_ascii_digit()));
+        }
+        if let Some(row) = cell.row {
+            if cell.row_abs {
+                text.push('$');
+            }
+            text.push_str(&(row + 1).to_string());
+        }
+        text
+    }
+
+    // Sheet names need quotes unless they are a plain identifier; `excel` picks the bare-name rules.
+    fn quote_sheet(name: &str, excel: bool) -> String {
+        let looks_like_cell = excel && Self::parse_cell(name).is_some_and(CellRef::is_cell);
+        let plain = !name.is_empty()
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && !looks_like_cell;
+        if plain {
+            name.to_string()
+        } else {
+            format!("'{}'", name.replace('\'', "''"))
+        }
+    }
+
+    fn unquote(name: &str) -> String {
+        match name.strip_prefix('\'').and_then(|n| n.strip_suffix('\'')) {
+            Some(inner) => inner.replace("''", "'"),
+            None => name.to_string(),
+        }
+    }
+
+    fn word_end(chars: &[char], start: usize) -> usize {
+        let mut end = start;
+        while end < chars.len()
+            && (chars[end].is_alphanumeric() || matches!(chars[end], '_' | '$' | '.'))
+        {
+            end += 1;
+        }
+        end
+    }
+
+    fn is_digit_at(chars: &[char], i: usize) -> bool {
+        chars.get(i).is_some_and(|c| c.is_ascii_digit())
+    }
+
+    // Index just past a "..." literal starting at `start`; doubled quotes stay inside.
+    fn string_end(chars: &[char], start: usize) -> usize {
+        Self::quoted_end(chars, start, '"')
+    }
+
+    fn quoted_end(chars: &[char], start: usize, quote: char) -> usize {
+        let mut i = start + 1;
+        while i < chars.len() {
+            if chars[i] == quote {
+                if chars.get(i + 1) == Some(&quote) {
+                    i += 2;
+                    continue;
+                }
+                return i + 1;
+            }
+            i += 1;
+        }
+        chars.len()
+    }
+
+    // Index just past the ']' closing the reference opened at `start`.
+    fn bracket_end(chars: &[char], start: usize) -> usize {
+        let mut i = start + 1;
+        while i < chars.len() {
+            match chars[i] {
+                '\'' => i = Self::quoted_end(chars, i, '\''),
+                ']' => return i + 1,
+                _ => i += 1,
+            }
+        }
+        chars.len()
+    }
+
+    fn split_outside_quotes(text: &str, sep: char) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let mut in_quotes = false;
+        let mut last = 0;
+        for (i, c) in text.char_indices() {
+            if c == '\'' {
+                in_quotes = !in_quotes;
+            } else if c == sep && !in_quotes {
+                parts.push(&text[last..i]);
+                last = i + c.len_utf8();
+            }
+        }
+        parts.push(&text[last..]);
+        parts
+    }
+
+    fn rsplit_outside_quotes(text: &str, sep: char) -> Option<(&str, &str)> {
+        let mut in_quotes = false;
+        let mut found = None;
+        for (i, c) in text.char_indices() {
+            if c == '\'' {
+                in_quotes = !in_quotes;
+            } else if c == sep && !in_quotes {
+                found = Some(i);
+            }
+        }
+        found.map(|i| (&text[..i], &text[i + sep.len_utf8()..]))
+    }
+}
+
+impl CellRef {
+    // Both coordinates present, as opposed to a whole-column or whole-row end.
+    pub(super) fn is_cell(self) -> bool {
+        self.col.is_some() && self.row.is_some()
+    }
+
+    // Ends of one reference must agree: A1:B2, A:B or 1:2, never A1:B.
+    pub(super) fn same_shape(self, other: CellRef) -> bool {
+        self.col.is_some() == other.col.is_some() && self.row.is_some() == other.row.is_some()
+    }
+}
@@ -0,0 +1,183 @@
+use crate::common::errors::AppError;
+
+// Picture formats that can be embedded under Pictures/.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn media_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+// Format and natural size of an image file, read from its own bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageInfo {
+    pub format: ImageFormat,
+    // Natural size in centimetres; None when the file does not state one (e.g. an SVG
+    // with neither width/height nor a viewBox).
+    pub size_cm: Option<(f64, f64)>,
+}
+
+// Pixels are taken at the CSS resolution of 96 per inch.
+const CM_PER_PX: f64 = 2.54 / 96.0;
+
+impl ImageInfo {
+    // Detects the format from the content, not the file name. A zero dimension leaves the
+    // natural size unstated, as nothing can be scaled from it.
+    pub fn inspect(data: &[u8]) -> Result<Self, AppError> {
+        let (format, size_cm) = if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            let size = Self::png_size(data).map(|(w, h)| (w * CM_PER_PX, h * CM_PER_PX));
+            (ImageFormat::Png, size)
+        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            let size = Self::jpeg_size(data).map(|(w, h)| (w * CM_PER_PX, h * CM_PER_PX));
+            (ImageFormat::Jpeg, size)
+        } else if let Some(root) = Self::svg_root(data) {
+            (ImageFormat::Svg, Self::svg_size(&root))
+        } else {
+            return Err(AppError::InvalidInput(
+                "unsupported image: expected a PNG, JPEG or SVG file".to_string(),
+            ));
+        };
+        Ok(Self {
+            format,
+            size_cm: size_cm.filter(|&(w, h)| w > 0.0 && h > 0.0),
+        })
+    }
+
+    // ODF length ("2.5cm", "10mm", "1in", "12pt", "96px") in centimetres.
+    pub fn length_cm(text: &str) -> Option<f64> {
+        let text = text.trim();
+        let split = text
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+            .unwrap_or(text.len());
+        let value: f64 = text[..split].parse().ok()?;
+        let factor = match text[split..].trim() {
+            "cm" => 1.0,
+            "mm" => 0.1,
+            "in" => 2.54,
+            "pt" => 2.54 / 72.0,
+            "pc" => 2.54 / 6.0,
+            "px" | "" => CM_PER_PX,
+            _ => return None,
+        };
+        Some(value * factor).filter(|cm| cm.is_finite())
+    }
+
+    pub fn format_cm(cm: f64) -> String {
+        // Adding 0.0 turns a negative zero into "0cm".
+        let rounded = (cm * 1000.0).round() / 1000.0 + 0.0;
+        format!("{rounded}cm")
+    }
+
+    fn png_size(data: &[u8]) -> Option<(f64, f64)> {
+        // IHDR is always the first chunk: width and height follow its type tag.
+        if data.get(12..16)? != b"IHDR" {
+            return None;
+        }
+        let width = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+        let height = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+        Some((width as f64, height as f64))
+    }
+
+    fn jpeg_size(data: &[u8]) -> Option<(f64, f64)> {
+        // Walk the marker segments up to the first start-of-frame.
+        let mut pos = 2usize;
+        while pos + 4 <= data.len() {
+            if data[pos] != 0xFF {
+                return None;
+            }
+            let marker = data[pos + 1];
+            if marker == 0xFF {
+                pos += 1;
+                continue;
+            }
+            if marker == 0xD8 || (0xD0..=0xD7).contains(&marker) || marker == 0x01 {
+                pos += 2;
+                continue;
+            }
+            let len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+            let is_sof = (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_sof {
+                let segment = data.get(pos + 4..pos + 2 + len)?;
+                let height = u16::from_be_bytes([*segment.get(1)?, *segment.get(2)?]);
+                let width = u16::from_be_bytes([*segment.get(3)?, *segment.get(4)?]);
+                return Some((width as f64, height as f64));
+            }
+            pos += 2 + len;
+        }
+        None
+    }
+
+    // Start tag of the root svg element, when the data is an SVG document.
+    fn svg_root(data: &[u8]) -> Option<String> {
+        let text = std::str::from_utf8(data).ok()?;
+        let start = text.find("<svg")?;
+        let after = text[start + 4..].chars().next()?;
+        if !(after.is_whitespace() || after == '>' || after == '/') {
+            return None;
+        }
+        let end = text[start..].find('>')?;
+        Some(text[start..start + end].to_string())
+    }
+
+    fn svg_size(root: &str) -> Option<(f64, f64)> {
+        let width = Self::svg_attr(root, "width").and_then(|v| Self::length_cm(&v));
+        let height = Self::svg_attr(root, "height").and_then(|v| Self::length_cm(&v));
+        if let (Some(w), Some(h)) = (width, height) {
+            if w > 0.0 && h > 0.0 {
+                return Some((w, h));
+            }
+        }
+        // viewBox="min-x min-y width height", in user units (pixels).
+        let view_box = Self::svg_attr(root, "viewBox")?;
+        let numbers: Vec<f64> = view_box
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| s.parse().ok())
+            .collect();
+        match numbers.as_slice() {
+            [_, _, w, h] if *w > 0.0 && *h > 0.0 => Some((w * CM_PER_PX, h * CM_PER_PX)),
+            _ => None,
+        }
+    }
+
+    fn svg_attr(tag: &str, key: &str) -> Option<String> {
+        let mut rest = tag;
+        while let Some(pos) = rest.find(key) {
+            let before = rest[..pos].chars().last();
+            let after = &rest[pos + key.len()..];
+            rest = after;
+            if !before.is_some_and(char::is_whitespace) {
+                continue;
+            }
+            let Some(after) = after.trim_start().strip_prefix('=') else {
+                continue;
+            };
+            let after = after.trim_start();
+            let quote = after.chars().next()?;
+            if quote != '"' && quote != '\'' {
+                continue;
+            }
+            let value = &after[1..];
+            return value.find(quote).map(|end| value[..end].to_string());
+        }
+        None
+    }
+}
@@ -1,4 +1,29 @@
-pub struct Manifest;
+use crate::common::errors::AppError;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+// META-INF/manifest.xml as a list of file entries. Entries read from a file keep their
+// source text, so encryption data and extension attributes survive a rewrite untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    // Everything up to and including the manifest:manifest start tag.
+    head: String,
+    // Namespace prefix of the root element, reused for new entries ("manifest").
+    prefix: String,
+    entries: Vec<ManifestEntry>,
+    // Root children other than file entries, written back after them.
+    other: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub full_path: String,
+    pub media_type: String,
+    // The part is encrypted (the entry carries manifest:encryption-data).
+    pub encrypted: bool,
+    raw: Option<String>,
+}
 
 impl Manifest {
     // Minimal manifest listing the XML entries used by this project.
@@ -12,4 +37,195 @@ impl Manifest {
   <manifest:file-entry manifest:full-path="settings.xml" manifest:media-type="text/xml"/>
 </manifest:manifest>"#
     }
+
+    pub fn parse(xml: &str) -> Result<Self, AppError> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(false);
+        let mut manifest = Manifest {
+            head: String::new(),
+            prefix: String::new(),
+            entries: Vec::new(),
+            other: Vec::new(),
+        };
+        let mut depth = 0usize;
+        // Start offset of the root child being read, and whether it is a file entry.
+        let mut child: Option<(usize, bool)> = None;
+        let mut closed = false;
+        loop {
+            let before = reader.buffer_position() as usize;
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+            let after = reader.buffer_position() as usize;
+            match &event {
+                Event::Start(e) if depth == 0 => {
+                    if e.local_name().as_ref() != b"manifest" {
+                        return Err(AppError::InvalidOdsFormat(
+                            "manifest root must be manifest:manifest".to_string(),
+                        ));
+                    }
+                    manifest.head = xml[..after].to_string();
+                    manifest.prefix = Self::prefix_of(e);
+                    depth = 1;
+                }
+                Event::Start(e) | Event::Empty(e) if depth == 1 => {
+                    let is_entry = e.local_name().as_ref() == b"file-entry";
+                    if is_entry {
+                        manifest.entries.push(ManifestEntry::from_tag(e)?);
+                    }
+                    if matches!(event, Event::Start(_)) {
+                        child = Some((before, is_entry));
+                        depth += 1;
+                    } else {
+                        Self::close_child(&mut manifest, &xml[before..after], is_entry);
+                    }
+                }
+                Event::Start(e) | Event::Empty(e) => {
+                    let in_entry = matches!(child, Some((_, true)));
+                    if in_entry && e.local_name().as_ref() == b"encryption-data" {
+                        if let Some(entry) = manifest.entries.last_mut() {
+                            entry.encrypted = true;
+                        }
+                    }
+                    if matches!(event, Event::Start(_)) {
+                        depth += 1;
+                    }
+                }
+                Event::End(_) => {
+                    depth = depth.saturating_sub(1);
+                    match (depth, child) {
+                        (1, Some((start, is_entry))) => {
+                            Self::close_child(&mut manifest, &xml[start..after], is_entry);
+                            child = None;
+                        }
+                        (0, _) => closed = true,
+                        _ => {}
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        if !closed {
+            return Err(AppError::InvalidOdsFormat(
+                "manifest has no manifest:manifest element".to_string(),
+            ));
+        }
+        Ok(manifest)
+    }
+
+    fn close_child(manifest: &mut Manifest, raw: &str, is_entry: bool) {
+        match manifest.entries.last_mut() {
+            Some(entry) if is_entry => entry.raw = Some(raw.to_string()),
+            _ => manifest.other.push(raw.to_string()),
+        }
+    }
+
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, full_path: &str) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|e| e.full_path == full_path)
+    }
+
+    // Lists a part, or changes the media type of an entry already listed.
+    pub fn add_file(&mut self, full_path: &str, media_type: &str) {
+        match self.entries.iter_mut().find(|e| e.full_path == full_path) {
+            Some(entry) if entry.media_type == media_type => {}
+            Some(entry) => {
+                entry.media_type = media_type.to_string();
+                entry.raw = None;
+            }
+            None => self.entries.push(ManifestEntry {
+                full_path: full_path.to_string(),
+                media_type: media_type.to_string(),
+                encrypted: false,
+                raw: None,
+            }),
+        }
+    }
+
+    // Drops the entry of a part; returns whether it was listed.
+    pub fn remove_file(&mut self, full_path: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.full_path != full_path);
+        self.entries.len() != before
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut xml = self.head.clone();
+        xml.push('\n');
+        for entry in &self.entries {
+            xml.push(' ');
+            match &entry.raw {
+                Some(raw) => xml.push_str(raw),
+                None => xml.push_str(&format!(
+                    "<{p}file-entry {p}full-path=\"{}\" {p}media-type=\"{}\"/>",
+                    escape(entry.full_path.as_str()),
+                    escape(entry.media_type.as_str()),
+                    p = self.prefix
+                )),
+            }
+            xml.push('\n');
+        }
+        for raw in &self.other {
+            xml.push(' ');
+            xml.push_str(raw);
+            xml.push('\n');
+        }
+        xml.push_str(&format!("</{}manifest>\n", self.prefix));
+        xml
+    }
+
+    // Media type a part must be listed with, when its extension fixes one.
+    pub fn media_type_for(path: &str) -> Option<&'static str> {
+        let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "xml" => Some("text/xml"),
+            "rdf" => Some("application/rdf+xml"),
+            "png" => Some("image/png"),
+            "jpg" | "jpeg" => Some("image/jpeg"),
+            "gif" => Some("image/gif"),
+            "svg" => Some("image/svg+xml"),
+            _ => None,
+        }
+    }
+
+    fn prefix_of(e: &BytesStart<'_>) -> String {
+        let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+        match name.rsplit_once(':') {
+            Some((prefix, _)) => format!("{prefix}:"),
+            None => String::new(),
+        }
+    }
+}
+
+impl ManifestEntry {
+    // Source text is attached once the whole element has been read.
+    fn from_tag(e: &BytesStart<'_>) -> Result<Self, AppError> {
+        let mut full_path = None;
+        let mut media_type = String::new();
+        for attr in e.attributes() {
+            let attr = attr.map_err(|x| AppError::XmlParseError(x.to_string()))?;
+            let value = attr
+                .unescape_value()
+                .map_err(|x| AppError::XmlParseError(x.to_string()))?
+                .into_owned();
+            match attr.key.local_name().as_ref() {
+                b"full-path" => full_path = Some(value),
+                b"media-type" => media_type = value,
+                _ => {}
+            }
+        }
+        let full_path = full_path.ok_or_else(|| {
+            AppError::InvalidOdsFormat("manifest entry without full-path".to_string())
+        })?;
+        Ok(Self {
+            full_path,
+            media_type,
+            encrypted: false,
+            raw: None,
+        })
+    }
 }
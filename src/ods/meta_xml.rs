@@ -0,0 +1,414 @@
+use crate::common::errors::AppError;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use serde::Serialize;
+use std::io::Cursor;
+
+const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
+const META_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:meta:1.0";
+
+// Document-level properties stored in meta.xml.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DocumentProperties {
+    pub title: Option<String>,
+    pub subject: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    pub initial_creator: Option<String>,
+    pub creator: Option<String>,
+    pub creation_date: Option<String>,
+    pub modification_date: Option<String>,
+    pub editing_cycles: Option<u32>,
+    pub generator: Option<String>,
+    pub user_defined: Vec<UserDefinedProperty>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UserDefinedProperty {
+    pub name: String,
+    pub value_type: String,
+    pub value: String,
+}
+
+// Partial update: `None` keeps the current element, an empty string removes it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentPropertiesUpdate {
+    pub title: Option<String>,
+    pub subject: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Option<Vec<String>>,
+    pub initial_creator: Option<String>,
+    pub creator: Option<String>,
+    pub creation_date: Option<String>,
+    pub user_defined: Vec<UserDefinedProperty>,
+    pub remove_user_defined: Vec<String>,
+}
+
+pub struct MetaXml;
+
+struct MetaElement {
+    qname: &'static str,
+    attrs: Vec<(&'static str, String)>,
+    text: String,
+}
+
+impl MetaXml {
+    pub fn parse(meta: &str) -> Result<DocumentProperties, AppError> {
+        let mut reader = Reader::from_str(meta);
+        reader.config_mut().trim_text(false);
+
+        let mut props = DocumentProperties::default();
+        let mut in_meta = false;
+        let mut child_depth = 0usize;
+        let mut current: Option<(String, Option<(String, String)>)> = None;
+        let mut text = String::new();
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) if Self::local_name(e.name().as_ref()) == b"meta" => {
+                    in_meta = true;
+                    child_depth = 0;
+                }
+                Ok(Event::End(e)) if Self::local_name(e.name().as_ref()) == b"meta" => {
+                    in_meta = false;
+                }
+                Ok(Event::Start(e)) if in_meta => {
+                    child_depth += 1;
+                    if child_depth == 1 {
+                        let local = String::from_utf8_lossy(Self::local_name(e.name().as_ref()))
+                            .into_owned();
+                        let user_defined = if local == "user-defined" {
+                            Some(Self::user_defined_attrs(&e, reader.decoder()))
+                        } else {
+                            None
+                        };
+                        current = Some((local, user_defined));
+                        text.clear();
+                    }
+                }
+                Ok(Event::Empty(e)) if in_meta && child_depth == 0 => {
+                    if Self::local_name(e.name().as_ref()) == b"user-defined" {
+                        let (name, value_type) = Self::user_defined_attrs(&e, reader.decoder());
+                        props.user_defined.push(UserDefinedProperty {
+                            name,
+                            value_type,
+                            value: String::new(),
+                        });
+                    }
+                }
+                Ok(Event::Text(t)) if in_meta && child_depth >= 1 => {
+                    let decoded = t
+                        .unescape()
+                        .map_err(|x| AppError::XmlParseError(x.to_string()))?;
+                    text.push_str(&decoded);
+                }
+                Ok(Event::End(_)) if in_meta && child_depth > 0 => {
+                    child_depth -= 1;
+                    if child_depth == 0 {
+                        if let Some((local, user_defined)) = current.take() {
+                            Self::assign(&mut props, &local, user_defined, text.clone());
+                        }
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(e) => return Err(AppError::XmlParseError(e.to_string())),
+            }
+        }
+
+        Ok(props)
+    }
+
+    pub fn apply_update(meta: &str, update: &DocumentPropertiesUpdate) -> Result<String, AppError> {
+        let mut drop: Vec<&[u8]> = Vec::new();
+        let mut additions = Vec::new();
+
+        let simple = [
+            (&update.title, "dc:title"),
+            (&update.subject, "dc:subject"),
+            (&update.description, "dc:description"),
+            (&update.initial_creator, "meta:initial-creator"),
+            (&update.creator, "dc:creator"),
+            (&update.creation_date, "meta:creation-date"),
+        ];
+        for (value, qname) in simple {
+            if let Some(value) = value {
+                drop.push(Self::local_name(qname.as_bytes()));
+                if !value.is_empty() {
+                    additions.push(MetaElement {
+                        qname,
+                        attrs: Vec::new(),
+                        text: value.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Some(keywords) = &update.keywords {
+            drop.push(b"keyword");
+            for keyword in keywords.iter().filter(|k| !k.is_empty()) {
+                additions.push(MetaElement {
+                    qname: "meta:keyword",
+                    attrs: Vec::new(),
+                    text: keyword.clone(),
+                });
+            }
+        }
+
+        let mut removed_names: Vec<&str> = update
+            .remove_user_defined
+            .iter()
+            .map(|n| n.as_str())
+            .collect();
+        for prop in &update.user_defined {
+            removed_names.push(prop.name.as_str());
+            additions.push(MetaElement {
+                qname: "meta:user-defined",
+                attrs: vec![
+                    ("meta:name", prop.name.clone()),
+                    ("meta:value-type", prop.value_type.clone()),
+                ],
+                text: prop.value.clone(),
+            });
+        }
+
+        Self::rewrite(meta, &drop, &removed_names, additions)
+    }
+
+    // Marks a save: bumps the modification date, editing cycles and generator.
+    pub fn stamp_modified(meta: &str, date: &str, generator: &str) -> Result<String, AppError> {
+        let cycles = Self::parse(meta)?.editing_cycles.unwrap_or(0) + 1;
+        let additions = vec![
+            MetaElement {
+                qname: "dc:date",
+                attrs: Vec::new(),
+                text: date.to_string(),
+            },
+            MetaElement {
+                qname: "meta:editing-cycles",
+                attrs: Vec::new(),
+                text: cycles.to_string(),
+            },
+            MetaElement {
+                qname: "meta:generator",
+                attrs: Vec::new(),
+                text: generator.to_string(),
+            },
+        ];
+        Self::rewrite(
+            meta,
+            &[b"date", b"editing-cycles", b"generator"],
+            &[],
+            additions,
+        )
+    }
+
+    pub fn generator() -> String {
+        format!("libreoffice-calc-mcp/{}", env!("CARGO_PKG_VERSION"))
+    }
+
+    fn rewrite(
+        meta: &str,
+        drop: &[&[u8]],
+        removed_user_defined: &[&str],
+        additions: Vec<MetaElement>,
+    ) -> Result<String, AppError> {
+        let mut reader = Reader::from_str(meta);
+        reader.config_mut().trim_text(false);
+        let mut writer = Writer::new(Cursor::new(Vec::new()));
+
+        let mut in_meta = false;
+        let mut child_depth = 0usize;
+        let mut skip_depth = 0usize;
+        let mut additions = Some(additions);
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| AppError::XmlParseError(e.to_string()))?;
+
+            if skip_depth > 0 {
+                match &event {
+                    Event::Start(_) => skip_depth += 1,
+                    Event::End(_) => skip_depth -= 1,
+                    Event::Eof => break,
+                    _ => {}
+                }
+                continue;
+            }
+
+            match event {
+                Event::Start(e) if Self::local_name(e.name().as_ref()) == b"document-meta" => {
+                    let root = Self::with_namespaces(&e);
+                    Self::write(&mut writer, Event::Start(root))?;
+                }
+                Event::Start(e) if !in_meta && Self::local_name(e.name().as_ref()) == b"meta" => {
+                    in_meta = true;
+                    child_depth = 0;
+                    Self::write(&mut writer, Event::Start(e.into_owned()))?;
+                }
+                Event::Empty(e) if !in_meta && Self::local_name(e.name().as_ref()) == b"meta" => {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                    Self::write(&mut writer, Event::Start(e.into_owned()))?;
+                    Self::write_additions(&mut writer, additions.take().unwrap_or_default())?;
+                    Self::write(&mut writer, Event::End(BytesEnd::new(name)))?;
+                }
+                Event::End(e) if in_meta && child_depth == 0 => {
+                    Self::write_additions(&mut writer, additions.take().unwrap_or_default())?;
+                    Self::write(&mut writer, Event::End(e.into_owned()))?;
+                    in_meta = false;
+                }
+                Event::Start(e) if in_meta && child_depth == 0 => {
+                    if Self::is_dropped(&e, drop, removed_user_defined, reader.decoder()) {
+                        skip_depth = 1;
+                        continue;
+                    }
+                    child_depth += 1;
+                    Self::write(&mut writer, Event::Start(e.into_owned()))?;
+                }
+                Event::Empty(e) if in_meta && child_depth == 0 => {
+                    if !Self::is_dropped(&e, drop, removed_user_defined, reader.decoder()) {
+                        Self::write(&mut writer, Event::Empty(e.into_owned()))?;
+                    }
+                }
+                Event::Start(e) if in_meta => {
+                    child_depth += 1;
+                    Self::write(&mut writer, Event::Start(e.into_owned()))?;
+                }
+                Event::End(e) if in_meta => {
+                    child_depth -= 1;
+                    Self::write(&mut writer, Event::End(e.into_owned()))?;
+                }
+                Event::Eof => break,
+                other => Self::write(&mut writer, other.into_owned())?,
+            }
+        }
+
+        if additions.is_some() {
+            return Err(AppError::InvalidOdsFormat(
+                "meta.xml has no office:meta element".to_string(),
+            ));
+        }
+
+        let bytes = writer.into_inner().into_inner();
+        String::from_utf8(bytes).map_err(|e| AppError::XmlParseError(e.to_string()))
+    }
+
+    fn is_dropped(
+        e: &BytesStart<'_>,
+        drop: &[&[u8]],
+        removed_user_defined: &[&str],
+        decoder: quick_xml::encoding::Decoder,
+    ) -> bool {
+        let name = e.name();
+        let local = Self::local_name(name.as_ref());
+        if local == b"user-defined" {
+            let (name, _) = Self::user_defined_attrs(e, decoder);
+            return removed_user_defined.contains(&name.as_str());
+        }
+        drop.contains(&local)
+    }
+
+    fn with_namespaces(e: &BytesStart<'_>) -> BytesStart<'static> {
+        // Older or hand-written meta.xml files may not declare dc/meta prefixes.
+        let mut root = e.to_owned();
+        let mut has_dc = false;
+        let mut has_meta = false;
+        for attr in e.attributes().flatten() {
+            match attr.key.as_ref() {
+                b"xmlns:dc" => has_dc = true,
+                b"xmlns:meta" => has_meta = true,
+                _ => {}
+            }
+        }
+        if !has_dc {
+            root.push_attribute(("xmlns:dc", DC_NS));
+        }
+        if !has_meta {
+            root.push_attribute(("xmlns:meta", META_NS));
+        }
+        root
+    }
+
+    fn write_additions(
+        writer: &mut Writer<Cursor<Vec<u8>>>,
+        additions: Vec<MetaElement>,
+    ) -> Result<(), AppError> {
+        for element in additions {
+            let mut start = BytesStart::new(element.qname);
+            for (key, value) in &element.attrs {
+                start.push_attribute((*key, value.as_str()));
+            }
+            if element.text.is_empty() {
+                Self::write(writer, Event::Empty(start))?;
+                continue;
+            }
+            Self::write(writer, Event::Start(start))?;
+            Self::write(writer, Event::Text(BytesText::new(&element.text)))?;
+            Self::write(writer, Event::End(BytesEnd::new(element.qname)))?;
+        }
+        Ok(())
+    }
+
+    fn write(writer: &mut Writer<Cursor<Vec<u8>>>, event: Event<'_>) -> Result<(), AppError> {
+        writer
+            .write_event(event)
+            .map_err(|e| AppError::XmlParseError(e.to_string()))
+    }
+
+    fn assign(
+        props: &mut DocumentProperties,
+        local: &str,
+        user_defined: Option<(String, String)>,
+        text: String,
+    ) {
+        match local {
+            "title" => props.title = Some(text),
+            "subject" => props.subject = Some(text),
+            "description" => props.description = Some(text),
+            "keyword" => props.keywords.push(text),
+            "initial-creator" => props.initial_creator = Some(text),
+            "creator" => props.creator = Some(text),
+            "creation-date" => props.creation_date = Some(text),
+            "date" => props.modification_date = Some(text),
+            "editing-cycles" => props.editing_cycles = text.trim().parse().ok(),
+            "generator" => props.generator = Some(text),
+            "user-defined" => {
+                if let Some((name, value_type)) = user_defined {
+                    props.user_defined.push(UserDefinedProperty {
+                        name,
+                        value_type,
+                        value: text,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn user_defined_attrs(
+        e: &BytesStart<'_>,
+        decoder: quick_xml::encoding::Decoder,
+    ) -> (String, String) {
+        let mut name = String::new();
+        let mut value_type = "string".to_string();
+        for attr in e.attributes().flatten() {
+            let Ok(value) = attr.decode_and_unescape_value(decoder) else {
+                continue;
+            };
+            match Self::local_name(attr.key.as_ref()) {
+                b"name" => name = value.into_owned(),
+                b"value-type" => value_type = value.into_owned(),
+                _ => {}
+            }
+        }
+        (name, value_type)
+    }
+
+    fn local_name(full_name: &[u8]) -> &[u8] {
+        match full_name.iter().rposition(|b| *b == b':') {
+            Some(pos) => &full_name[pos + 1..],
+            None => full_name,
+        }
+    }
+}
@@ -1,6 +1,13 @@
 pub mod cell_address;
 pub mod content_xml;
+pub mod flat_ods;
+pub mod formula;
+pub mod image;
 pub mod manifest;
+pub mod meta_xml;
 pub mod ods_file;
 pub mod ods_templates;
+pub mod package;
 pub mod sheet_model;
+pub mod validation;
+pub mod workbook_json;
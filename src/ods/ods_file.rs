@@ -1,77 +1,93 @@
 use crate::common::errors::AppError;
+use crate::common::time::TimeUtil;
 use crate::ods::content_xml::ContentXml;
+use crate::ods::flat_ods::FlatOds;
+use crate::ods::meta_xml::{DocumentProperties, DocumentPropertiesUpdate, MetaXml};
 use crate::ods::ods_templates::OdsTemplates;
+use crate::ods::package::OdsPackage;
 use crate::ods::sheet_model::Workbook;
+use crate::ods::validation::OdsValidator;
 use std::fs::File;
-use std::io::{Cursor, Read, Write};
+use std::io::Read;
 use std::path::Path;
-use zip::write::SimpleFileOptions;
-use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use zip::ZipArchive;
 
 pub struct OdsFile;
 
+// Physical layout behind a path: a zip package (.ods) or one flat XML document (.fods).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageLayout {
+    Zip,
+    Flat,
+}
+
+impl PackageLayout {
+    pub fn from_path(path: &Path) -> Self {
+        let is_flat = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("fods"));
+        if is_flat {
+            Self::Flat
+        } else {
+            Self::Zip
+        }
+    }
+}
+
 impl OdsFile {
-    // Creates a minimal but valid ODS zip package from templates.
+    // Creates a minimal but valid ODS zip package (or flat document) from templates.
     pub fn create(path: &Path, initial_sheet_name: String) -> Result<(), AppError> {
+        if PackageLayout::from_path(path) == PackageLayout::Flat {
+            return Self::create_flat(path, &initial_sheet_name);
+        }
         // Build new files from a LibreOffice-generated template so structure
         // matches what Calc expects by default.
-        let template_bytes = OdsTemplates::empty_calc_template();
-        let reader = Cursor::new(template_bytes);
-        let mut template = ZipArchive::new(reader)?;
+        let mut package = OdsPackage::from_bytes(OdsTemplates::empty_calc_template())?;
+        let content = Self::template_part(&package, "content.xml")?;
+        package.put(
+            "content.xml",
+            ContentXml::rename_first_sheet_name_raw(&content, &initial_sheet_name)?,
+        )?;
+        let meta = Self::template_part(&package, "meta.xml")?;
+        package.put("meta.xml", Self::stamp_created_meta(&meta)?)?;
+        // stamp_created_meta already set the modification stamp.
+        let options = SaveOptions {
+            stamp_meta: false,
+            ..SaveOptions::default()
+        };
+        package.save(path, &options)
+    }
 
-        let out_file = File::create(path)?;
-        let mut writer = ZipWriter::new(out_file);
-        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
-        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    fn create_flat(path: &Path, initial_sheet_name: &str) -> Result<(), AppError> {
+        // Same template as the zip layout, folded into a single office:document.
+        let template = OdsPackage::from_bytes(OdsTemplates::empty_calc_template())?;
+        let part = |name: &str| Self::template_part(&template, name);
+        let content =
+            ContentXml::rename_first_sheet_name_raw(&part("content.xml")?, initial_sheet_name)?;
+        let meta = Self::stamp_created_meta(&part("meta.xml")?)?;
+        let styles = part("styles.xml")?;
+        let settings = part("settings.xml")?;
+        let document = FlatOds::from_parts(&content, &styles, &meta, &settings)?;
+        std::fs::write(path, document)?;
+        Ok(())
+    }
 
-        // Keep ODS rule: first entry must be mimetype stored with no compression.
-        let mut mimetype = String::new();
+    fn template_part(template: &OdsPackage, name: &str) -> Result<String, AppError> {
         template
-            .by_name("mimetype")?
-            .read_to_string(&mut mimetype)?;
-        writer.start_file("mimetype", stored)?;
-        writer.write_all(mimetype.as_bytes())?;
-
-        let mut dir_names = Vec::new();
-        let mut names = Vec::new();
-        for i in 0..template.len() {
-            let name = template.by_index(i)?.name().to_string();
-            if name == "mimetype" {
-                continue;
-            }
-            if name.ends_with('/') {
-                dir_names.push(name);
-                continue;
-            }
-            names.push(name);
-        }
-        dir_names.sort();
-        names.sort();
-
-        for dir in dir_names {
-            let _ = writer.add_directory(dir, deflated);
-        }
-
-        for name in names {
-            let mut entry = template.by_name(&name)?;
-            let mut bytes = Vec::new();
-            entry.read_to_end(&mut bytes)?;
-
-            if name == "content.xml" {
-                let content = String::from_utf8(bytes)
-                    .map_err(|e| AppError::InvalidOdsFormat(e.to_string()))?;
-                let renamed =
-                    ContentXml::rename_first_sheet_name_raw(&content, &initial_sheet_name)?;
-                writer.start_file(name, deflated)?;
-                writer.write_all(renamed.as_bytes())?;
-            } else {
-                writer.start_file(name, deflated)?;
-                writer.write_all(&bytes)?;
-            }
-        }
+            .get_text(name)?
+            .ok_or_else(|| AppError::InvalidOdsFormat(format!("template without {name}")))
+    }
 
-        writer.finish()?;
-        Ok(())
+    fn stamp_created_meta(meta: &str) -> Result<String, AppError> {
+        // The template carries LibreOffice's own metadata; a new file starts its own history.
+        let now = TimeUtil::now_iso8601();
+        let update = DocumentPropertiesUpdate {
+            creation_date: Some(now.clone()),
+            ..Default::default()
+        };
+        let meta = MetaXml::apply_update(meta, &update)?;
+        MetaXml::stamp_modified(&meta, &now, &MetaXml::generator())
     }
 
     pub fn read_workbook(path: &Path) -> Result<Workbook, AppError> {
@@ -80,6 +96,9 @@ impl OdsFile {
     }
 
     pub fn read_content_xml(path: &Path) -> Result<String, AppError> {
+        if PackageLayout::from_path(path) == PackageLayout::Flat {
+            return FlatOds::content_xml(&std::fs::read_to_string(path)?);
+        }
         let file = File::open(path)?;
         let mut zip = ZipArchive::new(file)?;
 
@@ -102,54 +121,158 @@ impl OdsFile {
     }
 
     pub fn write_content_xml(path: &Path, content_xml: &str) -> Result<(), AppError> {
-        // Rebuild the zip preserving original entry order and directories.
-        let src = File::open(path)?;
-        let mut zip = ZipArchive::new(src)?;
-        let mut entries: Vec<(String, bool, Vec<u8>)> = Vec::new();
-
-        for i in 0..zip.len() {
-            let mut file = zip.by_index(i)?;
-            let name = file.name().to_string();
-            if name.ends_with('/') {
-                entries.push((name, true, Vec::new()));
-                continue;
-            }
-            let mut bytes = Vec::new();
-            file.read_to_end(&mut bytes)?;
-            entries.push((name, false, bytes));
-        }
-        drop(zip);
-
-        let out = File::create(path)?;
-        let mut writer = ZipWriter::new(out);
-
-        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
-        writer.start_file("mimetype", stored)?;
-        let mimetype = entries
-            .iter()
-            .find(|(name, is_dir, _)| name == "mimetype" && !*is_dir)
-            .map(|(_, _, data)| data.clone())
-            .unwrap_or_else(|| OdsTemplates::mimetype().as_bytes().to_vec());
-        writer.write_all(&mimetype)?;
-
-        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
-        for (name, is_dir, data) in entries {
-            if name == "mimetype" {
-                continue;
-            }
-            if is_dir {
-                let _ = writer.add_directory(name, deflated);
-                continue;
-            }
-            writer.start_file(name.clone(), deflated)?;
-            if name == "content.xml" {
-                writer.write_all(content_xml.as_bytes())?;
-            } else {
-                writer.write_all(&data)?;
+        Self::write_content_xml_with(path, content_xml, &SaveOptions::from_env())
+    }
+
+    pub fn write_content_xml_with(
+        path: &Path,
+        content_xml: &str,
+        options: &SaveOptions,
+    ) -> Result<(), AppError> {
+        if PackageLayout::from_path(path) == PackageLayout::Flat {
+            return Self::write_flat(path, options, |doc| {
+                FlatOds::replace_content(doc, content_xml)
+            });
+        }
+        Self::write_parts(path, &[("content.xml", content_xml)], options)
+    }
+
+    pub fn read_meta_xml(path: &Path) -> Result<Option<String>, AppError> {
+        if PackageLayout::from_path(path) == PackageLayout::Flat {
+            return FlatOds::meta_xml(&std::fs::read_to_string(path)?);
+        }
+        let file = File::open(path)?;
+        let mut zip = ZipArchive::new(file)?;
+        let mut meta = String::new();
+        let found = match zip.by_name("meta.xml") {
+            Ok(mut entry) => {
+                entry.read_to_string(&mut meta)?;
+                true
             }
+            Err(zip::result::ZipError::FileNotFound) => false,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(found.then_some(meta))
+    }
+
+    pub fn read_document_properties(path: &Path) -> Result<DocumentProperties, AppError> {
+        match Self::read_meta_xml(path)? {
+            Some(meta) => MetaXml::parse(&meta),
+            None => Ok(DocumentProperties::default()),
+        }
+    }
+
+    pub fn write_meta_xml(path: &Path, meta_xml: &str) -> Result<(), AppError> {
+        let options = SaveOptions::from_env();
+        if PackageLayout::from_path(path) == PackageLayout::Flat {
+            return Self::write_flat(path, &options, |doc| FlatOds::replace_meta(doc, meta_xml));
         }
+        Self::write_parts(path, &[("meta.xml", meta_xml)], &options)
+    }
 
-        writer.finish()?;
+    fn write_flat(
+        path: &Path,
+        options: &SaveOptions,
+        splice: impl FnOnce(&str) -> Result<String, AppError>,
+    ) -> Result<(), AppError> {
+        let mut document = splice(&std::fs::read_to_string(path)?)?;
+        if options.validate {
+            OdsValidator::check_flat(&document)?;
+        }
+        if options.stamp_meta {
+            if let Some(meta) = FlatOds::meta_xml(&document)? {
+                let stamped = MetaXml::stamp_modified(
+                    &meta,
+                    &TimeUtil::now_iso8601(),
+                    &MetaXml::generator(),
+                )?;
+                document = FlatOds::replace_meta(&document, &stamped)?;
+            }
+        }
+        std::fs::write(path, document)?;
         Ok(())
     }
+
+    // Stores any part of a zip package, listing it in the manifest when it is new. The media
+    // type defaults to the one implied by the extension.
+    pub fn write_part(
+        path: &Path,
+        name: &str,
+        data: &[u8],
+        media_type: Option<&str>,
+    ) -> Result<(), AppError> {
+        let mut package = Self::open_package(path)?;
+        match media_type {
+            Some(media_type) => package.put_with_media_type(name, data, media_type)?,
+            None => package.put(name, data)?,
+        }
+        package.save(path, &SaveOptions::from_env())
+    }
+
+    // Deletes a part and its manifest entry; returns whether the part existed.
+    pub fn remove_part(path: &Path, name: &str) -> Result<bool, AppError> {
+        let mut package = Self::open_package(path)?;
+        let removed = package.remove(name)?;
+        if removed {
+            package.save(path, &SaveOptions::from_env())?;
+        }
+        Ok(removed)
+    }
+
+    // Whole zip package, for edits spanning several parts; save it with OdsPackage::save.
+    pub fn open_package(path: &Path) -> Result<OdsPackage, AppError> {
+        if PackageLayout::from_path(path) == PackageLayout::Flat {
+            return Err(AppError::InvalidInput(
+                "flat documents (.fods) have no package parts".to_string(),
+            ));
+        }
+        OdsPackage::open(path)
+    }
+
+    fn write_parts(
+        path: &Path,
+        parts: &[(&str, &str)],
+        options: &SaveOptions,
+    ) -> Result<(), AppError> {
+        let mut package = OdsPackage::open(path)?;
+        for (name, data) in parts {
+            package.put(name, data.as_bytes())?;
+        }
+        package.save(path, options)
+    }
+}
+
+// Controls side effects applied to the package on every save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveOptions {
+    pub stamp_meta: bool,
+    // Refuse to write XML parts that fail OdsValidator::check_part.
+    pub validate: bool,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            stamp_meta: true,
+            validate: true,
+        }
+    }
+}
+
+impl SaveOptions {
+    // MCP_ODS_STAMP_META=0|false|off disables the automatic meta.xml stamp.
+    pub fn from_env() -> Self {
+        let stamp_meta = std::env::var("MCP_ODS_STAMP_META")
+            .map(|v| {
+                !matches!(
+                    v.trim().to_ascii_lowercase().as_str(),
+                    "0" | "false" | "off" | "no"
+                )
+            })
+            .unwrap_or(true);
+        Self {
+            stamp_meta,
+            validate: true,
+        }
+    }
 }
@@ -0,0 +1,217 @@
+use crate::common::errors::AppError;
+use crate::common::time::TimeUtil;
+use crate::ods::manifest::Manifest;
+use crate::ods::meta_xml::MetaXml;
+use crate::ods::ods_file::SaveOptions;
+use crate::ods::ods_templates::OdsTemplates;
+use crate::ods::validation::OdsValidator;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+// Every entry of a zip package held in memory, in archive order. Parts are read and
+// replaced by name; new and deleted parts are reflected in META-INF/manifest.xml.
+#[derive(Debug, Clone, Default)]
+pub struct OdsPackage {
+    entries: Vec<PackageEntry>,
+    // Parts put since loading; the pre-save check only looks at these.
+    changed: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone)]
+struct PackageEntry {
+    name: String,
+    is_dir: bool,
+    data: Vec<u8>,
+}
+
+const MANIFEST_PATH: &str = "META-INF/manifest.xml";
+
+impl OdsPackage {
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        Self::read(File::open(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AppError> {
+        Self::read(Cursor::new(bytes))
+    }
+
+    fn read(reader: impl Read + Seek) -> Result<Self, AppError> {
+        let mut zip = ZipArchive::new(reader)?;
+        let mut entries = Vec::with_capacity(zip.len());
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i)?;
+            let name = file.name().to_string();
+            let is_dir = name.ends_with('/');
+            let mut data = Vec::new();
+            if !is_dir {
+                file.read_to_end(&mut data)?;
+            }
+            entries.push(PackageEntry { name, is_dir, data });
+        }
+        Ok(Self {
+            entries,
+            changed: BTreeSet::new(),
+        })
+    }
+
+    // File entries in archive order; directories are left out.
+    pub fn part_names(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(|e| !e.is_dir)
+            .map(|e| e.name.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .find(|e| !e.is_dir && e.name == name)
+            .map(|e| e.data.as_slice())
+    }
+
+    // Part decoded as UTF-8, as every XML part of a package must be.
+    pub fn get_text(&self, name: &str) -> Result<Option<String>, AppError> {
+        self.get(name)
+            .map(|data| {
+                String::from_utf8(data.to_vec())
+                    .map_err(|e| AppError::InvalidOdsFormat(format!("{name}: {e}")))
+            })
+            .transpose()
+    }
+
+    // Stores a part; a new one is listed in the manifest with the media type implied by
+    // its extension.
+    pub fn put(&mut self, name: &str, data: impl Into<Vec<u8>>) -> Result<(), AppError> {
+        self.put_part(name, data.into(), None)
+    }
+
+    // Same, with an explicit media type that is also written over an existing entry's.
+    pub fn put_with_media_type(
+        &mut self,
+        name: &str,
+        data: impl Into<Vec<u8>>,
+        media_type: &str,
+    ) -> Result<(), AppError> {
+        self.put_part(name, data.into(), Some(media_type))
+    }
+
+    fn put_part(
+        &mut self,
+        name: &str,
+        data: Vec<u8>,
+        media_type: Option<&str>,
+    ) -> Result<(), AppError> {
+        let is_new = !self.contains(name);
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| !e.is_dir && e.name == name)
+        {
+            Some(entry) => entry.data = data,
+            None => self.entries.push(PackageEntry {
+                name: name.to_string(),
+                is_dir: false,
+                data,
+            }),
+        }
+        self.changed.insert(name.to_string());
+
+        let unlisted = name == "mimetype" || name.starts_with("META-INF/");
+        if !unlisted && (is_new || media_type.is_some()) {
+            let media_type = media_type
+                .or_else(|| Manifest::media_type_for(name))
+                .unwrap_or("application/octet-stream");
+            self.edit_manifest(|manifest| manifest.add_file(name, media_type))?;
+        }
+        Ok(())
+    }
+
+    // Deletes a part and its manifest entry; returns whether the part existed.
+    pub fn remove(&mut self, name: &str) -> Result<bool, AppError> {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.is_dir || e.name != name);
+        if self.entries.len() == before {
+            return Ok(false);
+        }
+        self.changed.remove(name);
+        self.edit_manifest(|manifest| {
+            manifest.remove_file(name);
+        })?;
+        Ok(true)
+    }
+
+    // Packages without a manifest are left without one.
+    fn edit_manifest(&mut self, edit: impl FnOnce(&mut Manifest)) -> Result<(), AppError> {
+        let Some(xml) = self.get_text(MANIFEST_PATH)? else {
+            return Ok(());
+        };
+        let mut manifest = Manifest::parse(&xml)?;
+        edit(&mut manifest);
+        let updated = manifest.to_xml();
+        if updated != xml {
+            if let Some(entry) = self
+                .entries
+                .iter_mut()
+                .find(|e| !e.is_dir && e.name == MANIFEST_PATH)
+            {
+                entry.data = updated.into_bytes();
+            }
+            self.changed.insert(MANIFEST_PATH.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn save(&mut self, path: &Path, options: &SaveOptions) -> Result<(), AppError> {
+        // Catch corruption before the original file is replaced.
+        if options.validate {
+            for name in &self.changed {
+                if let Some(data) = self.get(name) {
+                    OdsValidator::check_part(name, data)?;
+                }
+            }
+        }
+        if options.stamp_meta {
+            if let Some(meta) = self.get_text("meta.xml")? {
+                let stamped = MetaXml::stamp_modified(
+                    &meta,
+                    &TimeUtil::now_iso8601(),
+                    &MetaXml::generator(),
+                )?;
+                self.put("meta.xml", stamped)?;
+            }
+        }
+
+        let mut writer = ZipWriter::new(File::create(path)?);
+        // ODS rule: the first entry is mimetype, stored without compression.
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        writer.start_file("mimetype", stored)?;
+        writer.write_all(
+            self.get("mimetype")
+                .unwrap_or(OdsTemplates::mimetype().as_bytes()),
+        )?;
+
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for entry in &self.entries {
+            if entry.name == "mimetype" {
+                continue;
+            }
+            if entry.is_dir {
+                let _ = writer.add_directory(entry.name.as_str(), deflated);
+                continue;
+            }
+            writer.start_file(entry.name.as_str(), deflated)?;
+            writer.write_all(&entry.data)?;
+        }
+        writer.finish()?;
+        self.changed.clear();
+        Ok(())
+    }
+}
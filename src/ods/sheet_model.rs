@@ -6,18 +6,54 @@ pub enum CellValue {
     String(String),
     Number(f64),
     Boolean(bool),
+    // ISO 8601 date or date-time without zone (2024-02-29, 2024-02-29T13:45:00).
+    Date(String),
     Empty,
 }
 
+// Basic direct formatting carried across formats; colours are #RRGGBB.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CellStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub font_color: Option<String>,
+    pub background_color: Option<String>,
+    // "left", "center" or "right".
+    pub horizontal_align: Option<String>,
+}
+
+// Cell comment (office:annotation); paragraphs are joined with '\n'.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CellComment {
+    pub text: String,
+    pub author: Option<String>,
+    pub date: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Cell {
     pub value: CellValue,
+    // Formula in ODF syntax as stored in content.xml (for example: of:=SUM([.A1:.A3])).
+    pub formula: Option<String>,
+    pub style: CellStyle,
+    pub comment: Option<CellComment>,
+}
+
+// Merged area anchored at (row, col) spanning `rows` x `cols` cells (zero-based).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergedRange {
+    pub row: usize,
+    pub col: usize,
+    pub rows: usize,
+    pub cols: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Sheet {
     pub name: String,
     pub rows: Vec<Vec<Cell>>,
+    pub merges: Vec<MergedRange>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -25,10 +61,32 @@ pub struct Workbook {
     pub sheets: Vec<Sheet>,
 }
 
+impl CellStyle {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl MergedRange {
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        row >= self.row
+            && row < self.row + self.rows
+            && col >= self.col
+            && col < self.col + self.cols
+    }
+}
+
 impl Cell {
     pub fn empty() -> Self {
+        Self::with_value(CellValue::Empty)
+    }
+
+    pub fn with_value(value: CellValue) -> Self {
         Self {
-            value: CellValue::Empty,
+            value,
+            formula: None,
+            style: CellStyle::default(),
+            comment: None,
         }
     }
 }
@@ -38,6 +96,7 @@ impl Sheet {
         Self {
             name,
             rows: Vec::new(),
+            merges: Vec::new(),
         }
     }
 
@@ -0,0 +1,499 @@
+use crate::common::errors::AppError;
+use crate::ods::manifest::{Manifest, ManifestEntry};
+use crate::ods::ods_file::PackageLayout;
+use crate::ods::ods_templates::OdsTemplates;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use zip::{CompressionMethod, ZipArchive};
+
+// Structural checks on a package: zip layout, manifest and the XML parts. Used by the
+// validate_ods tool and, restricted to the parts being written, before every save.
+pub struct OdsValidator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationIssue {
+    pub severity: ValidationSeverity,
+    pub code: &'static str,
+    // Package entry the issue was found in ("content.xml", "META-INF/manifest.xml").
+    pub part: String,
+    pub message: String,
+}
+
+// Calc grid size; repeats adding up past it cannot be loaded.
+const MAX_ROWS: usize = 1_048_576;
+const MAX_COLS: usize = 16_384;
+
+const MANIFEST: &str = "META-INF/manifest.xml";
+
+impl OdsValidator {
+    pub fn validate_path(path: &Path) -> Result<Vec<ValidationIssue>, AppError> {
+        if PackageLayout::from_path(path) == PackageLayout::Flat {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let mut issues = Vec::new();
+            Self::scan_xml(&name, &std::fs::read(path)?, true, &mut issues);
+            return Ok(issues);
+        }
+        let mut zip = ZipArchive::new(File::open(path)?)?;
+        Self::validate_zip(&mut zip)
+    }
+
+    // Errors only, for a part about to be written; the first one aborts the save.
+    pub fn check_part(part: &str, data: &[u8]) -> Result<(), AppError> {
+        if !Self::is_xml_part(part) || Self::is_empty_config_part(part, data) {
+            return Ok(());
+        }
+        Self::check_xml(part, data, part == "content.xml")
+    }
+
+    // Same for a whole flat document, which carries the tables itself.
+    pub fn check_flat(document: &str) -> Result<(), AppError> {
+        Self::check_xml("flat document", document.as_bytes(), true)
+    }
+
+    fn check_xml(part: &str, data: &[u8], tables: bool) -> Result<(), AppError> {
+        let mut issues = Vec::new();
+        Self::scan_xml(part, data, tables, &mut issues);
+        match issues
+            .into_iter()
+            .find(|i| i.severity == ValidationSeverity::Error)
+        {
+            Some(issue) => Err(AppError::InvalidOdsFormat(format!(
+                "refusing to save {}: {}",
+                issue.part, issue.message
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_zip<R: Read + std::io::Seek>(
+        zip: &mut ZipArchive<R>,
+    ) -> Result<Vec<ValidationIssue>, AppError> {
+        let mut issues = Vec::new();
+        let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i)?;
+            let name = entry.name().to_string();
+            if name == "mimetype" {
+                Self::check_mimetype(i, entry.compression(), &mut entry, &mut issues)?;
+            }
+            if name.ends_with('/') {
+                continue;
+            }
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            entries.push((name, bytes));
+        }
+        if !entries.iter().any(|(name, _)| name == "mimetype") {
+            issues.push(Self::error(
+                "missing_mimetype",
+                "mimetype",
+                "no mimetype entry",
+            ));
+        }
+        for required in ["content.xml", MANIFEST] {
+            if !entries.iter().any(|(name, _)| name == required) {
+                issues.push(Self::error(
+                    "missing_part",
+                    required,
+                    "required part is missing",
+                ));
+            }
+        }
+
+        let manifest = entries
+            .iter()
+            .find(|(name, _)| name == MANIFEST)
+            .map(|(_, data)| Manifest::parse(&String::from_utf8_lossy(data)));
+        let encrypted: Vec<&str> = match &manifest {
+            Some(Ok(manifest)) => {
+                Self::check_manifest(manifest.entries(), &entries, &mut issues);
+                manifest
+                    .entries()
+                    .iter()
+                    .filter(|e| e.encrypted)
+                    .map(|e| e.full_path.as_str())
+                    .collect()
+            }
+            Some(Err(e)) => {
+                issues.push(Self::error("malformed_xml", MANIFEST, &e.to_string()));
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        for (name, data) in &entries {
+            if name != MANIFEST
+                && Self::is_xml_part(name)
+                && !Self::is_empty_config_part(name, data)
+                && !encrypted.contains(&name.as_str())
+            {
+                Self::scan_xml(name, data, name == "content.xml", &mut issues);
+            }
+        }
+        Ok(issues)
+    }
+
+    fn check_mimetype(
+        index: usize,
+        compression: CompressionMethod,
+        entry: &mut impl Read,
+        issues: &mut Vec<ValidationIssue>,
+    ) -> Result<(), AppError> {
+        // Applications sniff the type from the first bytes of the file, so the entry has
+        // to come first, uncompressed, with the exact string.
+        if index != 0 {
+            issues.push(Self::error(
+                "mimetype_not_first",
+                "mimetype",
+                &format!("mimetype is entry {} instead of the first", index + 1),
+            ));
+        }
+        if compression != CompressionMethod::Stored {
+            issues.push(Self::error(
+                "mimetype_compressed",
+                "mimetype",
+                "mimetype must be stored without compression",
+            ));
+        }
+        let mut text = String::new();
+        entry.read_to_string(&mut text)?;
+        if text != OdsTemplates::mimetype() {
+            issues.push(Self::error(
+                "invalid_mimetype",
+                "mimetype",
+                &format!("mimetype is '{text}'"),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_manifest(
+        listed: &[ManifestEntry],
+        entries: &[(String, Vec<u8>)],
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        for (name, _) in entries {
+            if name == "mimetype" || name.starts_with("META-INF/") {
+                continue;
+            }
+            if !listed.iter().any(|e| &e.full_path == name) {
+                issues.push(Self::error(
+                    "unlisted_part",
+                    name,
+                    "part is not listed in META-INF/manifest.xml",
+                ));
+            }
+        }
+        for entry in listed {
+            let expected = if entry.full_path == "/" {
+                Some(OdsTemplates::mimetype())
+            } else if entry.full_path.ends_with('/') {
+                // Sub-documents and directories such as Configurations2/ may have no entry.
+                continue;
+            } else {
+                if !entries.iter().any(|(name, _)| *name == entry.full_path) {
+                    issues.push(Self::error(
+                        "missing_part",
+                        &entry.full_path,
+                        "part listed in META-INF/manifest.xml is missing",
+                    ));
+                }
+                Manifest::media_type_for(&entry.full_path)
+            };
+            // LibreOffice lists some parts (Configurations2/accelerator/current.xml) with
+            // an empty media type, which leaves the type unspecified.
+            if entry.media_type.is_empty() && entry.full_path != "/" {
+                continue;
+            }
+            if let Some(expected) = expected.filter(|t| *t != entry.media_type) {
+                issues.push(Self::error(
+                    "wrong_media_type",
+                    &entry.full_path,
+                    &format!(
+                        "manifest media type is '{}' instead of '{expected}'",
+                        entry.media_type
+                    ),
+                ));
+            }
+        }
+    }
+
+    // LibreOffice writes Configurations2/accelerator/current.xml as a 0-byte placeholder.
+    fn is_empty_config_part(name: &str, data: &[u8]) -> bool {
+        data.is_empty() && name.starts_with("Configurations2/")
+    }
+
+    fn is_xml_part(name: &str) -> bool {
+        let lower = name.to_ascii_lowercase();
+        lower.ends_with(".xml") || lower.ends_with(".rdf")
+    }
+
+    // Well-formedness of any XML part; with `tables`, also the table checks below.
+    fn scan_xml(part: &str, data: &[u8], tables: bool, issues: &mut Vec<ValidationIssue>) {
+        let mut reader = Reader::from_reader(data);
+        let mut buf = Vec::new();
+        let mut scan = TableScan::default();
+        let mut depth = 0usize;
+        let mut root_seen = false;
+        loop {
+            let event = match reader.read_event_into(&mut buf) {
+                Ok(event) => event,
+                Err(e) => {
+                    issues.push(Self::error(
+                        "malformed_xml",
+                        part,
+                        &format!("at byte {}: {e}", reader.buffer_position()),
+                    ));
+                    return;
+                }
+            };
+            match &event {
+                Event::Start(e) | Event::Empty(e) => {
+                    if depth == 0 && root_seen {
+                        issues.push(Self::error(
+                            "malformed_xml",
+                            part,
+                            "more than one root element",
+                        ));
+                        return;
+                    }
+                    root_seen = true;
+                    if let Some(Err(e)) = e.attributes().find(Result::is_err) {
+                        issues.push(Self::error("malformed_xml", part, &e.to_string()));
+                        return;
+                    }
+                    if tables {
+                        scan.open(part, e, matches!(event, Event::Empty(_)), issues);
+                    }
+                    if matches!(event, Event::Start(_)) {
+                        depth += 1;
+                    }
+                }
+                Event::End(e) => {
+                    depth = depth.saturating_sub(1);
+                    if tables {
+                        scan.close(part, e.local_name().as_ref(), issues);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        if depth != 0 || !root_seen {
+            issues.push(Self::error(
+                "malformed_xml",
+                part,
+                "unexpected end of document",
+            ));
+        }
+    }
+
+    fn error(code: &'static str, part: &str, message: &str) -> ValidationIssue {
+        ValidationIssue {
+            severity: ValidationSeverity::Error,
+            code,
+            part: part.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+// Running totals for the table being read. Nested tables (sub-tables) are rare and share
+// the outer grid, so only the outermost table is tracked.
+#[derive(Default)]
+struct TableScan {
+    // Lowercased name -> name as first written.
+    names: HashMap<String, String>,
+    table: Option<String>,
+    table_depth: usize,
+    rows: usize,
+    columns: usize,
+    row_cells: usize,
+    row_repeat: usize,
+}
+
+impl TableScan {
+    fn open(
+        &mut self,
+        part: &str,
+        e: &BytesStart<'_>,
+        empty: bool,
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        let name = local_name(e);
+        if name == b"table" && !is_table_element(e) {
+            return;
+        }
+        match name {
+            b"table" => {
+                if self.table.is_some() {
+                    self.table_depth += usize::from(!empty);
+                    return;
+                }
+                let table = attr(e, b"name").unwrap_or_default();
+                match self.names.get(&table.to_lowercase()) {
+                    Some(first) if *first == table => issues.push(OdsValidator::error(
+                        "duplicate_table_name",
+                        part,
+                        &format!("more than one table is named '{table}'"),
+                    )),
+                    Some(first) => issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Warning,
+                        code: "duplicate_table_name",
+                        part: part.to_string(),
+                        message: format!("table '{table}' differs from '{first}' only in case"),
+                    }),
+                    None => {
+                        self.names.insert(table.to_lowercase(), table.clone());
+                    }
+                }
+                if !empty {
+                    self.table = Some(table);
+                    self.rows = 0;
+                    self.columns = 0;
+                }
+            }
+            _ if self.table.is_none() || self.table_depth > 0 => {}
+            b"table-column" => {
+                self.columns += self.count(part, e, b"number-columns-repeated", issues);
+                self.check_grid(part, issues);
+            }
+            b"table-row" => {
+                self.row_repeat = self.count(part, e, b"number-rows-repeated", issues);
+                self.row_cells = 0;
+                if empty {
+                    self.rows += self.row_repeat;
+                    self.check_grid(part, issues);
+                }
+            }
+            b"table-cell" | b"covered-table-cell" => {
+                self.row_cells += self.count(part, e, b"number-columns-repeated", issues);
+                self.count(part, e, b"number-columns-spanned", issues);
+                self.count(part, e, b"number-rows-spanned", issues);
+                if self.row_cells > MAX_COLS {
+                    issues.push(OdsValidator::error(
+                        "grid_overflow",
+                        part,
+                        &format!(
+                            "a row of table '{}' has {} cells, more than {MAX_COLS}",
+                            self.table.as_deref().unwrap_or_default(),
+                            self.row_cells
+                        ),
+                    ));
+                    // Counted again from zero so a long row is not reported for every cell.
+                    self.row_cells = 0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, part: &str, name: &[u8], issues: &mut Vec<ValidationIssue>) {
+        if self.table.is_none() {
+            return;
+        }
+        match name {
+            b"table" if self.table_depth > 0 => self.table_depth -= 1,
+            b"table" => self.table = None,
+            b"table-row" if self.table_depth == 0 => {
+                self.rows += self.row_repeat;
+                self.check_grid(part, issues);
+            }
+            _ => {}
+        }
+    }
+
+    // Value of a repeat or span attribute, 1 when absent; anything but a positive integer
+    // is reported and counted as 1.
+    fn count(
+        &self,
+        part: &str,
+        e: &BytesStart<'_>,
+        key: &[u8],
+        issues: &mut Vec<ValidationIssue>,
+    ) -> usize {
+        let Some(value) = attr(e, key) else {
+            return 1;
+        };
+        match value.trim().parse::<usize>() {
+            Ok(n) if n >= 1 => n,
+            _ => {
+                issues.push(OdsValidator::error(
+                    "invalid_repeat",
+                    part,
+                    &format!(
+                        "table '{}': {}=\"{value}\" is not a positive integer",
+                        self.table.as_deref().unwrap_or_default(),
+                        String::from_utf8_lossy(key)
+                    ),
+                ));
+                1
+            }
+        }
+    }
+
+    fn check_grid(&mut self, part: &str, issues: &mut Vec<ValidationIssue>) {
+        let table = self.table.as_deref().unwrap_or_default();
+        if self.rows > MAX_ROWS {
+            issues.push(OdsValidator::error(
+                "grid_overflow",
+                part,
+                &format!("table '{table}' has more than {MAX_ROWS} rows"),
+            ));
+            self.rows = 0;
+        }
+        if self.columns > MAX_COLS {
+            issues.push(OdsValidator::error(
+                "grid_overflow",
+                part,
+                &format!("table '{table}' declares more than {MAX_COLS} columns"),
+            ));
+            self.columns = 0;
+        }
+    }
+}
+
+fn local_name<'a>(e: &'a BytesStart<'_>) -> &'a [u8] {
+    let name = e.name().into_inner();
+    match name.iter().rposition(|&b| b == b':') {
+        Some(at) => &name[at + 1..],
+        None => name,
+    }
+}
+
+// table:table, as opposed to other namespaces' "table" elements.
+fn is_table_element(e: &BytesStart<'_>) -> bool {
+    let name = e.name().into_inner();
+    name == b"table" || name.starts_with(b"table:")
+}
+
+fn attr(e: &BytesStart<'_>, key: &[u8]) -> Option<String> {
+    e.attributes().flatten().find_map(|a| {
+        let name = a.key.as_ref();
+        let local = match name.iter().rposition(|&b| b == b':') {
+            Some(at) => &name[at + 1..],
+            None => name,
+        };
+        (local == key).then(|| {
+            a.unescape_value()
+                .map(|v| v.into_owned())
+                .unwrap_or_else(|_| String::from_utf8_lossy(&a.value).into_owned())
+        })
+    })
+}
@@ -0,0 +1,242 @@
+use crate::common::errors::AppError;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::sheet_model::{
+    Cell, CellComment, CellStyle, CellValue, MergedRange, Sheet, Workbook,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// JSON snapshot of a whole workbook (schema "mcp-ods/workbook", version 1). Cells are
+// sparse and addressed in A1 notation; only cells with a value, formula, style or comment
+// are listed, so the document stays readable as a test fixture.
+pub struct WorkbookJson;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkbookDoc {
+    schema: String,
+    version: u32,
+    sheets: Vec<SheetDoc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SheetDoc {
+    name: String,
+    #[serde(default)]
+    cells: Vec<CellDoc>,
+    // Ranges such as "A1:B2".
+    #[serde(default)]
+    merges: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CellDoc {
+    cell: String,
+    #[serde(default = "empty_value")]
+    value: CellValue,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    formula: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    style: Option<StyleDoc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<CommentDoc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StyleDoc {
+    #[serde(default, skip_serializing_if = "is_false")]
+    bold: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    italic: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    underline: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    font_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    background_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    horizontal_align: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CommentDoc {
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+}
+
+fn empty_value() -> CellValue {
+    CellValue::Empty
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl WorkbookJson {
+    pub const SCHEMA: &'static str = "mcp-ods/workbook";
+    pub const VERSION: u32 = 1;
+
+    pub fn to_value(workbook: &Workbook) -> Result<Value, AppError> {
+        let doc = WorkbookDoc {
+            schema: Self::SCHEMA.to_string(),
+            version: Self::VERSION,
+            sheets: workbook.sheets.iter().map(Self::sheet_doc).collect(),
+        };
+        serde_json::to_value(doc).map_err(|e| AppError::InvalidInput(e.to_string()))
+    }
+
+    pub fn from_value(value: Value) -> Result<Workbook, AppError> {
+        let doc: WorkbookDoc = serde_json::from_value(value)
+            .map_err(|e| AppError::InvalidInput(format!("invalid workbook json: {e}")))?;
+        if doc.schema != Self::SCHEMA || doc.version != Self::VERSION {
+            return Err(AppError::InvalidInput(format!(
+                "unsupported workbook json: expected schema {} version {}",
+                Self::SCHEMA,
+                Self::VERSION
+            )));
+        }
+        if doc.sheets.is_empty() {
+            return Err(AppError::InvalidInput(
+                "workbook json must contain at least one sheet".to_string(),
+            ));
+        }
+
+        let mut sheets: Vec<Sheet> = Vec::with_capacity(doc.sheets.len());
+        for sheet_doc in doc.sheets {
+            if sheet_doc.name.trim().is_empty() {
+                return Err(AppError::InvalidInput("sheet name is empty".to_string()));
+            }
+            if sheets.iter().any(|s| s.name == sheet_doc.name) {
+                return Err(AppError::SheetNameAlreadyExists(sheet_doc.name));
+            }
+            sheets.push(Self::sheet_from_doc(sheet_doc)?);
+        }
+        Ok(Workbook { sheets })
+    }
+
+    fn sheet_doc(sheet: &Sheet) -> SheetDoc {
+        let mut cells = Vec::new();
+        for (r, row) in sheet.rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                let is_blank = cell.value == CellValue::Empty
+                    && cell.formula.is_none()
+                    && cell.style.is_default()
+                    && cell.comment.is_none();
+                if is_blank {
+                    continue;
+                }
+                cells.push(CellDoc {
+                    cell: CellAddress { row: r, col: c }.to_a1(),
+                    value: cell.value.clone(),
+                    formula: cell.formula.clone(),
+                    style: (!cell.style.is_default()).then(|| StyleDoc {
+                        bold: cell.style.bold,
+                        italic: cell.style.italic,
+                        underline: cell.style.underline,
+                        font_color: cell.style.font_color.clone(),
+                        background_color: cell.style.background_color.clone(),
+                        horizontal_align: cell.style.horizontal_align.clone(),
+                    }),
+                    comment: cell.comment.as_ref().map(|c| CommentDoc {
+                        text: c.text.clone(),
+                        author: c.author.clone(),
+                        date: c.date.clone(),
+                    }),
+                });
+            }
+        }
+        SheetDoc {
+            name: sheet.name.clone(),
+            cells,
+            merges: sheet
+                .merges
+                .iter()
+                .map(|m| {
+                    let start = CellAddress {
+                        row: m.row,
+                        col: m.col,
+                    };
+                    let end = CellAddress {
+                        row: m.row + m.rows - 1,
+                        col: m.col + m.cols - 1,
+                    };
+                    format!("{}:{}", start.to_a1(), end.to_a1())
+                })
+                .collect(),
+        }
+    }
+
+    fn sheet_from_doc(doc: SheetDoc) -> Result<Sheet, AppError> {
+        let mut sheet = Sheet::new(doc.name);
+        for cell_doc in doc.cells {
+            let address = CellAddress::parse(&cell_doc.cell)?;
+            let style = cell_doc.style.unwrap_or_default();
+            if let Some(align) = &style.horizontal_align {
+                if !matches!(align.as_str(), "left" | "center" | "right") {
+                    return Err(AppError::InvalidInput(format!(
+                        "horizontal_align must be left, center or right: {align}"
+                    )));
+                }
+            }
+            let cell = sheet.ensure_cell_mut(address.row, address.col);
+            if *cell != Cell::empty() {
+                return Err(AppError::InvalidInput(format!(
+                    "cell {} listed twice in sheet",
+                    cell_doc.cell
+                )));
+            }
+            *cell = Cell {
+                value: cell_doc.value,
+                formula: cell_doc.formula,
+                style: CellStyle {
+                    bold: style.bold,
+                    italic: style.italic,
+                    underline: style.underline,
+                    font_color: style.font_color,
+                    background_color: style.background_color,
+                    horizontal_align: style.horizontal_align,
+                },
+                comment: cell_doc.comment.map(|c| CellComment {
+                    text: c.text,
+                    author: c.author,
+                    date: c.date,
+                }),
+            };
+        }
+
+        for range in doc.merges {
+            let (start, end) = CellAddress::parse_range(&range)?;
+            let merge = MergedRange {
+                row: start.row,
+                col: start.col,
+                rows: end.row - start.row + 1,
+                cols: end.col - start.col + 1,
+            };
+            if merge.rows == 1 && merge.cols == 1 {
+                continue;
+            }
+            let overlaps = sheet.merges.iter().any(|m| {
+                m.row < merge.row + merge.rows
+                    && merge.row < m.row + m.rows
+                    && m.col < merge.col + merge.cols
+                    && merge.col < m.col + m.cols
+            });
+            if overlaps {
+                return Err(AppError::InvalidInput(format!(
+                    "merged range overlaps another merge: {range}"
+                )));
+            }
+            // The renderer walks the grid, so the whole merged area must exist.
+            sheet.ensure_cell_mut(end.row, end.col);
+            sheet.merges.push(merge);
+        }
+        Ok(sheet)
+    }
+}
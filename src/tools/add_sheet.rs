@@ -3,6 +3,7 @@ use crate::common::fs::FsUtil;
 use crate::common::json::JsonUtil;
 use crate::ods::content_xml::ContentXml;
 use crate::ods::ods_file::OdsFile;
+use crate::tools::sheet_position::SheetPosition;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -11,7 +12,7 @@ struct AddSheetInput {
     path: String,
     sheet_name: String,
     #[serde(default = "default_position")]
-    position: String,
+    position: SheetPosition,
 }
 
 #[derive(Debug, Serialize)]
@@ -19,8 +20,8 @@ struct AddSheetOutput {
     sheets: Vec<String>,
 }
 
-fn default_position() -> String {
-    "end".to_string()
+fn default_position() -> SheetPosition {
+    SheetPosition::End
 }
 
 pub fn handle(params: Value) -> Result<Value, AppError> {
@@ -32,15 +33,15 @@ pub fn handle(params: Value) -> Result<Value, AppError> {
     }
 
     let original_content = OdsFile::read_content_xml(&path)?;
-    let updated_content = ContentXml::add_sheet_preserving_styles_raw(
+    let sheet_names = ContentXml::sheet_names_from_content_raw(&original_content)?;
+    let insert_index = input.position.resolve_insert_index(&sheet_names)?;
+    let updated_content = ContentXml::add_sheet_at_preserving_styles_raw(
         &original_content,
         &input.sheet_name,
-        &input.position,
+        insert_index,
     )?;
     OdsFile::write_content_xml(&path, &updated_content)?;
     let sheets = ContentXml::sheet_names_from_content_raw(&updated_content)?;
 
-    JsonUtil::to_value(AddSheetOutput {
-        sheets,
-    })
+    JsonUtil::to_value(AddSheetOutput { sheets })
 }
@@ -0,0 +1,371 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::{ContentXml, SearchCell};
+use crate::ods::formula::{FormulaError, FormulaSyntax, ReferenceGraph};
+use crate::ods::ods_file::OdsFile;
+use crate::ods::sheet_model::{CellValue, MergedRange, Workbook};
+use crate::tools::sheet_ref::SheetRef;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+struct CheckWorkbookInput {
+    path: String,
+    // Omitted: every sheet; workbook-level checks such as duplicate names always run.
+    sheet: Option<SheetRef>,
+    #[serde(default = "default_max_issues")]
+    max_issues: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckWorkbookOutput {
+    errors: usize,
+    warnings: usize,
+    total: usize,
+    truncated: bool,
+    issues: Vec<Issue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize)]
+struct Issue {
+    severity: Severity,
+    code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sheet: Option<String>,
+    // "B2", or "A1:C3" for merged areas; absent for workbook-level issues.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cell: Option<String>,
+    message: String,
+    #[serde(skip)]
+    position: (Option<usize>, usize, usize),
+}
+
+fn default_max_issues() -> usize {
+    200
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Read-only scan. Cell-level checks stream content.xml through find_cells_raw so repeated
+    // runs are judged once; formula and merge checks use the parsed workbook.
+    let input: CheckWorkbookInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let content = OdsFile::read_content_xml(&path)?;
+    let names = ContentXml::sheet_names_from_content_raw(&content)?;
+    let sheet_index = match &input.sheet {
+        Some(sheet) => Some(sheet.resolve_in_names(&names)?.0),
+        None => None,
+    };
+    let workbook = ContentXml::parse(&content)?;
+
+    // Sheet, formula and merge issues are few and always listed in full; cell issues are
+    // capped by the scan, which still counts every match.
+    let mut issues = duplicate_sheet_names(&names);
+    issues.extend(formula_issues(&workbook, sheet_index));
+    issues.extend(merge_issues(&workbook, sheet_index));
+    let number_text = Regex::new(r"^[-+]?(\d+|\d{1,3}([.,]\d{3})+)([.,]\d+)?%?$")
+        .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+    let found = ContentXml::find_cells_raw(&content, sheet_index, input.max_issues, |cell| {
+        cell_issue(cell, &number_text).is_some()
+    })?;
+    let total = issues.len() + found.total;
+    issues.extend(found.matches.into_iter().filter_map(|m| {
+        let (severity, code, message) = cell_issue(
+            &SearchCell {
+                value_type: &m.value_type,
+                value: &m.value,
+                formula: m.formula.as_deref(),
+                text: &m.text,
+                comment: m.comment.as_deref(),
+            },
+            &number_text,
+        )?;
+        Some(Issue {
+            severity,
+            code,
+            sheet: Some(m.sheet),
+            cell: Some(
+                CellAddress {
+                    row: m.row,
+                    col: m.col,
+                }
+                .to_a1(),
+            ),
+            message,
+            position: (Some(m.sheet_index), m.row, m.col),
+        })
+    }));
+
+    issues.sort_by_key(|issue| issue.position);
+    issues.truncate(input.max_issues);
+    JsonUtil::to_value(CheckWorkbookOutput {
+        errors: issues
+            .iter()
+            .filter(|i| i.severity == Severity::Error)
+            .count(),
+        warnings: issues
+            .iter()
+            .filter(|i| i.severity == Severity::Warning)
+            .count(),
+        truncated: total > issues.len(),
+        total,
+        issues,
+    })
+}
+
+fn duplicate_sheet_names(names: &[String]) -> Vec<Issue> {
+    // Calc compares sheet names without regard to case.
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    let mut issues = Vec::new();
+    for (index, name) in names.iter().enumerate() {
+        if let Some(first) = seen.insert(name.to_lowercase(), name) {
+            issues.push(Issue {
+                severity: Severity::Error,
+                code: "duplicate_sheet_name",
+                sheet: Some(name.clone()),
+                cell: None,
+                message: format!("sheet {} has the same name as '{first}'", index + 1),
+                position: (None, index, 0),
+            });
+        }
+    }
+    issues
+}
+
+fn cell_issue(
+    cell: &SearchCell<'_>,
+    number_text: &Regex,
+) -> Option<(Severity, &'static str, String)> {
+    if let Some(formula) = cell.formula {
+        let error = FormulaError::from_code(cell.text.trim())?;
+        let severity = match error {
+            FormulaError::Ref | FormulaError::Name => Severity::Error,
+            _ => Severity::Warning,
+        };
+        return Some((
+            severity,
+            "cached_error",
+            format!("formula {formula} evaluates to {}", error.code()),
+        ));
+    }
+    let text = cell.text.trim();
+    match cell.value_type {
+        "string" => {
+            // Leading zeros and long digit runs are usually codes (postcodes, IDs), not amounts.
+            let unsigned = text.trim_start_matches(['-', '+']);
+            let leading_zero = unsigned.len() > 1
+                && unsigned.starts_with('0')
+                && !unsigned[1..].starts_with(['.', ',']);
+            let is_code = leading_zero || text.chars().filter(char::is_ascii_digit).count() > 15;
+            (number_text.is_match(text) && !is_code).then(|| {
+                (
+                    Severity::Warning,
+                    "number_stored_as_text",
+                    format!("text '{text}' looks like a number"),
+                )
+            })
+        }
+        "float" | "percentage" | "currency" => {
+            let CellValue::Number(value) = cell.value else {
+                return None;
+            };
+            let scale = if cell.value_type == "percentage" || text.contains('%') {
+                100.0
+            } else {
+                1.0
+            };
+            let candidates = displayed_numbers(text)?;
+            let shown = (value * scale).abs();
+            let agrees = candidates.iter().any(|&(number, decimals)| {
+                (number - shown).abs() <= 0.5 * 10f64.powi(-decimals) + 1e-9 * shown.max(1.0)
+            });
+            (!agrees).then(|| {
+                (
+                    Severity::Warning,
+                    "value_text_mismatch",
+                    format!("stored value {value} is shown as '{text}'"),
+                )
+            })
+        }
+        _ => None,
+    }
+}
+
+// Absolute numbers a formatted text may show ("1.234,50 €", "12,5 %", "(3.14)") with their
+// decimal count. A single separator followed by three digits could be either kind, so both
+// readings are returned. None when the text is empty or not a plain formatted number.
+fn displayed_numbers(text: &str) -> Option<Vec<(f64, i32)>> {
+    if text.chars().any(|c| matches!(c, 'e' | 'E' | '/' | ':')) {
+        return None;
+    }
+    let kept: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ','))
+        .collect();
+    let kept = kept.trim_matches(['.', ',']);
+    if !kept.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let read = |decimal: Option<usize>| -> Option<(f64, i32)> {
+        let (int_part, frac_part) = match decimal {
+            Some(at) => (&kept[..at], &kept[at + 1..]),
+            None => (kept, ""),
+        };
+        let digits: String = int_part.chars().filter(char::is_ascii_digit).collect();
+        let number: f64 = format!(
+            "{}.{frac_part}",
+            if digits.is_empty() { "0" } else { &digits }
+        )
+        .trim_end_matches('.')
+        .parse()
+        .ok()?;
+        Some((number, frac_part.len() as i32))
+    };
+    let last = kept.rfind(['.', ',']);
+    let has_both = kept.contains('.') && kept.contains(',');
+    let separators = kept.matches(['.', ',']).count();
+    let candidates = match last {
+        None => vec![read(None)?],
+        Some(at) if has_both => vec![read(Some(at))?],
+        Some(_) if separators > 1 => vec![read(None)?],
+        Some(at) if kept.len() - at - 1 == 3 => vec![read(Some(at))?, read(None)?],
+        Some(at) => vec![read(Some(at))?],
+    };
+    Some(candidates)
+}
+
+fn formula_issues(workbook: &Workbook, only_sheet: Option<usize>) -> Vec<Issue> {
+    let graph = ReferenceGraph::build(workbook);
+    let mut issues = Vec::new();
+    let mut columns: HashMap<(usize, usize), Vec<(usize, String)>> = HashMap::new();
+    for cell in &graph.cells {
+        if only_sheet.is_some_and(|s| s != cell.sheet_index) {
+            continue;
+        }
+        let sheet = &workbook.sheets[cell.sheet_index].name;
+        let issue = |severity, code, message| Issue {
+            severity,
+            code,
+            sheet: Some(sheet.clone()),
+            cell: Some(
+                CellAddress {
+                    row: cell.row,
+                    col: cell.col,
+                }
+                .to_a1(),
+            ),
+            message,
+            position: (Some(cell.sheet_index), cell.row, cell.col),
+        };
+        if cell.text.to_ascii_uppercase().contains("#REF!") {
+            issues.push(issue(
+                Severity::Error,
+                "broken_reference",
+                format!("formula {} points at a deleted cell or sheet", cell.text),
+            ));
+        }
+        let mut missing: Vec<&str> = cell
+            .references
+            .iter()
+            .filter(|r| r.sheet_index.is_none())
+            .map(|r| r.sheet_name.as_str())
+            .collect();
+        missing.dedup();
+        for name in missing {
+            issues.push(issue(
+                Severity::Error,
+                "missing_sheet_reference",
+                format!("formula {} references missing sheet '{name}'", cell.text),
+            ));
+        }
+        columns
+            .entry((cell.sheet_index, cell.col))
+            .or_default()
+            .push((
+                cell.row,
+                FormulaSyntax::relative_form(&cell.text, cell.row, cell.col),
+            ));
+    }
+
+    // A formula is out of line when the formulas right above and below it agree with each
+    // other but not with it, as after a manual edit in a filled-down column.
+    let mut keys: Vec<_> = columns.keys().copied().collect();
+    keys.sort_unstable();
+    for (sheet_index, col) in keys {
+        let cells = &columns[&(sheet_index, col)];
+        for window in cells.windows(3) {
+            let [(above_row, above), (row, form), (below_row, below)] = window else {
+                continue;
+            };
+            if *above_row + 1 == *row && *row + 1 == *below_row && above == below && form != above {
+                issues.push(Issue {
+                    severity: Severity::Warning,
+                    code: "inconsistent_formula",
+                    sheet: Some(workbook.sheets[sheet_index].name.clone()),
+                    cell: Some(CellAddress { row: *row, col }.to_a1()),
+                    message: "formula differs from the formulas above and below it".to_string(),
+                    position: (Some(sheet_index), *row, col),
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn merge_issues(workbook: &Workbook, only_sheet: Option<usize>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (sheet_index, sheet) in workbook.sheets.iter().enumerate() {
+        if only_sheet.is_some_and(|s| s != sheet_index) {
+            continue;
+        }
+        for (i, first) in sheet.merges.iter().enumerate() {
+            for second in &sheet.merges[i + 1..] {
+                if !overlaps(first, second) {
+                    continue;
+                }
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    code: "overlapping_merge",
+                    sheet: Some(sheet.name.clone()),
+                    cell: Some(merge_a1(second)),
+                    message: format!("merged area overlaps {}", merge_a1(first)),
+                    position: (Some(sheet_index), second.row, second.col),
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn overlaps(a: &MergedRange, b: &MergedRange) -> bool {
+    a.row < b.row + b.rows
+        && b.row < a.row + a.rows
+        && a.col < b.col + b.cols
+        && b.col < a.col + a.cols
+}
+
+fn merge_a1(range: &MergedRange) -> String {
+    let start = CellAddress {
+        row: range.row,
+        col: range.col,
+    };
+    let end = CellAddress {
+        row: range.row + range.rows - 1,
+        col: range.col + range.cols - 1,
+    };
+    format!("{}:{}", start.to_a1(), end.to_a1())
+}
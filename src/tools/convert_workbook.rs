@@ -0,0 +1,128 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::ods_file::OdsFile;
+use crate::ods::sheet_model::{CellValue, Workbook};
+use crate::xlsx::xlsx_file::XlsxFile;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+struct ConvertWorkbookInput {
+    source: String,
+    target: String,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ConvertWorkbookOutput {
+    source: String,
+    target: String,
+    source_format: &'static str,
+    target_format: &'static str,
+    sheets: Vec<String>,
+    cells: usize,
+    formulas: usize,
+    merges: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WorkbookFormat {
+    Ods,
+    Xlsx,
+}
+
+impl WorkbookFormat {
+    fn from_path(path: &Path) -> Result<Self, AppError> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "ods" | "fods" => Ok(Self::Ods),
+            "xlsx" => Ok(Self::Xlsx),
+            _ => Err(AppError::InvalidPath(format!(
+                "expected .ods, .fods or .xlsx extension: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Ods => "ods",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Reads either format into the workbook model and writes it out in the target's format.
+    let input: ConvertWorkbookInput = JsonUtil::from_value(params)?;
+    let source = FsUtil::resolve_path(&input.source)?;
+    let target = FsUtil::resolve_path(&input.target)?;
+    let source_format = WorkbookFormat::from_path(&source)?;
+    let target_format = WorkbookFormat::from_path(&target)?;
+    if !source.exists() {
+        return Err(AppError::FileNotFound(source.display().to_string()));
+    }
+    if source == target {
+        return Err(AppError::InvalidInput(
+            "source and target must be different files".to_string(),
+        ));
+    }
+    if target.exists() && !input.overwrite {
+        return Err(AppError::AlreadyExists(target.display().to_string()));
+    }
+
+    let workbook = match source_format {
+        WorkbookFormat::Ods => OdsFile::read_workbook(&source)?,
+        WorkbookFormat::Xlsx => XlsxFile::read_workbook(&source)?,
+    };
+    let first_sheet = workbook
+        .sheets
+        .first()
+        .map(|sheet| sheet.name.clone())
+        .ok_or_else(|| AppError::InvalidOdsFormat("workbook has no sheets".to_string()))?;
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    match target_format {
+        WorkbookFormat::Ods => {
+            // The template supplies styles.xml, meta.xml and the manifest; content.xml is ours.
+            OdsFile::create(&target, first_sheet)?;
+            OdsFile::write_workbook(&target, &workbook)?;
+        }
+        WorkbookFormat::Xlsx => XlsxFile::write_workbook(&target, &workbook)?,
+    }
+
+    let (cells, formulas) = count_cells(&workbook);
+    JsonUtil::to_value(ConvertWorkbookOutput {
+        source: source.display().to_string(),
+        target: target.display().to_string(),
+        source_format: source_format.name(),
+        target_format: target_format.name(),
+        sheets: workbook.sheets.iter().map(|s| s.name.clone()).collect(),
+        cells,
+        formulas,
+        merges: workbook.sheets.iter().map(|s| s.merges.len()).sum(),
+    })
+}
+
+// Non-empty cells and formula cells across all sheets.
+fn count_cells(workbook: &Workbook) -> (usize, usize) {
+    workbook
+        .sheets
+        .iter()
+        .flat_map(|s| s.rows.iter().flatten())
+        .fold((0, 0), |(cells, formulas), cell| {
+            (
+                cells + usize::from(cell.value != CellValue::Empty),
+                formulas + usize::from(cell.formula.is_some()),
+            )
+        })
+}
@@ -0,0 +1,82 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::content_xml::ContentXml;
+use crate::ods::ods_file::{OdsFile, PackageLayout, SaveOptions};
+use crate::tools::sheet_ref::SheetRef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct DeleteImageInput {
+    path: String,
+    // draw:name of the frame, as reported by list_images.
+    name: String,
+    // Omitted: frames with that name on any sheet.
+    sheet: Option<SheetRef>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeleteImageOutput {
+    deleted: usize,
+    // Pictures/ parts no other frame or style used any more, removed with the frames.
+    removed_parts: Vec<String>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    let input: DeleteImageInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let original_content = OdsFile::read_content_xml(&path)?;
+    let sheet_index = match &input.sheet {
+        Some(sheet) => {
+            let names = ContentXml::sheet_names_from_content_raw(&original_content)?;
+            Some(sheet.resolve_in_names(&names)?.0)
+        }
+        None => None,
+    };
+    let frames = ContentXml::images_raw(&original_content)?;
+    let targets: Vec<_> = frames
+        .iter()
+        .filter(|frame| frame.name.as_deref() == Some(input.name.as_str()))
+        .filter(|frame| sheet_index.is_none_or(|index| frame.sheet_index == index))
+        .collect();
+    if targets.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "image not found: {}",
+            input.name
+        )));
+    }
+    let updated_content = ContentXml::remove_image_frames_raw(&original_content, &targets)?;
+
+    if PackageLayout::from_path(&path) == PackageLayout::Flat {
+        OdsFile::write_content_xml(&path, &updated_content)?;
+        return JsonUtil::to_value(DeleteImageOutput {
+            deleted: targets.len(),
+            removed_parts: Vec::new(),
+        });
+    }
+
+    // A part still named in content.xml (another frame, or a cell background among the
+    // automatic styles) or in styles.xml (e.g. a page background) stays in the package.
+    let mut package = OdsFile::open_package(&path)?;
+    let styles = package.get_text("styles.xml")?.unwrap_or_default();
+    let mut removed_parts: Vec<String> = Vec::new();
+    for href in targets.iter().filter_map(|frame| frame.href.as_deref()) {
+        let still_used = updated_content.contains(href)
+            || styles.contains(href)
+            || removed_parts.iter().any(|p| p == href);
+        if !still_used && href.starts_with("Pictures/") && package.remove(href)? {
+            removed_parts.push(href.to_string());
+        }
+    }
+    package.put("content.xml", updated_content)?;
+    package.save(&path, &SaveOptions::from_env())?;
+    JsonUtil::to_value(DeleteImageOutput {
+        deleted: targets.len(),
+        removed_parts,
+    })
+}
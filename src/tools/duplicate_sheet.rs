@@ -3,6 +3,7 @@ use crate::common::fs::FsUtil;
 use crate::common::json::JsonUtil;
 use crate::ods::content_xml::ContentXml;
 use crate::ods::ods_file::OdsFile;
+use crate::tools::sheet_position::SheetPosition;
 use crate::tools::sheet_ref::SheetRef;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -12,6 +13,8 @@ struct DuplicateSheetInput {
     path: String,
     source_sheet: SheetRef,
     new_sheet_name: String,
+    #[serde(default)]
+    position: Option<SheetPosition>,
 }
 
 #[derive(Debug, Serialize)]
@@ -20,7 +23,7 @@ struct DuplicateSheetOutput {
 }
 
 pub fn handle(params: Value) -> Result<Value, AppError> {
-    // Duplicates an existing sheet and inserts the copy right after source.
+    // Duplicates an existing sheet and inserts the copy right after source unless positioned.
     let input: DuplicateSheetInput = JsonUtil::from_value(params)?;
     let path = FsUtil::resolve_ods_path(&input.path)?;
     if !path.exists() {
@@ -30,12 +33,20 @@ pub fn handle(params: Value) -> Result<Value, AppError> {
     let original_content = OdsFile::read_content_xml(&path)?;
     let source_name = input.source_sheet.as_name();
     let source_index = input.source_sheet.as_index();
+    let insert_index = match &input.position {
+        Some(position) => {
+            let sheet_names = ContentXml::sheet_names_from_content_raw(&original_content)?;
+            Some(position.resolve_insert_index(&sheet_names)?)
+        }
+        None => None,
+    };
 
-    let updated_content = ContentXml::duplicate_sheet_preserving_styles_raw(
+    let updated_content = ContentXml::duplicate_sheet_at_preserving_styles_raw(
         &original_content,
         source_name,
         source_index,
         &input.new_sheet_name,
+        insert_index,
     )?;
     OdsFile::write_content_xml(&path, &updated_content)?;
 
@@ -0,0 +1,89 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::formula::{Formula, FormulaDialect, FormulaSyntax, FormulaValue};
+use crate::ods::ods_file::OdsFile;
+use crate::tools::sheet_ref::SheetRef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct EvaluateFormulaInput {
+    path: String,
+    formula: String,
+    // Sheet that unqualified references point at; omitted: the first sheet.
+    sheet: Option<SheetRef>,
+    // Same name as in the other formula tools; "syntax" is still read.
+    #[serde(default, alias = "syntax")]
+    formula_syntax: SyntaxInput,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SyntaxInput {
+    // "of:" prefix or bracketed references mean OpenFormula, anything else is read as Excel.
+    #[default]
+    Auto,
+    Odf,
+    Excel,
+}
+
+#[derive(Debug, Serialize)]
+struct EvaluateFormulaOutput {
+    sheet: String,
+    // Expression actually evaluated, in OpenFormula syntax.
+    formula: String,
+    value: ResultValue,
+    text: String,
+    // Functions or names the evaluator does not know; they make the result #NAME?.
+    unsupported: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "lowercase")]
+enum ResultValue {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Error(String),
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Read-only: formula cells contribute their cached results and nothing is written back.
+    let input: EvaluateFormulaInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let workbook = OdsFile::read_workbook(&path)?;
+    let (sheet_index, sheet_name) = input
+        .sheet
+        .unwrap_or(SheetRef::Index { index: 0 })
+        .resolve_in_workbook(&workbook)?;
+
+    let dialect = match input.formula_syntax {
+        SyntaxInput::Auto => None,
+        SyntaxInput::Odf => Some(FormulaDialect::Odf),
+        SyntaxInput::Excel => Some(FormulaDialect::Excel),
+    };
+    let odf = FormulaSyntax::to_odf(&input.formula, dialect);
+    let formula = Formula::parse(&odf)?;
+    let result = formula.evaluate_in_workbook(&workbook, sheet_index);
+    let text = result.display_text();
+    let value = match result {
+        FormulaValue::Number(n) => ResultValue::Number(n),
+        FormulaValue::Text(s) => ResultValue::String(s),
+        FormulaValue::Boolean(b) => ResultValue::Boolean(b),
+        FormulaValue::Error(e) => ResultValue::Error(e.code().to_string()),
+        FormulaValue::Empty => ResultValue::Number(0.0),
+    };
+
+    JsonUtil::to_value(EvaluateFormulaOutput {
+        sheet: sheet_name,
+        formula: odf,
+        value,
+        text,
+        unsupported: formula.unsupported_names(),
+    })
+}
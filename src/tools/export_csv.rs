@@ -0,0 +1,169 @@
+use crate::common::csv::{CsvQuoting, CsvUtil, CsvWriter};
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::{ContentXml, SparseCell};
+use crate::ods::ods_file::OdsFile;
+use crate::ods::sheet_model::CellValue;
+use crate::tools::sheet_ref::SheetRef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct ExportCsvInput {
+    path: String,
+    sheet: SheetRef,
+    csv_path: String,
+    range: Option<String>,
+    #[serde(default)]
+    overwrite: bool,
+    #[serde(default = "default_delimiter")]
+    delimiter: String,
+    #[serde(default = "default_quote")]
+    quote: String,
+    #[serde(default)]
+    quoting: QuotingInput,
+    #[serde(default)]
+    line_ending: LineEnding,
+    #[serde(default)]
+    values: ValuesMode,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum QuotingInput {
+    #[default]
+    Minimal,
+    All,
+    NonNumeric,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ValuesMode {
+    // Typed values as get_cell_value returns them (numbers unformatted, dates ISO 8601).
+    #[default]
+    Raw,
+    // The text Calc shows in the cell, falling back to the raw value.
+    Displayed,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportCsvOutput {
+    csv_path: String,
+    sheet: String,
+    range: Option<String>,
+    rows_written: usize,
+    cols_written: usize,
+    bytes_written: usize,
+}
+
+fn default_delimiter() -> String {
+    ",".to_string()
+}
+
+fn default_quote() -> String {
+    "\"".to_string()
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Streams the sheet's non-empty cells and lays them out over the requested window.
+    let input: ExportCsvInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+    let csv_path = FsUtil::resolve_path(&input.csv_path)?;
+    if csv_path.exists() && !input.overwrite {
+        return Err(AppError::AlreadyExists(csv_path.display().to_string()));
+    }
+
+    let delimiter = CsvUtil::option_char(&input.delimiter, "delimiter")?;
+    let quote = CsvUtil::option_char(&input.quote, "quote")?;
+    if delimiter == quote {
+        return Err(AppError::InvalidInput(
+            "delimiter and quote must differ".to_string(),
+        ));
+    }
+    let writer = CsvWriter {
+        delimiter,
+        quote,
+        quoting: match input.quoting {
+            QuotingInput::Minimal => CsvQuoting::Minimal,
+            QuotingInput::All => CsvQuoting::All,
+            QuotingInput::NonNumeric => CsvQuoting::NonNumeric,
+        },
+        line_ending: match input.line_ending {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        },
+    };
+
+    let content = OdsFile::read_content_xml(&path)?;
+    let sheet_names = ContentXml::sheet_names_from_content_raw(&content)?;
+    let (sheet_index, sheet_name) = input.sheet.resolve_in_names(&sheet_names)?;
+    let cells = ContentXml::sheet_cells_raw(&content, sheet_index, 0, usize::MAX)?.cells;
+
+    // Without a range the export spans A1 to the last used cell, as Calc does.
+    let window = match input.range.as_deref() {
+        Some(range) => Some(CellAddress::parse_range(range)?),
+        None => cells
+            .iter()
+            .fold(None, |bounds: Option<(usize, usize)>, cell| {
+                let (r, c) = bounds.unwrap_or((0, 0));
+                Some((r.max(cell.row), c.max(cell.col)))
+            })
+            .map(|(row, col)| (CellAddress { row: 0, col: 0 }, CellAddress { row, col })),
+    };
+
+    let mut out = String::new();
+    let (mut rows_written, mut cols_written) = (0, 0);
+    if let Some((start, end)) = window {
+        rows_written = end.row - start.row + 1;
+        cols_written = end.col - start.col + 1;
+        // Cells arrive in row-major order, so one cursor walks them alongside the window rows.
+        let mut next = cells.iter().peekable();
+        for row in start.row..=end.row {
+            let mut fields: Vec<(String, bool)> = vec![(String::new(), false); cols_written];
+            while let Some(cell) = next.next_if(|c| c.row <= row) {
+                if cell.row == row && cell.col >= start.col && cell.col <= end.col {
+                    fields[cell.col - start.col] = field_text(cell, input.values);
+                }
+            }
+            writer.write_record(&mut out, fields.iter().map(|(t, n)| (t.as_str(), *n)));
+        }
+    }
+    std::fs::write(&csv_path, &out)?;
+
+    JsonUtil::to_value(ExportCsvOutput {
+        csv_path: csv_path.display().to_string(),
+        sheet: sheet_name,
+        range: window.map(|(start, end)| format!("{}:{}", start.to_a1(), end.to_a1())),
+        rows_written,
+        cols_written,
+        bytes_written: out.len(),
+    })
+}
+
+fn field_text(cell: &SparseCell, mode: ValuesMode) -> (String, bool) {
+    let numeric = matches!(cell.value, CellValue::Number(_));
+    if mode == ValuesMode::Displayed && !cell.text.is_empty() {
+        return (cell.text.clone(), numeric);
+    }
+    let text = match &cell.value {
+        CellValue::String(v) | CellValue::Date(v) => v.clone(),
+        CellValue::Number(v) => v.to_string(),
+        CellValue::Boolean(v) => v.to_string(),
+        CellValue::Empty => String::new(),
+    };
+    (text, numeric)
+}
@@ -0,0 +1,86 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::ods_file::OdsFile;
+use crate::ods::workbook_json::WorkbookJson;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct ExportJsonInput {
+    path: String,
+    // Without json_path the document is returned inline in `workbook`.
+    json_path: Option<String>,
+    #[serde(default)]
+    overwrite: bool,
+    #[serde(default = "default_true")]
+    pretty: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportJsonOutput {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    json_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes_written: Option<usize>,
+    sheets: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workbook: Option<Value>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Serialises every sheet through the workbook model into the mcp-ods/workbook schema.
+    let input: ExportJsonInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+    let json_path = input
+        .json_path
+        .as_deref()
+        .map(FsUtil::resolve_path)
+        .transpose()?;
+    if let Some(json_path) = &json_path {
+        if json_path.exists() && !input.overwrite {
+            return Err(AppError::AlreadyExists(json_path.display().to_string()));
+        }
+    }
+
+    let workbook = OdsFile::read_workbook(&path)?;
+    let document = WorkbookJson::to_value(&workbook)?;
+    let sheets = workbook.sheets.iter().map(|s| s.name.clone()).collect();
+
+    let Some(json_path) = json_path else {
+        return JsonUtil::to_value(ExportJsonOutput {
+            path: path.display().to_string(),
+            json_path: None,
+            bytes_written: None,
+            sheets,
+            workbook: Some(document),
+        });
+    };
+    let mut text = if input.pretty {
+        serde_json::to_string_pretty(&document)
+    } else {
+        serde_json::to_string(&document)
+    }
+    .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+    text.push('\n');
+    if let Some(parent) = json_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&json_path, &text)?;
+
+    JsonUtil::to_value(ExportJsonOutput {
+        path: path.display().to_string(),
+        json_path: Some(json_path.display().to_string()),
+        bytes_written: Some(text.len()),
+        sheets,
+        workbook: None,
+    })
+}
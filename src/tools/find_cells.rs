@@ -0,0 +1,278 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::{ContentXml, SearchCell};
+use crate::ods::formula::{FormulaDialect, FormulaSyntax};
+use crate::ods::ods_file::OdsFile;
+use crate::ods::sheet_model::CellValue;
+use crate::tools::sheet_ref::SheetRef;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct FindCellsInput {
+    path: String,
+    // Omitted: search every sheet in workbook order.
+    sheet: Option<SheetRef>,
+    query: QueryInput,
+    #[serde(default)]
+    match_type: MatchType,
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    whole_cell: bool,
+    #[serde(default = "default_look_in")]
+    look_in: Vec<LookIn>,
+    #[serde(default = "default_max_results")]
+    max_results: usize,
+    // Dialect of the returned formulas; searching always looks at the stored text.
+    #[serde(default)]
+    formula_syntax: FormulaDialect,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum QueryInput {
+    Text(String),
+    Number(serde_json::Number),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MatchType {
+    #[default]
+    Text,
+    Number,
+    Regex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum LookIn {
+    Values,
+    Formulas,
+    Comments,
+}
+
+#[derive(Debug, Serialize)]
+struct FindCellsOutput {
+    matches: Vec<FoundCell>,
+    total: usize,
+    truncated: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct FoundCell {
+    sheet: String,
+    cell: String,
+    value: CellValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formula: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    matched_in: Vec<LookIn>,
+}
+
+fn default_look_in() -> Vec<LookIn> {
+    vec![LookIn::Values]
+}
+
+fn default_max_results() -> usize {
+    100
+}
+
+enum Matcher {
+    Text {
+        needle: String,
+        case_sensitive: bool,
+        whole_cell: bool,
+    },
+    // Numeric cells compare by value; formulas and comments fall back to the query text.
+    Number {
+        number: f64,
+        text: Box<Matcher>,
+    },
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(input: &FindCellsInput) -> Result<Self, AppError> {
+        let query = match &input.query {
+            QueryInput::Text(text) => text.clone(),
+            QueryInput::Number(number) => number.to_string(),
+        };
+        if query.is_empty() {
+            return Err(AppError::InvalidInput(
+                "query must not be empty".to_string(),
+            ));
+        }
+        let text = Matcher::Text {
+            needle: if input.case_sensitive {
+                query.clone()
+            } else {
+                query.to_lowercase()
+            },
+            case_sensitive: input.case_sensitive,
+            whole_cell: input.whole_cell,
+        };
+        match input.match_type {
+            MatchType::Text => Ok(text),
+            MatchType::Number => {
+                let number = query.trim().parse::<f64>().map_err(|_| {
+                    AppError::InvalidInput(format!("query is not a number: {query}"))
+                })?;
+                Ok(Matcher::Number {
+                    number,
+                    text: Box::new(text),
+                })
+            }
+            MatchType::Regex => {
+                let pattern = if input.whole_cell {
+                    format!("^(?:{query})$")
+                } else {
+                    query
+                };
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(!input.case_sensitive)
+                    .build()
+                    .map(Matcher::Regex)
+                    .map_err(|e| AppError::InvalidInput(format!("invalid regex: {e}")))
+            }
+        }
+    }
+
+    fn is_match(&self, haystack: &str) -> bool {
+        match self {
+            Matcher::Text {
+                needle,
+                case_sensitive,
+                whole_cell,
+            } => {
+                let haystack = if *case_sensitive {
+                    haystack.to_string()
+                } else {
+                    haystack.to_lowercase()
+                };
+                if *whole_cell {
+                    haystack == *needle
+                } else {
+                    haystack.contains(needle.as_str())
+                }
+            }
+            Matcher::Number { text, .. } => text.is_match(haystack),
+            Matcher::Regex(regex) => regex.is_match(haystack),
+        }
+    }
+
+    fn value_matches(&self, cell: &SearchCell<'_>) -> bool {
+        if let Matcher::Number { number, .. } = self {
+            let CellValue::Number(value) = cell.value else {
+                return false;
+            };
+            return (value - number).abs() <= 1e-9 * number.abs().max(1.0);
+        }
+        // Both what Calc shows and the stored value count, so "42" finds 42 shown as "42,00 €".
+        let raw = match cell.value {
+            CellValue::String(v) | CellValue::Date(v) => v.clone(),
+            CellValue::Number(v) => v.to_string(),
+            CellValue::Boolean(v) => v.to_string(),
+            CellValue::Empty => String::new(),
+        };
+        (!cell.text.is_empty() && self.is_match(cell.text))
+            || (!raw.is_empty() && self.is_match(&raw))
+    }
+
+    fn matched_in(&self, cell: &SearchCell<'_>, look_in: &[LookIn]) -> Vec<LookIn> {
+        look_in
+            .iter()
+            .copied()
+            .filter(|field| match field {
+                LookIn::Values => cell.value_type != "empty" && self.value_matches(cell),
+                LookIn::Formulas => cell
+                    .formula
+                    .is_some_and(|f| self.is_match(strip_formula_namespace(f))),
+                LookIn::Comments => cell.comment.is_some_and(|c| self.is_match(c)),
+            })
+            .collect()
+    }
+}
+
+// Formulas are matched as the user types them: "of:=SUM([.A1:.A3])" becomes "=SUM([.A1:.A3])".
+fn strip_formula_namespace(formula: &str) -> &str {
+    match formula.split_once(':') {
+        Some((namespace, rest)) if !namespace.contains('=') => rest,
+        _ => formula,
+    }
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Streams content.xml once; repeated runs are tested once and expanded only for the output.
+    let input: FindCellsInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+    if input.look_in.is_empty() {
+        return Err(AppError::InvalidInput(
+            "look_in must list at least one of values, formulas, comments".to_string(),
+        ));
+    }
+    let mut look_in: Vec<LookIn> = Vec::new();
+    for field in &input.look_in {
+        if !look_in.contains(field) {
+            look_in.push(*field);
+        }
+    }
+    let matcher = Matcher::new(&input)?;
+
+    let content = OdsFile::read_content_xml(&path)?;
+    let sheet_index = match &input.sheet {
+        Some(sheet) => {
+            let names = ContentXml::sheet_names_from_content_raw(&content)?;
+            Some(sheet.resolve_in_names(&names)?.0)
+        }
+        None => None,
+    };
+    let found = ContentXml::find_cells_raw(&content, sheet_index, input.max_results, |cell| {
+        !matcher.matched_in(cell, &look_in).is_empty()
+    })?;
+
+    let matches = found
+        .matches
+        .into_iter()
+        .map(|m| {
+            let matched_in = matcher.matched_in(
+                &SearchCell {
+                    value_type: &m.value_type,
+                    value: &m.value,
+                    formula: m.formula.as_deref(),
+                    text: &m.text,
+                    comment: m.comment.as_deref(),
+                },
+                &look_in,
+            );
+            FoundCell {
+                sheet: m.sheet,
+                cell: CellAddress {
+                    row: m.row,
+                    col: m.col,
+                }
+                .to_a1(),
+                value: m.value,
+                formula: m
+                    .formula
+                    .map(|f| FormulaSyntax::from_odf(&f, input.formula_syntax)),
+                comment: m.comment,
+                matched_in,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    JsonUtil::to_value(FindCellsOutput {
+        truncated: found.total > matches.len(),
+        total: found.total,
+        matches,
+    })
+}
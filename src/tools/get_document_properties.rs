@@ -0,0 +1,80 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::meta_xml::{DocumentProperties, UserDefinedProperty};
+use crate::ods::ods_file::OdsFile;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize)]
+struct GetDocumentPropertiesInput {
+    path: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DocumentPropertiesOutput {
+    title: Option<String>,
+    subject: Option<String>,
+    description: Option<String>,
+    keywords: Vec<String>,
+    initial_creator: Option<String>,
+    creator: Option<String>,
+    creation_date: Option<String>,
+    modification_date: Option<String>,
+    editing_cycles: Option<u32>,
+    generator: Option<String>,
+    user_defined: Vec<Value>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Reads meta.xml only; content.xml is never parsed for this tool.
+    let input: GetDocumentPropertiesInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let props = OdsFile::read_document_properties(&path)?;
+    JsonUtil::to_value(DocumentPropertiesOutput::from(props))
+}
+
+impl From<DocumentProperties> for DocumentPropertiesOutput {
+    fn from(props: DocumentProperties) -> Self {
+        Self {
+            title: props.title,
+            subject: props.subject,
+            description: props.description,
+            keywords: props.keywords,
+            initial_creator: props.initial_creator,
+            creator: props.creator,
+            creation_date: props.creation_date,
+            modification_date: props.modification_date,
+            editing_cycles: props.editing_cycles,
+            generator: props.generator,
+            user_defined: props
+                .user_defined
+                .iter()
+                .map(user_defined_as_json)
+                .collect(),
+        }
+    }
+}
+
+fn user_defined_as_json(prop: &UserDefinedProperty) -> Value {
+    // Numbers and booleans are exposed typed; dates and strings stay textual.
+    let value = match prop.value_type.as_str() {
+        "float" => prop
+            .value
+            .trim()
+            .parse::<f64>()
+            .map(|n| json!(n))
+            .unwrap_or_else(|_| json!(prop.value)),
+        "boolean" => json!(prop.value.trim().eq_ignore_ascii_case("true")),
+        _ => json!(prop.value),
+    };
+    json!({
+        "name": prop.name,
+        "type": prop.value_type,
+        "value": value
+    })
+}
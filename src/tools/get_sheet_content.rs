@@ -1,11 +1,17 @@
 use crate::common::errors::AppError;
 use crate::common::fs::FsUtil;
 use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::{ContentXml, MergedRange, SheetWindow, SparseCell};
+use crate::ods::formula::{FormulaDialect, FormulaSyntax};
 use crate::ods::ods_file::OdsFile;
 use crate::ods::sheet_model::CellValue;
+use crate::tools::page_cursor::{FileStamp, PageCursor};
 use crate::tools::sheet_ref::SheetRef;
+use crate::tools::sheet_render::RenderFormat;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Number, Value};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Deserialize)]
 struct GetSheetContentInput {
@@ -19,6 +25,28 @@ struct GetSheetContentInput {
     max_cols: usize,
     #[serde(default)]
     include_empty_trailing: bool,
+    // 1-based row holding the record keys; defaults to the first non-empty row.
+    #[serde(default)]
+    header_row: Option<usize>,
+    // Cells mode pagination: number of non-empty cells to skip and page size.
+    #[serde(default)]
+    cell_offset: usize,
+    #[serde(default = "default_max_cells")]
+    max_cells: usize,
+    // Rows (records in records mode) and columns to skip before the page starts.
+    #[serde(default)]
+    offset_row: usize,
+    #[serde(default)]
+    offset_col: usize,
+    // Opaque token from a previous page's next_cursor; overrides the offsets.
+    #[serde(default)]
+    cursor: Option<String>,
+    // A1 range (e.g. B2:F40) that sets the offsets and limits of grid modes in one go.
+    #[serde(default)]
+    range: Option<String>,
+    // Dialect of the formulas returned by cells mode.
+    #[serde(default)]
+    formula_syntax: FormulaDialect,
 }
 
 #[derive(Debug, Serialize)]
@@ -26,7 +54,179 @@ struct GetSheetContentOutput {
     sheet: String,
     rows: usize,
     cols: usize,
+    offset_row: usize,
+    offset_col: usize,
+    total_rows: usize,
+    total_cols: usize,
+    next_cursor: Option<String>,
     data: Vec<Vec<String>>,
+    // Only set by the markdown/csv/html modes; the dispatcher shows `rendered` as the text block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merged_ranges: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rendered: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct GetSheetRecordsOutput {
+    sheet: String,
+    header_row: Option<usize>,
+    first_col: Option<String>,
+    cols: usize,
+    headers: Vec<String>,
+    rows: usize,
+    offset_row: usize,
+    next_cursor: Option<String>,
+    records: Vec<Map<String, Value>>,
+}
+
+#[derive(Debug, Serialize)]
+struct GetSheetCellsOutput {
+    sheet: String,
+    total_cells: usize,
+    cell_offset: usize,
+    returned: usize,
+    next_cell_offset: Option<usize>,
+    next_cursor: Option<String>,
+    cells: Vec<SparseCellOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct SparseCellOutput {
+    cell: String,
+    #[serde(rename = "type")]
+    value_type: String,
+    value: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formula: Option<String>,
+}
+
+impl From<SparseCell> for SparseCellOutput {
+    fn from(cell: SparseCell) -> Self {
+        let value_type = match cell.value_type.as_str() {
+            "float" => "number".to_string(),
+            other => other.to_string(),
+        };
+        Self {
+            cell: CellAddress {
+                row: cell.row,
+                col: cell.col,
+            }
+            .to_a1(),
+            value_type,
+            value: value_as_json(&cell.value),
+            formula: cell.formula,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ContentMode {
+    Matrix,
+    Records,
+    Cells,
+    Rendered(RenderFormat),
+}
+
+// Sheet being read: its content.xml, position and name.
+struct SheetSource<'a> {
+    content: &'a str,
+    index: usize,
+    name: String,
+}
+
+// Non-empty values of the rows a records page reads, by (row, col).
+#[derive(Default)]
+struct RowCells {
+    values: HashMap<(usize, usize), CellValue>,
+    spans: HashMap<usize, (usize, usize)>,
+}
+
+impl RowCells {
+    // `cells` hold one row, left to right.
+    fn insert(&mut self, cells: Vec<SparseCell>) {
+        if let (Some(first), Some(last)) = (cells.first(), cells.last()) {
+            self.spans.insert(first.row, (first.col, last.col));
+        }
+        for cell in cells {
+            let value = model_value(&cell);
+            self.values.insert((cell.row, cell.col), value);
+        }
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&CellValue> {
+        self.values.get(&(row, col))
+    }
+
+    // First and last column holding a value.
+    fn used_span(&self, row: usize) -> Option<(usize, usize)> {
+        self.spans.get(&row).copied()
+    }
+}
+
+// Resolved pagination context: the file stamp and the cursor the caller sent back, if any.
+struct Paging {
+    mode: ContentMode,
+    stamp: FileStamp,
+    cursor: Option<PageCursor>,
+}
+
+impl Paging {
+    // Returns the (row, col) page start, taken from the cursor when present.
+    fn start(
+        &self,
+        sheet_index: usize,
+        row: usize,
+        col: usize,
+    ) -> Result<(usize, usize), AppError> {
+        match &self.cursor {
+            Some(cursor) => {
+                cursor.validate(self.mode.as_str(), sheet_index, self.stamp)?;
+                Ok((cursor.row, cursor.col))
+            }
+            None => Ok((row, col)),
+        }
+    }
+
+    fn next(&self, sheet_index: usize, row: usize, col: usize) -> String {
+        PageCursor {
+            mode: self.mode.as_str().to_string(),
+            sheet: sheet_index,
+            row,
+            col,
+            stamp: self.stamp,
+        }
+        .encode()
+    }
+}
+
+impl ContentMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            ContentMode::Matrix => "matrix",
+            ContentMode::Records => "records",
+            ContentMode::Cells => "cells",
+            ContentMode::Rendered(RenderFormat::Markdown) => "markdown",
+            ContentMode::Rendered(RenderFormat::Csv) => "csv",
+            ContentMode::Rendered(RenderFormat::Html) => "html",
+        }
+    }
+
+    fn parse(mode: &str) -> Result<Self, AppError> {
+        match mode.trim().to_ascii_lowercase().as_str() {
+            "matrix" => Ok(ContentMode::Matrix),
+            "records" => Ok(ContentMode::Records),
+            "cells" => Ok(ContentMode::Cells),
+            "markdown" => Ok(ContentMode::Rendered(RenderFormat::Markdown)),
+            "csv" => Ok(ContentMode::Rendered(RenderFormat::Csv)),
+            "html" => Ok(ContentMode::Rendered(RenderFormat::Html)),
+            _ => Err(AppError::InvalidInput(format!(
+                "unsupported mode: {mode} (expected matrix, records, cells, markdown, csv or html)"
+            ))),
+        }
+    }
 }
 
 fn default_mode() -> String {
@@ -38,13 +238,20 @@ fn default_max_rows() -> usize {
 fn default_max_cols() -> usize {
     50
 }
+fn default_max_cells() -> usize {
+    500
+}
 
 pub fn handle(params: Value) -> Result<Value, AppError> {
-    // Builds a bounded 2D matrix representation optimized for LLM consumption.
-    let input: GetSheetContentInput = JsonUtil::from_value(params)?;
-    if input.mode != "matrix" {
+    // Builds a bounded matrix, record list, sparse cell list or text rendering for LLM consumption.
+    let mut input: GetSheetContentInput = JsonUtil::from_value(params)?;
+    let mode = ContentMode::parse(&input.mode)?;
+    if let Some(range) = input.range.clone() {
+        apply_range(&mut input, &range)?;
+    }
+    if input.header_row == Some(0) {
         return Err(AppError::InvalidInput(
-            "only mode=matrix is supported".to_string(),
+            "header_row is 1-based and must be greater than 0".to_string(),
         ));
     }
 
@@ -53,24 +260,171 @@ pub fn handle(params: Value) -> Result<Value, AppError> {
         return Err(AppError::FileNotFound(path.display().to_string()));
     }
 
-    let workbook = OdsFile::read_workbook(&path)?;
-    let (sheet_index, sheet_name) = input.sheet.resolve_in_workbook(&workbook)?;
-    let sheet = &workbook.sheets[sheet_index];
+    let paging = Paging {
+        mode,
+        stamp: FileStamp::of(&path)?,
+        cursor: input
+            .cursor
+            .as_deref()
+            .map(PageCursor::decode)
+            .transpose()?,
+    };
+
+    // Every mode streams content.xml and only builds the rows of the requested page.
+    let content = OdsFile::read_content_xml(&path)?;
+    let names = ContentXml::sheet_names_from_content_raw(&content)?;
+    let (sheet_index, sheet_name) = input.sheet.resolve_in_names(&names)?;
+    let sheet = SheetSource {
+        content: &content,
+        index: sheet_index,
+        name: sheet_name,
+    };
+    match mode {
+        ContentMode::Matrix => JsonUtil::to_value(build_matrix(&input, &paging, sheet)?),
+        ContentMode::Records => records_output(&input, &paging, sheet),
+        ContentMode::Cells => cells_output(&input, &paging, sheet),
+        ContentMode::Rendered(format) => rendered_output(&input, &paging, sheet, format),
+    }
+}
+
+fn apply_range(input: &mut GetSheetContentInput, range: &str) -> Result<(), AppError> {
+    let (start, end) = CellAddress::parse_range(range)?;
+    input.offset_row = start.row;
+    input.offset_col = start.col;
+    input.max_rows = end.row - start.row + 1;
+    input.max_cols = end.col - start.col + 1;
+    Ok(())
+}
+
+fn rendered_output(
+    input: &GetSheetContentInput,
+    paging: &Paging,
+    sheet: SheetSource,
+    format: RenderFormat,
+) -> Result<Value, AppError> {
+    // Same window as matrix mode, rendered as text; merges are clipped to the returned grid.
+    let (content, sheet_index) = (sheet.content, sheet.index);
+    let mut output = build_matrix(input, paging, sheet)?;
+
+    let window = MergedRange {
+        row: output.offset_row,
+        col: output.offset_col,
+        rows: output.rows,
+        cols: output.cols,
+    };
+    let merges: Vec<MergedRange> = ContentXml::merged_ranges_raw(content, sheet_index)?
+        .into_iter()
+        .filter_map(|merge| clip_to_window(merge, window))
+        .collect();
+
+    output.rendered = Some(format.render(&output.data, &merges));
+    output.merged_ranges = Some(
+        merges
+            .iter()
+            .map(|m| {
+                let start = CellAddress {
+                    row: window.row + m.row,
+                    col: window.col + m.col,
+                };
+                let end = CellAddress {
+                    row: start.row + m.rows - 1,
+                    col: start.col + m.cols - 1,
+                };
+                format!("{}:{}", start.to_a1(), end.to_a1())
+            })
+            .collect(),
+    );
+    output.format = Some(paging.mode.as_str());
+    JsonUtil::to_value(output)
+}
+
+fn clip_to_window(merge: MergedRange, window: MergedRange) -> Option<MergedRange> {
+    // Returns the merge intersected with the window, in window-relative coordinates.
+    let top = merge.row.max(window.row);
+    let left = merge.col.max(window.col);
+    let bottom = (merge.row + merge.rows).min(window.row + window.rows);
+    let right = (merge.col + merge.cols).min(window.col + window.cols);
+    if top >= bottom || left >= right || (bottom - top == 1 && right - left == 1) {
+        return None;
+    }
+    Some(MergedRange {
+        row: top - window.row,
+        col: left - window.col,
+        rows: bottom - top,
+        cols: right - left,
+    })
+}
+
+fn cells_output(
+    input: &GetSheetContentInput,
+    paging: &Paging,
+    sheet: SheetSource,
+) -> Result<Value, AppError> {
+    // Sparse sheets never get expanded into a padded matrix.
+    if input.max_cells == 0 {
+        return Err(AppError::InvalidInput(
+            "max_cells must be greater than 0".to_string(),
+        ));
+    }
+    let SheetSource {
+        content,
+        index: sheet_index,
+        name: sheet_name,
+    } = sheet;
+    let (cell_offset, _) = paging.start(sheet_index, input.cell_offset, 0)?;
+    let page = ContentXml::sheet_cells_raw(content, sheet_index, cell_offset, input.max_cells)?;
+
+    let returned = page.cells.len();
+    let next = cell_offset + returned;
+    let next_cell_offset = (next < page.total).then_some(next);
+    JsonUtil::to_value(GetSheetCellsOutput {
+        sheet: sheet_name,
+        total_cells: page.total,
+        cell_offset,
+        returned,
+        next_cell_offset,
+        next_cursor: next_cell_offset.map(|offset| paging.next(sheet_index, offset, 0)),
+        cells: page
+            .cells
+            .into_iter()
+            .map(|cell| {
+                let mut output = SparseCellOutput::from(cell);
+                output.formula = output
+                    .formula
+                    .map(|f| FormulaSyntax::from_odf(&f, input.formula_syntax));
+                output
+            })
+            .collect(),
+    })
+}
 
-    let row_limit = std::cmp::min(sheet.rows.len(), input.max_rows);
-    let col_limit = std::cmp::min(sheet.max_cols(), input.max_cols);
+fn build_matrix(
+    input: &GetSheetContentInput,
+    paging: &Paging,
+    sheet: SheetSource,
+) -> Result<GetSheetContentOutput, AppError> {
+    let (offset_row, offset_col) = paging.start(sheet.index, input.offset_row, input.offset_col)?;
+    let row_window = offset_row..offset_row.saturating_add(input.max_rows);
+    let col_window = offset_col..offset_col.saturating_add(input.max_cols);
+    let SheetWindow {
+        cells,
+        grid_rows,
+        grid_cols,
+        used_rows: total_rows,
+        used_cols: total_cols,
+    } = ContentXml::sheet_window_raw(sheet.content, sheet.index, row_window, col_window)?;
+    let row_end = std::cmp::min(grid_rows, offset_row.saturating_add(input.max_rows));
+    let col_end = std::cmp::min(grid_cols, offset_col.saturating_add(input.max_cols));
+    let col_limit = col_end.saturating_sub(offset_col);
 
-    let mut matrix = Vec::with_capacity(row_limit);
-    for r in 0..row_limit {
-        let mut row_values = Vec::with_capacity(col_limit);
-        for c in 0..col_limit {
-            let text = sheet
-                .get_cell(r, c)
-                .map(|c| value_as_string(&c.value))
-                .unwrap_or_default();
-            row_values.push(text);
+    let mut matrix = vec![vec![String::new(); col_limit]; row_end.saturating_sub(offset_row)];
+    for cell in &cells {
+        if let Some(slot) = matrix
+            .get_mut(cell.row - offset_row)
+            .and_then(|row| row.get_mut(cell.col - offset_col))
+        {
+            *slot = value_as_string(&model_value(cell));
         }
-        matrix.push(row_values);
     }
 
     let (rows, cols, data) = if input.include_empty_trailing {
@@ -79,17 +433,194 @@ pub fn handle(params: Value) -> Result<Value, AppError> {
         trim_trailing(matrix)
     };
 
-    JsonUtil::to_value(GetSheetContentOutput {
-        sheet: sheet_name,
+    // Pages walk down the rows; the column window stays where the caller put it.
+    let next_row = offset_row.saturating_add(input.max_rows);
+    let next_cursor =
+        (next_row < total_rows).then(|| paging.next(sheet.index, next_row, offset_col));
+
+    Ok(GetSheetContentOutput {
+        sheet: sheet.name,
         rows,
         cols,
+        offset_row,
+        offset_col,
+        total_rows,
+        total_cols,
+        next_cursor,
         data,
+        format: None,
+        merged_ranges: None,
+        rendered: None,
     })
 }
 
+// Value as the workbook model reads it: percentages, currencies and times keep the text Calc
+// displays for them.
+fn model_value(cell: &SparseCell) -> CellValue {
+    match cell.value_type.as_str() {
+        "float" | "boolean" | "date" | "string" => cell.value.clone(),
+        _ if cell.text.is_empty() => CellValue::Empty,
+        _ => CellValue::String(cell.text.clone()),
+    }
+}
+
+fn records_output(
+    input: &GetSheetContentInput,
+    paging: &Paging,
+    sheet: SheetSource,
+) -> Result<Value, AppError> {
+    // Uses one row as keys and turns each following non-empty row into a JSON object.
+    let (offset_row, _) = paging.start(sheet.index, input.offset_row, 0)?;
+    let fixed_header = input.header_row.map(|row| row - 1);
+
+    // Rows are read until one past the page, only to learn whether another page exists.
+    let mut grid = RowCells::default();
+    let mut header_index = fixed_header;
+    let mut data_rows: Vec<usize> = Vec::new();
+    let mut skipped = 0usize;
+    ContentXml::visit_sheet_rows_raw(
+        sheet.content,
+        sheet.index,
+        0..input.max_cols,
+        &mut |row, cells| {
+            let cells: Vec<SparseCell> = cells
+                .into_iter()
+                .filter(|cell| model_value(cell) != CellValue::Empty)
+                .collect();
+            if cells.is_empty() {
+                return true;
+            }
+            match header_index {
+                Some(header) if row <= header => {
+                    if row == header {
+                        grid.insert(cells);
+                    }
+                    return true;
+                }
+                None => {
+                    header_index = Some(row);
+                    grid.insert(cells);
+                    return true;
+                }
+                Some(_) => {}
+            }
+            if skipped < offset_row {
+                skipped += 1;
+                return true;
+            }
+            data_rows.push(row);
+            grid.insert(cells);
+            data_rows.len() <= input.max_rows
+        },
+    )?;
+
+    let Some(header_index) = header_index else {
+        return JsonUtil::to_value(GetSheetRecordsOutput {
+            sheet: sheet.name,
+            header_row: None,
+            first_col: None,
+            cols: 0,
+            headers: Vec::new(),
+            rows: 0,
+            offset_row,
+            next_cursor: None,
+            records: Vec::new(),
+        });
+    };
+    let has_more = data_rows.len() > input.max_rows;
+    data_rows.truncate(input.max_rows);
+
+    // Span covers the header and every returned row so no value is dropped for lack of a key.
+    let (first_col, last_col) = std::iter::once(header_index)
+        .chain(data_rows.iter().copied())
+        .filter_map(|r| grid.used_span(r))
+        .fold((usize::MAX, 0), |(lo, hi), (first, last)| {
+            (lo.min(first), hi.max(last))
+        });
+    let columns: Vec<usize> = if first_col == usize::MAX {
+        Vec::new()
+    } else {
+        (first_col..=last_col).collect()
+    };
+
+    let headers = record_headers(&grid, header_index, &columns);
+    let records = data_rows
+        .iter()
+        .map(|r| {
+            columns
+                .iter()
+                .zip(&headers)
+                .map(|(c, key)| {
+                    let value = grid.get(*r, *c).map(value_as_json).unwrap_or(Value::Null);
+                    (key.clone(), value)
+                })
+                .collect::<Map<String, Value>>()
+        })
+        .collect::<Vec<_>>();
+
+    JsonUtil::to_value(GetSheetRecordsOutput {
+        sheet: sheet.name,
+        header_row: Some(header_index + 1),
+        first_col: columns.first().map(|c| column_letters(*c)),
+        cols: headers.len(),
+        headers,
+        rows: records.len(),
+        offset_row,
+        next_cursor: has_more.then(|| paging.next(sheet.index, offset_row + records.len(), 0)),
+        records,
+    })
+}
+
+fn record_headers(grid: &RowCells, header_index: usize, columns: &[usize]) -> Vec<String> {
+    // Blank headers fall back to the column letter; duplicates get a numeric suffix (_2, _3...).
+    let mut seen = HashSet::new();
+    let mut headers = Vec::with_capacity(columns.len());
+    for &c in columns {
+        let raw = grid
+            .get(header_index, c)
+            .map(value_as_string)
+            .unwrap_or_default();
+        let base = match raw.trim() {
+            "" => column_letters(c),
+            text => text.to_string(),
+        };
+        let mut key = base.clone();
+        let mut suffix = 2;
+        while !seen.insert(key.clone()) {
+            key = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        headers.push(key);
+    }
+    headers
+}
+
+fn column_letters(col: usize) -> String {
+    let a1 = CellAddress { row: 0, col }.to_a1();
+    a1.trim_end_matches('1').to_string()
+}
+
+fn value_as_json(value: &CellValue) -> Value {
+    match value {
+        CellValue::String(v) | CellValue::Date(v) => Value::String(v.clone()),
+        CellValue::Number(v) => {
+            // Whole numbers are emitted as integers so 3 does not round-trip as 3.0.
+            if v.fract() == 0.0 && v.abs() < 9_007_199_254_740_992.0 {
+                Value::from(*v as i64)
+            } else {
+                Number::from_f64(*v)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null)
+            }
+        }
+        CellValue::Boolean(v) => Value::Bool(*v),
+        CellValue::Empty => Value::Null,
+    }
+}
+
 fn value_as_string(value: &CellValue) -> String {
     match value {
-        CellValue::String(v) => v.clone(),
+        CellValue::String(v) | CellValue::Date(v) => v.clone(),
         CellValue::Number(v) => v.to_string(),
         CellValue::Boolean(v) => v.to_string(),
         CellValue::Empty => String::new(),
@@ -1,6 +1,7 @@
 use crate::common::errors::AppError;
 use crate::common::fs::FsUtil;
 use crate::common::json::JsonUtil;
+use crate::ods::content_xml::{ContentXml, SheetAttributes, SheetStats};
 use crate::ods::ods_file::OdsFile;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -13,17 +14,77 @@ struct GetSheetsInput {
 #[derive(Debug, Serialize)]
 struct GetSheetsOutput {
     sheets: Vec<String>,
+    details: Vec<SheetDetails>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SheetDetails {
+    name: String,
+    index: usize,
+    visible: bool,
+    tab_color: Option<String>,
+    protected: bool,
+    has_password: bool,
+    #[serde(flatten)]
+    usage: Option<SheetUsage>,
+}
+
+#[derive(Debug, Serialize)]
+struct SheetUsage {
+    used_range: Option<String>,
+    non_empty_cells: usize,
+    has_formulas: bool,
+    has_merges: bool,
+    has_comments: bool,
+}
+
+impl From<SheetStats> for SheetUsage {
+    fn from(stats: SheetStats) -> Self {
+        Self {
+            used_range: stats.used_range_a1(),
+            non_empty_cells: stats.non_empty_cells,
+            has_formulas: stats.has_formulas,
+            has_merges: stats.has_merges,
+            has_comments: stats.has_comments,
+        }
+    }
+}
+
+impl SheetDetails {
+    pub(crate) fn from_attributes(index: usize, attrs: SheetAttributes) -> Self {
+        Self {
+            name: attrs.name,
+            index,
+            visible: attrs.visible,
+            tab_color: attrs.tab_color,
+            protected: attrs.protected,
+            has_password: attrs.has_password,
+            usage: None,
+        }
+    }
 }
 
 pub fn handle(params: Value) -> Result<Value, AppError> {
-    // Returns sheet names preserving the same order as content.xml.
+    // Returns sheet names preserving the same order as content.xml, plus per-sheet attributes
+    // and usage stats gathered in a streaming pass (no full workbook parse).
     let input: GetSheetsInput = JsonUtil::from_value(params)?;
     let path = FsUtil::resolve_ods_path(&input.path)?;
     if !path.exists() {
         return Err(AppError::FileNotFound(path.display().to_string()));
     }
 
-    let workbook = OdsFile::read_workbook(&path)?;
-    let sheets = workbook.sheets.into_iter().map(|s| s.name).collect();
-    JsonUtil::to_value(GetSheetsOutput { sheets })
+    let content = OdsFile::read_content_xml(&path)?;
+    let attributes = ContentXml::sheet_attributes_raw(&content)?;
+    let mut stats = ContentXml::sheet_stats_raw(&content)?.into_iter();
+    let sheets = attributes.iter().map(|a| a.name.clone()).collect();
+    let details = attributes
+        .into_iter()
+        .enumerate()
+        .map(|(index, attrs)| {
+            let mut details = SheetDetails::from_attributes(index, attrs);
+            details.usage = stats.next().map(SheetUsage::from);
+            details
+        })
+        .collect();
+    JsonUtil::to_value(GetSheetsOutput { sheets, details })
 }
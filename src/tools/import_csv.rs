@@ -0,0 +1,316 @@
+use crate::common::csv::{CsvEncoding, CsvUtil};
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::ContentXml;
+use crate::ods::ods_file::OdsFile;
+use crate::ods::sheet_model::CellValue;
+use crate::tools::sheet_ref::SheetRef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+struct ImportCsvInput {
+    path: String,
+    csv_path: String,
+    sheet: SheetRef,
+    #[serde(default)]
+    create_sheet: bool,
+    #[serde(default = "default_start_cell")]
+    start_cell: String,
+    delimiter: Option<String>,
+    #[serde(default = "default_quote")]
+    quote: String,
+    #[serde(default = "default_encoding")]
+    encoding: String,
+    #[serde(default = "default_true")]
+    has_header: bool,
+    #[serde(default = "default_decimal_separator")]
+    decimal_separator: String,
+    #[serde(default = "default_true")]
+    infer_types: bool,
+    #[serde(default)]
+    clear_target: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportCsvOutput {
+    sheet: String,
+    created_sheet: bool,
+    range: String,
+    rows_written: usize,
+    cols_written: usize,
+    delimiter: String,
+    column_types: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cleared_range: Option<String>,
+}
+
+fn default_start_cell() -> String {
+    "A1".to_string()
+}
+
+fn default_quote() -> String {
+    "\"".to_string()
+}
+
+fn default_encoding() -> String {
+    "utf-8".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_decimal_separator() -> String {
+    ".".to_string()
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Loads a delimited file into one sheet in a single style-preserving pass over content.xml.
+    let input: ImportCsvInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+    let csv_path = FsUtil::resolve_path(&input.csv_path)?;
+    if !csv_path.exists() {
+        return Err(AppError::FileNotFound(csv_path.display().to_string()));
+    }
+
+    let quote = CsvUtil::option_char(&input.quote, "quote")?;
+    let decimal = match input.decimal_separator.as_str() {
+        "." => '.',
+        "," => ',',
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "decimal_separator must be \".\" or \",\", got {other:?}"
+            )))
+        }
+    };
+    let start = CellAddress::parse(&input.start_cell)?;
+    let encoding = CsvEncoding::parse(&input.encoding)?;
+    let text = CsvUtil::decode(&std::fs::read(&csv_path)?, encoding)?;
+    let delimiter = match input.delimiter.as_deref() {
+        Some(d) => CsvUtil::option_char(d, "delimiter")?,
+        None if is_tab_separated(&csv_path) => '\t',
+        None => CsvUtil::sniff_delimiter(&text),
+    };
+    if delimiter == quote {
+        return Err(AppError::InvalidInput(
+            "delimiter and quote must differ".to_string(),
+        ));
+    }
+
+    let records = CsvUtil::parse(&text, delimiter, quote)?;
+    if records.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "csv file has no rows: {}",
+            csv_path.display()
+        )));
+    }
+    let cols = records.iter().map(Vec::len).max().unwrap_or(0);
+    let header_rows = usize::from(input.has_header);
+    let column_types: Vec<ColumnType> = (0..cols)
+        .map(|c| {
+            if input.infer_types {
+                ColumnType::infer(
+                    records.iter().skip(header_rows).map(|r| field(r, c)),
+                    decimal,
+                )
+            } else {
+                ColumnType::String
+            }
+        })
+        .collect();
+
+    let mut content = OdsFile::read_content_xml(&path)?;
+    let mut sheet_names = ContentXml::sheet_names_from_content_raw(&content)?;
+    let mut created_sheet = false;
+    let sheet_index = match (&input.sheet, input.create_sheet) {
+        (SheetRef::Name { name }, true) if !sheet_names.contains(name) => {
+            content =
+                ContentXml::add_sheet_at_preserving_styles_raw(&content, name, sheet_names.len())?;
+            sheet_names.push(name.clone());
+            created_sheet = true;
+            sheet_names.len() - 1
+        }
+        (sheet, _) => sheet.resolve_in_names(&sheet_names)?.0,
+    };
+
+    let mut values: Vec<Vec<CellValue>> = records
+        .iter()
+        .enumerate()
+        .map(|(r, record)| {
+            (0..cols)
+                .map(|c| {
+                    let raw = field(record, c);
+                    if r < header_rows {
+                        text_value(raw)
+                    } else {
+                        column_types[c].convert(raw, decimal)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    // Blanks whatever the sheet holds beyond the imported block, from start_cell to the used range end.
+    let mut cleared_range = None;
+    if input.clear_target && !created_sheet {
+        let stats = ContentXml::sheet_stats_raw(&content)?;
+        if let Some((_, _, last_row, last_col)) = stats.get(sheet_index).and_then(|s| s.used_bounds)
+        {
+            if last_row >= start.row && last_col >= start.col {
+                let clear_rows = last_row - start.row + 1;
+                let clear_cols = last_col - start.col + 1;
+                let width = cols.max(clear_cols);
+                for row in &mut values {
+                    row.resize(width, CellValue::Empty);
+                }
+                while values.len() < clear_rows {
+                    values.push(vec![CellValue::Empty; width]);
+                }
+                cleared_range = Some(format!(
+                    "{}:{}",
+                    start.to_a1(),
+                    CellAddress {
+                        row: last_row,
+                        col: last_col
+                    }
+                    .to_a1()
+                ));
+            }
+        }
+    }
+
+    content = ContentXml::set_range_values_preserving_styles_raw(
+        &content,
+        sheet_index,
+        start.row,
+        start.col,
+        &values,
+    )?;
+    OdsFile::write_content_xml(&path, &content)?;
+
+    let end = CellAddress {
+        row: start.row + records.len() - 1,
+        col: start.col + cols.max(1) - 1,
+    };
+    JsonUtil::to_value(ImportCsvOutput {
+        sheet: sheet_names[sheet_index].clone(),
+        created_sheet,
+        range: format!("{}:{}", start.to_a1(), end.to_a1()),
+        rows_written: records.len(),
+        cols_written: cols,
+        delimiter: delimiter.to_string(),
+        column_types: column_types.iter().map(|t| t.name()).collect(),
+        cleared_range,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Number,
+    Boolean,
+    String,
+    Empty,
+}
+
+impl ColumnType {
+    // A column is typed only when every non-blank data field agrees; otherwise it stays text.
+    fn infer<'a>(fields: impl Iterator<Item = &'a str>, decimal: char) -> Self {
+        let mut kind = ColumnType::Empty;
+        for raw in fields {
+            let raw = raw.trim();
+            if raw.is_empty() {
+                continue;
+            }
+            let this = if parse_number(raw, decimal).is_some() {
+                ColumnType::Number
+            } else if parse_bool(raw).is_some() {
+                ColumnType::Boolean
+            } else {
+                return ColumnType::String;
+            };
+            if kind != ColumnType::Empty && kind != this {
+                return ColumnType::String;
+            }
+            kind = this;
+        }
+        kind
+    }
+
+    fn convert(self, raw: &str, decimal: char) -> CellValue {
+        let trimmed = raw.trim();
+        match self {
+            ColumnType::Number => parse_number(trimmed, decimal)
+                .map(CellValue::Number)
+                .unwrap_or(CellValue::Empty),
+            ColumnType::Boolean => parse_bool(trimmed)
+                .map(CellValue::Boolean)
+                .unwrap_or(CellValue::Empty),
+            ColumnType::String | ColumnType::Empty => text_value(raw),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ColumnType::Number => "number",
+            ColumnType::Boolean => "boolean",
+            ColumnType::String => "string",
+            ColumnType::Empty => "empty",
+        }
+    }
+}
+
+fn parse_number(raw: &str, decimal: char) -> Option<f64> {
+    let other = if decimal == ',' { '.' } else { ',' };
+    if raw.contains(other) || !raw.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if !raw
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == decimal || matches!(c, '+' | '-' | 'e' | 'E'))
+    {
+        return None;
+    }
+    // Leading zeros ("007", zip codes) mark identifiers, which must survive as text.
+    let digits = raw.trim_start_matches(['+', '-']);
+    if digits.len() > 1 && digits.starts_with('0') && !digits[1..].starts_with(decimal) {
+        return None;
+    }
+    raw.replace(decimal, ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+}
+
+fn parse_bool(raw: &str) -> Option<bool> {
+    match raw.to_ascii_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn text_value(raw: &str) -> CellValue {
+    if raw.is_empty() {
+        CellValue::Empty
+    } else {
+        CellValue::String(raw.replace("\r\n", "\n"))
+    }
+}
+
+fn field(record: &[String], col: usize) -> &str {
+    record.get(col).map(String::as_str).unwrap_or("")
+}
+
+fn is_tab_separated(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("tsv") || e.eq_ignore_ascii_case("tab"))
+        .unwrap_or(false)
+}
@@ -0,0 +1,74 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::ods_file::OdsFile;
+use crate::ods::sheet_model::CellValue;
+use crate::ods::workbook_json::WorkbookJson;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct ImportJsonInput {
+    path: String,
+    // Exactly one of json_path (a file written by export_json) or an inline workbook.
+    json_path: Option<String>,
+    workbook: Option<Value>,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportJsonOutput {
+    path: String,
+    sheets: Vec<String>,
+    cells: usize,
+    formulas: usize,
+    merges: usize,
+    comments: usize,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Rebuilds a fresh package from the JSON snapshot; the template supplies everything but content.xml.
+    let input: ImportJsonInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    let document = match (input.json_path, input.workbook) {
+        (Some(json_path), None) => {
+            let json_path = FsUtil::resolve_path(&json_path)?;
+            if !json_path.exists() {
+                return Err(AppError::FileNotFound(json_path.display().to_string()));
+            }
+            serde_json::from_slice(&std::fs::read(&json_path)?).map_err(|e| {
+                AppError::InvalidInput(format!("invalid json in {}: {e}", json_path.display()))
+            })?
+        }
+        (None, Some(workbook)) => workbook,
+        _ => {
+            return Err(AppError::InvalidInput(
+                "provide exactly one of json_path or workbook".to_string(),
+            ))
+        }
+    };
+    let workbook = WorkbookJson::from_value(document)?;
+
+    if path.exists() && !input.overwrite {
+        return Err(AppError::AlreadyExists(path.display().to_string()));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    OdsFile::create(&path, workbook.sheets[0].name.clone())?;
+    OdsFile::write_workbook(&path, &workbook)?;
+
+    let cells = workbook.sheets.iter().flat_map(|s| s.rows.iter().flatten());
+    JsonUtil::to_value(ImportJsonOutput {
+        path: path.display().to_string(),
+        sheets: workbook.sheets.iter().map(|s| s.name.clone()).collect(),
+        cells: cells
+            .clone()
+            .filter(|c| c.value != CellValue::Empty)
+            .count(),
+        formulas: cells.clone().filter(|c| c.formula.is_some()).count(),
+        merges: workbook.sheets.iter().map(|s| s.merges.len()).sum(),
+        comments: cells.filter(|c| c.comment.is_some()).count(),
+    })
+}
@@ -0,0 +1,185 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::{ContentXml, ImageData, NewImageFrame};
+use crate::ods::image::ImageInfo;
+use crate::ods::ods_file::{OdsFile, PackageLayout, SaveOptions};
+use crate::tools::sheet_ref::SheetRef;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Deserialize)]
+struct InsertImageInput {
+    path: String,
+    sheet: SheetRef,
+    // Local PNG, JPEG or SVG file.
+    source: String,
+    // Omitted: the image is anchored to the page instead of a cell.
+    cell: Option<String>,
+    // ODF lengths ("4cm", "30mm", "2in"; a bare number is pixels), stored in cm. Omitted
+    // sizes come from the image itself at 96 dpi; giving only one keeps the aspect ratio.
+    width: Option<String>,
+    height: Option<String>,
+    // Offset from the cell's top-left corner, or from the page origin.
+    #[serde(default = "default_offset")]
+    x: String,
+    #[serde(default = "default_offset")]
+    y: String,
+    // Omitted: "Image N" with the first free N.
+    name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct InsertImageOutput {
+    inserted: bool,
+    sheet: String,
+    // Cell in A1 form, or "page".
+    anchor: String,
+    name: String,
+    // Package part holding the picture; absent for flat documents, which embed it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    href: Option<String>,
+    media_type: String,
+    width: String,
+    height: String,
+}
+
+fn default_offset() -> String {
+    "0cm".to_string()
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // The picture part and the frame in content.xml are written in one package save.
+    let input: InsertImageInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+    let source_path = FsUtil::resolve_path(&input.source)?;
+    if !source_path.exists() {
+        return Err(AppError::FileNotFound(source_path.display().to_string()));
+    }
+    let data = std::fs::read(&source_path)?;
+    let info = ImageInfo::inspect(&data)?;
+    // Lengths are written in cm: ODF requires a unit, and a bare number here means pixels.
+    let (width, height) = frame_size(&info, input.width.as_deref(), input.height.as_deref())?;
+    let offset = |key: &str, value: &str| {
+        ImageInfo::length_cm(value)
+            .map(ImageInfo::format_cm)
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!("{key} must be a length such as \"1cm\": {value}"))
+            })
+    };
+    let (x, y) = (offset("x", &input.x)?, offset("y", &input.y)?);
+
+    let original_content = OdsFile::read_content_xml(&path)?;
+    let names = ContentXml::sheet_names_from_content_raw(&original_content)?;
+    let (sheet_index, sheet_name) = input.sheet.resolve_in_names(&names)?;
+    let cell = match &input.cell {
+        Some(cell) => {
+            let address = CellAddress::parse(cell)?;
+            Some(ContentXml::resolve_merged_anchor_raw(
+                &original_content,
+                sheet_index,
+                address.row,
+                address.col,
+            )?)
+        }
+        None => None,
+    };
+
+    let existing = ContentXml::images_raw(&original_content)?;
+    let used: Vec<&str> = existing.iter().filter_map(|f| f.name.as_deref()).collect();
+    let name = match input.name {
+        Some(name) if used.contains(&name.as_str()) => {
+            return Err(AppError::InvalidInput(format!(
+                "image name already in use: {name}"
+            )))
+        }
+        Some(name) => name,
+        None => (1..)
+            .map(|n| format!("Image {n}"))
+            .find(|candidate| !used.contains(&candidate.as_str()))
+            .unwrap_or_default(),
+    };
+
+    let flat = PackageLayout::from_path(&path) == PackageLayout::Flat;
+    // Named after the content hash, so inserting the same picture twice shares one part.
+    let href = (!flat).then(|| {
+        let digest = Sha256::digest(&data);
+        let hex: String = digest[..16].iter().map(|b| format!("{b:02X}")).collect();
+        format!("Pictures/{hex}.{}", info.format.extension())
+    });
+    let frame = NewImageFrame {
+        name: name.clone(),
+        data: match &href {
+            Some(href) => ImageData::Part(href.clone()),
+            None => ImageData::Inline(base64::engine::general_purpose::STANDARD.encode(&data)),
+        },
+        media_type: info.format.media_type().to_string(),
+        width: width.clone(),
+        height: height.clone(),
+        x,
+        y,
+    };
+    let updated_content =
+        ContentXml::insert_image_frame_raw(&original_content, sheet_index, cell, &frame)?;
+
+    match &href {
+        Some(href) => {
+            let mut package = OdsFile::open_package(&path)?;
+            package.put_with_media_type(href, data, info.format.media_type())?;
+            package.put("content.xml", updated_content)?;
+            package.save(&path, &SaveOptions::from_env())?;
+        }
+        None => OdsFile::write_content_xml(&path, &updated_content)?,
+    }
+
+    JsonUtil::to_value(InsertImageOutput {
+        inserted: true,
+        sheet: sheet_name,
+        anchor: cell
+            .map(|(row, col)| CellAddress { row, col }.to_a1())
+            .unwrap_or_else(|| "page".to_string()),
+        name,
+        href,
+        media_type: frame.media_type,
+        width,
+        height,
+    })
+}
+
+fn frame_size(
+    info: &ImageInfo,
+    width: Option<&str>,
+    height: Option<&str>,
+) -> Result<(String, String), AppError> {
+    let parse = |key: &str, value: &str| {
+        ImageInfo::length_cm(value)
+            .filter(|cm| *cm > 0.0)
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!(
+                    "{key} must be a positive length such as \"4cm\": {value}"
+                ))
+            })
+    };
+    let width = width.map(|w| parse("width", w)).transpose()?;
+    let height = height.map(|h| parse("height", h)).transpose()?;
+    if let (Some(w), Some(h)) = (width, height) {
+        return Ok((ImageInfo::format_cm(w), ImageInfo::format_cm(h)));
+    }
+    let (natural_w, natural_h) = info.size_cm.ok_or_else(|| {
+        AppError::InvalidInput(
+            "the image does not state its size; give width and height".to_string(),
+        )
+    })?;
+    let (w, h) = match (width, height) {
+        (Some(w), None) => (w, w * natural_h / natural_w),
+        (None, Some(h)) => (h * natural_w / natural_h, h),
+        _ => (natural_w, natural_h),
+    };
+    Ok((ImageInfo::format_cm(w), ImageInfo::format_cm(h)))
+}
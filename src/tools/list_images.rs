@@ -0,0 +1,84 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::ContentXml;
+use crate::ods::ods_file::OdsFile;
+use crate::tools::sheet_ref::SheetRef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct ListImagesInput {
+    path: String,
+    // Omitted: every sheet.
+    sheet: Option<SheetRef>,
+}
+
+#[derive(Debug, Serialize)]
+struct ListImagesOutput {
+    total: usize,
+    images: Vec<ImageEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct ImageEntry {
+    sheet: String,
+    // Cell in A1 form, or "page" for frames in table:shapes.
+    anchor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    // Absent when the picture is embedded in the frame (flat documents).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    href: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    media_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    x: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    y: Option<String>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Picture frames anchored to cells or to the page; charts and other objects are skipped.
+    let input: ListImagesInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let content = OdsFile::read_content_xml(&path)?;
+    let sheet_index = match &input.sheet {
+        Some(sheet) => {
+            let names = ContentXml::sheet_names_from_content_raw(&content)?;
+            Some(sheet.resolve_in_names(&names)?.0)
+        }
+        None => None,
+    };
+    let images: Vec<ImageEntry> = ContentXml::images_raw(&content)?
+        .into_iter()
+        .filter(|frame| sheet_index.is_none_or(|index| frame.sheet_index == index))
+        .map(|frame| ImageEntry {
+            sheet: frame.sheet,
+            anchor: frame
+                .cell
+                .map(|(row, col)| CellAddress { row, col }.to_a1())
+                .unwrap_or_else(|| "page".to_string()),
+            name: frame.name,
+            href: frame.href,
+            media_type: frame.media_type,
+            width: frame.width,
+            height: frame.height,
+            x: frame.x,
+            y: frame.y,
+        })
+        .collect();
+    JsonUtil::to_value(ListImagesOutput {
+        total: images.len(),
+        images,
+    })
+}
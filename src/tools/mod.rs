@@ -1,11 +1,38 @@
 pub mod add_sheet;
+pub mod check_workbook;
+pub mod convert_workbook;
 pub mod create_ods;
 pub mod duplicate_sheet;
+pub mod delete_image;
 pub mod delete_sheet;
+pub mod evaluate_formula;
+pub mod export_csv;
+pub mod export_json;
+pub mod find_cells;
 pub mod get_cell_value;
+pub mod get_document_properties;
 pub mod get_sheet_content;
 pub mod get_sheets;
+pub mod import_csv;
+pub mod import_json;
+pub mod insert_image;
+pub mod list_images;
+pub mod move_sheet;
+pub mod page_cursor;
+pub mod recalculate;
 pub mod rename_sheet;
+pub mod replace_in_cells;
+pub mod set_cell_formula;
 pub mod set_cell_value;
+pub mod set_document_properties;
 pub mod set_range_values;
+pub mod set_sheet_protection;
+pub mod set_sheet_tab_color;
+pub mod set_sheet_visibility;
+pub mod sheet_position;
 pub mod sheet_ref;
+pub mod sheet_render;
+pub mod trace_dependents;
+pub mod trace_precedents;
+pub mod trace_tree;
+pub mod validate_ods;
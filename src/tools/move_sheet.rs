@@ -0,0 +1,75 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::content_xml::ContentXml;
+use crate::ods::ods_file::OdsFile;
+use crate::tools::sheet_position::SheetPosition;
+use crate::tools::sheet_ref::SheetRef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct MoveSheetInput {
+    path: String,
+    sheet: SheetRef,
+    position: SheetPosition,
+}
+
+#[derive(Debug, Serialize)]
+struct MoveSheetOutput {
+    sheets: Vec<String>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Relocates the whole table:table block, so styles and contents travel untouched.
+    let input: MoveSheetInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let original_content = OdsFile::read_content_xml(&path)?;
+    let sheet_names = ContentXml::sheet_names_from_content_raw(&original_content)?;
+    let (source_index, source_name) = input.sheet.resolve_in_names(&sheet_names)?;
+
+    // Anchors name sheets in the current order; absolute positions count the sheets that
+    // remain once this one is lifted out.
+    let target_index = match input.position.anchor() {
+        Some(anchor) => {
+            let (anchor_index, _) = anchor.resolve_in_names(&sheet_names)?;
+            if anchor_index == source_index {
+                return Err(AppError::InvalidInput(format!(
+                    "cannot position sheet {source_name} relative to itself"
+                )));
+            }
+            // An anchor below the source moves up one place when the source is removed.
+            let anchor_index = if anchor_index > source_index {
+                anchor_index - 1
+            } else {
+                anchor_index
+            };
+            match input.position {
+                SheetPosition::After(_) => anchor_index + 1,
+                _ => anchor_index,
+            }
+        }
+        None => {
+            let mut remaining = sheet_names;
+            remaining.remove(source_index);
+            input.position.resolve_insert_index(&remaining)?
+        }
+    };
+
+    let updated_content = ContentXml::move_sheet_preserving_styles_raw(
+        &original_content,
+        None,
+        Some(source_index),
+        target_index,
+    )?;
+    if updated_content != original_content {
+        OdsFile::write_content_xml(&path, &updated_content)?;
+    }
+
+    let sheets = ContentXml::sheet_names_from_content_raw(&updated_content)?;
+    JsonUtil::to_value(MoveSheetOutput { sheets })
+}
@@ -0,0 +1,101 @@
+use crate::common::errors::AppError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+// Identifies the file version a cursor was issued for (mtime in nanoseconds plus size).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified_nanos: u128,
+    pub len: u64,
+}
+
+impl FileStamp {
+    pub fn of(path: &Path) -> Result<Self, AppError> {
+        let metadata = std::fs::metadata(path)?;
+        let modified_nanos = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        Ok(Self {
+            modified_nanos,
+            len: metadata.len(),
+        })
+    }
+}
+
+// Opaque continuation token for paginated reads; `row`/`col` meaning depends on the mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageCursor {
+    pub mode: String,
+    pub sheet: usize,
+    pub row: usize,
+    pub col: usize,
+    pub stamp: FileStamp,
+}
+
+impl PageCursor {
+    const VERSION: &'static str = "v1";
+
+    pub fn encode(&self) -> String {
+        let raw = format!(
+            "{}:{}:{}:{}:{}:{}:{}",
+            Self::VERSION,
+            self.mode,
+            self.sheet,
+            self.row,
+            self.col,
+            self.stamp.modified_nanos,
+            self.stamp.len
+        );
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::InvalidInput("invalid cursor".to_string());
+        let bytes = URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .map_err(|_| invalid())?;
+        let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let parts: Vec<&str> = raw.split(':').collect();
+        if parts.len() != 7 || parts[0] != Self::VERSION {
+            return Err(invalid());
+        }
+        Ok(Self {
+            mode: parts[1].to_string(),
+            sheet: parts[2].parse().map_err(|_| invalid())?,
+            row: parts[3].parse().map_err(|_| invalid())?,
+            col: parts[4].parse().map_err(|_| invalid())?,
+            stamp: FileStamp {
+                modified_nanos: parts[5].parse().map_err(|_| invalid())?,
+                len: parts[6].parse().map_err(|_| invalid())?,
+            },
+        })
+    }
+
+    // Rejects cursors issued for another mode, another sheet or an older version of the file.
+    pub fn validate(&self, mode: &str, sheet: usize, stamp: FileStamp) -> Result<(), AppError> {
+        if self.mode != mode {
+            return Err(AppError::InvalidInput(format!(
+                "cursor was issued for mode={}, not mode={mode}",
+                self.mode
+            )));
+        }
+        if self.sheet != sheet {
+            return Err(AppError::InvalidInput(format!(
+                "cursor was issued for sheet index {}, not {sheet}",
+                self.sheet
+            )));
+        }
+        if self.stamp != stamp {
+            return Err(AppError::InvalidInput(
+                "cursor is stale: the file changed since it was issued; restart without cursor"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::{CachedResult, ContentXml};
+use crate::ods::formula::{Formula, FormulaValue};
+use crate::ods::ods_file::OdsFile;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+struct RecalculateInput {
+    path: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RecalculateOutput {
+    formulas: usize,
+    recalculated: usize,
+    // Cells whose cached value differed and was rewritten.
+    changed: usize,
+    // Recalculated cells whose result is an error value such as #DIV/0!.
+    errors: usize,
+    issues: Vec<IssueOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct IssueOutput {
+    sheet: String,
+    cell: String,
+    formula: String,
+    reason: &'static str,
+    detail: String,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Evaluates every formula in dependency order and rewrites only the cached results that
+    // changed, through the raw content.xml path so formulas and styles stay untouched.
+    let input: RecalculateInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let content = OdsFile::read_content_xml(&path)?;
+    let workbook = ContentXml::parse(&content)?;
+    let outcome = Formula::recalculate(&workbook);
+
+    let changes: HashMap<(usize, usize, usize), CachedResult> = outcome
+        .results
+        .iter()
+        .filter(|r| r.previous != r.value)
+        .map(|r| {
+            (
+                (r.sheet_index, r.row, r.col),
+                CachedResult {
+                    value: r.value.to_cell_value(),
+                    text: r.value.display_text(),
+                },
+            )
+        })
+        .collect();
+    if !changes.is_empty() {
+        let updated = ContentXml::write_formula_results_raw(&content, &changes)?;
+        OdsFile::write_content_xml(&path, &updated)?;
+    }
+
+    let issues = outcome
+        .issues
+        .iter()
+        .map(|issue| IssueOutput {
+            sheet: workbook.sheets[issue.sheet_index].name.clone(),
+            cell: CellAddress {
+                row: issue.row,
+                col: issue.col,
+            }
+            .to_a1(),
+            formula: issue.formula.clone(),
+            reason: issue.kind.as_str(),
+            detail: issue.detail.clone(),
+        })
+        .collect();
+    JsonUtil::to_value(RecalculateOutput {
+        formulas: outcome.results.len() + outcome.issues.len(),
+        recalculated: outcome.results.len(),
+        changed: changes.len(),
+        errors: outcome
+            .results
+            .iter()
+            .filter(|r| matches!(r.value, FormulaValue::Error(_)))
+            .count(),
+        issues,
+    })
+}
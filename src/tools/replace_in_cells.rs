@@ -0,0 +1,152 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::{ContentXml, ReplaceScope, ReplacedField};
+use crate::ods::ods_file::OdsFile;
+use crate::tools::sheet_ref::SheetRef;
+use regex::{NoExpand, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct ReplaceInCellsInput {
+    path: String,
+    // Omitted: every sheet in workbook order.
+    sheet: Option<SheetRef>,
+    // "A1:C10"; needs `sheet`.
+    range: Option<String>,
+    find: String,
+    replace: String,
+    #[serde(default)]
+    regex: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    whole_cell: bool,
+    #[serde(default)]
+    include_formulas: bool,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default = "default_max_results")]
+    max_results: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ReplaceInCellsOutput {
+    dry_run: bool,
+    count: usize,
+    changes: Vec<PlannedChange>,
+    truncated: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct PlannedChange {
+    sheet: String,
+    cell: String,
+    field: &'static str,
+    before: String,
+    after: String,
+}
+
+fn default_max_results() -> usize {
+    100
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Rewrites matching cells in place through the raw content.xml path, so styles survive.
+    let input: ReplaceInCellsInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+    if input.find.is_empty() {
+        return Err(AppError::InvalidInput("find must not be empty".to_string()));
+    }
+    if input.range.is_some() && input.sheet.is_none() {
+        return Err(AppError::InvalidInput(
+            "range requires sheet to be set".to_string(),
+        ));
+    }
+
+    let pattern = if input.regex {
+        input.find.clone()
+    } else {
+        regex::escape(&input.find)
+    };
+    let pattern = if input.whole_cell {
+        format!("^(?:{pattern})$")
+    } else {
+        pattern
+    };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!input.case_sensitive)
+        .build()
+        .map_err(|e| AppError::InvalidInput(format!("invalid regex: {e}")))?;
+
+    let content = OdsFile::read_content_xml(&path)?;
+    let sheet_index = match &input.sheet {
+        Some(sheet) => {
+            let names = ContentXml::sheet_names_from_content_raw(&content)?;
+            Some(sheet.resolve_in_names(&names)?.0)
+        }
+        None => None,
+    };
+    let (rows, cols) = match input.range.as_deref() {
+        Some(range) => {
+            let (start, end) = CellAddress::parse_range(range)?;
+            ((start.row, end.row), (start.col, end.col))
+        }
+        None => ((0, usize::MAX), (0, usize::MAX)),
+    };
+    let scope = ReplaceScope {
+        sheet_index,
+        rows,
+        cols,
+        values: true,
+        formulas: input.include_formulas,
+    };
+
+    // Plain text is inserted literally; with regex, $1 and ${name} expand capture groups.
+    let outcome = ContentXml::replace_in_cells_raw(&content, &scope, input.max_results, |text| {
+        if !regex.is_match(text) {
+            return None;
+        }
+        let replaced = if input.regex {
+            regex.replace_all(text, input.replace.as_str())
+        } else {
+            regex.replace_all(text, NoExpand(&input.replace))
+        };
+        Some(replaced.into_owned())
+    })?;
+
+    if !input.dry_run && outcome.total > 0 {
+        OdsFile::write_content_xml(&path, &outcome.content)?;
+    }
+
+    let changes = outcome
+        .replacements
+        .into_iter()
+        .map(|r| PlannedChange {
+            sheet: r.sheet,
+            cell: CellAddress {
+                row: r.row,
+                col: r.col,
+            }
+            .to_a1(),
+            field: match r.field {
+                ReplacedField::Value => "value",
+                ReplacedField::Formula => "formula",
+            },
+            before: r.before,
+            after: r.after,
+        })
+        .collect::<Vec<_>>();
+
+    JsonUtil::to_value(ReplaceInCellsOutput {
+        dry_run: input.dry_run,
+        count: outcome.total,
+        truncated: outcome.total > changes.len(),
+        changes,
+    })
+}
@@ -0,0 +1,102 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::content_xml::{CachedResult, ContentXml};
+use crate::ods::formula::{Formula, FormulaDialect, FormulaSyntax};
+use crate::ods::ods_file::OdsFile;
+use crate::ods::sheet_model::CellValue;
+use crate::tools::sheet_ref::SheetRef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct SetCellFormulaInput {
+    path: String,
+    sheet: SheetRef,
+    cell: String,
+    formula: String,
+    // Omitted: detected from the text (see FormulaSyntax::detect).
+    formula_syntax: Option<FormulaDialect>,
+}
+
+#[derive(Debug, Serialize)]
+struct SetCellFormulaOutput {
+    updated: bool,
+    sheet: String,
+    cell: String,
+    // Formula as stored, in OpenFormula syntax.
+    formula: String,
+    // Cached result written with the formula; None when it could not be computed.
+    value: Option<CellValue>,
+    unsupported: Vec<String>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Stores the formula through the raw content.xml path, so the cell style survives, and
+    // caches its result when the evaluator can compute it.
+    let input: SetCellFormulaInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let stored = FormulaSyntax::to_odf(&input.formula, input.formula_syntax);
+    let formula = Formula::parse(&stored)?;
+
+    let original_content = OdsFile::read_content_xml(&path)?;
+    let workbook = ContentXml::parse(&original_content)?;
+    let (sheet_index, sheet_name) = input.sheet.resolve_in_workbook(&workbook)?;
+    let address = CellAddress::parse(&input.cell)?;
+    let (target_row, target_col) = ContentXml::resolve_merged_anchor_raw(
+        &original_content,
+        sheet_index,
+        address.row,
+        address.col,
+    )?;
+
+    // A formula reading its own cell is circular; it is stored without a cached result.
+    let unsupported = formula.unsupported_names();
+    let reads_itself = formula.references().iter().any(|range| {
+        let same_sheet = range
+            .sheet
+            .as_deref()
+            .is_none_or(|name| name.eq_ignore_ascii_case(&sheet_name));
+        same_sheet
+            && (range.start.row..=range.end.row).contains(&target_row)
+            && (range.start.col..=range.end.col).contains(&target_col)
+    });
+    let value = if unsupported.is_empty() && !reads_itself {
+        Some(formula.evaluate_in_workbook(&workbook, sheet_index))
+    } else {
+        None
+    };
+    let cached = value
+        .as_ref()
+        .map(|v| CachedResult {
+            value: v.to_cell_value(),
+            text: v.display_text(),
+        })
+        .unwrap_or(CachedResult {
+            value: CellValue::Empty,
+            text: String::new(),
+        });
+
+    let updated_content = ContentXml::set_cell_formula_raw(
+        &original_content,
+        sheet_index,
+        target_row,
+        target_col,
+        &stored,
+        &cached,
+    )?;
+    OdsFile::write_content_xml(&path, &updated_content)?;
+    JsonUtil::to_value(SetCellFormulaOutput {
+        updated: true,
+        sheet: sheet_name,
+        cell: input.cell,
+        formula: stored,
+        value: value.map(|v| v.to_cell_value()),
+        unsupported,
+    })
+}
@@ -0,0 +1,114 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::common::time::TimeUtil;
+use crate::ods::meta_xml::{DocumentPropertiesUpdate, MetaXml, UserDefinedProperty};
+use crate::ods::ods_file::OdsFile;
+use crate::ods::ods_templates::OdsTemplates;
+use crate::tools::get_document_properties::DocumentPropertiesOutput;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct SetDocumentPropertiesInput {
+    path: String,
+    title: Option<String>,
+    subject: Option<String>,
+    description: Option<String>,
+    keywords: Option<Vec<String>>,
+    initial_creator: Option<String>,
+    creator: Option<String>,
+    creation_date: Option<String>,
+    #[serde(default)]
+    user_defined: Vec<UserDefinedInput>,
+    #[serde(default)]
+    remove_user_defined: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserDefinedInput {
+    name: String,
+    value: Value,
+    #[serde(rename = "type")]
+    value_type: Option<String>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Patches meta.xml in place; unknown metadata elements are kept as-is.
+    let input: SetDocumentPropertiesInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    if let Some(date) = &input.creation_date {
+        if TimeUtil::serial_from_iso8601(date).is_none() {
+            return Err(AppError::InvalidInput(format!(
+                "creation_date must be an ISO 8601 date or date-time: {date}"
+            )));
+        }
+    }
+
+    let mut user_defined = Vec::with_capacity(input.user_defined.len());
+    for prop in input.user_defined {
+        user_defined.push(user_defined_from_input(prop)?);
+    }
+
+    let update = DocumentPropertiesUpdate {
+        title: input.title,
+        subject: input.subject,
+        description: input.description,
+        keywords: input.keywords,
+        initial_creator: input.initial_creator,
+        creator: input.creator,
+        creation_date: input.creation_date,
+        user_defined,
+        remove_user_defined: input.remove_user_defined,
+    };
+
+    let original_meta =
+        OdsFile::read_meta_xml(&path)?.unwrap_or_else(|| OdsTemplates::meta_xml().to_string());
+    let updated_meta = MetaXml::apply_update(&original_meta, &update)?;
+    OdsFile::write_meta_xml(&path, &updated_meta)?;
+
+    let props = OdsFile::read_document_properties(&path)?;
+    JsonUtil::to_value(DocumentPropertiesOutput::from(props))
+}
+
+fn user_defined_from_input(input: UserDefinedInput) -> Result<UserDefinedProperty, AppError> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "user_defined.name must not be empty".to_string(),
+        ));
+    }
+
+    let (inferred_type, value) = match &input.value {
+        Value::String(v) => ("string", v.clone()),
+        Value::Number(n) => ("float", n.to_string()),
+        Value::Bool(b) => ("boolean", b.to_string()),
+        _ => {
+            return Err(AppError::InvalidInput(format!(
+                "user_defined.value for {} must be a string, number or boolean",
+                input.name
+            )))
+        }
+    };
+
+    let value_type = input
+        .value_type
+        .unwrap_or_else(|| inferred_type.to_string());
+    if !matches!(
+        value_type.as_str(),
+        "string" | "float" | "boolean" | "date" | "time"
+    ) {
+        return Err(AppError::InvalidInput(format!(
+            "unsupported user_defined.type: {value_type}"
+        )));
+    }
+
+    Ok(UserDefinedProperty {
+        name: input.name,
+        value_type,
+        value,
+    })
+}
@@ -0,0 +1,65 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::content_xml::{ContentXml, SheetProtection};
+use crate::ods::ods_file::OdsFile;
+use crate::tools::get_sheets::SheetDetails;
+use crate::tools::sheet_ref::SheetRef;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct SetSheetProtectionInput {
+    path: String,
+    sheet: SheetRef,
+    protected: bool,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    password_hash: Option<String>,
+    #[serde(default)]
+    digest_algorithm: Option<String>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Protects or unprotects a sheet via table:protected and table:protection-key.
+    let input: SetSheetProtectionInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let protection = if input.protected {
+        Some(match (&input.password, &input.password_hash) {
+            (Some(_), Some(_)) => {
+                return Err(AppError::InvalidInput(
+                    "use either password or password_hash, not both".to_string(),
+                ))
+            }
+            (Some(password), None) if !password.is_empty() => {
+                SheetProtection::from_password(password)
+            }
+            (None, Some(hash)) if !hash.is_empty() => SheetProtection {
+                key: Some(hash.clone()),
+                digest_algorithm: input.digest_algorithm.clone(),
+            },
+            _ => SheetProtection {
+                key: None,
+                digest_algorithm: None,
+            },
+        })
+    } else {
+        None
+    };
+
+    let original_content = OdsFile::read_content_xml(&path)?;
+    let sheet_names = ContentXml::sheet_names_from_content_raw(&original_content)?;
+    let (sheet_index, _) = input.sheet.resolve_in_names(&sheet_names)?;
+
+    let updated_content =
+        ContentXml::set_sheet_protection_raw(&original_content, sheet_index, protection.as_ref())?;
+    OdsFile::write_content_xml(&path, &updated_content)?;
+
+    let attrs = ContentXml::sheet_attributes_raw(&updated_content)?.remove(sheet_index);
+    JsonUtil::to_value(SheetDetails::from_attributes(sheet_index, attrs))
+}
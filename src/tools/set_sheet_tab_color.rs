@@ -0,0 +1,52 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::content_xml::ContentXml;
+use crate::ods::ods_file::OdsFile;
+use crate::tools::get_sheets::SheetDetails;
+use crate::tools::sheet_ref::SheetRef;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct SetSheetTabColorInput {
+    path: String,
+    sheet: SheetRef,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Sets or clears tableooo:tab-color; null or empty color removes it.
+    let input: SetSheetTabColorInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let color = match input.color.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(raw) => Some(normalize_color(raw)?),
+    };
+
+    let original_content = OdsFile::read_content_xml(&path)?;
+    let sheet_names = ContentXml::sheet_names_from_content_raw(&original_content)?;
+    let (sheet_index, _) = input.sheet.resolve_in_names(&sheet_names)?;
+
+    let updated_content =
+        ContentXml::set_sheet_tab_color_raw(&original_content, sheet_index, color.as_deref())?;
+    OdsFile::write_content_xml(&path, &updated_content)?;
+
+    let attrs = ContentXml::sheet_attributes_raw(&updated_content)?.remove(sheet_index);
+    JsonUtil::to_value(SheetDetails::from_attributes(sheet_index, attrs))
+}
+
+fn normalize_color(raw: &str) -> Result<String, AppError> {
+    let hex = raw.strip_prefix('#').unwrap_or(raw);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::InvalidInput(format!(
+            "invalid color: {raw} (expected #RRGGBB)"
+        )));
+    }
+    Ok(format!("#{}", hex.to_ascii_lowercase()))
+}
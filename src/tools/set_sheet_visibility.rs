@@ -0,0 +1,48 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::content_xml::ContentXml;
+use crate::ods::ods_file::OdsFile;
+use crate::tools::get_sheets::SheetDetails;
+use crate::tools::sheet_ref::SheetRef;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct SetSheetVisibilityInput {
+    path: String,
+    sheet: SheetRef,
+    visible: bool,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Toggles table:display on the sheet's own table style.
+    let input: SetSheetVisibilityInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let original_content = OdsFile::read_content_xml(&path)?;
+    let attributes = ContentXml::sheet_attributes_raw(&original_content)?;
+    let names: Vec<String> = attributes.iter().map(|a| a.name.clone()).collect();
+    let (sheet_index, sheet_name) = input.sheet.resolve_in_names(&names)?;
+
+    // Calc refuses workbooks where every sheet is hidden.
+    let other_visible = attributes
+        .iter()
+        .enumerate()
+        .any(|(idx, a)| idx != sheet_index && a.visible);
+    if !input.visible && !other_visible {
+        return Err(AppError::InvalidInput(format!(
+            "cannot hide {sheet_name}: at least one sheet must stay visible"
+        )));
+    }
+
+    let updated_content =
+        ContentXml::set_sheet_visibility_raw(&original_content, sheet_index, input.visible)?;
+    OdsFile::write_content_xml(&path, &updated_content)?;
+
+    let attrs = ContentXml::sheet_attributes_raw(&updated_content)?.remove(sheet_index);
+    JsonUtil::to_value(SheetDetails::from_attributes(sheet_index, attrs))
+}
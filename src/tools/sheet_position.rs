@@ -0,0 +1,106 @@
+use crate::common::errors::AppError;
+use crate::tools::sheet_ref::SheetRef;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+// Where a sheet should land: absolute index or relative to another sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SheetPosition {
+    Start,
+    End,
+    Index(usize),
+    Before(SheetRef),
+    After(SheetRef),
+}
+
+impl SheetPosition {
+    // Resolves to an insertion index over `sheet_names` (0..=len).
+    pub fn resolve_insert_index(&self, sheet_names: &[String]) -> Result<usize, AppError> {
+        match self {
+            SheetPosition::Start => Ok(0),
+            SheetPosition::End => Ok(sheet_names.len()),
+            SheetPosition::Index(index) => {
+                if *index > sheet_names.len() {
+                    Err(AppError::InvalidInput(format!(
+                        "position index {index} out of range (sheet count {})",
+                        sheet_names.len()
+                    )))
+                } else {
+                    Ok(*index)
+                }
+            }
+            SheetPosition::Before(sheet) => Ok(sheet.resolve_in_names(sheet_names)?.0),
+            SheetPosition::After(sheet) => Ok(sheet.resolve_in_names(sheet_names)?.0 + 1),
+        }
+    }
+
+    pub fn anchor(&self) -> Option<&SheetRef> {
+        match self {
+            SheetPosition::Before(sheet) | SheetPosition::After(sheet) => Some(sheet),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SheetPosition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        parse_position_value(value).map_err(D::Error::custom)
+    }
+}
+
+fn parse_position_value(value: Value) -> Result<SheetPosition, String> {
+    match value {
+        Value::String(text) => parse_position_string(&text),
+        Value::Number(number) => number
+            .as_u64()
+            .and_then(|n| usize::try_from(n).ok())
+            .map(SheetPosition::Index)
+            .ok_or_else(|| "position index must be a non-negative integer".to_string()),
+        Value::Object(map) => {
+            if let Some(index) = map.get("index") {
+                return parse_position_value(index.clone()).and_then(|p| match p {
+                    SheetPosition::Index(_) => Ok(p),
+                    _ => Err("position.index must be a non-negative integer".to_string()),
+                });
+            }
+            if let Some(sheet) = map.get("before") {
+                return parse_anchor(sheet).map(SheetPosition::Before);
+            }
+            if let Some(sheet) = map.get("after") {
+                return parse_anchor(sheet).map(SheetPosition::After);
+            }
+            Err("position object must include index, before or after".to_string())
+        }
+        _ => Err("position must be start, end, an index or {before|after: sheet}".to_string()),
+    }
+}
+
+fn parse_position_string(text: &str) -> Result<SheetPosition, String> {
+    let trimmed = text.trim();
+    if trimmed.eq_ignore_ascii_case("start") {
+        return Ok(SheetPosition::Start);
+    }
+    if trimmed.eq_ignore_ascii_case("end") {
+        return Ok(SheetPosition::End);
+    }
+    if let Ok(index) = trimmed.parse::<usize>() {
+        return Ok(SheetPosition::Index(index));
+    }
+    if trimmed.starts_with('{') {
+        let nested: Value = serde_json::from_str(trimmed)
+            .map_err(|_| "position JSON string is not a valid object".to_string())?;
+        return parse_position_value(nested);
+    }
+    Err(format!(
+        "unknown sheet position: {text} (expected start, end, an index or {{before|after: sheet}})"
+    ))
+}
+
+fn parse_anchor(value: &Value) -> Result<SheetRef, String> {
+    serde_json::from_value(value.clone()).map_err(|e| e.to_string())
+}
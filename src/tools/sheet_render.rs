@@ -0,0 +1,110 @@
+use crate::common::csv::CsvWriter;
+use crate::ods::content_xml::MergedRange;
+
+// Text formats get_sheet_content can render a grid into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    Markdown,
+    Csv,
+    Html,
+}
+
+impl RenderFormat {
+    // `merges` are relative to `grid` and already clipped to it.
+    pub fn render(self, grid: &[Vec<String>], merges: &[MergedRange]) -> String {
+        match self {
+            RenderFormat::Markdown => render_markdown(grid),
+            RenderFormat::Csv => render_csv(grid),
+            RenderFormat::Html => render_html(grid, merges),
+        }
+    }
+}
+
+fn render_markdown(grid: &[Vec<String>]) -> String {
+    // GFM tables need a header row, so the first row of the window plays that role.
+    let Some(header) = grid.first() else {
+        return String::new();
+    };
+    let cols = header.len().max(1);
+    let line = |row: &[String]| {
+        let cells: Vec<String> = (0..cols)
+            .map(|c| escape_markdown(row.get(c).map(String::as_str).unwrap_or("")))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut out = vec![line(header), format!("|{}", " --- |".repeat(cols))];
+    out.extend(grid[1..].iter().map(|row| line(row)));
+    out.join("\n")
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '|' => out.push_str("\\|"),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("<br>"),
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn render_csv(grid: &[Vec<String>]) -> String {
+    // RFC 4180 quoting: fields with separators, quotes or line breaks are quoted and quotes doubled.
+    let writer = CsvWriter::default();
+    let mut out = String::new();
+    for row in grid {
+        writer.write_record(&mut out, row.iter().map(|field| (field.as_str(), false)));
+    }
+    // The rendered block has no trailing line ending.
+    out.pop();
+    out
+}
+
+fn render_html(grid: &[Vec<String>], merges: &[MergedRange]) -> String {
+    let mut out = String::from("<table>\n");
+    for (r, row) in grid.iter().enumerate() {
+        out.push_str("  <tr>");
+        for (c, value) in row.iter().enumerate() {
+            let anchor = merges.iter().find(|m| m.row == r && m.col == c);
+            if anchor.is_none() && merges.iter().any(|m| m.contains(r, c)) {
+                continue;
+            }
+            out.push_str("<td");
+            if let Some(merge) = anchor {
+                if merge.rows > 1 {
+                    out.push_str(&format!(" rowspan=\"{}\"", merge.rows));
+                }
+                if merge.cols > 1 {
+                    out.push_str(&format!(" colspan=\"{}\"", merge.cols));
+                }
+            }
+            out.push('>');
+            out.push_str(&escape_html(value));
+            out.push_str("</td>");
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            '\n' => out.push_str("<br>"),
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
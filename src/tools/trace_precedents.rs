@@ -0,0 +1,8 @@
+use crate::common::errors::AppError;
+use crate::tools::trace_tree::{self, TraceDirection};
+use serde_json::Value;
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Tree of the cells and ranges the given cell's formula reads, followed through formulas.
+    trace_tree::handle(params, TraceDirection::Precedents)
+}
@@ -0,0 +1,239 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::formula::{FormulaDialect, FormulaSyntax, GraphRange, ReferenceGraph};
+use crate::ods::ods_file::OdsFile;
+use crate::ods::sheet_model::{CellValue, Workbook};
+use crate::tools::sheet_ref::SheetRef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Shared by trace_precedents and trace_dependents: both walk the reference graph from one
+// cell and differ only in the direction they follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceDirection {
+    Precedents,
+    Dependents,
+}
+
+#[derive(Debug, Deserialize)]
+struct TraceInput {
+    path: String,
+    sheet: SheetRef,
+    cell: String,
+    #[serde(default = "default_max_depth")]
+    max_depth: usize,
+    #[serde(default = "default_max_nodes")]
+    max_nodes: usize,
+    #[serde(default)]
+    formula_syntax: FormulaDialect,
+}
+
+#[derive(Debug, Serialize)]
+struct TraceOutput {
+    sheet: String,
+    cell: String,
+    nodes: usize,
+    // Set when max_nodes stopped the walk; nodes past the limit are missing from the tree.
+    truncated: bool,
+    tree: TraceNode,
+}
+
+#[derive(Debug, Serialize)]
+struct TraceNode {
+    sheet: String,
+    // "B2" for a cell, "B2:D9" for a range read by a formula.
+    #[serde(rename = "ref")]
+    reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formula: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<CellValue>,
+    // The cell already appears on the path from the root.
+    #[serde(skip_serializing_if = "is_false")]
+    circular: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    missing_sheet: bool,
+    // max_depth was reached while the node still had children.
+    #[serde(skip_serializing_if = "is_false")]
+    depth_limited: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<TraceNode>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn default_max_depth() -> usize {
+    5
+}
+
+fn default_max_nodes() -> usize {
+    200
+}
+
+struct Tracer<'a> {
+    workbook: &'a Workbook,
+    graph: ReferenceGraph,
+    direction: TraceDirection,
+    max_depth: usize,
+    max_nodes: usize,
+    dialect: FormulaDialect,
+    nodes: usize,
+    truncated: bool,
+    // Cells from the root down to the node being expanded.
+    path: Vec<(usize, usize, usize)>,
+}
+
+pub fn handle(params: Value, direction: TraceDirection) -> Result<Value, AppError> {
+    let input: TraceInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+    if input.max_nodes == 0 {
+        return Err(AppError::InvalidInput(
+            "max_nodes must be greater than 0".to_string(),
+        ));
+    }
+
+    let workbook = OdsFile::read_workbook(&path)?;
+    let (sheet_index, sheet_name) = input.sheet.resolve_in_workbook(&workbook)?;
+    let address = CellAddress::parse(&input.cell)?;
+
+    let mut tracer = Tracer {
+        workbook: &workbook,
+        graph: ReferenceGraph::build(&workbook),
+        direction,
+        max_depth: input.max_depth,
+        max_nodes: input.max_nodes,
+        dialect: input.formula_syntax,
+        nodes: 0,
+        truncated: false,
+        path: Vec::new(),
+    };
+    let tree = tracer.cell_node(sheet_index, address.row, address.col, 0);
+
+    JsonUtil::to_value(TraceOutput {
+        sheet: sheet_name,
+        cell: address.to_a1(),
+        nodes: tracer.nodes,
+        truncated: tracer.truncated,
+        tree,
+    })
+}
+
+impl Tracer<'_> {
+    fn cell_node(&mut self, sheet_index: usize, row: usize, col: usize, depth: usize) -> TraceNode {
+        self.nodes += 1;
+        let cell = self.workbook.sheets[sheet_index].get_cell(row, col);
+        let key = (sheet_index, row, col);
+        let mut node = TraceNode {
+            sheet: self.workbook.sheets[sheet_index].name.clone(),
+            reference: CellAddress { row, col }.to_a1(),
+            formula: cell
+                .and_then(|c| c.formula.as_deref())
+                .map(|f| FormulaSyntax::from_odf(f, self.dialect)),
+            value: Some(cell.map(|c| c.value.clone()).unwrap_or(CellValue::Empty)),
+            circular: self.path.contains(&key),
+            missing_sheet: false,
+            depth_limited: false,
+            children: Vec::new(),
+        };
+        if node.circular {
+            return node;
+        }
+
+        self.path.push(key);
+        match self.direction {
+            TraceDirection::Precedents => {
+                let references = self
+                    .graph
+                    .formula_at(sheet_index, row, col)
+                    .map(|index| self.graph.cells[index].references.clone())
+                    .unwrap_or_default();
+                node.depth_limited = !references.is_empty() && depth >= self.max_depth;
+                if !node.depth_limited {
+                    for range in references {
+                        if !self.has_budget() {
+                            break;
+                        }
+                        let child = self.range_node(&range, depth + 1);
+                        node.children.push(child);
+                    }
+                }
+            }
+            TraceDirection::Dependents => {
+                let dependents = self.graph.dependents_of(sheet_index, row, col);
+                node.depth_limited = !dependents.is_empty() && depth >= self.max_depth;
+                if !node.depth_limited {
+                    node.children = self.formula_nodes(&dependents, depth + 1);
+                }
+            }
+        }
+        self.path.pop();
+        node
+    }
+
+    fn range_node(&mut self, range: &GraphRange, depth: usize) -> TraceNode {
+        let Some(sheet_index) = range.sheet_index else {
+            self.nodes += 1;
+            return TraceNode {
+                sheet: range.sheet_name.clone(),
+                reference: range.to_a1(),
+                formula: None,
+                value: None,
+                circular: false,
+                missing_sheet: true,
+                depth_limited: false,
+                children: Vec::new(),
+            };
+        };
+        if range.is_single_cell() {
+            return self.cell_node(sheet_index, range.start.row, range.start.col, depth);
+        }
+
+        // Plain values inside a range are not listed; only the formulas feeding it are.
+        self.nodes += 1;
+        let formulas = self.graph.formulas_in(range);
+        let depth_limited = !formulas.is_empty() && depth >= self.max_depth;
+        TraceNode {
+            sheet: range.sheet_name.clone(),
+            reference: range.to_a1(),
+            formula: None,
+            value: None,
+            circular: false,
+            missing_sheet: false,
+            depth_limited,
+            children: if depth_limited {
+                Vec::new()
+            } else {
+                self.formula_nodes(&formulas, depth + 1)
+            },
+        }
+    }
+
+    fn formula_nodes(&mut self, formulas: &[usize], depth: usize) -> Vec<TraceNode> {
+        let mut children = Vec::new();
+        for &index in formulas {
+            if !self.has_budget() {
+                break;
+            }
+            let (sheet_index, row, col) = {
+                let cell = &self.graph.cells[index];
+                (cell.sheet_index, cell.row, cell.col)
+            };
+            children.push(self.cell_node(sheet_index, row, col, depth));
+        }
+        children
+    }
+
+    fn has_budget(&mut self) -> bool {
+        if self.nodes >= self.max_nodes {
+            self.truncated = true;
+        }
+        !self.truncated
+    }
+}
@@ -0,0 +1,41 @@
+use crate::common::errors::AppError;
+use crate::common::fs::FsUtil;
+use crate::common::json::JsonUtil;
+use crate::ods::validation::{OdsValidator, ValidationIssue, ValidationSeverity};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct ValidateOdsInput {
+    path: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateOdsOutput {
+    // No error-level issues; warnings alone keep a package valid.
+    valid: bool,
+    errors: usize,
+    warnings: usize,
+    issues: Vec<ValidationIssue>,
+}
+
+pub fn handle(params: Value) -> Result<Value, AppError> {
+    // Reports problems instead of failing on them, so a damaged package can be inspected.
+    let input: ValidateOdsInput = JsonUtil::from_value(params)?;
+    let path = FsUtil::resolve_ods_path(&input.path)?;
+    if !path.exists() {
+        return Err(AppError::FileNotFound(path.display().to_string()));
+    }
+
+    let issues = OdsValidator::validate_path(&path)?;
+    let errors = issues
+        .iter()
+        .filter(|i| i.severity == ValidationSeverity::Error)
+        .count();
+    JsonUtil::to_value(ValidateOdsOutput {
+        valid: errors == 0,
+        errors,
+        warnings: issues.len() - errors,
+        issues,
+    })
+}
@@ -0,0 +1,4 @@
+pub mod xlsx_file;
+
+mod workbook_reader;
+mod workbook_writer;
@@ -0,0 +1,608 @@
+use super::xlsx_file::XlsxFile;
+use crate::common::errors::AppError;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::formula::FormulaSyntax;
+use crate::ods::sheet_model::{Cell, CellStyle, CellValue, MergedRange, Sheet, Workbook};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use zip::ZipArchive;
+
+// A cellXfs entry reduced to what the workbook model keeps.
+#[derive(Debug, Clone, Default)]
+struct CellFormat {
+    is_date: bool,
+    style: CellStyle,
+}
+
+#[derive(Debug, Default)]
+struct FontInfo {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    color: Option<String>,
+}
+
+// Cell being read from <c>...</c>.
+#[derive(Debug, Default)]
+struct PendingCell {
+    row: usize,
+    col: usize,
+    kind: Option<String>,
+    format: usize,
+    value: Option<String>,
+    inline_text: String,
+    formula: String,
+    formula_kind: Option<String>,
+    shared_index: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Other,
+    NumFmts,
+    Fonts,
+    Fills,
+    CellXfs,
+}
+
+impl XlsxFile {
+    pub fn read_workbook(path: &Path) -> Result<Workbook, AppError> {
+        let file = File::open(path)?;
+        let mut zip = ZipArchive::new(file)?;
+        let workbook_xml = Self::read_part(&mut zip, "xl/workbook.xml")?.ok_or_else(|| {
+            AppError::InvalidInput(format!("not an xlsx workbook: {}", path.display()))
+        })?;
+        let rels = Self::read_part(&mut zip, "xl/_rels/workbook.xml.rels")?.unwrap_or_default();
+        let relationships = Self::parse_relationships(&rels)?;
+        let (sheet_refs, date1904) = Self::parse_workbook_xml(&workbook_xml)?;
+
+        let part_of_type = |suffix: &str, fallback: &str| {
+            relationships
+                .iter()
+                .find(|(_, kind, _)| kind.ends_with(suffix))
+                .map(|(_, _, target)| target.clone())
+                .unwrap_or_else(|| fallback.to_string())
+        };
+        let shared_strings = match Self::read_part(
+            &mut zip,
+            &part_of_type("/sharedStrings", "xl/sharedStrings.xml"),
+        )? {
+            Some(xml) => Self::parse_shared_strings(&xml)?,
+            None => Vec::new(),
+        };
+        let formats = match Self::read_part(&mut zip, &part_of_type("/styles", "xl/styles.xml"))? {
+            Some(xml) => Self::parse_styles(&xml)?,
+            None => Vec::new(),
+        };
+
+        let mut sheets = Vec::new();
+        for (name, rel_id) in sheet_refs {
+            let target = relationships
+                .iter()
+                .find(|(id, _, _)| *id == rel_id)
+                .map(|(_, _, target)| target.clone())
+                .ok_or_else(|| AppError::InvalidInput(format!("xlsx sheet has no part: {name}")))?;
+            let xml = Self::read_part(&mut zip, &target)?
+                .ok_or_else(|| AppError::InvalidInput(format!("xlsx part is missing: {target}")))?;
+            sheets.push(Self::parse_worksheet(
+                name,
+                &xml,
+                &shared_strings,
+                &formats,
+                date1904,
+            )?);
+        }
+        if sheets.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "xlsx workbook has no sheets: {}",
+                path.display()
+            )));
+        }
+        Ok(Workbook { sheets })
+    }
+
+    // (Id, Type, zip path) for every relationship of xl/workbook.xml.
+    fn parse_relationships(xml: &str) -> Result<Vec<(String, String, String)>, AppError> {
+        let mut reader = Reader::from_str(xml);
+        let mut out = Vec::new();
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) | Event::Empty(e)
+                    if Self::is_local_name(e.name().as_ref(), b"Relationship") =>
+                {
+                    let target = Self::attr(&e, b"Target").unwrap_or_default();
+                    // Targets are relative to xl/ unless they start at the package root.
+                    let target = match target.strip_prefix('/') {
+                        Some(absolute) => absolute.to_string(),
+                        None => format!("xl/{target}"),
+                    };
+                    out.push((
+                        Self::attr(&e, b"Id").unwrap_or_default(),
+                        Self::attr(&e, b"Type").unwrap_or_default(),
+                        target,
+                    ));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(out)
+    }
+
+    // Sheet (name, relationship id) in tab order, plus the 1904 date system flag.
+    fn parse_workbook_xml(xml: &str) -> Result<(Vec<(String, String)>, bool), AppError> {
+        let mut reader = Reader::from_str(xml);
+        let mut sheets = Vec::new();
+        let mut date1904 = false;
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) | Event::Empty(e)
+                    if Self::is_local_name(e.name().as_ref(), b"sheet") =>
+                {
+                    sheets.push((
+                        Self::attr(&e, b"name").unwrap_or_default(),
+                        Self::attr(&e, b"id").unwrap_or_default(),
+                    ));
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if Self::is_local_name(e.name().as_ref(), b"workbookPr") =>
+                {
+                    date1904 = Self::attr(&e, b"date1904")
+                        .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok((sheets, date1904))
+    }
+
+    // Plain and rich-text shared strings; phonetic runs (rPh) are skipped.
+    fn parse_shared_strings(xml: &str) -> Result<Vec<String>, AppError> {
+        let mut reader = Reader::from_str(xml);
+        let mut strings = Vec::new();
+        let mut current = String::new();
+        let mut in_text = false;
+        let mut in_phonetic = false;
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) if Self::is_local_name(e.name().as_ref(), b"si") => {
+                    current.clear();
+                }
+                Event::Empty(e) if Self::is_local_name(e.name().as_ref(), b"si") => {
+                    strings.push(String::new());
+                }
+                Event::End(e) if Self::is_local_name(e.name().as_ref(), b"si") => {
+                    strings.push(std::mem::take(&mut current));
+                }
+                Event::Start(e) if Self::is_local_name(e.name().as_ref(), b"rPh") => {
+                    in_phonetic = true;
+                }
+                Event::End(e) if Self::is_local_name(e.name().as_ref(), b"rPh") => {
+                    in_phonetic = false;
+                }
+                Event::Start(e) if Self::is_local_name(e.name().as_ref(), b"t") => {
+                    in_text = !in_phonetic;
+                }
+                Event::End(e) if Self::is_local_name(e.name().as_ref(), b"t") => {
+                    in_text = false;
+                }
+                Event::Text(t) if in_text => {
+                    current.push_str(&t.unescape()?);
+                }
+                Event::CData(t) if in_text => {
+                    current.push_str(&String::from_utf8_lossy(&t));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(strings)
+    }
+
+    fn parse_styles(xml: &str) -> Result<Vec<CellFormat>, AppError> {
+        let mut reader = Reader::from_str(xml);
+        let mut section = Section::Other;
+        let mut custom_formats: HashMap<u32, String> = HashMap::new();
+        let mut fonts: Vec<FontInfo> = Vec::new();
+        let mut fills: Vec<Option<String>> = Vec::new();
+        let mut formats: Vec<CellFormat> = Vec::new();
+        let mut font = FontInfo::default();
+        let mut fill: Option<String> = None;
+        let mut solid_fill = false;
+        let mut xf: Option<(u32, usize, usize, Option<String>)> = None;
+
+        loop {
+            let event = reader.read_event()?;
+            let (e, is_empty) = match &event {
+                Event::Start(e) => (Some(e), false),
+                Event::Empty(e) => (Some(e), true),
+                _ => (None, false),
+            };
+            if let Some(e) = e {
+                let name = e.local_name();
+                match (section, name.as_ref()) {
+                    (_, b"numFmts") => section = Section::NumFmts,
+                    (_, b"fonts") => section = Section::Fonts,
+                    (_, b"fills") => section = Section::Fills,
+                    (_, b"cellXfs") => section = Section::CellXfs,
+                    (_, b"cellStyleXfs") | (_, b"dxfs") => section = Section::Other,
+                    (Section::NumFmts, b"numFmt") => {
+                        if let (Some(id), Some(code)) =
+                            (Self::attr(e, b"numFmtId"), Self::attr(e, b"formatCode"))
+                        {
+                            if let Ok(id) = id.parse() {
+                                custom_formats.insert(id, code);
+                            }
+                        }
+                    }
+                    (Section::Fonts, b"font") => {
+                        font = FontInfo::default();
+                        if is_empty {
+                            fonts.push(std::mem::take(&mut font));
+                        }
+                    }
+                    (Section::Fonts, b"b") => font.bold = Self::flag_on(e),
+                    (Section::Fonts, b"i") => font.italic = Self::flag_on(e),
+                    (Section::Fonts, b"u") => {
+                        font.underline = Self::attr(e, b"val").is_none_or(|v| v != "none");
+                    }
+                    (Section::Fonts, b"color") => font.color = Self::rgb_attr(e),
+                    (Section::Fills, b"fill") => {
+                        fill = None;
+                        solid_fill = false;
+                        if is_empty {
+                            fills.push(None);
+                        }
+                    }
+                    (Section::Fills, b"patternFill") => {
+                        solid_fill = Self::attr(e, b"patternType").as_deref() == Some("solid");
+                    }
+                    (Section::Fills, b"fgColor") if solid_fill => fill = Self::rgb_attr(e),
+                    (Section::CellXfs, b"xf") => {
+                        let index = |key: &[u8]| {
+                            Self::attr(e, key).and_then(|v| v.parse().ok()).unwrap_or(0)
+                        };
+                        let entry = (
+                            index(b"numFmtId") as u32,
+                            index(b"fontId"),
+                            index(b"fillId"),
+                            None,
+                        );
+                        if is_empty {
+                            formats.push(Self::cell_format(entry, &custom_formats, &fonts, &fills));
+                        } else {
+                            xf = Some(entry);
+                        }
+                    }
+                    (Section::CellXfs, b"alignment") => {
+                        if let Some(xf) = xf.as_mut() {
+                            xf.3 = Self::attr(e, b"horizontal");
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            match event {
+                Event::End(e) => match (section, e.local_name().as_ref()) {
+                    (Section::Fonts, b"font") => fonts.push(std::mem::take(&mut font)),
+                    (Section::Fills, b"fill") => fills.push(fill.take()),
+                    (Section::CellXfs, b"xf") => {
+                        if let Some(entry) = xf.take() {
+                            formats.push(Self::cell_format(entry, &custom_formats, &fonts, &fills));
+                        }
+                    }
+                    (
+                        _,
+                        b"numFmts" | b"fonts" | b"fills" | b"cellXfs" | b"cellStyleXfs" | b"dxfs",
+                    ) => {
+                        section = Section::Other;
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(formats)
+    }
+
+    fn cell_format(
+        (num_fmt, font_id, fill_id, align): (u32, usize, usize, Option<String>),
+        custom_formats: &HashMap<u32, String>,
+        fonts: &[FontInfo],
+        fills: &[Option<String>],
+    ) -> CellFormat {
+        let is_date = match custom_formats.get(&num_fmt) {
+            Some(code) => Self::is_date_format_code(code),
+            None => matches!(num_fmt, 14..=22 | 45..=47),
+        };
+        let font = fonts.get(font_id);
+        CellFormat {
+            is_date,
+            style: CellStyle {
+                bold: font.is_some_and(|f| f.bold),
+                italic: font.is_some_and(|f| f.italic),
+                underline: font.is_some_and(|f| f.underline),
+                font_color: font.and_then(|f| f.color.clone()),
+                background_color: fills.get(fill_id).cloned().flatten(),
+                horizontal_align: align
+                    .filter(|a| matches!(a.as_str(), "left" | "center" | "right")),
+            },
+        }
+    }
+
+    // A custom number format shows a date when, outside literals and [..] blocks, it uses d, m, y, h or s.
+    fn is_date_format_code(code: &str) -> bool {
+        let section = code.split(';').next().unwrap_or(code);
+        let mut chars = section.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    for q in chars.by_ref() {
+                        if q == '"' {
+                            break;
+                        }
+                    }
+                }
+                '[' => {
+                    for q in chars.by_ref() {
+                        if q == ']' {
+                            break;
+                        }
+                    }
+                }
+                '\\' | '_' | '*' => {
+                    chars.next();
+                }
+                'd' | 'D' | 'm' | 'M' | 'y' | 'Y' | 'h' | 'H' | 's' | 'S' => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn flag_on(e: &BytesStart<'_>) -> bool {
+        Self::attr(e, b"val").is_none_or(|v| v != "0" && v != "false")
+    }
+
+    // ARGB "FF1F4E79" to "#1f4e79"; theme and indexed colours are not resolved.
+    fn rgb_attr(e: &BytesStart<'_>) -> Option<String> {
+        let rgb = Self::attr(e, b"rgb").filter(|v| v.is_ascii())?;
+        let hex = if rgb.len() == 8 { &rgb[2..] } else { &rgb };
+        (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| format!("#{}", hex.to_ascii_lowercase()))
+    }
+
+    fn parse_worksheet(
+        name: String,
+        xml: &str,
+        shared_strings: &[String],
+        formats: &[CellFormat],
+        date1904: bool,
+    ) -> Result<Sheet, AppError> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(false);
+        let mut sheet = Sheet::new(name);
+        // Shared formula masters by si: (formula, row, col).
+        let mut shared_formulas: HashMap<String, (String, usize, usize)> = HashMap::new();
+        let mut row_index = 0usize;
+        let mut next_col = 0usize;
+        let mut pending: Option<PendingCell> = None;
+        let mut in_value = false;
+        let mut in_formula = false;
+        let mut in_inline_text = false;
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) if Self::is_local_name(e.name().as_ref(), b"row") => {
+                    row_index = Self::row_number(&e).unwrap_or(row_index + 1);
+                    next_col = 0;
+                }
+                Event::Empty(e) if Self::is_local_name(e.name().as_ref(), b"row") => {
+                    row_index = Self::row_number(&e).unwrap_or(row_index + 1);
+                }
+                Event::Start(e) if Self::is_local_name(e.name().as_ref(), b"c") => {
+                    pending = Some(Self::pending_cell(&e, row_index, &mut next_col));
+                }
+                Event::Empty(e) if Self::is_local_name(e.name().as_ref(), b"c") => {
+                    // A bare <c r="B2" s="3"/> only carries formatting.
+                    let cell = Self::pending_cell(&e, row_index, &mut next_col);
+                    Self::place_cell(
+                        &mut sheet,
+                        cell,
+                        shared_strings,
+                        formats,
+                        &mut shared_formulas,
+                        date1904,
+                    );
+                }
+                Event::End(e) if Self::is_local_name(e.name().as_ref(), b"c") => {
+                    if let Some(cell) = pending.take() {
+                        Self::place_cell(
+                            &mut sheet,
+                            cell,
+                            shared_strings,
+                            formats,
+                            &mut shared_formulas,
+                            date1904,
+                        );
+                    }
+                }
+                Event::Start(e) if Self::is_local_name(e.name().as_ref(), b"v") => {
+                    in_value = true;
+                }
+                Event::End(e) if Self::is_local_name(e.name().as_ref(), b"v") => {
+                    in_value = false;
+                }
+                Event::Start(e) if Self::is_local_name(e.name().as_ref(), b"f") => {
+                    if let Some(cell) = pending.as_mut() {
+                        cell.formula_kind = Self::attr(&e, b"t");
+                        cell.shared_index = Self::attr(&e, b"si");
+                    }
+                    in_formula = true;
+                }
+                Event::Empty(e) if Self::is_local_name(e.name().as_ref(), b"f") => {
+                    // <f t="shared" si="0"/> reuses the master formula of group 0.
+                    if let Some(cell) = pending.as_mut() {
+                        cell.formula_kind = Self::attr(&e, b"t");
+                        cell.shared_index = Self::attr(&e, b"si");
+                    }
+                }
+                Event::End(e) if Self::is_local_name(e.name().as_ref(), b"f") => {
+                    in_formula = false;
+                }
+                Event::Start(e) if Self::is_local_name(e.name().as_ref(), b"t") => {
+                    in_inline_text = pending.is_some();
+                }
+                Event::End(e) if Self::is_local_name(e.name().as_ref(), b"t") => {
+                    in_inline_text = false;
+                }
+                Event::Text(t) if in_value || in_formula || in_inline_text => {
+                    let text = t.unescape()?;
+                    if let Some(cell) = pending.as_mut() {
+                        if in_value {
+                            cell.value.get_or_insert_with(String::new).push_str(&text);
+                        } else if in_formula {
+                            cell.formula.push_str(&text);
+                        } else {
+                            cell.inline_text.push_str(&text);
+                        }
+                    }
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if Self::is_local_name(e.name().as_ref(), b"mergeCell") =>
+                {
+                    if let Some(Ok((start, end))) =
+                        Self::attr(&e, b"ref").map(|r| CellAddress::parse_range(&r))
+                    {
+                        sheet.merges.push(MergedRange {
+                            row: start.row,
+                            col: start.col,
+                            rows: end.row - start.row + 1,
+                            cols: end.col - start.col + 1,
+                        });
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        // Covered cells must exist so the ODS side can emit covered-table-cell slots.
+        for merge in sheet.merges.clone() {
+            Self::grow_to(
+                &mut sheet,
+                merge.row + merge.rows - 1,
+                merge.col + merge.cols - 1,
+            );
+        }
+        Ok(sheet)
+    }
+
+    fn pending_cell(e: &BytesStart<'_>, row_index: usize, next_col: &mut usize) -> PendingCell {
+        // Cells without r="A1" follow the previous cell of the same row.
+        let (row, col) = match Self::attr(e, b"r").map(|r| CellAddress::parse(&r)) {
+            Some(Ok(address)) => (address.row, address.col),
+            _ => (row_index.saturating_sub(1), *next_col),
+        };
+        *next_col = col + 1;
+        PendingCell {
+            row,
+            col,
+            kind: Self::attr(e, b"t"),
+            format: Self::attr(e, b"s")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            ..Default::default()
+        }
+    }
+
+    fn row_number(e: &BytesStart<'_>) -> Option<usize> {
+        Self::attr(e, b"r")
+            .and_then(|r| r.parse::<usize>().ok())
+            .filter(|r| *r > 0)
+    }
+
+    fn place_cell(
+        sheet: &mut Sheet,
+        cell: PendingCell,
+        shared_strings: &[String],
+        formats: &[CellFormat],
+        shared_formulas: &mut HashMap<String, (String, usize, usize)>,
+        date1904: bool,
+    ) {
+        let format = formats.get(cell.format).cloned().unwrap_or_default();
+        let raw = cell.value.as_deref();
+        let value = match cell.kind.as_deref() {
+            Some("s") => raw
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .and_then(|i| shared_strings.get(i))
+                .map(|s| CellValue::String(s.clone()))
+                .unwrap_or(CellValue::Empty),
+            Some("inlineStr") if !cell.inline_text.is_empty() => {
+                CellValue::String(cell.inline_text.clone())
+            }
+            Some("inlineStr") => CellValue::Empty,
+            Some("str") | Some("e") => raw
+                .map(|v| CellValue::String(v.to_string()))
+                .unwrap_or(CellValue::Empty),
+            Some("b") => raw
+                .map(|v| CellValue::Boolean(v.trim() == "1" || v.trim() == "true"))
+                .unwrap_or(CellValue::Empty),
+            Some("d") => raw
+                .map(|v| CellValue::Date(v.trim().trim_end_matches('Z').to_string()))
+                .unwrap_or(CellValue::Empty),
+            _ => match raw.and_then(|v| v.trim().parse::<f64>().ok()) {
+                Some(n) if format.is_date => {
+                    CellValue::Date(Self::iso8601_from_excel_serial(n, date1904))
+                }
+                Some(n) => CellValue::Number(n),
+                None => CellValue::Empty,
+            },
+        };
+
+        let formula = match (cell.formula_kind.as_deref(), cell.shared_index) {
+            (Some("shared"), Some(si)) if cell.formula.is_empty() => {
+                shared_formulas.get(&si).map(|(master, row, col)| {
+                    FormulaSyntax::shift_excel(
+                        master,
+                        cell.row as i64 - *row as i64,
+                        cell.col as i64 - *col as i64,
+                    )
+                })
+            }
+            (Some("shared"), Some(si)) => {
+                shared_formulas.insert(si, (cell.formula.clone(), cell.row, cell.col));
+                Some(cell.formula)
+            }
+            _ if !cell.formula.is_empty() => Some(cell.formula),
+            _ => None,
+        };
+
+        if value == CellValue::Empty && formula.is_none() && format.style.is_default() {
+            return;
+        }
+        Self::grow_to(sheet, cell.row, cell.col);
+        sheet.rows[cell.row][cell.col] = Cell {
+            value,
+            formula: formula.map(|f| FormulaSyntax::excel_to_odf(&f)),
+            style: format.style,
+            comment: None,
+        };
+    }
+
+    fn grow_to(sheet: &mut Sheet, row: usize, col: usize) {
+        if sheet.rows.len() <= row {
+            sheet.rows.resize_with(row + 1, Vec::new);
+        }
+        let cells = &mut sheet.rows[row];
+        if cells.len() <= col {
+            cells.resize_with(col + 1, Cell::empty);
+        }
+    }
+}
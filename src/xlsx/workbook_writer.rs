@@ -0,0 +1,430 @@
+use super::xlsx_file::XlsxFile;
+use crate::common::errors::AppError;
+use crate::ods::cell_address::CellAddress;
+use crate::ods::formula::FormulaSyntax;
+use crate::ods::sheet_model::{Cell, CellStyle, CellValue, Sheet, Workbook};
+use quick_xml::escape::escape;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// Built-in number formats used for dates: m/d/yyyy and m/d/yyyy h:mm.
+const DATE_FORMAT_ID: u32 = 14;
+const DATE_TIME_FORMAT_ID: u32 = 22;
+
+#[derive(Debug, Default)]
+struct SharedStrings {
+    strings: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+// Fonts, fills and cellXfs collected while the sheets are written; index 0 is the default everywhere.
+#[derive(Debug, Default)]
+struct StyleTable {
+    fonts: Vec<(bool, bool, bool, Option<String>)>,
+    fills: Vec<String>,
+    formats: Vec<(CellStyle, u32)>,
+    format_index: HashMap<(CellStyle, u32), usize>,
+}
+
+impl SharedStrings {
+    fn add(&mut self, text: &str) -> usize {
+        if let Some(i) = self.index.get(text) {
+            return *i;
+        }
+        self.strings.push(text.to_string());
+        self.index.insert(text.to_string(), self.strings.len() - 1);
+        self.strings.len() - 1
+    }
+}
+
+impl StyleTable {
+    fn format_id(&mut self, style: &CellStyle, num_fmt: u32) -> usize {
+        if style.is_default() && num_fmt == 0 {
+            return 0;
+        }
+        let key = (style.clone(), num_fmt);
+        if let Some(i) = self.format_index.get(&key) {
+            return *i;
+        }
+        self.formats.push(key.clone());
+        self.format_index.insert(key, self.formats.len());
+        self.formats.len()
+    }
+
+    fn font_id(&self, style: &CellStyle) -> usize {
+        let key = (
+            style.bold,
+            style.italic,
+            style.underline,
+            style.font_color.as_deref().and_then(XlsxFile::argb),
+        );
+        self.fonts
+            .iter()
+            .position(|f| *f == key)
+            .map_or(0, |i| i + 1)
+    }
+
+    fn register_font_and_fill(&mut self, style: &CellStyle) {
+        let key = (
+            style.bold,
+            style.italic,
+            style.underline,
+            style.font_color.as_deref().and_then(XlsxFile::argb),
+        );
+        if key != (false, false, false, None) && !self.fonts.contains(&key) {
+            self.fonts.push(key);
+        }
+        if let Some(fill) = style.background_color.as_deref().and_then(XlsxFile::argb) {
+            if !self.fills.contains(&fill) {
+                self.fills.push(fill);
+            }
+        }
+    }
+
+    fn to_xml(&self) -> String {
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="{}">"#,
+            XlsxFile::MAIN_NS
+        );
+        xml.push_str(&format!(
+            r#"<fonts count="{}"><font><sz val="11"/><name val="Calibri"/></font>"#,
+            self.fonts.len() + 1
+        ));
+        for (bold, italic, underline, color) in &self.fonts {
+            xml.push_str("<font>");
+            if *bold {
+                xml.push_str("<b/>");
+            }
+            if *italic {
+                xml.push_str("<i/>");
+            }
+            if *underline {
+                xml.push_str("<u/>");
+            }
+            xml.push_str(r#"<sz val="11"/>"#);
+            if let Some(color) = color {
+                xml.push_str(&format!(r#"<color rgb="{color}"/>"#));
+            }
+            xml.push_str(r#"<name val="Calibri"/></font>"#);
+        }
+        xml.push_str("</fonts>");
+
+        // The first two fills are reserved by Excel.
+        xml.push_str(&format!(
+            r#"<fills count="{}"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill>"#,
+            self.fills.len() + 2
+        ));
+        for color in &self.fills {
+            xml.push_str(&format!(
+                r#"<fill><patternFill patternType="solid"><fgColor rgb="{color}"/><bgColor indexed="64"/></patternFill></fill>"#
+            ));
+        }
+        xml.push_str("</fills>");
+        xml.push_str(
+            r#"<borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>"#,
+        );
+        xml.push_str(
+            r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#,
+        );
+
+        xml.push_str(&format!(
+            r#"<cellXfs count="{}"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/>"#,
+            self.formats.len() + 1
+        ));
+        for (style, num_fmt) in &self.formats {
+            let font = self.font_id(style);
+            let fill = style
+                .background_color
+                .as_deref()
+                .and_then(XlsxFile::argb)
+                .and_then(|c| self.fills.iter().position(|f| *f == c))
+                .map_or(0, |i| i + 2);
+            xml.push_str(&format!(
+                r#"<xf numFmtId="{num_fmt}" fontId="{font}" fillId="{fill}" borderId="0" xfId="0""#
+            ));
+            if *num_fmt != 0 {
+                xml.push_str(r#" applyNumberFormat="1""#);
+            }
+            if font != 0 {
+                xml.push_str(r#" applyFont="1""#);
+            }
+            if fill != 0 {
+                xml.push_str(r#" applyFill="1""#);
+            }
+            match &style.horizontal_align {
+                Some(align) => xml.push_str(&format!(
+                    r#" applyAlignment="1"><alignment horizontal="{}"/></xf>"#,
+                    escape(align.as_str())
+                )),
+                None => xml.push_str("/>"),
+            }
+        }
+        xml.push_str("</cellXfs>");
+        xml.push_str(
+            r#"<cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>"#,
+        );
+        xml.push_str("</styleSheet>");
+        xml
+    }
+}
+
+impl XlsxFile {
+    pub fn write_workbook(path: &Path, workbook: &Workbook) -> Result<(), AppError> {
+        for sheet in &workbook.sheets {
+            Self::validate_sheet_name(&sheet.name)?;
+        }
+        let mut shared = SharedStrings::default();
+        let mut styles = StyleTable::default();
+        let sheet_parts: Vec<String> = workbook
+            .sheets
+            .iter()
+            .map(|sheet| Self::render_worksheet(sheet, &mut shared, &mut styles))
+            .collect();
+
+        let parts: Vec<(String, String)> = [
+            (
+                "[Content_Types].xml".to_string(),
+                Self::content_types(workbook),
+            ),
+            ("_rels/.rels".to_string(), Self::package_rels()),
+            ("xl/workbook.xml".to_string(), Self::workbook_xml(workbook)),
+            (
+                "xl/_rels/workbook.xml.rels".to_string(),
+                Self::workbook_rels(workbook),
+            ),
+            ("xl/styles.xml".to_string(), styles.to_xml()),
+            (
+                "xl/sharedStrings.xml".to_string(),
+                Self::shared_strings_xml(&shared),
+            ),
+        ]
+        .into_iter()
+        .chain(
+            sheet_parts
+                .into_iter()
+                .enumerate()
+                .map(|(i, xml)| (format!("xl/worksheets/sheet{}.xml", i + 1), xml)),
+        )
+        .collect();
+
+        let mut writer = ZipWriter::new(File::create(path)?);
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, xml) in parts {
+            writer.start_file(name, deflated)?;
+            writer.write_all(xml.as_bytes())?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn render_worksheet(
+        sheet: &Sheet,
+        shared: &mut SharedStrings,
+        styles: &mut StyleTable,
+    ) -> String {
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="{}" xmlns:r="{}"><sheetData>"#,
+            Self::MAIN_NS,
+            Self::REL_NS
+        );
+        for (r, row) in sheet.rows.iter().enumerate() {
+            let cells: Vec<String> = row
+                .iter()
+                .enumerate()
+                .filter_map(|(c, cell)| Self::render_cell(r, c, cell, shared, styles))
+                .collect();
+            if cells.is_empty() {
+                continue;
+            }
+            xml.push_str(&format!(r#"<row r="{}">"#, r + 1));
+            for cell in cells {
+                xml.push_str(&cell);
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData>");
+        if !sheet.merges.is_empty() {
+            xml.push_str(&format!(r#"<mergeCells count="{}">"#, sheet.merges.len()));
+            for merge in &sheet.merges {
+                let start = CellAddress {
+                    row: merge.row,
+                    col: merge.col,
+                };
+                let end = CellAddress {
+                    row: merge.row + merge.rows - 1,
+                    col: merge.col + merge.cols - 1,
+                };
+                xml.push_str(&format!(
+                    r#"<mergeCell ref="{}:{}"/>"#,
+                    start.to_a1(),
+                    end.to_a1()
+                ));
+            }
+            xml.push_str("</mergeCells>");
+        }
+        xml.push_str("</worksheet>");
+        xml
+    }
+
+    fn render_cell(
+        row: usize,
+        col: usize,
+        cell: &Cell,
+        shared: &mut SharedStrings,
+        styles: &mut StyleTable,
+    ) -> Option<String> {
+        let date_serial = match &cell.value {
+            CellValue::Date(iso) => Self::excel_serial_from_iso8601(iso),
+            _ => None,
+        };
+        let num_fmt = match (&cell.value, date_serial) {
+            (CellValue::Date(iso), Some(_)) if iso.contains('T') => DATE_TIME_FORMAT_ID,
+            (CellValue::Date(_), Some(_)) => DATE_FORMAT_ID,
+            _ => 0,
+        };
+        if cell.value == CellValue::Empty && cell.formula.is_none() && cell.style.is_default() {
+            return None;
+        }
+        styles.register_font_and_fill(&cell.style);
+        let format = styles.format_id(&cell.style, num_fmt);
+
+        let mut xml = format!(r#"<c r="{}""#, CellAddress { row, col }.to_a1());
+        if format != 0 {
+            xml.push_str(&format!(r#" s="{format}""#));
+        }
+        let formula = cell
+            .formula
+            .as_deref()
+            .map(|f| format!("<f>{}</f>", escape(FormulaSyntax::odf_to_excel(f).as_str())))
+            .unwrap_or_default();
+        // A formula's cached string result is stored inline (t="str"), not in the shared table.
+        let (kind, value) = match (&cell.value, date_serial) {
+            (CellValue::String(s), _) | (CellValue::Date(s), None) if !formula.is_empty() => {
+                (Some("str"), Some(escape(s.as_str()).into_owned()))
+            }
+            (CellValue::String(s), _) | (CellValue::Date(s), None) => {
+                (Some("s"), Some(shared.add(s).to_string()))
+            }
+            (CellValue::Number(n), _) => (None, Some(n.to_string())),
+            (CellValue::Boolean(b), _) => (Some("b"), Some(u8::from(*b).to_string())),
+            (CellValue::Date(_), Some(serial)) => (None, Some(serial.to_string())),
+            (CellValue::Empty, _) => (None, None),
+        };
+        if let Some(kind) = kind {
+            xml.push_str(&format!(r#" t="{kind}""#));
+        }
+        if formula.is_empty() && value.is_none() {
+            xml.push_str("/>");
+            return Some(xml);
+        }
+        xml.push('>');
+        xml.push_str(&formula);
+        if let Some(value) = value {
+            xml.push_str(&format!("<v>{value}</v>"));
+        }
+        xml.push_str("</c>");
+        Some(xml)
+    }
+
+    fn content_types(workbook: &Workbook) -> String {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/><Override PartName="/xl/sharedStrings.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml"/>"#,
+        );
+        for i in 1..=workbook.sheets.len() {
+            xml.push_str(&format!(
+                r#"<Override PartName="/xl/worksheets/sheet{i}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+            ));
+        }
+        xml.push_str("</Types>");
+        xml
+    }
+
+    fn package_rels() -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="{}"><Relationship Id="rId1" Type="{}/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+            Self::PACKAGE_REL_NS,
+            Self::REL_NS
+        )
+    }
+
+    fn workbook_xml(workbook: &Workbook) -> String {
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="{}" xmlns:r="{}"><sheets>"#,
+            Self::MAIN_NS,
+            Self::REL_NS
+        );
+        for (i, sheet) in workbook.sheets.iter().enumerate() {
+            xml.push_str(&format!(
+                r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+                escape(sheet.name.as_str()),
+                i + 1,
+                i + 1
+            ));
+        }
+        xml.push_str("</sheets></workbook>");
+        xml
+    }
+
+    // Worksheets take rId1..rIdN; styles and shared strings follow them.
+    fn workbook_rels(workbook: &Workbook) -> String {
+        let count = workbook.sheets.len();
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="{}">"#,
+            Self::PACKAGE_REL_NS
+        );
+        for i in 1..=count {
+            xml.push_str(&format!(
+                r#"<Relationship Id="rId{i}" Type="{}/worksheet" Target="worksheets/sheet{i}.xml"/>"#,
+                Self::REL_NS
+            ));
+        }
+        xml.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="{}/styles" Target="styles.xml"/><Relationship Id="rId{}" Type="{}/sharedStrings" Target="sharedStrings.xml"/></Relationships>"#,
+            count + 1,
+            Self::REL_NS,
+            count + 2,
+            Self::REL_NS
+        ));
+        xml
+    }
+
+    fn shared_strings_xml(shared: &SharedStrings) -> String {
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<sst xmlns="{}" count="{}" uniqueCount="{}">"#,
+            Self::MAIN_NS,
+            shared.strings.len(),
+            shared.strings.len()
+        );
+        for s in &shared.strings {
+            // Leading or trailing blanks are dropped by readers unless xml:space says otherwise.
+            if s.starts_with(char::is_whitespace) || s.ends_with(char::is_whitespace) {
+                xml.push_str(&format!(
+                    r#"<si><t xml:space="preserve">{}</t></si>"#,
+                    escape(s.as_str())
+                ));
+            } else {
+                xml.push_str(&format!("<si><t>{}</t></si>", escape(s.as_str())));
+            }
+        }
+        xml.push_str("</sst>");
+        xml
+    }
+
+    // "#1f4e79" to the ARGB form "FF1F4E79"; anything else is dropped.
+    fn argb(color: &str) -> Option<String> {
+        let hex = color.strip_prefix('#')?;
+        (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| format!("FF{}", hex.to_ascii_uppercase()))
+    }
+}
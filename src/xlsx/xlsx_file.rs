@@ -0,0 +1,82 @@
+use crate::common::errors::AppError;
+use crate::common::time::TimeUtil;
+use quick_xml::events::BytesStart;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+// Office Open XML spreadsheet (.xlsx) package mapped onto sheet_model::Workbook.
+// Reading lives in workbook_reader.rs and writing in workbook_writer.rs.
+pub struct XlsxFile;
+
+impl XlsxFile {
+    pub(super) const MAIN_NS: &'static str =
+        "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+    pub(super) const REL_NS: &'static str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+    pub(super) const PACKAGE_REL_NS: &'static str =
+        "http://schemas.openxmlformats.org/package/2006/relationships";
+
+    // Excel sheet names: 1..=31 characters, none of []:*?/\ and no leading or trailing quote.
+    pub fn validate_sheet_name(name: &str) -> Result<(), AppError> {
+        let len = name.chars().count();
+        if len == 0 || len > 31 {
+            return Err(AppError::InvalidInput(format!(
+                "sheet name must be 1 to 31 characters for xlsx: {name}"
+            )));
+        }
+        if name.contains(['[', ']', ':', '*', '?', '/', '\\'])
+            || name.starts_with('\'')
+            || name.ends_with('\'')
+        {
+            return Err(AppError::InvalidInput(format!(
+                "sheet name contains characters xlsx does not allow: {name}"
+            )));
+        }
+        Ok(())
+    }
+
+    // Excel serial (1900 system, with its phantom 1900-02-29) to ISO 8601.
+    pub fn iso8601_from_excel_serial(serial: f64, date1904: bool) -> String {
+        let serial = if date1904 {
+            serial + 1462.0
+        } else if serial < 60.0 {
+            serial + 1.0
+        } else {
+            serial
+        };
+        TimeUtil::iso8601_from_serial(serial)
+    }
+
+    pub fn excel_serial_from_iso8601(iso: &str) -> Option<f64> {
+        let serial = TimeUtil::serial_from_iso8601(iso)?;
+        Some(if serial < 61.0 { serial - 1.0 } else { serial })
+    }
+
+    pub(super) fn read_part<R: Read + Seek>(
+        zip: &mut ZipArchive<R>,
+        name: &str,
+    ) -> Result<Option<String>, AppError> {
+        let mut entry = match zip.by_name(name) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut text = String::new();
+        entry.read_to_string(&mut text)?;
+        Ok(Some(text))
+    }
+
+    pub(super) fn is_local_name(full_name: &[u8], local_name: &[u8]) -> bool {
+        match full_name.iter().rposition(|b| *b == b':') {
+            Some(pos) => &full_name[pos + 1..] == local_name,
+            None => full_name == local_name,
+        }
+    }
+
+    pub(super) fn attr(e: &BytesStart<'_>, local_name: &[u8]) -> Option<String> {
+        e.attributes()
+            .flatten()
+            .find(|a| Self::is_local_name(a.key.as_ref(), local_name))
+            .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+    }
+}
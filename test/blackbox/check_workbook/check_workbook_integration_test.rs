@@ -0,0 +1,38 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn check_workbook_flags_formula_written_against_missing_sheet() {
+    let (_dir, file_path) = new_ods_path("check.ods");
+    create_base_ods(&file_path, "Hoja1");
+    let clean = dispatch(
+        "check_workbook",
+        json!({ "path": file_path.to_string_lossy() }),
+    )
+    .expect("check_workbook");
+    assert_eq!(clean["total"], 0);
+
+    dispatch(
+        "set_cell_formula",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "cell": "B2",
+            "formula": "=Falta!A1*2"
+        }),
+    )
+    .expect("set_cell_formula");
+
+    let out = dispatch(
+        "check_workbook",
+        json!({ "path": file_path.to_string_lossy(), "sheet": { "name": "Hoja1" } }),
+    )
+    .expect("check_workbook");
+    // set_cell_formula caches #REF! for the missing sheet, which is reported as well.
+    assert_eq!(out["errors"], 2);
+    assert_eq!(out["issues"][0]["code"], "missing_sheet_reference");
+    assert_eq!(out["issues"][0]["sheet"], "Hoja1");
+    assert_eq!(out["issues"][0]["cell"], "B2");
+    assert_eq!(out["issues"][1]["code"], "cached_error");
+    assert_eq!(out["issues"][1]["cell"], "B2");
+}
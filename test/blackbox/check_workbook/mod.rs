@@ -0,0 +1 @@
+mod check_workbook_integration_test;
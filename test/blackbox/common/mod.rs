@@ -1,7 +1,7 @@
 use mcp_ods::common::errors::AppError;
 use mcp_ods::mcp::dispatcher::Dispatcher;
 use serde_json::{json, Value};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
 pub fn new_ods_path(filename: &str) -> (TempDir, PathBuf) {
@@ -14,7 +14,7 @@ pub fn dispatch(method: &str, params: Value) -> Result<Value, AppError> {
     Dispatcher::dispatch(method, Some(params))
 }
 
-pub fn create_base_ods(path: &PathBuf, sheet_name: &str) {
+pub fn create_base_ods(path: &Path, sheet_name: &str) {
     dispatch(
         "create_ods",
         json!({
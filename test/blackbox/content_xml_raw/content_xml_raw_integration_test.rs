@@ -1,7 +1,7 @@
 use mcp_ods::common::errors::AppError;
 use mcp_ods::common::json::JsonUtil;
 use mcp_ods::ods::cell_address::CellAddress;
-use mcp_ods::ods::content_xml::ContentXml;
+use mcp_ods::ods::content_xml::{ContentXml, SheetProtection};
 use mcp_ods::ods::manifest::Manifest;
 use mcp_ods::ods::ods_templates::OdsTemplates;
 use mcp_ods::ods::sheet_model::CellValue;
@@ -419,3 +419,91 @@ fn content_xml_raw_returns_error_when_target_sheet_is_missing() {
     .expect_err("missing");
     assert!(err.to_string().contains("target cell could not be written"));
 }
+
+#[test]
+fn content_xml_raw_move_sheet_keeps_block_contents() {
+    let original = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+  <office:body><office:spreadsheet><table:table table:name="S1" table:style-name="ta1"><table:table-row><table:table-cell/></table:table-row></table:table><table:table table:name="S2"/><table:table table:name="S3"/></office:spreadsheet></office:body>
+</office:document-content>"#;
+
+    let moved = ContentXml::move_sheet_preserving_styles_raw(original, Some("S1"), None, 2)
+        .expect("move last");
+    assert_eq!(
+        ContentXml::sheet_names_from_content_raw(&moved).expect("names"),
+        vec!["S2", "S3", "S1"]
+    );
+    assert!(moved.contains(
+        r#"<table:table table:name="S1" table:style-name="ta1"><table:table-row><table:table-cell/></table:table-row></table:table></office:spreadsheet>"#
+    ));
+
+    let back = ContentXml::move_sheet_preserving_styles_raw(&moved, None, Some(2), 0)
+        .expect("move first");
+    assert_eq!(back, original);
+
+    let err = ContentXml::move_sheet_preserving_styles_raw(original, Some("S1"), None, 3)
+        .expect_err("out of range");
+    assert!(err.to_string().contains("out of range"));
+}
+
+#[test]
+fn content_xml_raw_add_sheet_rejects_unknown_position() {
+    let original = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+  <office:body><office:spreadsheet><table:table table:name="S1"/></office:spreadsheet></office:body>
+</office:document-content>"#;
+
+    let err = ContentXml::add_sheet_preserving_styles_raw(original, "N", "middle")
+        .expect_err("unknown position");
+    assert!(err.to_string().contains("unknown sheet position"));
+
+    let at_index =
+        ContentXml::add_sheet_at_preserving_styles_raw(original, "N", 0).expect("add at 0");
+    assert_eq!(
+        ContentXml::sheet_names_from_content_raw(&at_index).expect("names"),
+        vec!["N", "S1"]
+    );
+}
+
+#[test]
+fn content_xml_raw_sheet_attributes_clone_shared_table_style() {
+    let original = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"><office:automatic-styles><style:style style:name="ta1" style:family="table" style:master-page-name="Default"><style:table-properties table:display="true" style:writing-mode="lr-tb"/></style:style></office:automatic-styles><office:body><office:spreadsheet><table:table table:name="S1" table:style-name="ta1"/><table:table table:name="S2" table:style-name="ta1"/></office:spreadsheet></office:body>
+</office:document-content>"#;
+
+    let hidden = ContentXml::set_sheet_visibility_raw(original, 1, false).expect("hide");
+    assert!(hidden.contains(r#"<table:table table:name="S2" table:style-name="ta2"/>"#));
+    assert!(hidden.contains(r#"<style:style style:name="ta2" style:family="table" style:master-page-name="Default"><style:table-properties table:display="false" style:writing-mode="lr-tb"/></style:style>"#));
+
+    let attrs = ContentXml::sheet_attributes_raw(&hidden).expect("attrs");
+    assert!(attrs[0].visible);
+    assert!(!attrs[1].visible);
+
+    // S2 now owns ta2, so a second edit updates it in place instead of cloning again.
+    let colored = ContentXml::set_sheet_tab_color_raw(&hidden, 1, Some("#00ff00")).expect("color");
+    assert!(!colored.contains("style:name=\"ta3\""));
+    assert!(colored.contains("xmlns:tableooo=\"http://openoffice.org/2009/table\""));
+    let attrs = ContentXml::sheet_attributes_raw(&colored).expect("attrs");
+    assert_eq!(attrs[1].tab_color.as_deref(), Some("#00ff00"));
+    assert_eq!(attrs[0].tab_color, None);
+}
+
+#[test]
+fn content_xml_raw_sheet_protection_sets_and_removes_attributes() {
+    let original = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:loext="urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0"><office:body><office:spreadsheet><table:table table:name="S1" table:protected="true" table:protection-key="abc"><loext:table-protection loext:select-protected-cells="true"/><table:table-row><table:table-cell/></table:table-row></table:table></office:spreadsheet></office:body>
+</office:document-content>"#;
+
+    let unprotected = ContentXml::set_sheet_protection_raw(original, 0, None).expect("unprotect");
+    assert!(unprotected.contains(r#"<table:table table:name="S1"><table:table-row>"#));
+
+    let protection = SheetProtection::from_password("test");
+    let protected =
+        ContentXml::set_sheet_protection_raw(&unprotected, 0, Some(&protection)).expect("protect");
+    assert!(protected.contains(r#"table:protected="true""#));
+    assert!(protected
+        .contains(r#"table:protection-key="n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=""#));
+    assert!(protected.contains(
+        r#"table:protection-key-digest-algorithm="http://www.w3.org/2000/09/xmldsig#sha256""#
+    ));
+}
@@ -0,0 +1,40 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn convert_workbook_exports_xlsx_and_imports_it_back() {
+    let (dir, file_path) = new_ods_path("informe.ods");
+    create_base_ods(&file_path, "Hoja1");
+    dispatch(
+        "set_range_values",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "start_cell": "A1",
+            "data": [["item", "qty"], ["pens", "12"]]
+        }),
+    )
+    .expect("set_range_values");
+
+    let xlsx = dir.path().join("informe.xlsx");
+    let out = dispatch(
+        "convert_workbook",
+        json!({ "source": file_path.to_string_lossy(), "target": xlsx.to_string_lossy() }),
+    )
+    .expect("export");
+    assert_eq!(out["sheets"], json!(["Hoja1"]));
+
+    let back = dir.path().join("copia.ods");
+    dispatch(
+        "convert_workbook",
+        json!({ "source": xlsx.to_string_lossy(), "target": back.to_string_lossy() }),
+    )
+    .expect("import");
+
+    let content = dispatch(
+        "get_sheet_content",
+        json!({ "path": back.to_string_lossy(), "sheet": { "index": 0 } }),
+    )
+    .expect("get_sheet_content");
+    assert_eq!(content["data"], json!([["item", "qty"], ["pens", "12"]]));
+}
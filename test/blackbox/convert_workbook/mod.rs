@@ -0,0 +1 @@
+mod convert_workbook_integration_test;
@@ -24,11 +24,11 @@ fn dispatcher_returns_clear_errors_for_invalid_inputs() {
         json!({
             "path": file_path.to_string_lossy(),
             "sheet": { "index": 0 },
-            "mode": "records"
+            "mode": "table"
         }),
     )
     .expect_err("invalid mode");
-    assert!(bad_mode.to_string().contains("mode=matrix"));
+    assert!(bad_mode.to_string().contains("unsupported mode"));
 }
 
 #[test]
@@ -118,3 +118,43 @@ fn dispatcher_tools_call_accepts_json_encoded_sheet_ref_string() {
         json!({"type":"string","data":"ok"})
     );
 }
+
+#[test]
+fn dispatcher_tools_call_uses_rendered_markdown_as_text_content() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let file_path = dir.path().join("markdown.ods");
+
+    dispatch(
+        "create_ods",
+        json!({ "path": file_path.to_string_lossy(), "overwrite": true }),
+    )
+    .expect("create");
+    dispatch(
+        "set_range_values",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "start_cell": "A1",
+            "data": [["item", "qty"], ["pen", "3"]]
+        }),
+    )
+    .expect("set range");
+
+    let result = Dispatcher::dispatch(
+        "tools/call",
+        Some(json!({
+            "name": "get_sheet_content",
+            "arguments": {
+                "path": file_path.to_string_lossy(),
+                "sheet": { "index": 0 },
+                "mode": "markdown"
+            }
+        })),
+    )
+    .expect("markdown");
+
+    let markdown = "| item | qty |\n| --- | --- |\n| pen | 3 |";
+    assert_eq!(result["content"][0]["text"], markdown);
+    assert_eq!(result["structuredContent"]["rendered"], markdown);
+    assert_eq!(result["structuredContent"]["data"][1], json!(["pen", "3"]));
+}
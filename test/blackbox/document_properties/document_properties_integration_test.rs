@@ -0,0 +1,61 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use mcp_ods::ods::ods_file::{OdsFile, SaveOptions};
+use serde_json::json;
+
+#[test]
+fn document_properties_roundtrip_survives_sheet_edits() {
+    let (_dir, file_path) = new_ods_path("props.ods");
+    create_base_ods(&file_path, "Base");
+
+    dispatch(
+        "set_document_properties",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "title": "Informe mensual",
+            "creator": "Agente",
+            "user_defined": [{ "name": "Cliente", "value": "ACME" }]
+        }),
+    )
+    .expect("set props");
+
+    dispatch(
+        "add_sheet",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet_name": "Datos"
+        }),
+    )
+    .expect("add sheet");
+
+    let props = dispatch(
+        "get_document_properties",
+        json!({ "path": file_path.to_string_lossy() }),
+    )
+    .expect("get props");
+
+    assert_eq!(props["title"], "Informe mensual");
+    assert_eq!(props["creator"], "Agente");
+    assert_eq!(props["user_defined"][0]["value"], "ACME");
+    assert_eq!(props["editing_cycles"], 3);
+}
+
+#[test]
+fn write_content_xml_can_skip_meta_stamp() {
+    let (_dir, file_path) = new_ods_path("props_no_stamp.ods");
+    create_base_ods(&file_path, "Base");
+
+    let before = OdsFile::read_meta_xml(&file_path)
+        .expect("meta")
+        .expect("exists");
+    let content = OdsFile::read_content_xml(&file_path).expect("content");
+    let options = SaveOptions {
+        stamp_meta: false,
+        ..SaveOptions::default()
+    };
+    OdsFile::write_content_xml_with(&file_path, &content, &options).expect("write");
+
+    let after = OdsFile::read_meta_xml(&file_path)
+        .expect("meta")
+        .expect("exists");
+    assert_eq!(before, after);
+}
@@ -0,0 +1 @@
+mod document_properties_integration_test;
@@ -0,0 +1,34 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn evaluate_formula_reads_values_written_through_other_tools() {
+    let (_dir, file_path) = new_ods_path("eval.ods");
+    create_base_ods(&file_path, "Ventas");
+    for (cell, value) in [
+        ("B2", json!({ "type": "number", "data": 3 })),
+        ("B3", json!({ "type": "number", "data": 4.5 })),
+        ("B4", json!({ "type": "string", "data": "texto" })),
+    ] {
+        dispatch(
+            "set_cell_value",
+            json!({
+                "path": file_path.to_string_lossy(),
+                "sheet": { "name": "Ventas" },
+                "cell": cell,
+                "value": value
+            }),
+        )
+        .expect("set_cell_value");
+    }
+
+    let out = dispatch(
+        "evaluate_formula",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "formula": "=SUM(Ventas!B2:B500) + COUNTA(B2:B4)"
+        }),
+    )
+    .expect("evaluate_formula");
+    assert_eq!(out["value"], json!({ "type": "number", "data": 10.5 }));
+}
@@ -0,0 +1 @@
+mod evaluate_formula_integration_test;
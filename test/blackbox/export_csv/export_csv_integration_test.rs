@@ -0,0 +1,34 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn export_csv_round_trips_an_imported_file() {
+    let (dir, file_path) = new_ods_path("roundtrip.ods");
+    create_base_ods(&file_path, "Hoja1");
+    let source = dir.path().join("source.csv");
+    let original = "id,name,amount,paid\n007,\"Smith, J\",12.5,true\n008,\"line\nbreak\",3,false\n";
+    std::fs::write(&source, original).expect("write csv");
+
+    dispatch(
+        "import_csv",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "csv_path": source.to_string_lossy(),
+            "sheet": { "index": 0 }
+        }),
+    )
+    .expect("import_csv");
+
+    let target = dir.path().join("target.csv");
+    let out = dispatch(
+        "export_csv",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "csv_path": target.to_string_lossy()
+        }),
+    )
+    .expect("export_csv");
+    assert_eq!(out["range"], "A1:D3");
+    assert_eq!(std::fs::read_to_string(&target).expect("read"), original);
+}
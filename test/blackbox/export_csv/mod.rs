@@ -0,0 +1 @@
+mod export_csv_integration_test;
@@ -0,0 +1,47 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn export_json_and_import_json_round_trip_a_workbook() {
+    let (dir, file_path) = new_ods_path("source.ods");
+    create_base_ods(&file_path, "Hoja1");
+    dispatch(
+        "set_range_values",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "start_cell": "A1",
+            "data": [["uno", "2"], ["3", "cuatro"]]
+        }),
+    )
+    .expect("set_range_values");
+    dispatch(
+        "add_sheet",
+        json!({ "path": file_path.to_string_lossy(), "sheet_name": "Vacía", "position": "end" }),
+    )
+    .expect("add_sheet");
+
+    let json_path = dir.path().join("snapshot.json");
+    dispatch(
+        "export_json",
+        json!({ "path": file_path.to_string_lossy(), "json_path": json_path.to_string_lossy() }),
+    )
+    .expect("export_json");
+
+    let copy = dir.path().join("copy.fods");
+    let out = dispatch(
+        "import_json",
+        json!({ "path": copy.to_string_lossy(), "json_path": json_path.to_string_lossy() }),
+    )
+    .expect("import_json");
+    assert_eq!(out["sheets"], json!(["Hoja1", "Vacía"]));
+
+    let original = dispatch(
+        "export_json",
+        json!({ "path": file_path.to_string_lossy() }),
+    )
+    .expect("export original");
+    let rebuilt =
+        dispatch("export_json", json!({ "path": copy.to_string_lossy() })).expect("export copy");
+    assert_eq!(original["workbook"], rebuilt["workbook"]);
+}
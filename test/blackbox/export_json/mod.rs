@@ -0,0 +1 @@
+mod export_json_integration_test;
@@ -0,0 +1,28 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn find_cells_locates_values_written_through_tools() {
+    let (_dir, file_path) = new_ods_path("find.ods");
+    create_base_ods(&file_path, "Hoja1");
+    dispatch(
+        "set_range_values",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "start_cell": "C3",
+            "data": [["Concepto", "Importe"], ["Total", "99"]]
+        }),
+    )
+    .expect("set_range_values");
+
+    let out = dispatch(
+        "find_cells",
+        json!({ "path": file_path.to_string_lossy(), "query": "total" }),
+    )
+    .expect("find_cells");
+    assert_eq!(out["total"], 1);
+    assert_eq!(out["matches"][0]["cell"], "C4");
+    assert_eq!(out["matches"][0]["sheet"], "Hoja1");
+    assert_eq!(out["matches"][0]["matched_in"], json!(["values"]));
+}
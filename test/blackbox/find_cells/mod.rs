@@ -0,0 +1 @@
+mod find_cells_integration_test;
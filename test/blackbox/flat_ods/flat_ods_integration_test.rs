@@ -0,0 +1,92 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn flat_ods_supports_the_regular_editing_flow() {
+    let (_dir, file_path) = new_ods_path("flat.fods");
+    create_base_ods(&file_path, "Hoja1");
+
+    let raw = std::fs::read_to_string(&file_path).expect("flat xml");
+    assert!(raw.contains("<office:document "));
+    assert!(raw.contains("office:mimetype=\"application/vnd.oasis.opendocument.spreadsheet\""));
+
+    dispatch(
+        "set_cell_value",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "name": "Hoja1" },
+            "cell": "B2",
+            "value": { "type": "number", "data": 42 }
+        }),
+    )
+    .expect("set_cell_value");
+    dispatch(
+        "add_sheet",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet_name": "Datos",
+            "position": "end"
+        }),
+    )
+    .expect("add_sheet");
+    dispatch(
+        "set_document_properties",
+        json!({ "path": file_path.to_string_lossy(), "title": "Plano" }),
+    )
+    .expect("set props");
+
+    let value = dispatch(
+        "get_cell_value",
+        json!({ "path": file_path.to_string_lossy(), "sheet": { "name": "Hoja1" }, "cell": "B2" }),
+    )
+    .expect("get_cell_value");
+    assert_eq!(value["value"], json!({"type":"number","data":42.0}));
+
+    let sheets =
+        dispatch("get_sheets", json!({ "path": file_path.to_string_lossy() })).expect("get_sheets");
+    assert_eq!(sheets["sheets"], json!(["Hoja1", "Datos"]));
+
+    let props = dispatch(
+        "get_document_properties",
+        json!({ "path": file_path.to_string_lossy() }),
+    )
+    .expect("get props");
+    assert_eq!(props["title"], "Plano");
+
+    // Styles and master pages embedded in the flat document survive every save.
+    let raw = std::fs::read_to_string(&file_path).expect("flat xml");
+    assert!(raw.contains("<office:master-styles"));
+    assert!(raw.contains("<office:styles"));
+    assert!(raw.contains("<office:settings"));
+}
+
+#[test]
+fn convert_workbook_writes_flat_ods() {
+    let (dir, source) = new_ods_path("zip.ods");
+    create_base_ods(&source, "Hoja1");
+    dispatch(
+        "set_cell_value",
+        json!({
+            "path": source.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "cell": "A1",
+            "value": { "type": "string", "data": "hola" }
+        }),
+    )
+    .expect("set_cell_value");
+    let target = dir.path().join("plano.fods");
+
+    let out = dispatch(
+        "convert_workbook",
+        json!({ "source": source.to_string_lossy(), "target": target.to_string_lossy() }),
+    )
+    .expect("convert");
+    assert_eq!(out["target_format"], "ods");
+
+    let value = dispatch(
+        "get_cell_value",
+        json!({ "path": target.to_string_lossy(), "sheet": { "index": 0 }, "cell": "A1" }),
+    )
+    .expect("get_cell_value");
+    assert_eq!(value["value"], json!({"type":"string","data":"hola"}));
+}
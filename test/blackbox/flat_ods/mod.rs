@@ -0,0 +1 @@
+mod flat_ods_integration_test;
@@ -47,3 +47,70 @@ fn get_sheet_content_supports_trailing_trim_and_limits() {
     assert_eq!(with_trailing["rows"], 1);
     assert_eq!(with_trailing["cols"], 2);
 }
+
+#[test]
+fn get_sheet_content_records_mode_returns_objects_keyed_by_header() {
+    let (_dir, file_path) = new_ods_path("records.ods");
+    create_base_ods(&file_path, "Hoja1");
+
+    dispatch(
+        "set_range_values",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "name": "Hoja1" },
+            "start_cell": "B3",
+            "data": [["city", "country"], ["Lima", "PE"], ["Quito", "EC"]]
+        }),
+    )
+    .expect("set range");
+
+    let out = dispatch(
+        "get_sheet_content",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "name": "Hoja1" },
+            "mode": "records"
+        }),
+    )
+    .expect("records");
+    assert_eq!(out["header_row"], 3);
+    assert_eq!(out["first_col"], "B");
+    assert_eq!(
+        out["records"],
+        json!([
+            { "city": "Lima", "country": "PE" },
+            { "city": "Quito", "country": "EC" }
+        ])
+    );
+}
+
+#[test]
+fn get_sheet_content_cells_mode_skips_empty_padding() {
+    let (_dir, file_path) = new_ods_path("cells.ods");
+    create_base_ods(&file_path, "Hoja1");
+
+    dispatch(
+        "set_range_values",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "start_cell": "D10",
+            "data": [["x", "", "y"]]
+        }),
+    )
+    .expect("set range");
+
+    let out = dispatch(
+        "get_sheet_content",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "mode": "cells"
+        }),
+    )
+    .expect("cells");
+    assert_eq!(out["total_cells"], 2);
+    assert_eq!(out["cells"][0]["cell"], "D10");
+    assert_eq!(out["cells"][1]["cell"], "F10");
+    assert_eq!(out["next_cell_offset"], json!(null));
+}
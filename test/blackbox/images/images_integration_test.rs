@@ -0,0 +1,166 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use mcp_ods::ods::manifest::Manifest;
+use mcp_ods::ods::package::OdsPackage;
+use serde_json::json;
+use std::path::Path;
+
+fn write_png(dir: &Path, width: u32, height: u32) -> String {
+    let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    data.extend_from_slice(&width.to_be_bytes());
+    data.extend_from_slice(&height.to_be_bytes());
+    data.extend_from_slice(&[8, 6, 0, 0, 0]);
+    let path = dir.join("logo.png");
+    std::fs::write(&path, data).expect("write png");
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn insert_list_and_delete_images_keep_the_package_consistent() {
+    let (dir, file_path) = new_ods_path("images.ods");
+    create_base_ods(&file_path, "Hoja1");
+    let source = write_png(dir.path(), 192, 96);
+    let path = file_path.to_string_lossy();
+
+    let out = dispatch(
+        "insert_image",
+        json!({ "path": path, "sheet": { "index": 0 }, "source": source, "cell": "B2", "width": "4cm" }),
+    )
+    .expect("insert_image");
+    assert_eq!(out["anchor"], "B2");
+    assert_eq!(out["name"], "Image 1");
+    assert_eq!(out["media_type"], "image/png");
+    assert_eq!(
+        (&out["width"], &out["height"]),
+        (&json!("4cm"), &json!("2cm"))
+    );
+    let href = out["href"].as_str().expect("href").to_string();
+    assert!(href.starts_with("Pictures/") && href.ends_with(".png"));
+
+    // Same picture again: the frame is new, the part is shared.
+    dispatch(
+        "insert_image",
+        json!({ "path": path, "sheet": { "name": "Hoja1" }, "source": source, "name": "Fondo", "x": "1cm" }),
+    )
+    .expect("insert_image page");
+    let err = dispatch(
+        "insert_image",
+        json!({ "path": path, "sheet": { "index": 0 }, "source": source, "name": "Fondo" }),
+    )
+    .expect_err("duplicate name");
+    assert!(err.to_string().contains("Fondo"));
+
+    let listed = dispatch("list_images", json!({ "path": path })).expect("list_images");
+    assert_eq!(listed["total"], 2);
+    // Document order: table:shapes comes before the rows.
+    assert_eq!(listed["images"][0]["anchor"], "page");
+    assert_eq!(listed["images"][0]["x"], "1cm");
+    assert_eq!(listed["images"][0]["href"], href.as_str());
+    assert_eq!(listed["images"][1]["anchor"], "B2");
+    let valid = dispatch("validate_ods", json!({ "path": path })).expect("validate_ods");
+    assert_eq!(valid["issues"], json!([]));
+
+    let out =
+        dispatch("delete_image", json!({ "path": path, "name": "Image 1" })).expect("delete_image");
+    assert_eq!(out, json!({ "deleted": 1, "removed_parts": [] }));
+    let out = dispatch(
+        "delete_image",
+        json!({ "path": path, "name": "Fondo", "sheet": { "index": 0 } }),
+    )
+    .expect("delete_image last");
+    assert_eq!(
+        out,
+        json!({ "deleted": 1, "removed_parts": [href.clone()] })
+    );
+
+    let package = OdsPackage::open(&file_path).expect("open");
+    assert!(!package.contains(&href));
+    let manifest = Manifest::parse(
+        &package
+            .get_text("META-INF/manifest.xml")
+            .expect("utf-8")
+            .expect("manifest"),
+    )
+    .expect("manifest");
+    assert!(manifest.get(&href).is_none());
+    let listed = dispatch("list_images", json!({ "path": path })).expect("list_images");
+    assert_eq!(listed, json!({ "total": 0, "images": [] }));
+    assert!(dispatch("delete_image", json!({ "path": path, "name": "Fondo" })).is_err());
+}
+
+#[test]
+fn insert_image_embeds_pictures_in_flat_documents() {
+    let (dir, file_path) = new_ods_path("images.fods");
+    create_base_ods(&file_path, "Hoja1");
+    let svg = dir.path().join("logo.svg");
+    std::fs::write(&svg, r#"<svg xmlns="http://www.w3.org/2000/svg"/>"#).expect("write svg");
+    let path = file_path.to_string_lossy();
+
+    let err = dispatch(
+        "insert_image",
+        json!({ "path": path, "sheet": { "index": 0 }, "source": svg.to_string_lossy() }),
+    )
+    .expect_err("svg without size");
+    assert!(err.to_string().contains("width and height"));
+
+    let out = dispatch(
+        "insert_image",
+        json!({
+            "path": path,
+            "sheet": { "index": 0 },
+            "source": svg.to_string_lossy(),
+            "cell": "A1",
+            "width": "3cm",
+            "height": "3cm"
+        }),
+    )
+    .expect("insert_image");
+    assert!(out.get("href").is_none());
+    assert!(std::fs::read_to_string(&file_path)
+        .expect("read fods")
+        .contains("<office:binary-data>"));
+
+    let listed = dispatch("list_images", json!({ "path": path })).expect("list_images");
+    assert_eq!(listed["images"][0]["media_type"], "image/svg+xml");
+    assert!(listed["images"][0].get("href").is_none());
+    let out =
+        dispatch("delete_image", json!({ "path": path, "name": "Image 1" })).expect("delete_image");
+    assert_eq!(out["deleted"], 1);
+}
+
+#[test]
+fn delete_image_keeps_pictures_used_by_cell_styles() {
+    let (dir, file_path) = new_ods_path("background.ods");
+    create_base_ods(&file_path, "Hoja1");
+    let source = write_png(dir.path(), 10, 10);
+    let path = file_path.to_string_lossy();
+    let out = dispatch(
+        "insert_image",
+        json!({ "path": path, "sheet": { "index": 0 }, "source": source, "cell": "A1" }),
+    )
+    .expect("insert_image");
+    let href = out["href"].as_str().expect("href").to_string();
+
+    // The same picture as a cell background, as Calc stores it in an automatic style.
+    let mut package = OdsPackage::open(&file_path).expect("open");
+    let content = package
+        .get_text("content.xml")
+        .expect("utf-8")
+        .expect("content");
+    let background = format!(
+        "<style:style style:name=\"ceimg\" style:family=\"table-cell\"><style:table-cell-properties><style:background-image xlink:href=\"{href}\" xlink:type=\"simple\" xlink:actuate=\"onLoad\"/></style:table-cell-properties></style:style></office:automatic-styles>"
+    );
+    package
+        .put(
+            "content.xml",
+            content.replacen("</office:automatic-styles>", &background, 1),
+        )
+        .expect("put content");
+    package.save(&file_path, &Default::default()).expect("save");
+
+    let out =
+        dispatch("delete_image", json!({ "path": path, "name": "Image 1" })).expect("delete_image");
+    assert_eq!(out, json!({ "deleted": 1, "removed_parts": [] }));
+    assert!(OdsPackage::open(&file_path)
+        .expect("reopen")
+        .contains(&href));
+}
@@ -0,0 +1 @@
+mod images_integration_test;
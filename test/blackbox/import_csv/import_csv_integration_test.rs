@@ -0,0 +1,54 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn import_csv_loads_legacy_encoded_file_with_multiline_fields() {
+    let (dir, file_path) = new_ods_path("import.ods");
+    create_base_ods(&file_path, "Hoja1");
+    let csv_path = dir.path().join("ventas.csv");
+    std::fs::write(
+        &csv_path,
+        b"producto,precio,nota\r\nCaf\xE9,1.25,\"linea 1\r\nlinea 2\"\r\nT\xE9,0.8,\r\n",
+    )
+    .expect("write csv");
+
+    let out = dispatch(
+        "import_csv",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "csv_path": csv_path.to_string_lossy(),
+            "sheet": { "name": "Hoja1" },
+            "encoding": "windows-1252"
+        }),
+    )
+    .expect("import_csv");
+    assert_eq!(out["range"], "A1:C3");
+    assert_eq!(out["column_types"], json!(["string", "number", "string"]));
+
+    let a2 = dispatch(
+        "get_cell_value",
+        json!({ "path": file_path.to_string_lossy(), "sheet": { "index": 0 }, "cell": "A2" }),
+    )
+    .expect("a2");
+    let b3 = dispatch(
+        "get_cell_value",
+        json!({ "path": file_path.to_string_lossy(), "sheet": { "index": 0 }, "cell": "B3" }),
+    )
+    .expect("b3");
+    let c2 = dispatch(
+        "get_cell_value",
+        json!({ "path": file_path.to_string_lossy(), "sheet": { "index": 0 }, "cell": "C2" }),
+    )
+    .expect("c2");
+    assert_eq!(a2["value"], json!({"type":"string","data":"Café"}));
+    assert_eq!(b3["value"], json!({"type":"number","data":0.8}));
+    assert_eq!(
+        c2["value"],
+        json!({"type":"string","data":"linea 1\nlinea 2"})
+    );
+
+    let sheets =
+        dispatch("get_sheets", json!({ "path": file_path.to_string_lossy() })).expect("get_sheets");
+    assert_eq!(sheets["details"][0]["used_range"], "A1:C3");
+    assert_eq!(sheets["details"][0]["non_empty_cells"], 8);
+}
@@ -0,0 +1 @@
+mod import_csv_integration_test;
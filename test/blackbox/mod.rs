@@ -1,15 +1,30 @@
 mod add_sheet;
+mod check_workbook;
 mod common;
 mod common_raw;
 mod content_xml_raw;
+mod convert_workbook;
 mod create_ods;
 mod delete_sheet;
 mod dispatcher;
+mod document_properties;
 mod duplicate_sheet;
+mod evaluate_formula;
+mod export_csv;
+mod export_json;
+mod find_cells;
+mod flat_ods;
 mod get_cell_value;
 mod get_sheet_content;
 mod get_sheets;
+mod images;
+mod import_csv;
+mod recalculate;
 mod rename_sheet;
+mod replace_in_cells;
 mod server_stdio;
+mod set_cell_formula;
 mod set_cell_value;
 mod set_range_values;
+mod trace;
+mod validate_ods;
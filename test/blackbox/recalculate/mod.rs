@@ -0,0 +1 @@
+mod recalculate_integration_test;
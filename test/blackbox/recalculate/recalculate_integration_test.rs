@@ -0,0 +1,43 @@
+use crate::common::{dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn recalculate_updates_formulas_imported_with_stale_results() {
+    let (_dir, file_path) = new_ods_path("recalc.ods");
+    dispatch(
+        "import_json",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "workbook": {
+                "schema": "mcp-ods/workbook",
+                "version": 1,
+                "sheets": [{
+                    "name": "Hoja1",
+                    "cells": [
+                        { "cell": "A1", "value": { "type": "number", "data": 4 } },
+                        { "cell": "A2", "value": { "type": "number", "data": 6 } },
+                        { "cell": "A3", "value": { "type": "number", "data": 0 }, "formula": "of:=SUM([.A1:.A2])" },
+                        { "cell": "B1", "value": { "type": "number", "data": 0 }, "formula": "of:=IF([.A3]>5;\"alto\";\"bajo\")" }
+                    ]
+                }]
+            }
+        }),
+    )
+    .expect("import_json");
+
+    let out = dispatch(
+        "recalculate",
+        json!({ "path": file_path.to_string_lossy() }),
+    )
+    .expect("recalculate");
+    assert_eq!(out["recalculated"], 2);
+    assert_eq!(out["changed"], 2);
+    assert_eq!(out["issues"], json!([]));
+
+    let b1 = dispatch(
+        "get_cell_value",
+        json!({ "path": file_path.to_string_lossy(), "sheet": { "index": 0 }, "cell": "B1" }),
+    )
+    .expect("get_cell_value");
+    assert_eq!(b1["value"], json!({ "type": "string", "data": "alto" }));
+}
@@ -0,0 +1 @@
+mod replace_in_cells_integration_test;
@@ -0,0 +1,57 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn replace_in_cells_previews_then_applies_over_values_written_through_tools() {
+    let (_dir, file_path) = new_ods_path("replace.ods");
+    create_base_ods(&file_path, "Hoja1");
+    dispatch(
+        "set_range_values",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "start_cell": "A1",
+            "data": [["Proveedor ACME", "ACME S.L."], ["Otro", "acme"]]
+        }),
+    )
+    .expect("set_range_values");
+
+    let preview = dispatch(
+        "replace_in_cells",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "find": "acme",
+            "replace": "Globex",
+            "dry_run": true
+        }),
+    )
+    .expect("dry run");
+    assert_eq!(preview["count"], 3);
+    assert_eq!(preview["changes"][1]["cell"], "B1");
+    assert_eq!(preview["changes"][1]["after"], "Globex S.L.");
+
+    let applied = dispatch(
+        "replace_in_cells",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "find": "acme",
+            "replace": "Globex"
+        }),
+    )
+    .expect("replace");
+    assert_eq!(applied["dry_run"], false);
+    assert_eq!(applied["count"], 3);
+
+    let found = dispatch(
+        "find_cells",
+        json!({ "path": file_path.to_string_lossy(), "query": "globex" }),
+    )
+    .expect("find_cells");
+    assert_eq!(found["total"], 3);
+    let stale = dispatch(
+        "find_cells",
+        json!({ "path": file_path.to_string_lossy(), "query": "acme" }),
+    )
+    .expect("find_cells");
+    assert_eq!(stale["total"], 0);
+}
@@ -0,0 +1 @@
+mod set_cell_formula_integration_test;
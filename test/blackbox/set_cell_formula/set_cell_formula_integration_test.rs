@@ -0,0 +1,41 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn set_cell_formula_excel_formula_survives_recalculate() {
+    let (_dir, file_path) = new_ods_path("formula.ods");
+    create_base_ods(&file_path, "Hoja1");
+    for (cell, data) in [("A1", 2), ("A2", 3)] {
+        dispatch(
+            "set_cell_value",
+            json!({
+                "path": file_path.to_string_lossy(),
+                "sheet": { "index": 0 },
+                "cell": cell,
+                "value": { "type": "number", "data": data }
+            }),
+        )
+        .expect("set_cell_value");
+    }
+
+    let out = dispatch(
+        "set_cell_formula",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "cell": "A3",
+            "formula": "=IF(SUM(A1:A2)>4,\"alto\",\"bajo\")"
+        }),
+    )
+    .expect("set_cell_formula");
+    assert_eq!(out["formula"], "of:=IF(SUM([.A1:.A2])>4;\"alto\";\"bajo\")");
+    assert_eq!(out["value"], json!({ "type": "string", "data": "alto" }));
+
+    let recalc = dispatch(
+        "recalculate",
+        json!({ "path": file_path.to_string_lossy() }),
+    )
+    .expect("recalculate");
+    assert_eq!(recalc["recalculated"], 1);
+    assert_eq!(recalc["changed"], 0);
+}
@@ -0,0 +1 @@
+mod trace_integration_test;
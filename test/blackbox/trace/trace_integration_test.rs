@@ -0,0 +1,45 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn trace_tools_follow_formulas_written_through_set_cell_formula() {
+    let (_dir, file_path) = new_ods_path("trace.ods");
+    create_base_ods(&file_path, "Hoja1");
+    for (cell, formula) in [("B1", "=A1*2"), ("C1", "=B1+A1")] {
+        dispatch(
+            "set_cell_formula",
+            json!({
+                "path": file_path.to_string_lossy(),
+                "sheet": { "index": 0 },
+                "cell": cell,
+                "formula": formula
+            }),
+        )
+        .expect("set_cell_formula");
+    }
+
+    let precedents = dispatch(
+        "trace_precedents",
+        json!({ "path": file_path.to_string_lossy(), "sheet": { "index": 0 }, "cell": "C1" }),
+    )
+    .expect("trace_precedents");
+    assert_eq!(precedents["tree"]["children"][0]["ref"], "B1");
+    assert_eq!(
+        precedents["tree"]["children"][0]["children"][0]["ref"],
+        "A1"
+    );
+    assert_eq!(precedents["tree"]["children"][1]["ref"], "A1");
+
+    let dependents = dispatch(
+        "trace_dependents",
+        json!({ "path": file_path.to_string_lossy(), "sheet": { "index": 0 }, "cell": "A1" }),
+    )
+    .expect("trace_dependents");
+    assert_eq!(dependents["nodes"], 4);
+    assert_eq!(dependents["tree"]["children"][0]["ref"], "B1");
+    assert_eq!(
+        dependents["tree"]["children"][0]["children"][0]["ref"],
+        "C1"
+    );
+    assert_eq!(dependents["tree"]["children"][1]["ref"], "C1");
+}
@@ -0,0 +1 @@
+mod validate_ods_integration_test;
@@ -0,0 +1,58 @@
+use crate::common::{create_base_ods, dispatch, new_ods_path};
+use serde_json::json;
+
+#[test]
+fn validate_ods_accepts_files_after_edits_through_the_tools() {
+    let (_dir, file_path) = new_ods_path("validate.ods");
+    create_base_ods(&file_path, "Hoja1");
+    dispatch(
+        "add_sheet",
+        json!({ "path": file_path.to_string_lossy(), "sheet_name": "Resumen" }),
+    )
+    .expect("add_sheet");
+    dispatch(
+        "set_cell_formula",
+        json!({
+            "path": file_path.to_string_lossy(),
+            "sheet": { "name": "Resumen" },
+            "cell": "A1",
+            "formula": "=Hoja1!A1+1"
+        }),
+    )
+    .expect("set_cell_formula");
+
+    let out = dispatch(
+        "validate_ods",
+        json!({ "path": file_path.to_string_lossy() }),
+    )
+    .expect("validate_ods");
+    assert_eq!(
+        out,
+        json!({ "valid": true, "errors": 0, "warnings": 0, "issues": [] })
+    );
+}
+
+#[test]
+fn validate_ods_checks_flat_documents_as_one_part() {
+    let (_dir, file_path) = new_ods_path("validate.fods");
+    create_base_ods(&file_path, "Hoja1");
+    let document = std::fs::read_to_string(&file_path).expect("read fods");
+    std::fs::write(
+        &file_path,
+        document.replace(
+            "</office:spreadsheet>",
+            "<table:table table:name=\"hoja1\"/></office:spreadsheet>",
+        ),
+    )
+    .expect("write fods");
+
+    let out = dispatch(
+        "validate_ods",
+        json!({ "path": file_path.to_string_lossy() }),
+    )
+    .expect("validate_ods");
+    assert_eq!(out["valid"], true);
+    assert_eq!(out["warnings"], 1);
+    assert_eq!(out["issues"][0]["code"], "duplicate_table_name");
+    assert_eq!(out["issues"][0]["part"], "validate.fods");
+}
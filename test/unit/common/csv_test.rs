@@ -0,0 +1,66 @@
+use mcp_ods::common::csv::{CsvEncoding, CsvQuoting, CsvUtil, CsvWriter};
+
+#[test]
+fn parse_handles_quotes_embedded_separators_and_line_breaks() {
+    let text = "name,notes\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\nempty,\n";
+    let rows = CsvUtil::parse(text, ',', '"').expect("parse");
+    assert_eq!(
+        rows,
+        vec![
+            vec!["name".to_string(), "notes".to_string()],
+            vec!["Smith, J".to_string(), "said \"hi\"\nthen left".to_string()],
+            vec!["empty".to_string(), String::new()],
+        ]
+    );
+
+    let err = CsvUtil::parse("a,\"open\n", ',', '"').expect_err("unterminated");
+    assert!(err.to_string().contains("unterminated quoted field"));
+}
+
+#[test]
+fn parse_supports_custom_delimiter_and_quote() {
+    let rows = CsvUtil::parse("'a;b';c\n", ';', '\'').expect("parse");
+    assert_eq!(rows, vec![vec!["a;b".to_string(), "c".to_string()]]);
+}
+
+#[test]
+fn sniff_delimiter_prefers_most_frequent_separator() {
+    assert_eq!(CsvUtil::sniff_delimiter("a;b;c\n1,5;2;3"), ';');
+    assert_eq!(CsvUtil::sniff_delimiter("a\tb\n"), '\t');
+    assert_eq!(CsvUtil::sniff_delimiter("single"), ',');
+}
+
+#[test]
+fn decode_strips_bom_and_maps_legacy_encodings() {
+    let utf8 = CsvUtil::decode(b"\xEF\xBB\xBFcaf\xC3\xA9", CsvEncoding::Utf8).expect("utf8");
+    assert_eq!(utf8, "café");
+    let latin1 = CsvUtil::decode(b"caf\xE9", CsvEncoding::Latin1).expect("latin1");
+    assert_eq!(latin1, "café");
+    let cp1252 = CsvUtil::decode(b"\x80 5", CsvEncoding::Windows1252).expect("cp1252");
+    assert_eq!(cp1252, "€ 5");
+
+    let err = CsvUtil::decode(b"caf\xE9", CsvEncoding::Utf8).expect_err("invalid utf8");
+    assert!(err.to_string().contains("not valid utf-8"));
+    assert!(CsvEncoding::parse("utf-16").is_err());
+}
+
+#[test]
+fn writer_applies_quoting_policy_and_line_ending() {
+    let fields = [("a,b", false), ("1.5", true), ("say \"x\"", false)];
+    let mut minimal = String::new();
+    CsvWriter::default().write_record(&mut minimal, fields);
+    assert_eq!(minimal, "\"a,b\",1.5,\"say \"\"x\"\"\"\n");
+
+    let writer = CsvWriter {
+        delimiter: ';',
+        quoting: CsvQuoting::NonNumeric,
+        line_ending: "\r\n",
+        ..CsvWriter::default()
+    };
+    let mut non_numeric = String::new();
+    writer.write_record(&mut non_numeric, fields);
+    assert_eq!(non_numeric, "\"a,b\";1.5;\"say \"\"x\"\"\"\r\n");
+
+    assert_eq!(CsvUtil::option_char("tab", "delimiter").expect("tab"), '\t');
+    assert!(CsvUtil::option_char(";;", "delimiter").is_err());
+}
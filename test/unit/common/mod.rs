@@ -1,3 +1,18 @@
+mod csv_test;
 mod errors_test;
 mod fs_test;
 mod json_test;
+mod time_test;
+
+use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::tools::create_ods;
+use serde_json::json;
+use tempfile::TempDir;
+
+// New document `name` in `dir` whose content.xml is replaced by `xml`; returns its path.
+pub fn ods_with_content(dir: &TempDir, name: &str, xml: &str) -> String {
+    let path = dir.path().join(name);
+    create_ods::handle(json!({ "path": path.to_string_lossy() })).expect("create");
+    OdsFile::write_content_xml(&path, xml).expect("content");
+    path.to_string_lossy().into_owned()
+}
@@ -0,0 +1,24 @@
+use mcp_ods::common::time::TimeUtil;
+
+#[test]
+fn time_util_days_from_civil_inverts_civil_from_days() {
+    for days in [-25_569, -1, 0, 59, 19_782, 2_932_896] {
+        let (y, m, d) = TimeUtil::civil_from_days(days);
+        assert_eq!(TimeUtil::days_from_civil(y, m, d), days);
+    }
+}
+
+#[test]
+fn time_util_serial_dates_use_the_1899_12_30_epoch() {
+    assert_eq!(TimeUtil::iso8601_from_serial(0.0), "1899-12-30");
+    assert_eq!(
+        TimeUtil::iso8601_from_serial(45351.5),
+        "2024-02-29T12:00:00"
+    );
+    assert_eq!(
+        TimeUtil::serial_from_iso8601("2024-02-29T12:00:00"),
+        Some(45351.5)
+    );
+    assert_eq!(TimeUtil::serial_from_iso8601("2024-13-01"), None);
+    assert_eq!(TimeUtil::serial_from_iso8601("hoy"), None);
+}
@@ -2,3 +2,4 @@ mod common;
 mod mcp;
 mod ods;
 mod tools;
+mod xlsx;
@@ -14,3 +14,16 @@ fn invalid_cell_address_is_rejected() {
     let err = CellAddress::parse("12A").expect_err("invalid");
     assert!(matches!(err, AppError::InvalidCellAddress(_)));
 }
+
+#[test]
+fn parse_range_normalizes_corners_and_accepts_single_cell() {
+    let (start, end) = CellAddress::parse_range("D5:b2").expect("range");
+    assert_eq!(
+        (start.to_a1(), end.to_a1()),
+        ("B2".to_string(), "D5".to_string())
+    );
+
+    let (start, end) = CellAddress::parse_range("C3").expect("single cell");
+    assert_eq!(start, end);
+    assert!(CellAddress::parse_range("A1:").is_err());
+}
@@ -1,6 +1,11 @@
 use mcp_ods::common::errors::AppError;
 use mcp_ods::ods::content_xml::ContentXml;
+use mcp_ods::ods::content_xml::SparseCell;
+use mcp_ods::ods::content_xml::{
+    CachedResult, ImageData, NewImageFrame, ReplaceScope, ReplacedField,
+};
 use mcp_ods::ods::sheet_model::{CellValue, Workbook};
+use std::collections::HashMap;
 
 #[test]
 fn content_xml_render_and_parse_preserves_basic_values() {
@@ -1085,3 +1090,556 @@ fn set_cell_value_preserving_styles_raw_repeated_row_capture_handles_invalid_rep
     .expect("set");
     assert!(updated.contains("<text:p>A2</text:p>"));
 }
+
+#[test]
+fn sheet_stats_raw_honours_repeats_and_flags_features() {
+    let original = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body>
+    <office:spreadsheet>
+      <table:table table:name="Data">
+        <table:table-row table:number-rows-repeated="2"><table:table-cell table:number-columns-repeated="3"/></table:table-row>
+        <table:table-row>
+          <table:table-cell/>
+          <table:table-cell office:value-type="float" office:value="1" table:number-columns-repeated="2"><text:p>1</text:p></table:table-cell>
+          <table:table-cell table:formula="of:=[.B3]*2" office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+        </table:table-row>
+        <table:table-row table:number-rows-repeated="3">
+          <table:table-cell table:number-columns-spanned="2" table:number-rows-spanned="1"><text:p>x</text:p></table:table-cell>
+          <table:covered-table-cell/>
+        </table:table-row>
+        <table:table-row table:number-rows-repeated="1048570"><table:table-cell table:number-columns-repeated="1024"/></table:table-row>
+      </table:table>
+      <table:table table:name="Notes">
+        <table:table-row>
+          <table:table-cell><office:annotation><text:p>note</text:p></office:annotation></table:table-cell>
+        </table:table-row>
+      </table:table>
+      <table:table table:name="Empty"/>
+    </office:spreadsheet>
+  </office:body>
+</office:document-content>"#;
+
+    let stats = ContentXml::sheet_stats_raw(original).expect("stats");
+    assert_eq!(stats.len(), 3);
+
+    assert_eq!(stats[0].name, "Data");
+    assert_eq!(stats[0].used_range_a1().as_deref(), Some("A3:D6"));
+    assert_eq!(stats[0].non_empty_cells, 6);
+    assert!(stats[0].has_formulas);
+    assert!(stats[0].has_merges);
+    assert!(!stats[0].has_comments);
+
+    assert_eq!(stats[1].name, "Notes");
+    assert_eq!(stats[1].used_range_a1(), None);
+    assert_eq!(stats[1].non_empty_cells, 0);
+    assert!(stats[1].has_comments);
+
+    assert_eq!(stats[2].name, "Empty");
+    assert_eq!(stats[2].used_bounds, None);
+}
+
+#[test]
+fn sheet_cells_raw_pages_through_repeated_runs_without_padding() {
+    let original = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body>
+    <office:spreadsheet>
+      <table:table table:name="Skip"><table:table-row><table:table-cell office:value-type="string"><text:p>no</text:p></table:table-cell></table:table-row></table:table>
+      <table:table table:name="Form">
+        <table:table-row table:number-rows-repeated="5"><table:table-cell table:number-columns-repeated="1024"/></table:table-row>
+        <table:table-row>
+          <table:table-cell table:number-columns-repeated="1000"/>
+          <table:table-cell office:value-type="string"><text:p>a<text:s text:c="2"/>b</text:p><text:p>c</text:p><office:annotation><text:p>note</text:p></office:annotation></table:table-cell>
+        </table:table-row>
+        <table:table-row table:number-rows-repeated="2">
+          <table:table-cell office:value-type="float" office:value="1.5" table:number-columns-repeated="2"><text:p>1.5</text:p></table:table-cell>
+          <table:table-cell table:formula="of:=[.A7]*2" office:value-type="percentage" office:value="0.25"><text:p>25%</text:p></table:table-cell>
+          <table:covered-table-cell/>
+          <table:table-cell office:value-type="date" office:date-value="2024-02-29"><text:p>29/02/24</text:p></table:table-cell>
+        </table:table-row>
+        <table:table-row table:number-rows-repeated="1048000"><table:table-cell table:number-columns-repeated="1024"/></table:table-row>
+      </table:table>
+    </office:spreadsheet>
+  </office:body>
+</office:document-content>"#;
+
+    let all = ContentXml::sheet_cells_raw(original, 1, 0, 100).expect("cells");
+    assert_eq!(all.total, 9);
+    assert_eq!(all.cells.len(), 9);
+    assert_eq!(
+        all.cells[0],
+        SparseCell {
+            row: 5,
+            col: 1000,
+            value_type: "string".to_string(),
+            value: CellValue::String("a  b\nc".to_string()),
+            formula: None,
+            text: "a  b\nc".to_string(),
+        }
+    );
+    assert_eq!((all.cells[4].row, all.cells[4].col), (6, 4));
+    assert_eq!(all.cells[4].value, CellValue::String("2024-02-29".to_string()));
+    assert_eq!(all.cells[3].value_type, "percentage");
+    assert_eq!(all.cells[3].text, "25%");
+    assert_eq!(all.cells[3].formula.as_deref(), Some("of:=[.A7]*2"));
+
+    let page = ContentXml::sheet_cells_raw(original, 1, 6, 2).expect("page");
+    assert_eq!(page.total, 9);
+    let coords: Vec<(usize, usize)> = page.cells.iter().map(|c| (c.row, c.col)).collect();
+    assert_eq!(coords, vec![(7, 1), (7, 2)]);
+    assert_eq!(page.cells[0].value, CellValue::Number(1.5));
+
+    let err = ContentXml::sheet_cells_raw(original, 2, 0, 10).expect_err("missing sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+}
+
+#[test]
+fn set_range_values_preserving_styles_raw_splits_repeats_only_where_written() {
+    let original = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body>
+    <office:spreadsheet>
+      <table:table table:name="Data">
+        <table:table-row table:style-name="ro1" table:number-rows-repeated="4">
+          <table:table-cell table:style-name="ce1" table:number-columns-repeated="4"/>
+        </table:table-row>
+        <table:table-row>
+          <table:table-cell table:formula="of:=1+1" office:value-type="float" office:value="2" table:style-name="ce2"><text:p>2</text:p></table:table-cell>
+          <table:table-cell table:number-columns-spanned="2"><text:p>merged</text:p></table:table-cell>
+          <table:covered-table-cell/>
+        </table:table-row>
+      </table:table>
+    </office:spreadsheet>
+  </office:body>
+</office:document-content>"#;
+
+    let values = vec![
+        vec![CellValue::String("a".to_string()), CellValue::Number(1.5)],
+        vec![CellValue::Boolean(true), CellValue::Empty],
+    ];
+    let updated = ContentXml::set_range_values_preserving_styles_raw(original, 0, 1, 1, &values)
+        .expect("write range");
+
+    assert!(updated.contains(r#"table:style-name="ro1""#));
+    assert_eq!(
+        updated.matches(r#"table:number-rows-repeated="2""#).count(),
+        0
+    );
+    assert!(updated.contains(
+        r#"<table:table-cell table:style-name="ce1" office:value-type="string"><text:p>a</text:p>"#
+    ));
+    assert!(updated.contains(r#"office:value-type="boolean" office:boolean-value="true""#));
+    let parsed = ContentXml::parse(&updated).expect("parse");
+    let sheet = &parsed.sheets[0];
+    assert_eq!(
+        sheet.get_cell(1, 1).expect("b2").value,
+        CellValue::String("a".to_string())
+    );
+    assert_eq!(
+        sheet.get_cell(1, 2).expect("c2").value,
+        CellValue::Number(1.5)
+    );
+    assert_eq!(
+        sheet.get_cell(2, 1).expect("b3").value,
+        CellValue::Boolean(true)
+    );
+    assert_eq!(sheet.get_cell(0, 1).expect("b1").value, CellValue::Empty);
+    assert_eq!(sheet.get_cell(3, 1).expect("b4").value, CellValue::Empty);
+
+    // Rows past the stored ones are appended; formulas on overwritten cells are dropped.
+    let appended = ContentXml::set_range_values_preserving_styles_raw(
+        original,
+        0,
+        4,
+        0,
+        &[
+            vec![CellValue::Number(7.0)],
+            vec![],
+            vec![CellValue::String("z".to_string())],
+        ],
+    )
+    .expect("append");
+    assert!(!appended.contains("of:=1+1"));
+    assert!(appended.contains(r#"table:style-name="ce2""#));
+    let parsed = ContentXml::parse(&appended).expect("parse appended");
+    assert_eq!(
+        parsed.sheets[0].get_cell(4, 0).expect("a5").value,
+        CellValue::Number(7.0)
+    );
+    assert_eq!(
+        parsed.sheets[0].get_cell(6, 0).expect("a7").value,
+        CellValue::String("z".to_string())
+    );
+
+    let err = ContentXml::set_range_values_preserving_styles_raw(
+        original,
+        0,
+        4,
+        2,
+        &[vec![CellValue::Number(1.0)]],
+    )
+    .expect_err("covered cell");
+    assert!(err.to_string().contains("merged cell at C5"));
+
+    let err = ContentXml::set_range_values_preserving_styles_raw(original, 3, 0, 0, &values)
+        .expect_err("missing sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+}
+
+#[test]
+fn content_xml_render_and_parse_preserve_formulas_dates_merges_and_styles() {
+    use mcp_ods::ods::sheet_model::{CellStyle, MergedRange};
+
+    let mut workbook = Workbook::new("S1".to_string());
+    let sheet = &mut workbook.sheets[0];
+    sheet.ensure_cell_mut(0, 0).value = CellValue::Date("2024-02-29".to_string());
+    let total = sheet.ensure_cell_mut(0, 1);
+    total.value = CellValue::Number(2.0);
+    total.formula = Some("of:=SUM([.A2:.A3])".to_string());
+    total.style = CellStyle {
+        bold: true,
+        background_color: Some("#ffff00".to_string()),
+        horizontal_align: Some("right".to_string()),
+        ..Default::default()
+    };
+    sheet.ensure_cell_mut(2, 2).value = CellValue::String("after".to_string());
+    sheet.merges.push(MergedRange {
+        row: 1,
+        col: 0,
+        rows: 2,
+        cols: 2,
+    });
+
+    let xml = ContentXml::render(&workbook).expect("render");
+    assert!(xml.contains("table:number-columns-spanned=\"2\""));
+    assert!(xml.contains("<table:covered-table-cell/>"));
+    let parsed = ContentXml::parse(&xml).expect("parse");
+    let sheet = &parsed.sheets[0];
+    assert_eq!(
+        sheet.get_cell(0, 0).expect("a1").value,
+        CellValue::Date("2024-02-29".to_string())
+    );
+    assert_eq!(sheet.get_cell(0, 1), workbook.sheets[0].get_cell(0, 1));
+    assert_eq!(
+        sheet.get_cell(2, 2).expect("c3").value,
+        CellValue::String("after".to_string())
+    );
+    assert_eq!(sheet.merges, workbook.sheets[0].merges);
+}
+
+#[test]
+fn content_xml_parse_keeps_annotations_out_of_cell_text() {
+    use mcp_ods::ods::sheet_model::CellComment;
+
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet><table:table table:name="S1"><table:table-row>
+    <table:table-cell office:value-type="string">
+      <office:annotation><dc:creator>Ana</dc:creator><dc:date>2024-05-01T10:00:00</dc:date><text:p>Revisar</text:p><text:p>otra <text:span>vez</text:span></text:p></office:annotation>
+      <text:p>valor</text:p>
+    </table:table-cell>
+  </table:table-row></table:table></office:spreadsheet></office:body>
+</office:document-content>"#;
+
+    let workbook = ContentXml::parse(xml).expect("parse");
+    let cell = workbook.sheets[0].get_cell(0, 0).expect("a1");
+    assert_eq!(cell.value, CellValue::String("valor".to_string()));
+    let expected = CellComment {
+        text: "Revisar\notra vez".to_string(),
+        author: Some("Ana".to_string()),
+        date: Some("2024-05-01T10:00:00".to_string()),
+    };
+    assert_eq!(cell.comment.as_ref(), Some(&expected));
+
+    let rendered = ContentXml::render(&workbook).expect("render");
+    let reparsed = ContentXml::parse(&rendered).expect("reparse");
+    assert_eq!(reparsed.sheets[0].get_cell(0, 0), Some(cell));
+}
+
+#[test]
+fn find_cells_raw_tests_each_run_once_and_expands_repeats() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Uno">
+      <table:table-row table:number-rows-repeated="1000">
+        <table:table-cell/>
+        <table:table-cell table:number-columns-repeated="3" office:value-type="string"><text:p>x</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Dos">
+      <table:table-row>
+        <table:table-cell><office:annotation><dc:creator>x</dc:creator><text:p>nota x</text:p></office:annotation></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>y</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+    let mut checks = 0;
+    let found = ContentXml::find_cells_raw(xml, None, 5, |cell| {
+        checks += 1;
+        cell.text == "x" || cell.comment.is_some_and(|c| c.contains('x'))
+    })
+    .expect("search");
+    assert_eq!(checks, 3);
+    assert_eq!(found.total, 3001);
+    assert_eq!(found.matches.len(), 5);
+    assert_eq!((found.matches[3].row, found.matches[3].col), (1, 1));
+
+    let dos = ContentXml::find_cells_raw(xml, Some(1), 10, |cell| cell.comment.is_some())
+        .expect("search dos");
+    assert_eq!(dos.total, 1);
+    assert_eq!(dos.matches[0].sheet, "Dos");
+    assert_eq!(dos.matches[0].value_type, "empty");
+    assert_eq!(dos.matches[0].comment.as_deref(), Some("nota x"));
+
+    let err = ContentXml::find_cells_raw(xml, Some(2), 10, |_| true).expect_err("no sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+}
+
+#[test]
+fn replace_in_cells_raw_splits_repeats_only_at_scope_edges() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Uno">
+      <table:table-row table:number-rows-repeated="4">
+        <table:table-cell table:number-columns-repeated="3" table:style-name="ce1" office:value-type="string" office:string-value="gato"><office:annotation><dc:creator>Ana</dc:creator><text:p>gato</text:p></office:annotation><text:p>gato</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=CONCATENATE(&quot;gato&quot;;[.A1])" office:value-type="string"><text:p>gatogato</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+    let scope = ReplaceScope {
+        sheet_index: Some(0),
+        rows: (1, 2),
+        cols: (1, 3),
+        values: true,
+        formulas: true,
+    };
+    let mut calls = 0;
+    let outcome = ContentXml::replace_in_cells_raw(xml, &scope, 3, |text| {
+        calls += 1;
+        text.contains("gato").then(|| text.replace("gato", "perro"))
+    })
+    .expect("replace");
+    assert_eq!(calls, 2);
+    assert_eq!(outcome.total, 6);
+    assert_eq!(outcome.replacements.len(), 3);
+    let first = &outcome.replacements[0];
+    assert_eq!(
+        (first.row, first.col, first.field),
+        (1, 1, ReplacedField::Value)
+    );
+    assert_eq!(
+        (first.before.as_str(), first.after.as_str()),
+        ("gato", "perro")
+    );
+    assert_eq!(outcome.replacements[2].field, ReplacedField::Formula);
+    assert_eq!(
+        outcome.replacements[2].after,
+        "of:=CONCATENATE(\"perro\";[.A1])"
+    );
+
+    let workbook = ContentXml::parse(&outcome.content).expect("parse");
+    let sheet = &workbook.sheets[0];
+    let text = |row, col| sheet.get_cell(row, col).map(|c| c.value.clone());
+    assert_eq!(text(0, 1), Some(CellValue::String("gato".to_string())));
+    assert_eq!(text(1, 0), Some(CellValue::String("gato".to_string())));
+    assert_eq!(text(1, 1), Some(CellValue::String("perro".to_string())));
+    assert_eq!(text(2, 2), Some(CellValue::String("perro".to_string())));
+    assert_eq!(text(3, 2), Some(CellValue::String("gato".to_string())));
+    let edited = sheet.get_cell(2, 1).expect("b3");
+    assert_eq!(
+        edited.comment.as_ref().map(|c| c.text.as_str()),
+        Some("gato")
+    );
+    assert_eq!(
+        outcome.content.matches("table:style-name=\"ce1\"").count(),
+        4
+    );
+    assert!(!outcome.content.contains("office:string-value=\"perro\""));
+
+    let untouched = ContentXml::replace_in_cells_raw(xml, &scope, 3, |_| None).expect("noop");
+    assert_eq!(untouched.total, 0);
+    let err = ContentXml::replace_in_cells_raw(
+        xml,
+        &ReplaceScope {
+            sheet_index: Some(1),
+            ..scope
+        },
+        3,
+        |_| None,
+    )
+    .expect_err("no sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+}
+
+#[test]
+fn write_formula_results_raw_updates_cached_values_only() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Uno">
+      <table:table-row table:number-rows-repeated="2">
+        <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+        <table:table-cell table:number-columns-repeated="2" table:style-name="ce1" table:formula="of:=[.A1]*2" office:value-type="currency" office:currency="EUR" office:value="0"><office:annotation><dc:creator>Ana</dc:creator><text:p>nota</text:p></office:annotation><text:p>0,00 €</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Dos">
+      <table:table-row>
+        <table:table-cell table:formula="of:=1/0"/>
+        <table:table-cell table:formula="of:=[.A1]=1" office:value-type="string" office:string-value="x"><text:p>x</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+    let results = HashMap::from([
+        (
+            (0, 0, 1),
+            CachedResult {
+                value: CellValue::Number(2.0),
+                text: "2".to_string(),
+            },
+        ),
+        (
+            (1, 0, 0),
+            CachedResult {
+                value: CellValue::String("#DIV/0!".to_string()),
+                text: "#DIV/0!".to_string(),
+            },
+        ),
+        (
+            (1, 0, 1),
+            CachedResult {
+                value: CellValue::Boolean(false),
+                text: "FALSE".to_string(),
+            },
+        ),
+    ]);
+    let updated = ContentXml::write_formula_results_raw(xml, &results).expect("write");
+
+    assert!(updated.contains(r#"table:number-rows-repeated="2""#));
+    assert!(updated.contains(r#"table:number-columns-repeated="2" table:style-name="ce1" table:formula="of:=[.A1]*2" office:currency="EUR" office:value-type="currency" office:value="2""#));
+    assert!(!updated.contains("0,00 €"));
+    assert!(!updated.contains("office:string-value"));
+
+    let workbook = ContentXml::parse(&updated).expect("parse");
+    let uno = &workbook.sheets[0];
+    for (row, col) in [(0, 1), (0, 2), (1, 1), (1, 2)] {
+        let cell = uno.get_cell(row, col).expect("cell");
+        assert_eq!(cell.formula.as_deref(), Some("of:=[.A1]*2"));
+        assert_eq!(cell.comment.as_ref().map(|c| c.text.as_str()), Some("nota"));
+    }
+    let dos = &workbook.sheets[1];
+    assert_eq!(
+        dos.get_cell(0, 0).map(|c| c.value.clone()),
+        Some(CellValue::String("#DIV/0!".to_string()))
+    );
+    assert_eq!(
+        dos.get_cell(0, 1).map(|c| c.value.clone()),
+        Some(CellValue::Boolean(false))
+    );
+}
+
+const IMAGE_CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Hoja1">
+      <table:table-column table:number-columns-repeated="4"/>
+      <table:table-row table:number-rows-repeated="3">
+        <table:table-cell table:style-name="ce1" office:value-type="float" office:value="7" table:number-columns-repeated="4"><text:p>7</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Hoja2"/>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+fn image_frame(name: &str) -> NewImageFrame {
+    NewImageFrame {
+        name: name.to_string(),
+        data: ImageData::Part("Pictures/logo.png".to_string()),
+        media_type: "image/png".to_string(),
+        width: "2cm".to_string(),
+        height: "1cm".to_string(),
+        x: "0.5cm".to_string(),
+        y: "0cm".to_string(),
+    }
+}
+
+#[test]
+fn insert_image_frame_raw_anchors_to_a_split_cell_and_to_the_page() {
+    let with_cell =
+        ContentXml::insert_image_frame_raw(IMAGE_CONTENT, 0, Some((1, 2)), &image_frame("Logo"))
+            .expect("cell frame");
+    let updated =
+        ContentXml::insert_image_frame_raw(&with_cell, 1, None, &image_frame("Fondo"))
+            .expect("page frame");
+    assert!(updated.contains("xmlns:draw="));
+    assert!(updated.contains("xmlns:xlink="));
+
+    let frames = ContentXml::images_raw(&updated).expect("images");
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].sheet, "Hoja1");
+    assert_eq!(frames[0].cell, Some((1, 2)));
+    assert_eq!(frames[0].name.as_deref(), Some("Logo"));
+    assert_eq!(frames[0].href.as_deref(), Some("Pictures/logo.png"));
+    assert_eq!(frames[0].x.as_deref(), Some("0.5cm"));
+    assert_eq!(frames[0].z_index, Some(0));
+    assert_eq!((frames[1].sheet_index, frames[1].cell), (1, None));
+    assert_eq!(frames[1].z_index, Some(1));
+
+    // The split cell keeps its value and style, like the copies around it.
+    let workbook = ContentXml::parse(&updated).expect("parse");
+    for (row, col) in [(0, 0), (1, 1), (1, 2), (1, 3), (2, 3)] {
+        assert_eq!(
+            workbook.sheets[0].get_cell(row, col).map(|c| c.value.clone()),
+            Some(CellValue::Number(7.0))
+        );
+    }
+    assert!(updated.contains("<table:table table:name=\"Hoja2\"><table:shapes><draw:frame"));
+
+    let err = ContentXml::insert_image_frame_raw(IMAGE_CONTENT, 2, None, &image_frame("X"))
+        .expect_err("missing sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+}
+
+#[test]
+fn insert_image_frame_raw_extends_the_grid_for_cells_past_the_end() {
+    let updated =
+        ContentXml::insert_image_frame_raw(IMAGE_CONTENT, 1, Some((4, 1)), &image_frame("Logo"))
+            .expect("frame");
+    let frames = ContentXml::images_raw(&updated).expect("images");
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].cell, Some((4, 1)));
+}
+
+#[test]
+fn remove_image_frames_raw_drops_frames_and_empty_shapes() {
+    let content =
+        ContentXml::insert_image_frame_raw(IMAGE_CONTENT, 0, None, &image_frame("A")).expect("a");
+    let content =
+        ContentXml::insert_image_frame_raw(&content, 0, Some((0, 0)), &image_frame("B")).expect("b");
+    let frames = ContentXml::images_raw(&content).expect("images");
+    let page: Vec<_> = frames.iter().filter(|f| f.cell.is_none()).collect();
+
+    let updated = ContentXml::remove_image_frames_raw(&content, &page).expect("remove");
+    assert!(!updated.contains("table:shapes"));
+    let left = ContentXml::images_raw(&updated).expect("images");
+    assert_eq!(left.len(), 1);
+    assert_eq!(left[0].name.as_deref(), Some("B"));
+    assert_eq!(ContentXml::parse(&updated).expect("parse").sheets.len(), 2);
+}
+
+#[test]
+fn images_raw_skips_chart_frames() {
+    let content = IMAGE_CONTENT.replace(
+        "<table:table-column ",
+        "<table:shapes><draw:frame draw:name=\"Chart 1\"><draw:object xlink:href=\"./Object 1\"/><draw:image xlink:href=\"./ObjectReplacements/Object 1\"/></draw:frame></table:shapes><table:table-column ",
+    );
+    assert_eq!(ContentXml::images_raw(&content).expect("images"), vec![]);
+}
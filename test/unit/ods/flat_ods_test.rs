@@ -0,0 +1,92 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::content_xml::ContentXml;
+use mcp_ods::ods::flat_ods::FlatOds;
+use mcp_ods::ods::ods_file::{OdsFile, PackageLayout};
+use std::path::Path;
+use tempfile::tempdir;
+
+const FLAT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.2" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+ <office:meta><meta:generator xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0">Test &amp; Co</meta:generator></office:meta>
+ <office:styles><style:style style:name="Default" style:family="table-cell"/></office:styles>
+ <office:automatic-styles><style:page-layout style:name="pm1"/><style:style style:name="co1" style:family="table-column"/></office:automatic-styles>
+ <office:master-styles><style:master-page style:name="Default" style:page-layout-name="pm1"/></office:master-styles>
+ <office:body><office:spreadsheet><table:table table:name="Hoja1"><table:table-row><table:table-cell/></table:table-row></table:table></office:spreadsheet></office:body>
+</office:document>"#;
+
+#[test]
+fn package_layout_follows_extension() {
+    assert_eq!(
+        PackageLayout::from_path(Path::new("a.fods")),
+        PackageLayout::Flat
+    );
+    assert_eq!(
+        PackageLayout::from_path(Path::new("a.FODS")),
+        PackageLayout::Flat
+    );
+    assert_eq!(
+        PackageLayout::from_path(Path::new("a.ods")),
+        PackageLayout::Zip
+    );
+}
+
+#[test]
+fn flat_ods_content_view_holds_body_and_automatic_styles_only() {
+    let content = FlatOds::content_xml(FLAT).expect("content");
+    assert!(content.contains("<office:document-content "));
+    assert!(content.contains("table:name=\"Hoja1\""));
+    assert!(content.contains("style:name=\"co1\""));
+    assert!(!content.contains("<office:master-styles"));
+    assert!(!content.contains("office:mimetype"));
+    assert_eq!(
+        ContentXml::sheet_names_from_content_raw(&content).expect("names"),
+        vec!["Hoja1"]
+    );
+}
+
+#[test]
+fn flat_ods_replace_content_keeps_page_layouts_and_other_parts() {
+    let content = FlatOds::content_xml(FLAT).expect("content");
+    let renamed = ContentXml::rename_first_sheet_name_raw(&content, "Datos").expect("rename");
+    // A renderer that only knows cell styles drops the page layout from content.xml.
+    let stripped = renamed.replace("<style:page-layout style:name=\"pm1\"/>", "");
+
+    let doc = FlatOds::replace_content(FLAT, &stripped).expect("replace");
+    assert!(doc.contains("table:name=\"Datos\""));
+    assert!(doc.contains("<style:page-layout style:name=\"pm1\"/>"));
+    assert!(doc.contains("<office:master-styles>"));
+    assert!(doc.contains("Test &amp; Co"));
+    let styles_at = doc.find("<office:styles>").expect("styles");
+    let auto_at = doc.find("<office:automatic-styles>").expect("auto");
+    let body_at = doc.find("<office:body>").expect("body");
+    assert!(styles_at < auto_at && auto_at < body_at);
+}
+
+#[test]
+fn flat_ods_meta_roundtrip() {
+    let meta = FlatOds::meta_xml(FLAT).expect("meta").expect("present");
+    assert!(meta.contains("<office:document-meta "));
+    let updated = meta.replace("Test &amp; Co", "Otro");
+    let doc = FlatOds::replace_meta(FLAT, &updated).expect("replace");
+    assert!(doc.contains("Otro"));
+    assert!(doc.contains("table:name=\"Hoja1\""));
+}
+
+#[test]
+fn flat_ods_rejects_other_document_types() {
+    let text = FLAT.replace("opendocument.spreadsheet", "opendocument.text");
+    let err = FlatOds::content_xml(&text).expect_err("not a spreadsheet");
+    assert!(matches!(err, AppError::InvalidOdsFormat(_)));
+}
+
+#[test]
+fn ods_file_create_flat_roundtrip() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("nuevo.fods");
+
+    OdsFile::create(&path, "Datos".to_string()).expect("create");
+    let workbook = OdsFile::read_workbook(&path).expect("read");
+    assert_eq!(workbook.sheets[0].name, "Datos");
+    let props = OdsFile::read_document_properties(&path).expect("props");
+    assert!(props.creation_date.is_some());
+}
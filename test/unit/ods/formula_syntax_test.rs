@@ -0,0 +1,136 @@
+use mcp_ods::ods::formula::{FormulaDialect, FormulaSyntax};
+
+#[test]
+fn formula_syntax_odf_to_excel_rewrites_references_and_separators() {
+    assert_eq!(
+        FormulaSyntax::odf_to_excel("of:=SUM([.A1:.B2];[$Data.$C$3])"),
+        "SUM(A1:B2,Data!$C$3)"
+    );
+    assert_eq!(
+        FormulaSyntax::odf_to_excel("of:=[$'My data'.A1]&\"[.x;y]\""),
+        "'My data'!A1&\"[.x;y]\""
+    );
+    assert_eq!(FormulaSyntax::odf_to_excel("of:={1;2|3;4}"), "{1,2;3,4}");
+}
+
+#[test]
+fn formula_syntax_excel_to_odf_wraps_references() {
+    assert_eq!(
+        FormulaSyntax::excel_to_odf("SUM(A1:B2,Data!$C$3)"),
+        "of:=SUM([.A1:.B2];[$Data.$C$3])"
+    );
+    assert_eq!(
+        FormulaSyntax::excel_to_odf("='My data'!A1*1.5E+3"),
+        "of:=[$'My data'.A1]*1.5E+3"
+    );
+    assert_eq!(
+        FormulaSyntax::excel_to_odf("LOG10(A1)&\"B2,C3\""),
+        "of:=LOG10([.A1])&\"B2,C3\""
+    );
+    assert_eq!(FormulaSyntax::excel_to_odf("{1,2;3,4}"), "of:={1;2|3;4}");
+}
+
+#[test]
+fn formula_syntax_round_trips_between_dialects() {
+    let odf = "of:=IF([.A1]>0;SUM([$Other.B1:.B10]);\"none\")";
+    let excel = FormulaSyntax::odf_to_excel(odf);
+    assert_eq!(excel, "IF(A1>0,SUM(Other!B1:B10),\"none\")");
+    assert_eq!(FormulaSyntax::excel_to_odf(&excel), odf);
+}
+
+#[test]
+fn formula_syntax_translates_whole_columns_and_rows() {
+    assert_eq!(FormulaSyntax::excel_to_odf("=SUM(A:A)"), "of:=SUM([.A:.A])");
+    assert_eq!(
+        FormulaSyntax::excel_to_odf("=SUM(Hoja2!1:1)"),
+        "of:=SUM([$Hoja2.1:.1])"
+    );
+    assert_eq!(
+        FormulaSyntax::excel_to_odf("$B:C+'Mis datos'!$2:3"),
+        "of:=[.$B:.C]+[$'Mis datos'.$2:.3]"
+    );
+    assert_eq!(FormulaSyntax::odf_to_excel("of:=SUM([.A:.A])"), "SUM(A:A)");
+    assert_eq!(
+        FormulaSyntax::odf_to_excel("of:=SUM([$Hoja2.1:.1])"),
+        "SUM(Hoja2!1:1)"
+    );
+    // A lone column or row stays a name or a number.
+    assert_eq!(FormulaSyntax::excel_to_odf("A+1"), "of:=A+1");
+    assert_eq!(
+        FormulaSyntax::shift_excel("SUM(A:$B)+SUM(1:$2)", 2, 1),
+        "SUM(B:$B)+SUM(3:$2)"
+    );
+    assert_eq!(
+        FormulaSyntax::relative_form("of:=SUM([.B:.B])+[.$3:.3]", 4, 2),
+        "of:=SUM([.C[-1]:.C[-1]])+[.R3:.R[-2]]"
+    );
+}
+
+#[test]
+fn formula_syntax_shift_excel_moves_only_relative_parts() {
+    assert_eq!(
+        FormulaSyntax::shift_excel("A1+$B$1+C$1+$D1+Data!E2", 2, 1),
+        "B3+$B$1+D$1+$D3+Data!F4"
+    );
+}
+
+#[test]
+fn formula_syntax_maps_function_names_between_dialects() {
+    assert_eq!(
+        FormulaSyntax::excel_to_odf("=_xlfn.CONCAT(A1,\"x\")&textjoin(\",\",TRUE,B1:B3)"),
+        "of:=COM.MICROSOFT.CONCAT([.A1];\"x\")&COM.MICROSOFT.TEXTJOIN(\",\";TRUE;[.B1:.B3])"
+    );
+    assert_eq!(
+        FormulaSyntax::odf_to_excel("of:=LEGACY.NORMSDIST([.A1])+COM.MICROSOFT.STDEV.S([.B1:.B9])"),
+        "NORMSDIST(A1)+STDEV.S(B1:B9)"
+    );
+    // Names that only look like mapped functions are left alone.
+    assert_eq!(
+        FormulaSyntax::excel_to_odf("SORT_KEY+Sort!A1"),
+        "of:=SORT_KEY+[$Sort.A1]"
+    );
+}
+
+#[test]
+fn formula_syntax_detects_dialect_and_normalises_stored_form() {
+    assert_eq!(FormulaSyntax::detect("of:=1+2"), FormulaDialect::Odf);
+    assert_eq!(
+        FormulaSyntax::detect("=SUM([.A1:.A3])"),
+        FormulaDialect::Odf
+    );
+    assert_eq!(FormulaSyntax::detect("=\"[x]\"&A1"), FormulaDialect::Excel);
+    assert_eq!(FormulaSyntax::detect("1+2"), FormulaDialect::Excel);
+
+    assert_eq!(
+        FormulaSyntax::to_odf(" =Sheet2!B3*2 ", None),
+        "of:=[$Sheet2.B3]*2"
+    );
+    assert_eq!(
+        FormulaSyntax::to_odf("=SUM([.A1:.A10])", Some(FormulaDialect::Odf)),
+        "of:=SUM([.A1:.A10])"
+    );
+    assert_eq!(
+        FormulaSyntax::from_odf("of:=SUM([.A1:.A10];['Sheet 2'.B3])", FormulaDialect::Excel),
+        "=SUM(A1:A10,'Sheet 2'!B3)"
+    );
+    assert_eq!(
+        FormulaSyntax::from_odf("of:=[.A1]", FormulaDialect::Odf),
+        "of:=[.A1]"
+    );
+}
+
+#[test]
+fn formula_syntax_relative_form_matches_copies_down_a_column() {
+    assert_eq!(
+        FormulaSyntax::relative_form("of:=[.B3]*[$Datos.$A$1]", 4, 2),
+        "of:=[.R[-2]C[-1]]*[$Datos.R1C1]"
+    );
+    assert_eq!(
+        FormulaSyntax::relative_form("of:=SUM([.A1:.A4])", 4, 0),
+        FormulaSyntax::relative_form("of:=SUM([.A2:.A5])", 5, 0)
+    );
+    assert_eq!(
+        FormulaSyntax::relative_form("of:=\"[.A1]\"&[.$B2]", 0, 0),
+        "of:=\"[.A1]\"&[.R[1]C2]"
+    );
+}
@@ -0,0 +1,407 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::common::time::TimeUtil;
+use mcp_ods::ods::cell_address::CellAddress;
+use mcp_ods::ods::formula::{
+    Formula, FormulaError, FormulaSyntax, FormulaValue, RangeRef, ReferenceGraph,
+};
+use mcp_ods::ods::sheet_model::{CellValue, Workbook};
+
+fn workbook() -> Workbook {
+    let mut workbook = Workbook::new("Hoja1".to_string());
+    let rows: [[CellValue; 3]; 4] = [
+        [
+            CellValue::String("manzana".to_string()),
+            CellValue::Number(10.0),
+            CellValue::Boolean(true),
+        ],
+        [
+            CellValue::String("pera".to_string()),
+            CellValue::Number(20.0),
+            CellValue::String("3".to_string()),
+        ],
+        [
+            CellValue::String("uva".to_string()),
+            CellValue::Number(30.5),
+            CellValue::Empty,
+        ],
+        [
+            CellValue::String("Manzana roja".to_string()),
+            CellValue::Number(-4.0),
+            CellValue::Date("2024-01-02".to_string()),
+        ],
+    ];
+    for (r, row) in rows.into_iter().enumerate() {
+        for (c, value) in row.into_iter().enumerate() {
+            workbook.sheets[0].ensure_cell_mut(r, c).value = value;
+        }
+    }
+    let mut datos = mcp_ods::ods::sheet_model::Sheet::new("Mis datos".to_string());
+    datos.ensure_cell_mut(0, 0).value = CellValue::Number(100.0);
+    let stale = datos.ensure_cell_mut(1, 0);
+    stale.value = CellValue::String("#DIV/0!".to_string());
+    stale.formula = Some("of:=1/0".to_string());
+    workbook.sheets.push(datos);
+    workbook
+}
+
+fn eval(formula: &str) -> FormulaValue {
+    Formula::parse(formula)
+        .expect("parse")
+        .evaluate_in_workbook(&workbook(), 0)
+}
+
+fn number(formula: &str) -> f64 {
+    match eval(formula) {
+        FormulaValue::Number(n) => n,
+        other => panic!("{formula}: expected number, got {other:?}"),
+    }
+}
+
+fn error(formula: &str) -> FormulaError {
+    match eval(formula) {
+        FormulaValue::Error(e) => e,
+        other => panic!("{formula}: expected error, got {other:?}"),
+    }
+}
+
+#[test]
+fn formula_arithmetic_follows_spreadsheet_precedence() {
+    assert_eq!(number("of:=1+2*3"), 7.0);
+    assert_eq!(number("=(1+2)*3"), 9.0);
+    assert_eq!(number("=-2^2"), 4.0);
+    assert_eq!(number("=2^3^2"), 64.0);
+    assert_eq!(number("=50%*4"), 2.0);
+    assert_eq!(number("=10/4-1"), 1.5);
+    assert_eq!(number("=\"3\"+TRUE"), 4.0);
+    assert_eq!(number("=1.5E2"), 150.0);
+    assert_eq!(
+        eval("=\"a\"&1/4&TRUE"),
+        FormulaValue::Text("a0.25TRUE".to_string())
+    );
+    assert_eq!(eval("=0.1+0.2&\"\""), FormulaValue::Text("0.3".to_string()));
+}
+
+#[test]
+fn formula_comparisons_are_case_insensitive_and_typed() {
+    assert_eq!(eval("=\"ABC\"=\"abc\""), FormulaValue::Boolean(true));
+    assert_eq!(eval("=2<>2"), FormulaValue::Boolean(false));
+    assert_eq!(eval("=1<\"a\""), FormulaValue::Boolean(true));
+    assert_eq!(eval("=\"a\"<TRUE"), FormulaValue::Boolean(true));
+    assert_eq!(eval("=[.C3]=0"), FormulaValue::Boolean(true));
+    assert_eq!(eval("=[.C3]=\"\""), FormulaValue::Boolean(true));
+    assert_eq!(eval("=3>=3"), FormulaValue::Boolean(true));
+}
+
+#[test]
+fn formula_references_read_cells_ranges_and_other_sheets() {
+    assert_eq!(number("of:=[.B1]+[.$B$2]"), 30.0);
+    assert_eq!(number("of:=[$'Mis datos'.A1]*2"), 200.0);
+    assert_eq!(number("of:=[.C3]"), 0.0);
+    assert_eq!(number("of:=[.C4]"), 45_293.0);
+    assert_eq!(error("of:=[$Nada.A1]"), FormulaError::Ref);
+    assert_eq!(error("of:=[.#REF!]+1"), FormulaError::Ref);
+    assert_eq!(error("of:=[.B1:.B2]+1"), FormulaError::Value);
+    assert_eq!(error("of:=[$'Mis datos'.A2]"), FormulaError::Div0);
+
+    let formula = Formula::parse("of:=SUM([.A1:.B3];[$'Mis datos'.A1])").expect("parse");
+    assert_eq!(
+        formula.references(),
+        vec![
+            RangeRef {
+                sheet: None,
+                start: CellAddress { row: 0, col: 0 },
+                end: CellAddress { row: 2, col: 1 },
+            },
+            RangeRef {
+                sheet: Some("Mis datos".to_string()),
+                start: CellAddress { row: 0, col: 0 },
+                end: CellAddress { row: 0, col: 0 },
+            },
+        ]
+    );
+}
+
+#[test]
+fn formula_whole_columns_and_rows_span_the_full_sheet() {
+    let mut workbook = workbook();
+    let mut hoja2 = mcp_ods::ods::sheet_model::Sheet::new("Hoja2".to_string());
+    hoja2.ensure_cell_mut(0, 0).value = CellValue::Number(1.0);
+    hoja2.ensure_cell_mut(0, 5).value = CellValue::Number(2.5);
+    hoja2.ensure_cell_mut(3, 0).value = CellValue::Number(100.0);
+    workbook.sheets.push(hoja2);
+    let eval_excel = |formula: &str, sheet_index: usize| {
+        Formula::parse(&FormulaSyntax::excel_to_odf(formula))
+            .expect(formula)
+            .evaluate_in_workbook(&workbook, sheet_index)
+    };
+
+    assert_eq!(eval_excel("=SUM(A:A)", 2), FormulaValue::Number(101.0));
+    assert_eq!(eval_excel("=SUM(B:B)", 0), FormulaValue::Number(56.5));
+    assert_eq!(eval_excel("=SUM(Hoja2!1:1)", 0), FormulaValue::Number(3.5));
+    assert_eq!(eval_excel("=COUNTA(1:2)", 0), FormulaValue::Number(6.0));
+    assert_eq!(eval_excel("=SUM(Hoja2!G:H)", 0), FormulaValue::Number(0.0));
+    assert_eq!(
+        eval_excel("=COUNTIF(A:A;\"\")", 2),
+        FormulaValue::Number(1_048_574.0)
+    );
+    assert_eq!(
+        eval_excel("=COUNTIF(Hoja2!1:1;\"\")", 0),
+        FormulaValue::Number(16_382.0)
+    );
+
+    let formula = Formula::parse("of:=SUM([.A:.B];[$Hoja2.$1:.1])").expect("parse");
+    assert_eq!(
+        formula.references(),
+        vec![
+            RangeRef {
+                sheet: None,
+                start: CellAddress { row: 0, col: 0 },
+                end: CellAddress {
+                    row: RangeRef::LAST_ROW,
+                    col: 1
+                },
+            },
+            RangeRef {
+                sheet: Some("Hoja2".to_string()),
+                start: CellAddress { row: 0, col: 0 },
+                end: CellAddress {
+                    row: 0,
+                    col: RangeRef::LAST_COL
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn formula_aggregates_skip_text_in_ranges() {
+    assert_eq!(number("of:=SUM([.B1:.B4])"), 56.5);
+    assert_eq!(number("of:=SUM([.A1:.C2];1;\"2\")"), 33.0);
+    assert_eq!(number("of:=AVERAGE([.B1:.B2])"), 15.0);
+    assert_eq!(number("of:=MIN([.B1:.B4])"), -4.0);
+    assert_eq!(number("of:=MAX([.B1:.B4];99)"), 99.0);
+    assert_eq!(number("of:=MAX([.A1:.A4])"), 0.0);
+    assert_eq!(number("of:=COUNT([.A1:.C4])"), 5.0);
+    assert_eq!(number("of:=COUNTA([.A1:.C4])"), 11.0);
+    assert_eq!(number("of:=COUNT(1;\"x\";\"2\")"), 2.0);
+    assert_eq!(error("of:=AVERAGE([.A1:.A4])"), FormulaError::Div0);
+    assert_eq!(error("of:=SUM(1;\"x\")"), FormulaError::Value);
+    assert_eq!(error("of:=SUM([$'Mis datos'.A1:.A2])"), FormulaError::Div0);
+}
+
+#[test]
+fn formula_logical_functions() {
+    assert_eq!(
+        eval("of:=IF([.B1]>5;\"alto\";\"bajo\")"),
+        FormulaValue::Text("alto".to_string())
+    );
+    assert_eq!(eval("of:=IF(0;1)"), FormulaValue::Boolean(false));
+    assert_eq!(
+        eval("of:=IF(1;1/0;2)"),
+        FormulaValue::Error(FormulaError::Div0)
+    );
+    assert_eq!(number("of:=IF(0;1/0;2)"), 2.0);
+    assert_eq!(error("of:=IF(\"x\";1;2)"), FormulaError::Value);
+    assert_eq!(eval("of:=AND(TRUE;[.B1]>5)"), FormulaValue::Boolean(true));
+    assert_eq!(eval("of:=AND([.C1:.C3];0)"), FormulaValue::Boolean(false));
+    assert_eq!(eval("of:=OR(FALSE;[.B4]<0)"), FormulaValue::Boolean(true));
+    assert_eq!(error("of:=OR([.A1:.A2])"), FormulaError::Value);
+    assert_eq!(number("of:=IFERROR(1/0;-1)"), -1.0);
+    assert_eq!(number("of:=IFERROR(5;-1)"), 5.0);
+}
+
+#[test]
+fn formula_round_concatenate_and_today() {
+    assert_eq!(number("of:=ROUND(2.675;2)"), 2.68);
+    assert_eq!(number("of:=ROUND(-2.5)"), -3.0);
+    assert_eq!(number("of:=ROUND(1234.5;-2)"), 1200.0);
+    assert_eq!(
+        eval("of:=CONCATENATE([.A2];\" \";[.B2];\"kg\")"),
+        FormulaValue::Text("pera 20kg".to_string())
+    );
+    let today = TimeUtil::serial_from_iso8601(&TimeUtil::now_iso8601()[..10]).expect("today");
+    assert_eq!(number("of:=TODAY()"), today);
+}
+
+#[test]
+fn formula_lookup_and_conditional_aggregates() {
+    assert_eq!(number("of:=VLOOKUP(\"PERA\";[.A1:.B4];2;0)"), 20.0);
+    assert_eq!(
+        error("of:=VLOOKUP(\"kiwi\";[.A1:.B4];2;FALSE())"),
+        FormulaError::Na
+    );
+    assert_eq!(
+        error("of:=VLOOKUP(\"kiwi\";[.A1:.B4];2;FALSE)"),
+        FormulaError::Na
+    );
+    assert_eq!(
+        error("of:=VLOOKUP(\"pera\";[.A1:.B4];3;0)"),
+        FormulaError::Ref
+    );
+    assert_eq!(
+        eval("of:=VLOOKUP(25;[.B1:.C3];2)"),
+        FormulaValue::Text("3".to_string())
+    );
+    assert_eq!(error("of:=VLOOKUP(1;[.B1:.C3];2)"), FormulaError::Na);
+
+    assert_eq!(number("of:=SUMIF([.B1:.B4];\">10\")"), 50.5);
+    assert_eq!(number("of:=SUMIF([.A1:.A4];\"manzana*\";[.B1:.B4])"), 6.0);
+    assert_eq!(number("of:=SUMIF([.A1:.A4];\"<>uva\";[.B1:.B4])"), 26.0);
+    assert_eq!(number("of:=COUNTIF([.B1:.B4];20)"), 1.0);
+    assert_eq!(number("of:=COUNTIF([.A1:.A4];\"?era\")"), 1.0);
+    assert_eq!(number("of:=COUNTIF([.C1:.C9];\"\")"), 6.0);
+    assert_eq!(number("of:=COUNTIF([.B1:.B4];\"<=20\")"), 3.0);
+}
+
+#[test]
+fn formula_unknown_names_and_bad_arity_are_error_values() {
+    assert_eq!(error("of:=NOSUCH(1)"), FormulaError::Name);
+    assert_eq!(error("of:=precio*2"), FormulaError::Name);
+    assert_eq!(error("of:=IF()"), FormulaError::Value);
+    assert_eq!(error("of:=1/0"), FormulaError::Div0);
+    assert_eq!(error("of:=#N/A+1"), FormulaError::Na);
+    assert_eq!(error("of:=(-1)^0.5"), FormulaError::Num);
+}
+
+#[test]
+fn formula_parse_rejects_malformed_input() {
+    for bad in [
+        "of:=",
+        "of:=1+",
+        "of:=SUM(1;2",
+        "of:=\"abc",
+        "of:=[.A1",
+        "of:=1 2",
+        "of:=[.ZZZZ]",
+        "of:=[.A]",
+        "of:=[.A1:.B]",
+        "msoxl:=SUM(A1)",
+    ] {
+        let err = Formula::parse(bad).expect_err(bad);
+        assert!(matches!(err, AppError::InvalidInput(_)), "{bad}: {err:?}");
+    }
+}
+
+#[test]
+fn formula_value_converts_to_cell_values() {
+    assert_eq!(
+        FormulaValue::Error(FormulaError::Div0).to_cell_value(),
+        CellValue::String("#DIV/0!".to_string())
+    );
+    assert_eq!(FormulaError::from_code("#n/a"), Some(FormulaError::Na));
+    assert_eq!(
+        FormulaValue::Number(1e20).display_text(),
+        "100000000000000000000"
+    );
+    assert_eq!(FormulaValue::Number(-0.5).display_text(), "-0.5");
+}
+
+#[test]
+fn formula_recalculate_orders_chains_and_reports_blocked_cells() {
+    let mut workbook = Workbook::new("Hoja1".to_string());
+    let sheet = &mut workbook.sheets[0];
+    let mut set = |row: usize, col: usize, value: CellValue, formula: Option<&str>| {
+        let cell = sheet.ensure_cell_mut(row, col);
+        cell.value = value;
+        cell.formula = formula.map(str::to_string);
+    };
+    set(0, 0, CellValue::Number(2.0), None);
+    // B1 reads B2, which is defined later in document order.
+    set(0, 1, CellValue::Number(0.0), Some("of:=[.B2]*10"));
+    set(1, 1, CellValue::Number(0.0), Some("of:=[.A1]+1"));
+    set(2, 0, CellValue::Number(7.0), Some("of:=[.B3]"));
+    set(2, 1, CellValue::Number(7.0), Some("of:=[.A3]"));
+    set(3, 0, CellValue::Number(1.0), Some("of:=[.A3]+1"));
+    set(4, 0, CellValue::Number(5.0), Some("of:=NPV(0.1;[.A1])"));
+    set(4, 1, CellValue::Number(6.0), Some("of:=[.A5]*2"));
+    set(5, 0, CellValue::Empty, Some("of:=1/0"));
+    set(5, 1, CellValue::Empty, Some("of:=SUM("));
+
+    let outcome = Formula::recalculate(&workbook);
+    let value_at = |row, col| {
+        outcome
+            .results
+            .iter()
+            .find(|r| (r.row, r.col) == (row, col))
+            .map(|r| r.value.clone())
+    };
+    assert_eq!(value_at(1, 1), Some(FormulaValue::Number(3.0)));
+    assert_eq!(value_at(0, 1), Some(FormulaValue::Number(30.0)));
+    assert_eq!(
+        value_at(5, 0),
+        Some(FormulaValue::Error(FormulaError::Div0))
+    );
+    assert_eq!(outcome.results.len(), 3);
+
+    let issues: Vec<_> = outcome
+        .issues
+        .iter()
+        .map(|i| (i.row, i.col, i.kind.as_str(), i.detail.as_str()))
+        .collect();
+    assert_eq!(
+        issues,
+        vec![
+            (
+                2,
+                0,
+                "circular_reference",
+                "formula is part of a circular reference"
+            ),
+            (
+                2,
+                1,
+                "circular_reference",
+                "formula is part of a circular reference"
+            ),
+            (3, 0, "depends_on_unresolved", "Hoja1.A3"),
+            (4, 0, "unsupported_function", "NPV"),
+            (4, 1, "depends_on_unresolved", "Hoja1.A5"),
+            (
+                5,
+                1,
+                "parse_error",
+                "invalid input: invalid formula: unexpected end of formula"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn reference_graph_resolves_sheets_and_finds_dependents() {
+    let mut workbook = Workbook::new("Hoja1".to_string());
+    workbook
+        .sheets
+        .push(mcp_ods::ods::sheet_model::Sheet::new("Datos".to_string()));
+    let mut set = |sheet: usize, row: usize, col: usize, formula: &str| {
+        workbook.sheets[sheet].ensure_cell_mut(row, col).formula = Some(formula.to_string());
+    };
+    set(0, 0, 0, "of:=SUM([$datos.A1:.B3])");
+    set(0, 1, 0, "of:=[.A1]+[$Falta.C1]");
+    set(1, 1, 1, "of:=1");
+    set(1, 4, 0, "of:=SUM(");
+    set(1, 5, 0, "of:=SUM([$Hoja1.B:.B])+SUM([.2:.2])");
+
+    let graph = ReferenceGraph::build(&workbook);
+    assert_eq!(graph.cells.len(), 5);
+    let a1 = &graph.cells[0];
+    assert_eq!(a1.references[0].sheet_index, Some(1));
+    assert_eq!(a1.references[0].sheet_name, "Datos");
+    assert_eq!(a1.references[0].to_a1(), "A1:B3");
+    let missing = &graph.cells[1].references[1];
+    assert_eq!(
+        (missing.sheet_index, missing.sheet_name.as_str()),
+        (None, "Falta")
+    );
+    assert!(graph.cells[3].parsed.is_err());
+    let whole = &graph.cells[4].references;
+    assert_eq!(
+        (whole[0].to_a1(), whole[1].to_a1()),
+        ("B:B".into(), "2:2".into())
+    );
+
+    let datos_b2 = graph.formula_at(1, 1, 1).expect("formula");
+    assert_eq!(graph.formulas_in(&a1.references[0]), vec![datos_b2]);
+    assert_eq!(graph.dependents_of(1, 1, 1), vec![0, 4]);
+    assert_eq!(graph.dependents_of(0, 0, 0), vec![1]);
+    assert_eq!(graph.precedents()[1], vec![0]);
+}
@@ -0,0 +1,64 @@
+use mcp_ods::ods::image::{ImageFormat, ImageInfo};
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    data.extend_from_slice(&width.to_be_bytes());
+    data.extend_from_slice(&height.to_be_bytes());
+    data.extend_from_slice(&[8, 6, 0, 0, 0]);
+    data
+}
+
+#[test]
+fn image_info_reads_format_and_natural_size() {
+    let info = ImageInfo::inspect(&png(96, 48)).expect("png");
+    assert_eq!(info.format, ImageFormat::Png);
+    assert_eq!(info.size_cm, Some((2.54, 1.27)));
+
+    // APP0 segment, then a baseline start-of-frame: 192 wide, 96 high.
+    let jpeg = [
+        0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x60,
+        0x00, 0xC0, 0x01, 0x01, 0x11, 0x00,
+    ];
+    let info = ImageInfo::inspect(&jpeg).expect("jpeg");
+    assert_eq!(info.format, ImageFormat::Jpeg);
+    assert_eq!(info.size_cm, Some((5.08, 2.54)));
+
+    let svg = br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" width="3cm" height="20mm"/>"#;
+    assert_eq!(
+        ImageInfo::inspect(svg).expect("svg").size_cm,
+        Some((3.0, 2.0))
+    );
+    let view_box = br#"<svg viewBox="0 0 96 192" xmlns="http://www.w3.org/2000/svg"></svg>"#;
+    assert_eq!(
+        ImageInfo::inspect(view_box).expect("svg").size_cm,
+        Some((2.54, 5.08))
+    );
+    let no_size = br#"<svg xmlns="http://www.w3.org/2000/svg" width="100%"/>"#;
+    assert_eq!(ImageInfo::inspect(no_size).expect("svg").size_cm, None);
+
+    // Zero dimensions state no size; an SVG falls back to its viewBox.
+    assert_eq!(ImageInfo::inspect(&png(0, 48)).expect("png").size_cm, None);
+    let zero = br#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="2cm"/>"#;
+    assert_eq!(ImageInfo::inspect(zero).expect("svg").size_cm, None);
+    let zero_view_box =
+        br#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="0" viewBox="0 0 96 48"/>"#;
+    assert_eq!(
+        ImageInfo::inspect(zero_view_box).expect("svg").size_cm,
+        Some((2.54, 1.27))
+    );
+
+    assert!(ImageInfo::inspect(b"GIF89a").is_err());
+    assert!(ImageInfo::inspect(b"<svgfoo/>").is_err());
+}
+
+#[test]
+fn image_info_converts_odf_lengths() {
+    assert_eq!(ImageInfo::length_cm("4cm"), Some(4.0));
+    assert_eq!(ImageInfo::length_cm(" 25mm "), Some(2.5));
+    assert_eq!(ImageInfo::length_cm("1in"), Some(2.54));
+    assert_eq!(ImageInfo::length_cm("72pt"), Some(2.54));
+    assert_eq!(ImageInfo::length_cm("96"), Some(2.54));
+    assert_eq!(ImageInfo::length_cm("2em"), None);
+    assert_eq!(ImageInfo::length_cm("cm"), None);
+    assert_eq!(ImageInfo::format_cm(2.0 / 3.0), "0.667cm");
+}
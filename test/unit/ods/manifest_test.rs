@@ -10,3 +10,56 @@ fn manifest_xml_contains_required_entries() {
     assert!(xml.contains("manifest:full-path=\"meta.xml\""));
     assert!(xml.contains("manifest:full-path=\"settings.xml\""));
 }
+
+const LIBREOFFICE_MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.4">
+ <manifest:file-entry manifest:full-path="/" manifest:version="1.4" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/>
+ <manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"><manifest:encryption-data manifest:checksum="abc"/></manifest:file-entry>
+ <manifest:file-entry manifest:full-path="Thumbnails/thumbnail.png" manifest:media-type="image/png"/>
+</manifest:manifest>
+"#;
+
+#[test]
+fn manifest_parse_reads_entries_and_round_trips_unchanged() {
+    let manifest = Manifest::parse(LIBREOFFICE_MANIFEST).expect("parse");
+    let paths: Vec<&str> = manifest
+        .entries()
+        .iter()
+        .map(|e| e.full_path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["/", "content.xml", "Thumbnails/thumbnail.png"]);
+    assert!(manifest.get("content.xml").expect("content").encrypted);
+    assert!(!manifest.get("/").expect("root").encrypted);
+    assert_eq!(manifest.to_xml(), LIBREOFFICE_MANIFEST);
+}
+
+#[test]
+fn manifest_add_and_remove_file_entries() {
+    let mut manifest = Manifest::parse(LIBREOFFICE_MANIFEST).expect("parse");
+    manifest.add_file("Pictures/logo & co.png", "image/png");
+    manifest.add_file("Thumbnails/thumbnail.png", "image/png");
+    assert!(manifest.remove_file("Thumbnails/thumbnail.png"));
+    assert!(!manifest.remove_file("missing.xml"));
+
+    let xml = manifest.to_xml();
+    assert!(xml.contains(
+        r#"<manifest:file-entry manifest:full-path="Pictures/logo &amp; co.png" manifest:media-type="image/png"/>"#
+    ));
+    assert!(!xml.contains("thumbnail.png"));
+    assert!(xml.contains("<manifest:encryption-data manifest:checksum=\"abc\"/>"));
+
+    let reparsed = Manifest::parse(&xml).expect("reparse");
+    assert_eq!(reparsed.entries().len(), 3);
+    assert_eq!(
+        reparsed.get("Pictures/logo & co.png").map(|e| e.media_type.as_str()),
+        Some("image/png")
+    );
+}
+
+#[test]
+fn manifest_parse_rejects_other_documents() {
+    assert!(Manifest::parse("<office:document-content/>").is_err());
+    assert!(Manifest::parse("<manifest:manifest>").is_err());
+    assert_eq!(Manifest::media_type_for("Pictures/A.JPG"), Some("image/jpeg"));
+    assert_eq!(Manifest::media_type_for("Object 1"), None);
+}
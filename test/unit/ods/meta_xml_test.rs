@@ -0,0 +1,89 @@
+use mcp_ods::common::time::TimeUtil;
+use mcp_ods::ods::meta_xml::{DocumentPropertiesUpdate, MetaXml, UserDefinedProperty};
+use mcp_ods::ods::ods_templates::OdsTemplates;
+
+const LIBREOFFICE_META: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-meta xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" office:version="1.4"><office:meta><meta:document-statistic meta:table-count="1" meta:cell-count="0" meta:object-count="0"/><dc:title>Ventas</dc:title><meta:keyword>q1</meta:keyword><meta:keyword>ventas</meta:keyword><meta:editing-cycles>4</meta:editing-cycles><meta:user-defined meta:name="Cliente" meta:value-type="string">ACME</meta:user-defined><meta:generator>LibreOffice/25.8</meta:generator></office:meta></office:document-meta>"#;
+
+#[test]
+fn meta_xml_parse_reads_standard_and_user_defined_fields() {
+    let props = MetaXml::parse(LIBREOFFICE_META).expect("parse");
+    assert_eq!(props.title.as_deref(), Some("Ventas"));
+    assert_eq!(props.keywords, vec!["q1", "ventas"]);
+    assert_eq!(props.editing_cycles, Some(4));
+    assert_eq!(props.generator.as_deref(), Some("LibreOffice/25.8"));
+    assert_eq!(
+        props.user_defined,
+        vec![UserDefinedProperty {
+            name: "Cliente".to_string(),
+            value_type: "string".to_string(),
+            value: "ACME".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn meta_xml_stamp_modified_bumps_cycles_date_and_generator() {
+    let stamped = MetaXml::stamp_modified(LIBREOFFICE_META, "2026-03-01T10:00:00Z", "test/1.0")
+        .expect("stamp");
+    let props = MetaXml::parse(&stamped).expect("parse");
+
+    assert_eq!(props.editing_cycles, Some(5));
+    assert_eq!(
+        props.modification_date.as_deref(),
+        Some("2026-03-01T10:00:00Z")
+    );
+    assert_eq!(props.generator.as_deref(), Some("test/1.0"));
+    assert_eq!(props.title.as_deref(), Some("Ventas"));
+    assert!(stamped.contains("meta:document-statistic"));
+    assert_eq!(stamped.matches("<meta:generator>").count(), 1);
+}
+
+#[test]
+fn meta_xml_apply_update_replaces_removes_and_keeps_fields() {
+    let update = DocumentPropertiesUpdate {
+        title: Some(String::new()),
+        subject: Some("Informe".to_string()),
+        keywords: Some(vec!["anual".to_string()]),
+        user_defined: vec![UserDefinedProperty {
+            name: "Version".to_string(),
+            value_type: "float".to_string(),
+            value: "3".to_string(),
+        }],
+        remove_user_defined: vec!["Cliente".to_string()],
+        ..Default::default()
+    };
+
+    let updated = MetaXml::apply_update(LIBREOFFICE_META, &update).expect("update");
+    let props = MetaXml::parse(&updated).expect("parse");
+
+    assert_eq!(props.title, None);
+    assert_eq!(props.subject.as_deref(), Some("Informe"));
+    assert_eq!(props.keywords, vec!["anual"]);
+    assert_eq!(props.editing_cycles, Some(4));
+    assert_eq!(props.user_defined.len(), 1);
+    assert_eq!(props.user_defined[0].name, "Version");
+}
+
+#[test]
+fn meta_xml_apply_update_declares_namespaces_on_minimal_meta() {
+    let update = DocumentPropertiesUpdate {
+        creator: Some("Agente".to_string()),
+        ..Default::default()
+    };
+    let updated = MetaXml::apply_update(OdsTemplates::meta_xml(), &update).expect("update");
+
+    assert!(updated.contains("xmlns:dc=\"http://purl.org/dc/elements/1.1/\""));
+    assert!(updated.contains("<dc:creator>Agente</dc:creator>"));
+    let props = MetaXml::parse(&updated).expect("parse");
+    assert_eq!(props.creator.as_deref(), Some("Agente"));
+}
+
+#[test]
+fn time_util_formats_unix_seconds_as_iso8601() {
+    assert_eq!(TimeUtil::iso8601_from_unix(0), "1970-01-01T00:00:00Z");
+    assert_eq!(
+        TimeUtil::iso8601_from_unix(1_772_359_200),
+        "2026-03-01T10:00:00Z"
+    );
+}
@@ -1,6 +1,14 @@
 mod cell_address_test;
 mod content_xml_test;
+mod flat_ods_test;
+mod formula_syntax_test;
+mod formula_test;
+mod image_test;
 mod manifest_test;
+mod meta_xml_test;
 mod ods_file_test;
 mod ods_templates_test;
+mod package_test;
 mod sheet_model_test;
+mod validation_test;
+mod workbook_json_test;
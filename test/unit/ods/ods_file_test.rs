@@ -1,6 +1,8 @@
 use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::manifest::Manifest;
 use mcp_ods::ods::ods_file::OdsFile;
 use mcp_ods::ods::sheet_model::{CellValue, Workbook};
+use mcp_ods::ods::validation::OdsValidator;
 use std::fs::File;
 use std::io::{Read, Write};
 use tempfile::tempdir;
@@ -107,6 +109,54 @@ fn ods_file_write_workbook_persists_new_values() {
     );
 }
 
+#[test]
+fn ods_file_write_and_remove_part_keep_manifest_in_sync() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("parts.ods");
+    OdsFile::create(&path, "Hoja1".to_string()).expect("create");
+
+    OdsFile::write_part(&path, "Pictures/logo.png", b"\x89PNG", None).expect("write png");
+    OdsFile::write_part(&path, "Object 1/data.bin", b"raw", Some("application/x-test"))
+        .expect("write bin");
+    let manifest = Manifest::parse(&read_entry(&path, "META-INF/manifest.xml")).expect("manifest");
+    assert_eq!(
+        manifest.get("Pictures/logo.png").map(|e| e.media_type.as_str()),
+        Some("image/png")
+    );
+    assert_eq!(
+        manifest.get("Object 1/data.bin").map(|e| e.media_type.as_str()),
+        Some("application/x-test")
+    );
+    assert_eq!(OdsValidator::validate_path(&path).expect("validate"), vec![]);
+
+    assert!(OdsFile::remove_part(&path, "Pictures/logo.png").expect("remove"));
+    assert!(!OdsFile::remove_part(&path, "Pictures/logo.png").expect("remove again"));
+    let manifest = Manifest::parse(&read_entry(&path, "META-INF/manifest.xml")).expect("manifest");
+    assert!(manifest.get("Pictures/logo.png").is_none());
+    assert!(manifest.get("content.xml").is_some());
+    let mut zip = ZipArchive::new(File::open(&path).expect("open")).expect("zip");
+    assert!(zip.by_name("Pictures/logo.png").is_err());
+}
+
+#[test]
+fn ods_file_write_part_rejects_flat_documents() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("flat.fods");
+    OdsFile::create(&path, "Hoja1".to_string()).expect("create");
+    let err = OdsFile::write_part(&path, "Pictures/a.png", b"x", None).expect_err("flat");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+}
+
+fn read_entry(path: &std::path::Path, name: &str) -> String {
+    let mut zip = ZipArchive::new(File::open(path).expect("open")).expect("zip");
+    let mut text = String::new();
+    zip.by_name(name)
+        .expect("entry")
+        .read_to_string(&mut text)
+        .expect("read entry");
+    text
+}
+
 fn write_ods_like_zip(path: &std::path::Path, entries: Vec<(String, bool, Vec<u8>)>) {
     let out = File::create(path).expect("create zip");
     let mut writer = ZipWriter::new(out);
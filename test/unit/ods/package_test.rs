@@ -0,0 +1,119 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::manifest::Manifest;
+use mcp_ods::ods::ods_file::{OdsFile, SaveOptions};
+use mcp_ods::ods::package::OdsPackage;
+use mcp_ods::ods::validation::OdsValidator;
+use std::fs::File;
+use tempfile::tempdir;
+use zip::{CompressionMethod, ZipArchive};
+
+fn manifest_of(package: &OdsPackage) -> Manifest {
+    let xml = package
+        .get_text("META-INF/manifest.xml")
+        .expect("utf-8")
+        .expect("manifest");
+    Manifest::parse(&xml).expect("parse manifest")
+}
+
+#[test]
+fn package_edits_several_parts_in_one_save() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("package.ods");
+    OdsFile::create(&path, "Hoja1".to_string()).expect("create");
+
+    let mut package = OdsFile::open_package(&path).expect("open");
+    assert!(package.contains("styles.xml"));
+    assert!(!package.contains("Configurations2/"));
+    let settings = package
+        .get_text("settings.xml")
+        .expect("utf-8")
+        .expect("settings");
+    package
+        .put(
+            "settings.xml",
+            settings.replace("</office:settings>", "</office:settings>\n"),
+        )
+        .expect("put settings");
+    package
+        .put(
+            "Pictures/logo.svg",
+            "<svg xmlns=\"http://www.w3.org/2000/svg\"/>",
+        )
+        .expect("put svg");
+    package
+        .put_with_media_type("Object 1/data", vec![1, 2, 3], "application/x-test")
+        .expect("put data");
+    assert!(package.remove("Thumbnails/thumbnail.png").expect("remove"));
+    assert!(!package.remove("missing.xml").expect("remove missing"));
+
+    let manifest = manifest_of(&package);
+    assert_eq!(
+        manifest
+            .get("Pictures/logo.svg")
+            .map(|e| e.media_type.as_str()),
+        Some("image/svg+xml")
+    );
+    assert_eq!(
+        manifest.get("Object 1/data").map(|e| e.media_type.as_str()),
+        Some("application/x-test")
+    );
+    assert!(manifest.get("Thumbnails/thumbnail.png").is_none());
+    package.save(&path, &SaveOptions::default()).expect("save");
+
+    let reopened = OdsPackage::open(&path).expect("reopen");
+    assert_eq!(reopened.get("Object 1/data"), Some(&[1u8, 2, 3][..]));
+    assert!(reopened
+        .part_names()
+        .all(|name| name != "Thumbnails/thumbnail.png"));
+    assert_eq!(
+        OdsValidator::validate_path(&path).expect("validate"),
+        vec![]
+    );
+}
+
+#[test]
+fn package_save_writes_mimetype_first_and_uncompressed() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mimetype.ods");
+    OdsFile::create(&path, "Hoja1".to_string()).expect("create");
+
+    let mut package = OdsPackage::open(&path).expect("open");
+    package.remove("mimetype").expect("remove mimetype");
+    package.put("z.xml", "<z/>").expect("put");
+    package.save(&path, &SaveOptions::default()).expect("save");
+
+    let mut zip = ZipArchive::new(File::open(&path).expect("open")).expect("zip");
+    let first = zip.by_index(0).expect("first entry");
+    assert_eq!(first.name(), "mimetype");
+    assert_eq!(first.compression(), CompressionMethod::Stored);
+    drop(first);
+    let last = zip.len() - 1;
+    assert_eq!(zip.by_index(last).expect("last entry").name(), "z.xml");
+}
+
+#[test]
+fn package_save_checks_changed_parts_only() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("checked.ods");
+    OdsFile::create(&path, "Hoja1".to_string()).expect("create");
+
+    let mut package = OdsPackage::open(&path).expect("open");
+    package
+        .put("styles.xml", "<office:document-styles>")
+        .expect("put");
+    let err = package
+        .save(&path, &SaveOptions::default())
+        .expect_err("malformed styles");
+    assert!(matches!(err, AppError::InvalidOdsFormat(_)));
+    assert!(err.to_string().contains("styles.xml"));
+
+    let unchecked = SaveOptions {
+        validate: false,
+        ..SaveOptions::default()
+    };
+    package.save(&path, &unchecked).expect("save unchecked");
+    OdsPackage::open(&path)
+        .expect("reopen")
+        .save(&path, &SaveOptions::default())
+        .expect("untouched parts are not checked");
+}
@@ -0,0 +1,176 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::ods_file::{OdsFile, SaveOptions};
+use mcp_ods::ods::validation::{OdsValidator, ValidationSeverity};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use tempfile::tempdir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2">
+  <manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/>
+  <manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>
+  <manifest:file-entry manifest:full-path="styles.xml" manifest:media-type="image/png"/>
+  <manifest:file-entry manifest:full-path="Pictures/logo.png" manifest:media-type="image/png"/>
+</manifest:manifest>"#;
+
+const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Datos">
+      <table:table-column table:number-columns-repeated="0"/>
+      <table:table-row table:number-rows-repeated="1048576"><table:table-cell/></table:table-row>
+      <table:table-row><table:table-cell table:number-columns-spanned="x"/></table:table-row>
+    </table:table>
+    <table:table table:name="Datos"/>
+    <table:table table:name="DATOS"/>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+fn write_zip(path: &Path, entries: &[(&str, CompressionMethod, &str)]) {
+    let mut writer = ZipWriter::new(File::create(path).expect("create zip"));
+    for (name, method, data) in entries {
+        let options = SimpleFileOptions::default().compression_method(*method);
+        writer.start_file(*name, options).expect("start file");
+        writer.write_all(data.as_bytes()).expect("write entry");
+    }
+    writer.finish().expect("finish zip");
+}
+
+#[test]
+fn validator_accepts_packages_created_from_the_template() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("valid.ods");
+    OdsFile::create(&path, "Hoja1".to_string()).expect("create");
+    assert_eq!(
+        OdsValidator::validate_path(&path).expect("validate"),
+        vec![]
+    );
+}
+
+#[test]
+fn validator_reports_package_manifest_and_table_problems() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("broken.ods");
+    write_zip(
+        &path,
+        &[
+            ("content.xml", CompressionMethod::Deflated, CONTENT),
+            (
+                "mimetype",
+                CompressionMethod::Deflated,
+                "application/vnd.oasis.opendocument.spreadsheet",
+            ),
+            (
+                "styles.xml",
+                CompressionMethod::Deflated,
+                "<office:document-styles>",
+            ),
+            ("settings.xml", CompressionMethod::Deflated, "<a/>"),
+            (
+                "META-INF/manifest.xml",
+                CompressionMethod::Deflated,
+                MANIFEST,
+            ),
+        ],
+    );
+
+    let issues = OdsValidator::validate_path(&path).expect("validate");
+    let found: Vec<(&str, &str, ValidationSeverity)> = issues
+        .iter()
+        .map(|i| (i.code, i.part.as_str(), i.severity))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("mimetype_not_first", "mimetype", ValidationSeverity::Error),
+            ("mimetype_compressed", "mimetype", ValidationSeverity::Error),
+            ("unlisted_part", "settings.xml", ValidationSeverity::Error),
+            ("wrong_media_type", "styles.xml", ValidationSeverity::Error),
+            (
+                "missing_part",
+                "Pictures/logo.png",
+                ValidationSeverity::Error
+            ),
+            ("invalid_repeat", "content.xml", ValidationSeverity::Error),
+            ("invalid_repeat", "content.xml", ValidationSeverity::Error),
+            // The full-height first row is fine; the second one goes past the last row.
+            ("grid_overflow", "content.xml", ValidationSeverity::Error),
+            (
+                "duplicate_table_name",
+                "content.xml",
+                ValidationSeverity::Error
+            ),
+            (
+                "duplicate_table_name",
+                "content.xml",
+                ValidationSeverity::Warning
+            ),
+            ("malformed_xml", "styles.xml", ValidationSeverity::Error),
+        ]
+    );
+}
+
+#[test]
+fn validator_check_part_rejects_broken_xml_before_saving() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("guarded.ods");
+    OdsFile::create(&path, "Hoja1".to_string()).expect("create");
+    let before = OdsFile::read_content_xml(&path).expect("content");
+
+    let err = OdsFile::write_content_xml(&path, "<office:document-content>")
+        .expect_err("malformed content");
+    assert!(matches!(err, AppError::InvalidOdsFormat(_)));
+    assert!(err.to_string().contains("content.xml"));
+
+    let duplicated = before.replace(
+        "</office:spreadsheet>",
+        "<table:table table:name=\"Hoja1\"/></office:spreadsheet>",
+    );
+    let err = OdsFile::write_content_xml(&path, &duplicated).expect_err("duplicate table");
+    assert!(err.to_string().contains("Hoja1"));
+    assert_eq!(OdsFile::read_content_xml(&path).expect("content"), before);
+
+    assert!(OdsValidator::check_part("Pictures/logo.png", b"\x89PNG").is_ok());
+}
+
+// As saved by LibreOffice 6.4: the accelerator configuration is an empty part listed with
+// an empty media type.
+const LIBREOFFICE_MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2" xmlns:loext="urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0">
+ <manifest:file-entry manifest:full-path="/" manifest:version="1.2" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/>
+ <manifest:file-entry manifest:full-path="Thumbnails/thumbnail.png" manifest:media-type="image/png"/>
+ <manifest:file-entry manifest:full-path="settings.xml" manifest:media-type="text/xml"/>
+ <manifest:file-entry manifest:full-path="Configurations2/accelerator/current.xml" manifest:media-type=""/>
+ <manifest:file-entry manifest:full-path="Configurations2/" manifest:media-type="application/vnd.sun.xml.ui.configuration"/>
+ <manifest:file-entry manifest:full-path="manifest.rdf" manifest:media-type="application/rdf+xml"/>
+ <manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>
+ <manifest:file-entry manifest:full-path="meta.xml" manifest:media-type="text/xml"/>
+ <manifest:file-entry manifest:full-path="styles.xml" manifest:media-type="text/xml"/>
+</manifest:manifest>
+"#;
+
+#[test]
+fn validator_accepts_libreoffice_empty_configuration_parts() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("libreoffice.ods");
+    OdsFile::create(&path, "Hoja1".to_string()).expect("create");
+    let mut package = OdsFile::open_package(&path).expect("open");
+    package
+        .put("Configurations2/accelerator/current.xml", Vec::new())
+        .expect("put accelerator");
+    package
+        .put("META-INF/manifest.xml", LIBREOFFICE_MANIFEST)
+        .expect("put manifest");
+    package.save(&path, &SaveOptions::default()).expect("save");
+
+    assert_eq!(
+        OdsValidator::validate_path(&path).expect("validate"),
+        vec![]
+    );
+    // An empty part elsewhere is still malformed XML.
+    assert!(OdsValidator::check_part("Configurations2/accelerator/current.xml", b"").is_ok());
+    assert!(OdsValidator::check_part("styles.xml", b"").is_err());
+}
@@ -0,0 +1,88 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::sheet_model::{CellComment, CellStyle, CellValue, MergedRange, Workbook};
+use mcp_ods::ods::workbook_json::WorkbookJson;
+use serde_json::json;
+
+#[test]
+fn workbook_json_lists_only_meaningful_cells() {
+    let mut workbook = Workbook::new("Datos".to_string());
+    let sheet = &mut workbook.sheets[0];
+    sheet.ensure_cell_mut(0, 0).value = CellValue::String("Total".to_string());
+    let b2 = sheet.ensure_cell_mut(1, 1);
+    b2.value = CellValue::Number(3.0);
+    b2.formula = Some("of:=1+2".to_string());
+    b2.style = CellStyle {
+        bold: true,
+        ..Default::default()
+    };
+    b2.comment = Some(CellComment {
+        text: "calculado".to_string(),
+        ..Default::default()
+    });
+    sheet.merges.push(MergedRange {
+        row: 2,
+        col: 0,
+        rows: 1,
+        cols: 2,
+    });
+
+    let value = WorkbookJson::to_value(&workbook).expect("to json");
+    assert_eq!(
+        value,
+        json!({
+            "schema": "mcp-ods/workbook",
+            "version": 1,
+            "sheets": [{
+                "name": "Datos",
+                "cells": [
+                    { "cell": "A1", "value": { "type": "string", "data": "Total" } },
+                    {
+                        "cell": "B2",
+                        "value": { "type": "number", "data": 3.0 },
+                        "formula": "of:=1+2",
+                        "style": { "bold": true },
+                        "comment": { "text": "calculado" }
+                    }
+                ],
+                "merges": ["A3:B3"]
+            }]
+        })
+    );
+
+    let back = WorkbookJson::from_value(value).expect("from json");
+    assert_eq!(
+        back.sheets[0].get_cell(1, 1),
+        workbook.sheets[0].get_cell(1, 1)
+    );
+    assert_eq!(back.sheets[0].merges, workbook.sheets[0].merges);
+    // The merged area is materialised so the renderer can emit covered cells.
+    assert!(back.sheets[0].get_cell(2, 1).is_some());
+}
+
+#[test]
+fn workbook_json_rejects_invalid_documents() {
+    let doc = |sheets: serde_json::Value| json!({ "schema": "mcp-ods/workbook", "version": 1, "sheets": sheets });
+
+    let err = WorkbookJson::from_value(json!({ "schema": "other", "version": 1, "sheets": [] }))
+        .expect_err("schema");
+    assert!(err.to_string().contains("unsupported workbook json"));
+
+    let err = WorkbookJson::from_value(doc(json!([]))).expect_err("no sheets");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    let err = WorkbookJson::from_value(doc(json!([{ "name": "A" }, { "name": "A" }])))
+        .expect_err("duplicate sheet");
+    assert!(matches!(err, AppError::SheetNameAlreadyExists(_)));
+
+    let err = WorkbookJson::from_value(doc(json!([{ "name": "A", "cells": [{ "cell": "1A" }] }])))
+        .expect_err("bad address");
+    assert!(matches!(err, AppError::InvalidCellAddress(_)));
+
+    let err = WorkbookJson::from_value(doc(json!([{ "name": "A", "merges": ["A1:B2", "B2:C3"] }])))
+        .expect_err("overlap");
+    assert!(err.to_string().contains("overlaps"));
+
+    let err = WorkbookJson::from_value(doc(json!([{ "name": "A", "colour": "red" }])))
+        .expect_err("unknown field");
+    assert!(err.to_string().contains("invalid workbook json"));
+}
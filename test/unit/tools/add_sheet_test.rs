@@ -103,3 +103,51 @@ fn add_sheet_uses_end_as_default_position_when_omitted() {
     let out = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("sheets");
     assert_eq!(out["sheets"], json!(["Base", "Nueva"]));
 }
+
+#[test]
+fn add_sheet_accepts_index_and_relative_positions() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("add_sheet_positions.ods");
+
+    create_ods::handle(json!({
+        "path": path.to_string_lossy(),
+        "overwrite": true,
+        "initial_sheet_name": "A"
+    }))
+    .expect("create");
+
+    add_sheet::handle(json!({ "path": path.to_string_lossy(), "sheet_name": "C" }))
+        .expect("add end");
+    add_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet_name": "B",
+        "position": 1
+    }))
+    .expect("add index");
+    add_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet_name": "Z",
+        "position": { "before": { "name": "A" } }
+    }))
+    .expect("add before");
+
+    let out = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("sheets");
+    assert_eq!(out["sheets"], json!(["Z", "A", "B", "C"]));
+}
+
+#[test]
+fn add_sheet_rejects_unknown_position_string() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("add_sheet_bad_position.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let err = add_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet_name": "Nueva",
+        "position": "middle"
+    }))
+    .expect_err("unknown position");
+    assert!(err.to_string().contains("unknown sheet position"));
+}
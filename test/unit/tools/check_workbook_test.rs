@@ -0,0 +1,138 @@
+use crate::common::ods_with_content;
+use mcp_ods::common::errors::AppError;
+use mcp_ods::tools::check_workbook;
+use serde_json::{json, Value};
+use tempfile::tempdir;
+
+const CONTENT: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Datos">
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>1.234,5</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>00123</text:p></table:table-cell>
+        <table:table-cell office:value-type="currency" office:value="12.5"><text:p>12,50 €</text:p></table:table-cell>
+        <table:table-cell office:value-type="float" office:value="3"><text:p>4</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.D1]*2" office:value-type="float" office:value="6"><text:p>6</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[$Falta.A1]" office:value-type="string" office:string-value="#REF!"><text:p>#REF!</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell office:value-type="percentage" office:value="0.125"><text:p>12,5 %</text:p></table:table-cell>
+        <table:table-cell office:value-type="float" office:value="1234.5"><text:p>1,234.50</text:p></table:table-cell>
+        <table:table-cell office:value-type="float" office:value="1500"><text:p>1.500</text:p></table:table-cell>
+        <table:table-cell office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.D2]*2" office:value-type="float" office:value="4"><text:p>4</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=1/0" office:value-type="string" office:string-value="#DIV/0!"><text:p>#DIV/0!</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell table:number-columns-repeated="3" office:value-type="string"><text:p>42</text:p></table:table-cell>
+        <table:table-cell office:value-type="float" office:value="5"><text:p>5</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.D3]+1" office:value-type="float" office:value="6"><text:p>6</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell/>
+        <table:table-cell/>
+        <table:table-cell/>
+        <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.D4]*2" office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell table:number-columns-spanned="2" table:number-rows-spanned="2"/>
+        <table:covered-table-cell/>
+      </table:table-row>
+      <table:table-row>
+        <table:covered-table-cell/>
+        <table:table-cell table:number-columns-spanned="2" table:number-rows-spanned="1"/>
+        <table:covered-table-cell/>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="datos">
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>7</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"##;
+
+fn summary(out: &Value) -> Vec<(String, String, String, String)> {
+    out["issues"]
+        .as_array()
+        .expect("issues")
+        .iter()
+        .map(|issue| {
+            (
+                issue["severity"].as_str().unwrap_or_default().to_string(),
+                issue["code"].as_str().unwrap_or_default().to_string(),
+                issue["sheet"].as_str().unwrap_or_default().to_string(),
+                issue["cell"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn row(severity: &str, code: &str, sheet: &str, cell: &str) -> (String, String, String, String) {
+    (
+        severity.to_string(),
+        code.to_string(),
+        sheet.to_string(),
+        cell.to_string(),
+    )
+}
+
+#[test]
+fn check_workbook_reports_every_kind_of_issue_in_sheet_order() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "check.ods", CONTENT);
+
+    let out = check_workbook::handle(json!({ "path": path })).expect("check");
+    assert_eq!(
+        summary(&out),
+        vec![
+            row("error", "duplicate_sheet_name", "datos", ""),
+            row("warning", "number_stored_as_text", "Datos", "A1"),
+            row("warning", "value_text_mismatch", "Datos", "D1"),
+            row("error", "missing_sheet_reference", "Datos", "F1"),
+            row("error", "cached_error", "Datos", "F1"),
+            row("warning", "cached_error", "Datos", "F2"),
+            row("warning", "number_stored_as_text", "Datos", "A3"),
+            row("warning", "number_stored_as_text", "Datos", "B3"),
+            row("warning", "number_stored_as_text", "Datos", "C3"),
+            row("warning", "inconsistent_formula", "Datos", "E3"),
+            row("error", "overlapping_merge", "Datos", "B6:C6"),
+            row("warning", "number_stored_as_text", "datos", "A1"),
+        ]
+    );
+    assert_eq!(out["errors"], 4);
+    assert_eq!(out["warnings"], 8);
+    assert_eq!(out["total"], 12);
+    assert_eq!(out["truncated"], false);
+    assert_eq!(out["issues"][10]["message"], "merged area overlaps A5:B6");
+}
+
+#[test]
+fn check_workbook_limits_issues_to_one_sheet_and_max_issues() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "check.ods", CONTENT);
+
+    let out = check_workbook::handle(json!({
+        "path": path,
+        "sheet": { "name": "datos" },
+        "max_issues": 1
+    }))
+    .expect("check");
+    assert_eq!(
+        summary(&out),
+        vec![row("error", "duplicate_sheet_name", "datos", "")]
+    );
+    assert_eq!(out["total"], 2);
+    assert_eq!(out["truncated"], true);
+}
+
+#[test]
+fn check_workbook_reports_missing_file() {
+    let dir = tempdir().expect("tempdir");
+    let missing = dir.path().join("missing.ods");
+    let err = check_workbook::handle(json!({ "path": missing.to_string_lossy() }))
+        .expect_err("missing file");
+    assert!(matches!(err, AppError::FileNotFound(_)));
+}
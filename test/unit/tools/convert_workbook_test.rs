@@ -0,0 +1,137 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::ods::sheet_model::CellValue;
+use mcp_ods::tools::{convert_workbook, create_ods};
+use mcp_ods::xlsx::xlsx_file::XlsxFile;
+use serde_json::json;
+use tempfile::tempdir;
+
+const CONTENT: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:automatic-styles>
+    <style:style style:name="ce1" style:family="table-cell">
+      <style:text-properties fo:font-weight="bold" fo:color="#c9211e"/>
+    </style:style>
+  </office:automatic-styles>
+  <office:body><office:spreadsheet>
+    <table:table table:name="Resumen">
+      <table:table-row>
+        <table:table-cell table:style-name="ce1" office:value-type="string"><text:p>Total</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=SUM([$Datos.A1:.A2])" office:value-type="float" office:value="5"><text:p>5</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Datos">
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+        <table:table-cell table:number-columns-spanned="2" office:value-type="date" office:date-value="2024-02-29"><text:p>29/02/24</text:p></table:table-cell>
+        <table:covered-table-cell/>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="3"><text:p>3</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"##;
+
+#[test]
+fn convert_workbook_round_trips_ods_through_xlsx() {
+    let dir = tempdir().expect("tempdir");
+    let ods = dir.path().join("source.ods");
+    let xlsx = dir.path().join("partner.xlsx");
+    let back = dir.path().join("back.ods");
+    create_ods::handle(json!({ "path": ods.to_string_lossy() })).expect("create");
+    OdsFile::write_content_xml(&ods, CONTENT).expect("write content");
+
+    let out = convert_workbook::handle(json!({
+        "source": ods.to_string_lossy(),
+        "target": xlsx.to_string_lossy()
+    }))
+    .expect("to xlsx");
+    assert_eq!(out["source_format"], "ods");
+    assert_eq!(out["target_format"], "xlsx");
+    assert_eq!(out["sheets"], json!(["Resumen", "Datos"]));
+    assert_eq!(out["cells"], 5);
+    assert_eq!(out["formulas"], 1);
+    assert_eq!(out["merges"], 1);
+
+    let excel = XlsxFile::read_workbook(&xlsx).expect("read xlsx");
+    assert_eq!(
+        excel.sheets[0]
+            .get_cell(0, 1)
+            .expect("b1")
+            .formula
+            .as_deref(),
+        Some("of:=SUM([$Datos.A1:.A2])")
+    );
+
+    convert_workbook::handle(json!({
+        "source": xlsx.to_string_lossy(),
+        "target": back.to_string_lossy()
+    }))
+    .expect("to ods");
+    let original = OdsFile::read_workbook(&ods).expect("read source");
+    let restored = OdsFile::read_workbook(&back).expect("read back");
+    assert_eq!(restored.sheets.len(), 2);
+    for (a, b) in original.sheets.iter().zip(&restored.sheets) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.merges, b.merges);
+        for (r, row) in a.rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if cell.value != CellValue::Empty {
+                    assert_eq!(b.get_cell(r, c), Some(cell), "{} r{r} c{c}", a.name);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn convert_workbook_validates_paths() {
+    let dir = tempdir().expect("tempdir");
+    let ods = dir.path().join("a.ods");
+    create_ods::handle(json!({ "path": ods.to_string_lossy() })).expect("create");
+
+    let err = convert_workbook::handle(json!({
+        "source": ods.to_string_lossy(),
+        "target": dir.path().join("a.csv").to_string_lossy()
+    }))
+    .expect_err("bad extension");
+    assert!(matches!(err, AppError::InvalidPath(_)));
+
+    let err = convert_workbook::handle(json!({
+        "source": dir.path().join("missing.xlsx").to_string_lossy(),
+        "target": dir.path().join("b.ods").to_string_lossy()
+    }))
+    .expect_err("missing source");
+    assert!(matches!(err, AppError::FileNotFound(_)));
+
+    let other = dir.path().join("b.ods");
+    create_ods::handle(json!({ "path": other.to_string_lossy() })).expect("create");
+    let err = convert_workbook::handle(json!({
+        "source": ods.to_string_lossy(),
+        "target": other.to_string_lossy()
+    }))
+    .expect_err("exists");
+    assert!(matches!(err, AppError::AlreadyExists(_)));
+}
+
+#[test]
+fn convert_workbook_rejects_sources_without_sheets() {
+    let dir = tempdir().expect("tempdir");
+    let ods = dir.path().join("empty.ods");
+    create_ods::handle(json!({ "path": ods.to_string_lossy() })).expect("create");
+    let content = OdsFile::read_content_xml(&ods).expect("read");
+    let start = content.find("<table:table ").expect("table start");
+    let end = content.rfind("</table:table>").expect("table end") + "</table:table>".len();
+    OdsFile::write_content_xml(&ods, &format!("{}{}", &content[..start], &content[end..]))
+        .expect("write");
+
+    let target = dir.path().join("out.ods");
+    let err = convert_workbook::handle(json!({
+        "source": ods.to_string_lossy(),
+        "target": target.to_string_lossy()
+    }))
+    .expect_err("no sheets");
+    assert!(matches!(err, AppError::InvalidOdsFormat(_)));
+    assert!(!target.exists());
+}
@@ -0,0 +1,124 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::tools::{
+    create_ods, get_document_properties, set_cell_value, set_document_properties,
+};
+use serde_json::json;
+use tempfile::tempdir;
+
+#[test]
+fn create_ods_initializes_creation_date_and_generator() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("props_new.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let out =
+        get_document_properties::handle(json!({ "path": path.to_string_lossy() })).expect("props");
+    assert!(out["creation_date"].is_string());
+    assert_eq!(out["creation_date"], out["modification_date"]);
+    assert_eq!(out["editing_cycles"], 1);
+    assert!(out["generator"]
+        .as_str()
+        .expect("generator")
+        .starts_with("libreoffice-calc-mcp/"));
+}
+
+#[test]
+fn set_document_properties_updates_fields_and_typed_user_properties() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("props_set.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let out = set_document_properties::handle(json!({
+        "path": path.to_string_lossy(),
+        "title": "Presupuesto",
+        "keywords": ["2026", "finanzas"],
+        "user_defined": [
+            { "name": "Version", "value": 2 },
+            { "name": "Aprobado", "value": true },
+            { "name": "Revision", "value": "2026-02-01", "type": "date" }
+        ]
+    }))
+    .expect("set");
+
+    assert_eq!(out["title"], "Presupuesto");
+    assert_eq!(out["keywords"], json!(["2026", "finanzas"]));
+    assert_eq!(
+        out["user_defined"],
+        json!([
+            { "name": "Version", "type": "float", "value": 2.0 },
+            { "name": "Aprobado", "type": "boolean", "value": true },
+            { "name": "Revision", "type": "date", "value": "2026-02-01" }
+        ])
+    );
+}
+
+#[test]
+fn set_document_properties_rejects_unknown_user_defined_type() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("props_bad_type.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let err = set_document_properties::handle(json!({
+        "path": path.to_string_lossy(),
+        "user_defined": [{ "name": "X", "value": "1", "type": "money" }]
+    }))
+    .expect_err("bad type");
+    assert!(err.to_string().contains("unsupported user_defined.type"));
+}
+
+#[test]
+fn set_document_properties_validates_creation_date() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("props_creation.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let err = set_document_properties::handle(json!({
+        "path": path.to_string_lossy(),
+        "creation_date": "ayer"
+    }))
+    .expect_err("bad date");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+    assert!(err.to_string().contains("creation_date"));
+
+    let out = set_document_properties::handle(json!({
+        "path": path.to_string_lossy(),
+        "creation_date": "2025-03-14T09:30:00"
+    }))
+    .expect("set");
+    assert_eq!(out["creation_date"], "2025-03-14T09:30:00");
+}
+
+#[test]
+fn content_edits_bump_editing_cycles() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("props_cycles.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    set_cell_value::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cell": "A1",
+        "value": { "type": "string", "data": "x" }
+    }))
+    .expect("set");
+
+    let out =
+        get_document_properties::handle(json!({ "path": path.to_string_lossy() })).expect("props");
+    assert_eq!(out["editing_cycles"], 2);
+}
+
+#[test]
+fn get_document_properties_returns_not_found_for_missing_file() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("missing_props.ods");
+
+    let err = get_document_properties::handle(json!({ "path": path.to_string_lossy() }))
+        .expect_err("missing");
+    assert!(err.to_string().contains("file not found"));
+}
@@ -83,3 +83,34 @@ fn duplicate_sheet_returns_file_not_found_for_missing_path() {
 
     assert!(err.to_string().contains("file not found"));
 }
+
+#[test]
+fn duplicate_sheet_honours_explicit_position() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("duplicate_position.ods");
+
+    create_ods::handle(json!({
+        "path": path.to_string_lossy(),
+        "overwrite": true,
+        "initial_sheet_name": "Datos"
+    }))
+    .expect("create");
+
+    duplicate_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "source_sheet": { "name": "Datos" },
+        "new_sheet_name": "Plantilla",
+        "position": "start"
+    }))
+    .expect("duplicate at start");
+    duplicate_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "source_sheet": { "name": "Datos" },
+        "new_sheet_name": "Copia",
+        "position": { "before": { "name": "Datos" } }
+    }))
+    .expect("duplicate before");
+
+    let out = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("get sheets");
+    assert_eq!(out["sheets"], json!(["Plantilla", "Copia", "Datos"]));
+}
@@ -0,0 +1,96 @@
+use crate::common::ods_with_content;
+use mcp_ods::common::errors::AppError;
+use mcp_ods::tools::evaluate_formula;
+use serde_json::json;
+use tempfile::tempdir;
+
+const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Resumen">
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Ventas">
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>Importe</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row table:number-rows-repeated="3">
+        <table:table-cell office:value-type="float" office:value="10"><text:p>10</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell table:formula="of:=SUM([.A2:.A4])" office:value-type="float" office:value="99"><text:p>99</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+#[test]
+fn evaluate_formula_accepts_excel_and_odf_syntax() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "eval.ods", CONTENT);
+
+    let excel =
+        evaluate_formula::handle(json!({ "path": path, "formula": "=SUM(Ventas!A2:A500)*A1" }))
+            .expect("excel");
+    assert_eq!(excel["sheet"], "Resumen");
+    assert_eq!(excel["formula"], "of:=SUM([$Ventas.A2:.A500])*[.A1]");
+    // (10 + 10 + 10 + cached 99 in A5) * 2
+    assert_eq!(excel["value"], json!({ "type": "number", "data": 258.0 }));
+    assert_eq!(excel["text"], "258");
+
+    let odf = evaluate_formula::handle(json!({
+        "path": path,
+        "formula": "IF([.A5]>20;\"alto\";\"bajo\")",
+        "sheet": { "name": "Ventas" }
+    }))
+    .expect("odf");
+    // A5 contributes its cached (stale) result.
+    assert_eq!(odf["formula"], "of:=IF([.A5]>20;\"alto\";\"bajo\")");
+    assert_eq!(odf["value"], json!({ "type": "string", "data": "alto" }));
+    assert_eq!(odf["unsupported"], json!([]));
+}
+
+#[test]
+fn evaluate_formula_returns_errors_and_unsupported_names_without_writing() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "eval.ods", CONTENT);
+    let before = std::fs::read(&path).expect("read");
+
+    let div = evaluate_formula::handle(
+        json!({ "path": path, "formula": "1/0", "formula_syntax": "excel" }),
+    )
+    .expect("div");
+    assert_eq!(div["value"], json!({ "type": "error", "data": "#DIV/0!" }));
+    // The former parameter name still forces the dialect.
+    assert!(evaluate_formula::handle(
+        json!({ "path": path, "formula": "SUM(A1:A2)", "syntax": "odf" }),
+    )
+    .is_err());
+
+    let npv =
+        evaluate_formula::handle(json!({ "path": path, "formula": "NPV(0.1,A1)" })).expect("npv");
+    assert_eq!(npv["value"], json!({ "type": "error", "data": "#NAME?" }));
+    assert_eq!(npv["unsupported"], json!(["NPV"]));
+
+    assert_eq!(std::fs::read(&path).expect("read"), before);
+}
+
+#[test]
+fn evaluate_formula_rejects_invalid_formula_and_unknown_sheet() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "eval.ods", CONTENT);
+
+    let err =
+        evaluate_formula::handle(json!({ "path": path, "formula": "SUM(" })).expect_err("invalid");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+
+    let err = evaluate_formula::handle(json!({
+        "path": path,
+        "formula": "1",
+        "sheet": { "name": "Nope" }
+    }))
+    .expect_err("sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+}
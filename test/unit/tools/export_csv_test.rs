@@ -0,0 +1,116 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::tools::{create_ods, export_csv};
+use serde_json::json;
+use tempfile::tempdir;
+
+const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Hoja1">
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>name</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>when</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>share</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>Ana, "M"</text:p></table:table-cell>
+        <table:table-cell office:value-type="date" office:date-value="2024-02-29"><text:p>29/02/24</text:p></table:table-cell>
+        <table:table-cell office:value-type="percentage" office:value="0.25"><text:p>25%</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row table:number-rows-repeated="1"><table:table-cell table:number-columns-repeated="4"/></table:table-row>
+      <table:table-row>
+        <table:table-cell table:number-columns-repeated="3"/>
+        <table:table-cell office:value-type="boolean" office:boolean-value="true"><text:p>TRUE</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+#[test]
+fn export_csv_writes_raw_values_from_a1_to_last_used_cell() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("export.ods");
+    let csv_path = dir.path().join("out.csv");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    OdsFile::write_content_xml(&path, CONTENT).expect("write content");
+
+    let out = export_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "csv_path": csv_path.to_string_lossy()
+    }))
+    .expect("export");
+    assert_eq!(out["range"], "A1:D4");
+    assert_eq!(out["rows_written"], 4);
+    assert_eq!(out["cols_written"], 4);
+
+    let text = std::fs::read_to_string(&csv_path).expect("read csv");
+    assert_eq!(
+        text,
+        "name,when,share,\n\"Ana, \"\"M\"\"\",2024-02-29,0.25,\n,,,\n,,,true\n"
+    );
+    assert_eq!(out["bytes_written"], text.len());
+
+    let err = export_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "csv_path": csv_path.to_string_lossy()
+    }))
+    .expect_err("existing file");
+    assert!(matches!(err, AppError::AlreadyExists(_)));
+}
+
+#[test]
+fn export_csv_honours_range_displayed_text_and_format_options() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("export_opts.ods");
+    let csv_path = dir.path().join("out.tsv");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    OdsFile::write_content_xml(&path, CONTENT).expect("write content");
+
+    export_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "Hoja1" },
+        "csv_path": csv_path.to_string_lossy(),
+        "range": "C2:B1",
+        "delimiter": "tab",
+        "quoting": "non_numeric",
+        "line_ending": "crlf",
+        "values": "displayed"
+    }))
+    .expect("export range");
+    let text = std::fs::read_to_string(&csv_path).expect("read tsv");
+    assert_eq!(text, "\"when\"\t\"share\"\r\n\"29/02/24\"\t25%\r\n");
+
+    let err = export_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "csv_path": csv_path.to_string_lossy(),
+        "overwrite": true,
+        "quoting": "sometimes"
+    }))
+    .expect_err("bad quoting");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+}
+
+#[test]
+fn export_csv_writes_empty_file_for_empty_sheet() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("export_empty.ods");
+    let csv_path = dir.path().join("empty.csv");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let out = export_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "csv_path": csv_path.to_string_lossy()
+    }))
+    .expect("export empty");
+    assert_eq!(out["range"], json!(null));
+    assert_eq!(out["rows_written"], 0);
+    assert_eq!(std::fs::read_to_string(&csv_path).expect("read"), "");
+}
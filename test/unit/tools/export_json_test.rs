@@ -0,0 +1,46 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::tools::{create_ods, export_json, set_cell_value};
+use serde_json::json;
+use tempfile::tempdir;
+
+#[test]
+fn export_json_returns_inline_document_or_writes_file() {
+    let dir = tempdir().expect("tempdir");
+    let ods = dir.path().join("book.ods");
+    let json_path = dir.path().join("snap").join("book.json");
+    create_ods::handle(json!({ "path": ods.to_string_lossy(), "initial_sheet_name": "Datos" }))
+        .expect("create");
+    set_cell_value::handle(json!({
+        "path": ods.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cell": "B1",
+        "value": { "type": "boolean", "data": true }
+    }))
+    .expect("set");
+
+    let inline = export_json::handle(json!({ "path": ods.to_string_lossy() })).expect("inline");
+    assert_eq!(inline["sheets"], json!(["Datos"]));
+    assert_eq!(
+        inline["workbook"]["sheets"][0]["cells"],
+        json!([{ "cell": "B1", "value": { "type": "boolean", "data": true } }])
+    );
+    assert!(inline.get("json_path").is_none());
+
+    let out = export_json::handle(json!({
+        "path": ods.to_string_lossy(),
+        "json_path": json_path.to_string_lossy()
+    }))
+    .expect("to file");
+    assert!(out.get("workbook").is_none());
+    let text = std::fs::read_to_string(&json_path).expect("json file");
+    assert_eq!(out["bytes_written"], text.len());
+    let written: serde_json::Value = serde_json::from_str(&text).expect("valid json");
+    assert_eq!(written, inline["workbook"]);
+
+    let err = export_json::handle(json!({
+        "path": ods.to_string_lossy(),
+        "json_path": json_path.to_string_lossy()
+    }))
+    .expect_err("exists");
+    assert!(matches!(err, AppError::AlreadyExists(_)));
+}
@@ -0,0 +1,130 @@
+use crate::common::ods_with_content;
+use mcp_ods::common::errors::AppError;
+use mcp_ods::tools::find_cells;
+use serde_json::json;
+use tempfile::tempdir;
+
+const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Facturas">
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>Factura</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>INV-2024-001</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>Total</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=SUM([.B5:.B9])" office:value-type="currency" office:value="1234.5"><text:p>1.234,50 €</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Resumen">
+      <table:table-row>
+        <table:table-cell office:value-type="string"><office:annotation><text:p>revisar total</text:p></office:annotation><text:p>subtotal</text:p></table:table-cell>
+        <table:table-cell office:value-type="float" office:value="1234.5"><text:p>1234.5</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+#[test]
+fn find_cells_text_search_is_case_insensitive_substring_across_sheets() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "search.ods", CONTENT);
+
+    let out = find_cells::handle(json!({ "path": path, "query": "TOTAL" })).expect("find");
+    assert_eq!(out["total"], 2);
+    assert_eq!(out["matches"][0]["sheet"], "Facturas");
+    assert_eq!(out["matches"][0]["cell"], "A2");
+    assert_eq!(out["matches"][1]["sheet"], "Resumen");
+    assert_eq!(out["matches"][1]["cell"], "A1");
+    assert_eq!(out["truncated"], false);
+
+    let whole = find_cells::handle(json!({
+        "path": path,
+        "query": "total",
+        "whole_cell": true,
+        "sheet": { "name": "Facturas" }
+    }))
+    .expect("whole");
+    assert_eq!(whole["total"], 1);
+    assert_eq!(
+        whole["matches"][0]["value"],
+        json!({ "type": "string", "data": "Total" })
+    );
+
+    let strict =
+        find_cells::handle(json!({ "path": path, "query": "TOTAL", "case_sensitive": true }))
+            .expect("case");
+    assert_eq!(strict["total"], 0);
+}
+
+#[test]
+fn find_cells_numbers_regex_formulas_and_comments() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "search.ods", CONTENT);
+
+    let numbers =
+        find_cells::handle(json!({ "path": path, "query": 1234.5, "match_type": "number" }))
+            .expect("number");
+    assert_eq!(numbers["total"], 2);
+    assert_eq!(numbers["matches"][0]["cell"], "B2");
+    assert_eq!(numbers["matches"][0]["formula"], "of:=SUM([.B5:.B9])");
+
+    let regex = find_cells::handle(json!({
+        "path": path,
+        "query": "inv-\\d{4}-\\d+",
+        "match_type": "regex",
+        "whole_cell": true
+    }))
+    .expect("regex");
+    assert_eq!(regex["matches"][0]["cell"], "B1");
+
+    let formulas = find_cells::handle(json!({
+        "path": path,
+        "query": "=SUM(",
+        "look_in": ["formulas"]
+    }))
+    .expect("formulas");
+    assert_eq!(formulas["total"], 1);
+    assert_eq!(formulas["matches"][0]["matched_in"], json!(["formulas"]));
+
+    let both = find_cells::handle(json!({
+        "path": path,
+        "query": "total",
+        "look_in": ["values", "comments"],
+        "max_results": 1
+    }))
+    .expect("comments");
+    assert_eq!(both["total"], 2);
+    assert_eq!(both["truncated"], true);
+    assert_eq!(both["matches"].as_array().expect("array").len(), 1);
+
+    let comment = find_cells::handle(json!({
+        "path": path,
+        "query": "revisar",
+        "look_in": ["comments"]
+    }))
+    .expect("comment");
+    assert_eq!(comment["matches"][0]["comment"], "revisar total");
+    assert_eq!(comment["matches"][0]["matched_in"], json!(["comments"]));
+}
+
+#[test]
+fn find_cells_rejects_bad_queries() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "search.ods", CONTENT);
+
+    let err = find_cells::handle(json!({ "path": path, "query": "(", "match_type": "regex" }))
+        .expect_err("regex");
+    assert!(err.to_string().contains("invalid regex"));
+    let err = find_cells::handle(json!({ "path": path, "query": "abc", "match_type": "number" }))
+        .expect_err("number");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+    let err = find_cells::handle(json!({ "path": path, "query": "x", "look_in": [] }))
+        .expect_err("look_in");
+    assert!(err.to_string().contains("look_in"));
+    let err =
+        find_cells::handle(json!({ "path": path, "query": "x", "sheet": { "name": "Nope" } }))
+            .expect_err("sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+}
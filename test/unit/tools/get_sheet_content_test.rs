@@ -1,3 +1,4 @@
+use mcp_ods::ods::ods_file::OdsFile;
 use mcp_ods::tools::{create_ods, get_sheet_content, set_cell_value, set_range_values};
 use serde_json::json;
 use tempfile::tempdir;
@@ -44,7 +45,7 @@ fn get_sheet_content_rejects_unsupported_mode() {
         "mode": "table"
     }))
     .expect_err("invalid mode");
-    assert!(err.to_string().contains("mode=matrix"));
+    assert!(err.to_string().contains("unsupported mode: table"));
 }
 
 #[test]
@@ -149,3 +150,423 @@ fn get_sheet_content_returns_not_found_for_missing_file() {
     .expect_err("missing file");
     assert!(err.to_string().contains("file not found"));
 }
+
+#[test]
+fn get_sheet_content_records_mode_types_values_and_dedupes_headers() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("content_records.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    set_range_values::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "start_cell": "A2",
+        "data": [["name", "", "name"], ["ana", "x", "dup"], ["", "", ""], ["luis", "", ""]]
+    }))
+    .expect("set range");
+    set_cell_value::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cell": "B5",
+        "value": { "type": "number", "data": 3 }
+    }))
+    .expect("set number");
+    set_cell_value::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cell": "D3",
+        "value": { "type": "boolean", "data": true }
+    }))
+    .expect("set bool");
+
+    let out = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records"
+    }))
+    .expect("records");
+
+    assert_eq!(out["header_row"], 2);
+    assert_eq!(out["headers"], json!(["name", "B", "name_2", "D"]));
+    assert_eq!(out["rows"], 2);
+    assert_eq!(
+        out["records"][0],
+        json!({ "name": "ana", "B": "x", "name_2": "dup", "D": true })
+    );
+    assert_eq!(
+        out["records"][1],
+        json!({ "name": "luis", "B": 3, "name_2": null, "D": null })
+    );
+}
+
+#[test]
+fn get_sheet_content_records_mode_honours_header_row_and_max_rows() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("content_records_header.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    set_range_values::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "start_cell": "A1",
+        "data": [["Report"], ["id", "qty"], ["1", "10"], ["2", "20"]]
+    }))
+    .expect("set range");
+
+    let out = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records",
+        "header_row": 2,
+        "max_rows": 1
+    }))
+    .expect("records");
+    assert_eq!(out["headers"], json!(["id", "qty"]));
+    assert_eq!(out["records"], json!([{ "id": "1", "qty": "10" }]));
+
+    let err = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records",
+        "header_row": 0
+    }))
+    .expect_err("zero header row");
+    assert!(err.to_string().contains("header_row"));
+}
+
+#[test]
+fn get_sheet_content_records_mode_handles_empty_sheet() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("content_records_empty.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    let out = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records"
+    }))
+    .expect("records");
+    assert_eq!(out["header_row"], json!(null));
+    assert_eq!(out["records"], json!([]));
+}
+
+#[test]
+fn get_sheet_content_cells_mode_lists_only_non_empty_cells_with_pagination() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("content_cells.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    set_cell_value::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cell": "B7",
+        "value": { "type": "number", "data": 42 }
+    }))
+    .expect("set number");
+    set_cell_value::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cell": "AA2",
+        "value": { "type": "string", "data": "total" }
+    }))
+    .expect("set string");
+    set_cell_value::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cell": "C9",
+        "value": { "type": "boolean", "data": false }
+    }))
+    .expect("set bool");
+
+    let first = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "cells",
+        "max_cells": 2
+    }))
+    .expect("cells");
+    assert_eq!(first["total_cells"], 3);
+    assert_eq!(
+        first["cells"],
+        json!([
+            { "cell": "AA2", "type": "string", "value": "total" },
+            { "cell": "B7", "type": "number", "value": 42 }
+        ])
+    );
+    assert_eq!(first["next_cell_offset"], 2);
+    assert!(first["next_cursor"].is_string());
+
+    let second = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "cells",
+        "max_cells": 2,
+        "cell_offset": 2
+    }))
+    .expect("cells page 2");
+    assert_eq!(
+        second["cells"],
+        json!([{ "cell": "C9", "type": "boolean", "value": false }])
+    );
+    assert_eq!(second["next_cell_offset"], json!(null));
+
+    let via_cursor = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "cells",
+        "max_cells": 2,
+        "cursor": first["next_cursor"]
+    }))
+    .expect("cells via cursor");
+    assert_eq!(via_cursor["cells"], second["cells"]);
+}
+
+#[test]
+fn get_sheet_content_pages_rows_with_offsets_and_cursor() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("content_pages.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    set_range_values::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "start_cell": "A1",
+        "data": [["r1", "x1"], ["r2", "x2"], ["r3", "x3"], ["r4", "x4"], ["r5", "x5"]]
+    }))
+    .expect("set range");
+
+    let first = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "max_rows": 2
+    }))
+    .expect("page 1");
+    assert_eq!(first["data"], json!([["r1", "x1"], ["r2", "x2"]]));
+    assert_eq!(first["total_rows"], 5);
+    assert_eq!(first["total_cols"], 2);
+    let cursor = first["next_cursor"].as_str().expect("cursor").to_string();
+
+    let second = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "max_rows": 2,
+        "cursor": cursor
+    }))
+    .expect("page 2");
+    assert_eq!(second["offset_row"], 2);
+    assert_eq!(second["data"], json!([["r3", "x3"], ["r4", "x4"]]));
+
+    let third = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "max_rows": 2,
+        "cursor": second["next_cursor"]
+    }))
+    .expect("page 3");
+    assert_eq!(third["data"], json!([["r5", "x5"]]));
+    assert_eq!(third["next_cursor"], json!(null));
+
+    let shifted = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "offset_row": 3,
+        "offset_col": 1
+    }))
+    .expect("offsets");
+    assert_eq!(shifted["data"], json!([["x4"], ["x5"]]));
+}
+
+#[test]
+fn get_sheet_content_rejects_stale_or_mismatched_cursor() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("content_stale_cursor.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    set_range_values::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "start_cell": "A1",
+        "data": [["h"], ["1"], ["2"], ["3"]]
+    }))
+    .expect("set range");
+
+    let page = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records",
+        "max_rows": 1
+    }))
+    .expect("records");
+    assert_eq!(page["records"], json!([{ "h": "1" }]));
+    let cursor = page["next_cursor"].clone();
+
+    let next = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records",
+        "max_rows": 1,
+        "cursor": cursor
+    }))
+    .expect("records page 2");
+    assert_eq!(next["records"], json!([{ "h": "2" }]));
+
+    let err = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "matrix",
+        "cursor": cursor
+    }))
+    .expect_err("mode mismatch");
+    assert!(err.to_string().contains("mode=records"));
+
+    let err = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cursor": "not-a-cursor"
+    }))
+    .expect_err("garbage");
+    assert!(err.to_string().contains("invalid cursor"));
+
+    set_cell_value::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "cell": "A5",
+        "value": { "type": "string", "data": "4" }
+    }))
+    .expect("modify");
+    let err = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records",
+        "max_rows": 1,
+        "cursor": cursor
+    }))
+    .expect_err("stale");
+    assert!(err.to_string().contains("stale"));
+}
+
+#[test]
+fn get_sheet_content_renders_html_with_merged_cells_in_range() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("content_html.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Hoja1">
+      <table:table-row>
+        <table:table-cell table:number-columns-spanned="2" office:value-type="string"><text:p>Title</text:p></table:table-cell>
+        <table:covered-table-cell/>
+        <table:table-cell office:value-type="string"><text:p>C1</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>a&lt;b</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>C2</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+    OdsFile::write_content_xml(&path, content).expect("write content");
+
+    let out = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "html",
+        "range": "A1:C2"
+    }))
+    .expect("html");
+    assert_eq!(out["format"], "html");
+    assert_eq!(out["merged_ranges"], json!(["A1:B1"]));
+    assert_eq!(out["data"][0], json!(["Title", "", "C1"]));
+    assert_eq!(
+        out["rendered"],
+        "<table>\n  <tr><td colspan=\"2\">Title</td><td>C1</td></tr>\n  <tr><td>1</td><td>a&lt;b</td><td>C2</td></tr>\n</table>"
+    );
+
+    let csv = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "csv",
+        "range": "B1:C2"
+    }))
+    .expect("csv");
+    assert_eq!(csv["rendered"], ",C1\na<b,C2");
+    assert_eq!(csv["merged_ranges"], json!([]));
+}
+
+#[test]
+fn get_sheet_content_pages_full_size_sheets_without_expanding_them() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("content_full_size.ods");
+
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    // Calc pads saved sheets to the full grid with repeated empty rows and cells.
+    let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Hoja1">
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>name</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>qty</text:p></table:table-cell>
+        <table:table-cell table:number-columns-repeated="16382"/>
+      </table:table-row>
+      <table:table-row table:number-rows-repeated="3">
+        <table:table-cell office:value-type="string"><text:p>pera</text:p></table:table-cell>
+        <table:table-cell office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+        <table:table-cell table:number-columns-repeated="16382"/>
+      </table:table-row>
+      <table:table-row table:number-rows-repeated="1048572">
+        <table:table-cell table:number-columns-repeated="16384"/>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+    OdsFile::write_content_xml(&path, content).expect("write content");
+
+    let matrix = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "offset_row": 2,
+        "max_rows": 2
+    }))
+    .expect("matrix");
+    assert_eq!(
+        (matrix["total_rows"].clone(), matrix["total_cols"].clone()),
+        (json!(4), json!(2))
+    );
+    assert_eq!(matrix["data"], json!([["pera", "2"], ["pera", "2"]]));
+    assert_eq!(matrix["next_cursor"], json!(null));
+
+    let records = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records",
+        "max_rows": 2
+    }))
+    .expect("records");
+    assert_eq!(records["headers"], json!(["name", "qty"]));
+    assert_eq!(records["rows"], 2);
+    assert_eq!(records["records"][1], json!({ "name": "pera", "qty": 2 }));
+    let rest = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "records",
+        "cursor": records["next_cursor"]
+    }))
+    .expect("next page");
+    assert_eq!(
+        (rest["offset_row"].clone(), rest["rows"].clone()),
+        (json!(2), json!(1))
+    );
+    assert_eq!(rest["next_cursor"], json!(null));
+}
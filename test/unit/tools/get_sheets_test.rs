@@ -1,4 +1,4 @@
-use mcp_ods::tools::{create_ods, get_sheets};
+use mcp_ods::tools::{create_ods, get_sheets, set_range_values};
 use serde_json::json;
 use tempfile::tempdir;
 
@@ -32,3 +32,38 @@ fn get_sheets_rejects_non_ods_path_extension() {
     let err = get_sheets::handle(json!({ "path": "demo.xlsx" })).expect_err("invalid extension");
     assert!(err.to_string().contains("expected .ods extension"));
 }
+
+#[test]
+fn get_sheets_reports_used_range_and_cell_count() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("get_sheets_usage.ods");
+
+    create_ods::handle(json!({
+        "path": path.to_string_lossy(),
+        "overwrite": true,
+        "initial_sheet_name": "Base"
+    }))
+    .expect("create");
+    let empty = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("get_sheets");
+    assert_eq!(empty["details"][0]["used_range"], json!(null));
+    assert_eq!(empty["details"][0]["non_empty_cells"], 0);
+
+    set_range_values::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "start_cell": "B2",
+        "data": [["a","b","c"],["1","2","3"]]
+    }))
+    .expect("set range");
+
+    let out = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("get_sheets");
+    let details = &out["details"][0];
+    assert_eq!(details["name"], "Base");
+    assert_eq!(details["index"], 0);
+    assert_eq!(details["visible"], true);
+    assert_eq!(details["used_range"], "B2:D3");
+    assert_eq!(details["non_empty_cells"], 6);
+    assert_eq!(details["has_formulas"], false);
+    assert_eq!(details["has_merges"], false);
+    assert_eq!(details["has_comments"], false);
+}
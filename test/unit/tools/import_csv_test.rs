@@ -0,0 +1,145 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::tools::{create_ods, get_sheet_content, import_csv, set_range_values};
+use serde_json::json;
+use tempfile::tempdir;
+
+#[test]
+fn import_csv_infers_column_types_and_keeps_identifiers_as_text() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("import.ods");
+    let csv_path = dir.path().join("people.csv");
+    std::fs::write(
+        &csv_path,
+        "id;name;score;active\n007;Ana;9,5;true\n012;\"Luis; Jr\";;FALSE\n",
+    )
+    .expect("write csv");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let out = import_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "csv_path": csv_path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "start_cell": "B2",
+        "decimal_separator": ","
+    }))
+    .expect("import");
+
+    assert_eq!(out["delimiter"], ";");
+    assert_eq!(out["range"], "B2:E4");
+    assert_eq!(out["rows_written"], 3);
+    assert_eq!(
+        out["column_types"],
+        json!(["string", "string", "number", "boolean"])
+    );
+
+    let cells = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "cells"
+    }))
+    .expect("cells");
+    let by_cell = |addr: &str| {
+        cells["cells"]
+            .as_array()
+            .expect("cells array")
+            .iter()
+            .find(|c| c["cell"] == addr)
+            .cloned()
+            .unwrap_or_else(|| panic!("missing {addr}"))
+    };
+    assert_eq!(by_cell("B3")["value"], "007");
+    assert_eq!(by_cell("D3")["type"], "number");
+    assert_eq!(by_cell("D3")["value"], 9.5);
+    assert_eq!(by_cell("E4")["value"], false);
+    assert_eq!(by_cell("C4")["value"], "Luis; Jr");
+    assert_eq!(cells["total_cells"], 11);
+}
+
+#[test]
+fn import_csv_creates_sheet_and_clears_leftovers() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("import_clear.ods");
+    let csv_path = dir.path().join("data.tsv");
+    std::fs::write(&csv_path, "a\tb\n1\t2\n").expect("write tsv");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    set_range_values::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "start_cell": "A1",
+        "data": [["x","x","x"],["x","x","x"],["x","x","x"]]
+    }))
+    .expect("seed");
+
+    let out = import_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "csv_path": csv_path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "clear_target": true
+    }))
+    .expect("import with clear");
+    assert_eq!(out["delimiter"], "\t");
+    assert_eq!(out["cleared_range"], "A1:C3");
+    let content = get_sheet_content::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "mode": "matrix",
+        "include_empty_trailing": false
+    }))
+    .expect("content");
+    assert_eq!(content["data"], json!([["a", "b"], ["1", "2"]]));
+
+    let created = import_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "csv_path": csv_path.to_string_lossy(),
+        "sheet": { "name": "Imported" },
+        "create_sheet": true,
+        "has_header": false
+    }))
+    .expect("import into new sheet");
+    assert_eq!(created["sheet"], "Imported");
+    assert_eq!(created["created_sheet"], true);
+    assert_eq!(created["column_types"], json!(["string", "string"]));
+
+    let err = import_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "csv_path": csv_path.to_string_lossy(),
+        "sheet": { "name": "Missing" }
+    }))
+    .expect_err("missing sheet without create_sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+}
+
+#[test]
+fn import_csv_rejects_bad_options() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("import_bad.ods");
+    let csv_path = dir.path().join("data.csv");
+    std::fs::write(&csv_path, "a,b\n").expect("write csv");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    for (key, value, needle) in [
+        ("delimiter", "::", "delimiter must be a single character"),
+        ("decimal_separator", ";", "decimal_separator"),
+        ("encoding", "utf-16", "unsupported encoding"),
+    ] {
+        let mut params = json!({
+            "path": path.to_string_lossy(),
+            "csv_path": csv_path.to_string_lossy(),
+            "sheet": { "index": 0 }
+        });
+        params[key] = json!(value);
+        let err = import_csv::handle(params).expect_err(key);
+        assert!(err.to_string().contains(needle), "{key}: {err}");
+    }
+
+    let err = import_csv::handle(json!({
+        "path": path.to_string_lossy(),
+        "csv_path": dir.path().join("missing.csv").to_string_lossy(),
+        "sheet": { "index": 0 }
+    }))
+    .expect_err("missing csv");
+    assert!(matches!(err, AppError::FileNotFound(_)));
+}
@@ -0,0 +1,118 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::ods::sheet_model::CellValue;
+use mcp_ods::tools::import_json;
+use serde_json::json;
+use tempfile::tempdir;
+
+#[test]
+fn import_json_builds_package_from_inline_workbook() {
+    let dir = tempdir().expect("tempdir");
+    let ods = dir.path().join("fixture.ods");
+    let workbook = json!({
+        "schema": "mcp-ods/workbook",
+        "version": 1,
+        "sheets": [
+            {
+                "name": "Resumen",
+                "cells": [
+                    { "cell": "A1", "value": { "type": "date", "data": "2024-02-29" } },
+                    { "cell": "B1", "value": { "type": "number", "data": 5 }, "formula": "of:=SUM([$Datos.A1:.A2])" }
+                ],
+                "merges": ["A2:B3"]
+            },
+            {
+                "name": "Datos",
+                "cells": [
+                    { "cell": "A1", "value": { "type": "number", "data": 2 }, "comment": { "text": "origen", "author": "Ana" } },
+                    { "cell": "A2", "value": { "type": "number", "data": 3 } }
+                ]
+            }
+        ]
+    });
+
+    let out = import_json::handle(json!({ "path": ods.to_string_lossy(), "workbook": workbook }))
+        .expect("import");
+    assert_eq!(out["sheets"], json!(["Resumen", "Datos"]));
+    assert_eq!(out["cells"], 4);
+    assert_eq!(out["formulas"], 1);
+    assert_eq!(out["merges"], 1);
+    assert_eq!(out["comments"], 1);
+
+    let read = OdsFile::read_workbook(&ods).expect("read");
+    assert_eq!(
+        read.sheets[0].get_cell(0, 0).expect("a1").value,
+        CellValue::Date("2024-02-29".to_string())
+    );
+    let comment = read.sheets[1].get_cell(0, 0).expect("a1").comment.clone();
+    assert_eq!(comment.expect("comment").author.as_deref(), Some("Ana"));
+    assert!(OdsFile::read_document_properties(&ods)
+        .expect("props")
+        .creation_date
+        .is_some());
+}
+
+#[test]
+fn import_json_validates_sources_and_target() {
+    let dir = tempdir().expect("tempdir");
+    let ods = dir.path().join("fixture.ods");
+    let minimal =
+        json!({ "schema": "mcp-ods/workbook", "version": 1, "sheets": [{ "name": "A" }] });
+
+    let err = import_json::handle(json!({ "path": ods.to_string_lossy() })).expect_err("no source");
+    assert!(err
+        .to_string()
+        .contains("exactly one of json_path or workbook"));
+
+    let err = import_json::handle(json!({
+        "path": ods.to_string_lossy(),
+        "json_path": dir.path().join("missing.json").to_string_lossy()
+    }))
+    .expect_err("missing json");
+    assert!(matches!(err, AppError::FileNotFound(_)));
+
+    import_json::handle(json!({ "path": ods.to_string_lossy(), "workbook": minimal.clone() }))
+        .expect("first import");
+    let err = import_json::handle(json!({ "path": ods.to_string_lossy(), "workbook": minimal }))
+        .expect_err("exists");
+    assert!(matches!(err, AppError::AlreadyExists(_)));
+}
+
+#[test]
+fn import_json_writes_blank_gaps_as_repeated_rows_and_cells() {
+    let dir = tempdir().expect("tempdir");
+    let ods = dir.path().join("fixture.ods");
+    let workbook = json!({
+        "schema": "mcp-ods/workbook",
+        "version": 1,
+        "sheets": [{
+            "name": "Datos",
+            "cells": [
+                { "cell": "A1", "value": { "type": "number", "data": 1 } },
+                { "cell": "A200000", "value": { "type": "number", "data": 2 } },
+                { "cell": "Z2", "value": { "type": "string", "data": "fin" } }
+            ]
+        }]
+    });
+
+    import_json::handle(json!({ "path": ods.to_string_lossy(), "workbook": workbook }))
+        .expect("import");
+
+    let content = OdsFile::read_content_xml(&ods).expect("content");
+    assert!(
+        content.len() < 10_000,
+        "content.xml is {} bytes",
+        content.len()
+    );
+    assert!(content.contains("table:number-rows-repeated=\"199997\""));
+    assert!(content.contains("table:number-columns-repeated=\"25\""));
+    let read = OdsFile::read_workbook(&ods).expect("read");
+    assert_eq!(
+        read.sheets[0].get_cell(199_999, 0).expect("a200000").value,
+        CellValue::Number(2.0)
+    );
+    assert_eq!(
+        read.sheets[0].get_cell(1, 25).expect("z2").value,
+        CellValue::String("fin".to_string())
+    );
+}
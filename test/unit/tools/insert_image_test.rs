@@ -0,0 +1,104 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::tools::{create_ods, insert_image, list_images};
+use serde_json::json;
+use tempfile::tempdir;
+
+#[test]
+fn insert_image_rejects_bad_sources_and_lengths() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("images.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy() })).expect("create");
+    let gif = dir.path().join("anim.gif");
+    std::fs::write(&gif, b"GIF89a").expect("write gif");
+    let svg = dir.path().join("logo.svg");
+    std::fs::write(
+        &svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20"/>"#,
+    )
+    .expect("write svg");
+    let zero = dir.path().join("zero.svg");
+    std::fs::write(
+        &zero,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="20"/>"#,
+    )
+    .expect("write svg");
+    let call = |source: &std::path::Path, extra: serde_json::Value| {
+        let mut params = json!({
+            "path": path.to_string_lossy(),
+            "sheet": { "index": 0 },
+            "source": source.to_string_lossy()
+        });
+        for (key, value) in extra.as_object().expect("object") {
+            params[key] = value.clone();
+        }
+        insert_image::handle(params)
+    };
+
+    let missing = dir.path().join("missing.png");
+    assert!(matches!(
+        call(&missing, json!({})),
+        Err(AppError::FileNotFound(_))
+    ));
+    assert!(matches!(
+        call(&gif, json!({})),
+        Err(AppError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        call(&svg, json!({ "width": "-2cm" })),
+        Err(AppError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        call(&svg, json!({ "x": "left" })),
+        Err(AppError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        call(&svg, json!({ "cell": "A0" })),
+        Err(AppError::InvalidCellAddress(_))
+    ));
+    let err = call(&zero, json!({ "width": "2cm" })).expect_err("zero size");
+    assert!(err.to_string().contains("give width and height"));
+    let listed = list_images::handle(json!({ "path": path.to_string_lossy() })).expect("list");
+    assert_eq!(listed["total"], 0);
+
+    // Height follows the SVG's 2:1 aspect ratio.
+    let out = call(&svg, json!({ "height": "1cm" })).expect("insert");
+    assert_eq!(
+        (&out["width"], &out["height"]),
+        (&json!("2cm"), &json!("1cm"))
+    );
+    assert_eq!(out["anchor"], "page");
+}
+
+#[test]
+fn insert_image_writes_lengths_with_units() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("units.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy() })).expect("create");
+    let svg = dir.path().join("logo.svg");
+    std::fs::write(
+        &svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="4cm" height="2cm"/>"#,
+    )
+    .expect("write svg");
+
+    // Bare numbers are pixels; the frame gets cm, as ODF lengths need a unit.
+    let out = insert_image::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "source": svg.to_string_lossy(),
+        "width": "96",
+        "x": "5",
+        "y": "10mm"
+    }))
+    .expect("insert");
+    assert_eq!(
+        (&out["width"], &out["height"]),
+        (&json!("2.54cm"), &json!("1.27cm"))
+    );
+
+    let listed = list_images::handle(json!({ "path": path.to_string_lossy() })).expect("list");
+    let image = &listed["images"][0];
+    assert_eq!(image["width"], "2.54cm");
+    assert_eq!(image["x"], "0.132cm");
+    assert_eq!(image["y"], "1cm");
+}
@@ -1,11 +1,30 @@
 mod add_sheet_test;
+mod check_workbook_test;
+mod convert_workbook_test;
 mod create_ods_test;
 mod delete_sheet_test;
+mod document_properties_test;
 mod duplicate_sheet_test;
+mod evaluate_formula_test;
+mod export_csv_test;
+mod export_json_test;
+mod find_cells_test;
 mod get_cell_value_test;
 mod get_sheet_content_test;
 mod get_sheets_test;
+mod import_csv_test;
+mod import_json_test;
+mod insert_image_test;
+mod move_sheet_test;
+mod page_cursor_test;
+mod recalculate_test;
 mod rename_sheet_test;
+mod replace_in_cells_test;
+mod set_cell_formula_test;
 mod set_cell_value_test;
 mod set_range_values_test;
+mod sheet_attributes_test;
+mod sheet_position_test;
 mod sheet_ref_test;
+mod sheet_render_test;
+mod trace_test;
@@ -0,0 +1,105 @@
+use mcp_ods::tools::{add_sheet, create_ods, get_sheets, move_sheet};
+use serde_json::json;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn create_with_sheets(path: &Path, extra: &[&str]) {
+    create_ods::handle(json!({
+        "path": path.to_string_lossy(),
+        "overwrite": true,
+        "initial_sheet_name": "A"
+    }))
+    .expect("create");
+    for name in extra {
+        add_sheet::handle(json!({ "path": path.to_string_lossy(), "sheet_name": name }))
+            .expect("add");
+    }
+}
+
+#[test]
+fn move_sheet_to_index_and_relative_positions() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("move_sheet.ods");
+    create_with_sheets(&path, &["B", "C", "D"]);
+
+    let out = move_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "D" },
+        "position": 0
+    }))
+    .expect("move to 0");
+    assert_eq!(out["sheets"], json!(["D", "A", "B", "C"]));
+
+    let out = move_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "D" },
+        "position": { "after": { "name": "B" } }
+    }))
+    .expect("move after");
+    assert_eq!(out["sheets"], json!(["A", "B", "D", "C"]));
+
+    let out = move_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "position": "end"
+    }))
+    .expect("move end");
+    assert_eq!(out["sheets"], json!(["B", "D", "C", "A"]));
+
+    let persisted = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("get");
+    assert_eq!(persisted["sheets"], json!(["B", "D", "C", "A"]));
+}
+
+#[test]
+fn move_sheet_rejects_out_of_range_and_self_reference() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("move_sheet_errors.ods");
+    create_with_sheets(&path, &["B"]);
+
+    let err = move_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "A" },
+        "position": 2
+    }))
+    .expect_err("out of range");
+    assert!(err.to_string().contains("out of range"));
+
+    let err = move_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "A" },
+        "position": { "before": "A" }
+    }))
+    .expect_err("self");
+    assert!(err.to_string().contains("relative to itself"));
+}
+
+#[test]
+fn move_sheet_anchors_below_the_source_use_the_current_order() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("move_sheet_anchor.ods");
+    create_with_sheets(&path, &["B", "C"]);
+
+    let out = move_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "position": { "after": { "index": 2 } }
+    }))
+    .expect("move after last");
+    assert_eq!(out["sheets"], json!(["B", "C", "A"]));
+
+    let out = move_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "position": { "before": { "index": 1 } }
+    }))
+    .expect("move before next");
+    assert_eq!(out["sheets"], json!(["B", "C", "A"]));
+
+    let out = move_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "position": { "before": { "index": 2 } }
+    }))
+    .expect("move before last");
+    assert_eq!(out["sheets"], json!(["C", "B", "A"]));
+}
@@ -0,0 +1,49 @@
+use mcp_ods::tools::page_cursor::{FileStamp, PageCursor};
+
+fn sample() -> PageCursor {
+    PageCursor {
+        mode: "matrix".to_string(),
+        sheet: 2,
+        row: 400,
+        col: 10,
+        stamp: FileStamp {
+            modified_nanos: 1_700_000_000_123_456_789,
+            len: 9_876,
+        },
+    }
+}
+
+#[test]
+fn page_cursor_round_trips_through_opaque_token() {
+    let cursor = sample();
+    let token = cursor.encode();
+    assert!(token
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(PageCursor::decode(&token).expect("decode"), cursor);
+}
+
+#[test]
+fn page_cursor_validate_checks_mode_sheet_and_stamp() {
+    let cursor = sample();
+    cursor.validate("matrix", 2, cursor.stamp).expect("valid");
+
+    let err = cursor.validate("cells", 2, cursor.stamp).expect_err("mode");
+    assert!(err.to_string().contains("mode=matrix"));
+    let err = cursor
+        .validate("matrix", 0, cursor.stamp)
+        .expect_err("sheet");
+    assert!(err.to_string().contains("sheet index 2"));
+    let changed = FileStamp {
+        len: 1,
+        ..cursor.stamp
+    };
+    let err = cursor.validate("matrix", 2, changed).expect_err("stale");
+    assert!(err.to_string().contains("stale"));
+}
+
+#[test]
+fn page_cursor_decode_rejects_tampered_tokens() {
+    assert!(PageCursor::decode("%%%").is_err());
+    assert!(PageCursor::decode("djE6bWF0cml4").is_err());
+}
@@ -0,0 +1,115 @@
+use crate::common::ods_with_content;
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::tools::{get_cell_value, recalculate};
+use serde_json::json;
+use tempfile::tempdir;
+
+const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Ventas">
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="10"><text:p>10</text:p></table:table-cell>
+        <table:table-cell table:style-name="ce1" table:formula="of:=[.A2]*2" office:value-type="float" office:value="0"><text:p>0</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.C2]+1" office:value-type="float" office:value="0"><text:p>0</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell table:formula="of:=SUM([.A1];[$Datos.A1])" office:value-type="float" office:value="0"><text:p>0</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.A1]/[Datos.B1]" office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.C1]+1" office:value-type="float" office:value="0"><text:p>0</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell table:formula="of:=NPV(0.1;[.A1])" office:value-type="float" office:value="7"><text:p>7</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.A1]" office:value-type="float" office:value="10"><text:p>10</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Datos">
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="5"><text:p>5</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+fn value(path: &str, cell: &str) -> serde_json::Value {
+    get_cell_value::handle(json!({ "path": path, "sheet": { "name": "Ventas" }, "cell": cell }))
+        .expect("get")["value"]
+        .clone()
+}
+
+#[test]
+fn recalculate_refreshes_stale_results_in_dependency_order() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "recalc.ods", CONTENT);
+
+    let out = recalculate::handle(json!({ "path": path })).expect("recalculate");
+    assert_eq!(out["formulas"], 7);
+    assert_eq!(out["recalculated"], 4);
+    // B3 already held the right value.
+    assert_eq!(out["changed"], 3);
+    assert_eq!(out["errors"], 1);
+
+    assert_eq!(
+        value(&path, "A2"),
+        json!({ "type": "number", "data": 15.0 })
+    );
+    assert_eq!(
+        value(&path, "B1"),
+        json!({ "type": "number", "data": 30.0 })
+    );
+    assert_eq!(
+        value(&path, "B2"),
+        json!({ "type": "string", "data": "#DIV/0!" })
+    );
+
+    let content = OdsFile::read_content_xml(std::path::Path::new(&path)).expect("read");
+    assert!(content.contains(r#"table:style-name="ce1" table:formula="of:=[.A2]*2""#));
+    assert!(content.contains("<text:p>30</text:p>"));
+}
+
+#[test]
+fn recalculate_reports_cycles_and_unsupported_functions() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "recalc.ods", CONTENT);
+
+    let out = recalculate::handle(json!({ "path": path })).expect("recalculate");
+    assert_eq!(
+        out["issues"],
+        json!([
+            {
+                "sheet": "Ventas",
+                "cell": "C1",
+                "formula": "of:=[.C2]+1",
+                "reason": "circular_reference",
+                "detail": "formula is part of a circular reference"
+            },
+            {
+                "sheet": "Ventas",
+                "cell": "C2",
+                "formula": "of:=[.C1]+1",
+                "reason": "circular_reference",
+                "detail": "formula is part of a circular reference"
+            },
+            {
+                "sheet": "Ventas",
+                "cell": "A3",
+                "formula": "of:=NPV(0.1;[.A1])",
+                "reason": "unsupported_function",
+                "detail": "NPV"
+            }
+        ])
+    );
+    // Unresolved cells keep their cached value.
+    assert_eq!(value(&path, "A3"), json!({ "type": "number", "data": 7.0 }));
+    assert_eq!(value(&path, "C1"), json!({ "type": "number", "data": 0.0 }));
+}
+
+#[test]
+fn recalculate_rejects_missing_file() {
+    let dir = tempdir().expect("tempdir");
+    let missing = dir.path().join("missing.ods");
+    let err = recalculate::handle(json!({ "path": missing.to_string_lossy() }))
+        .expect_err("missing file");
+    assert!(matches!(err, AppError::FileNotFound(_)));
+}
@@ -0,0 +1,182 @@
+use crate::common::ods_with_content;
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::tools::{get_cell_value, replace_in_cells};
+use serde_json::json;
+use tempfile::tempdir;
+
+const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Facturas">
+      <table:table-row>
+        <table:table-cell table:style-name="ce1" office:value-type="string"><text:p>INV-2024-001</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>inv-2024-002</text:p></table:table-cell>
+        <table:table-cell office:value-type="float" office:value="2024"><text:p>2024</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell table:formula="of:=[Datos2024.A1]" office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+        <table:table-cell office:value-type="string"><text:p>Total 2024</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Notas">
+      <table:table-row>
+        <table:table-cell office:value-type="string"><text:p>Cierre 2024</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+fn value(path: &str, sheet: &str, cell: &str) -> serde_json::Value {
+    get_cell_value::handle(json!({ "path": path, "sheet": { "name": sheet }, "cell": cell }))
+        .expect("get")["value"]
+        .clone()
+}
+
+#[test]
+fn replace_in_cells_dry_run_lists_changes_without_saving() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "replace.ods", CONTENT);
+    let before = OdsFile::read_content_xml(std::path::Path::new(&path)).expect("read");
+
+    let out = replace_in_cells::handle(json!({
+        "path": path,
+        "find": "2024",
+        "replace": "2025",
+        "dry_run": true
+    }))
+    .expect("dry run");
+    assert_eq!(out["dry_run"], true);
+    assert_eq!(out["count"], 4);
+    assert_eq!(
+        out["changes"][0],
+        json!({
+            "sheet": "Facturas",
+            "cell": "A1",
+            "field": "value",
+            "before": "INV-2024-001",
+            "after": "INV-2025-001"
+        })
+    );
+    assert_eq!(out["changes"][3]["sheet"], "Notas");
+    let after = OdsFile::read_content_xml(std::path::Path::new(&path)).expect("read");
+    assert_eq!(before, after);
+}
+
+#[test]
+fn replace_in_cells_regex_expands_groups_and_keeps_styles() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "replace.ods", CONTENT);
+
+    let out = replace_in_cells::handle(json!({
+        "path": path,
+        "sheet": { "name": "Facturas" },
+        "range": "A1:B1",
+        "find": r"inv-(\d{4})-(\d+)",
+        "replace": "$2/$1",
+        "regex": true
+    }))
+    .expect("replace");
+    assert_eq!(out["count"], 2);
+    assert_eq!(
+        value(&path, "Facturas", "A1"),
+        json!({ "type": "string", "data": "001/2024" })
+    );
+    assert_eq!(
+        value(&path, "Facturas", "B1"),
+        json!({ "type": "string", "data": "002/2024" })
+    );
+    assert_eq!(
+        value(&path, "Facturas", "B2"),
+        json!({ "type": "string", "data": "Total 2024" })
+    );
+    let content = OdsFile::read_content_xml(std::path::Path::new(&path)).expect("read");
+    assert!(content.contains(r#"table:style-name="ce1""#));
+
+    let sensitive = replace_in_cells::handle(json!({
+        "path": path,
+        "find": "total",
+        "replace": "x",
+        "case_sensitive": true
+    }))
+    .expect("case sensitive");
+    assert_eq!(sensitive["count"], 0);
+}
+
+#[test]
+fn replace_in_cells_formulas_only_when_requested() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "replace.ods", CONTENT);
+
+    let out = replace_in_cells::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "find": "Datos2024",
+        "replace": "Datos2025"
+    }))
+    .expect("values only");
+    assert_eq!(out["count"], 0);
+
+    let out = replace_in_cells::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "find": "Datos2024",
+        "replace": "Datos2025",
+        "include_formulas": true
+    }))
+    .expect("formulas");
+    assert_eq!(out["count"], 1);
+    assert_eq!(out["changes"][0]["field"], "formula");
+    assert_eq!(out["changes"][0]["after"], "of:=[Datos2025.A1]");
+    let content = OdsFile::read_content_xml(std::path::Path::new(&path)).expect("read");
+    assert!(content.contains(r#"table:formula="of:=[Datos2025.A1]""#));
+}
+
+#[test]
+fn replace_in_cells_whole_cell_and_literal_dollar() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "replace.ods", CONTENT);
+
+    let out = replace_in_cells::handle(json!({
+        "path": path,
+        "find": "cierre 2024",
+        "replace": "$1 cerrado",
+        "whole_cell": true
+    }))
+    .expect("replace");
+    assert_eq!(out["count"], 1);
+    assert_eq!(
+        value(&path, "Notas", "A1"),
+        json!({ "type": "string", "data": "$1 cerrado" })
+    );
+}
+
+#[test]
+fn replace_in_cells_rejects_bad_input() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "replace.ods", CONTENT);
+
+    let empty = replace_in_cells::handle(json!({ "path": path, "find": "", "replace": "x" }));
+    assert!(matches!(empty, Err(AppError::InvalidInput(_))));
+    let regex = replace_in_cells::handle(json!({
+        "path": path,
+        "find": "(",
+        "replace": "x",
+        "regex": true
+    }));
+    assert!(matches!(regex, Err(AppError::InvalidInput(msg)) if msg.starts_with("invalid regex")));
+    let range = replace_in_cells::handle(json!({
+        "path": path,
+        "range": "A1:B2",
+        "find": "a",
+        "replace": "b"
+    }));
+    assert!(matches!(range, Err(AppError::InvalidInput(_))));
+    let sheet = replace_in_cells::handle(json!({
+        "path": path,
+        "sheet": { "name": "Nope" },
+        "find": "a",
+        "replace": "b"
+    }));
+    assert!(matches!(sheet, Err(AppError::SheetNotFound(_))));
+}
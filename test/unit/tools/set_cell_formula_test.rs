@@ -0,0 +1,111 @@
+use crate::common::ods_with_content;
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::tools::{find_cells, get_sheet_content, set_cell_formula};
+use serde_json::json;
+use std::path::Path;
+use tempfile::tempdir;
+
+const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Ventas">
+      <table:table-row table:number-rows-repeated="2">
+        <table:table-cell office:value-type="float" office:value="4"><text:p>4</text:p></table:table-cell>
+        <table:table-cell table:style-name="ce1" table:number-columns-repeated="2"/>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Hoja 2">
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="10"><text:p>10</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+#[test]
+fn set_cell_formula_translates_excel_syntax_and_caches_the_result() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "formulas.ods", CONTENT);
+
+    let out = set_cell_formula::handle(json!({
+        "path": path,
+        "sheet": { "name": "Ventas" },
+        "cell": "B2",
+        "formula": "=SUM(A1:A2)*'Hoja 2'!A1"
+    }))
+    .expect("set");
+    assert_eq!(out["formula"], "of:=SUM([.A1:.A2])*[$'Hoja 2'.A1]");
+    assert_eq!(out["value"], json!({ "type": "number", "data": 80.0 }));
+    assert_eq!(out["unsupported"], json!([]));
+
+    let content = OdsFile::read_content_xml(Path::new(&path)).expect("read");
+    assert!(content.contains(r#"<table:table-cell table:style-name="ce1" table:formula="of:=SUM([.A1:.A2])*[$&apos;Hoja 2&apos;.A1]" office:value-type="float" office:value="80"><text:p>80</text:p></table:table-cell>"#));
+
+    let cells = get_sheet_content::handle(json!({
+        "path": path,
+        "sheet": { "name": "Ventas" },
+        "mode": "cells",
+        "formula_syntax": "excel"
+    }))
+    .expect("cells");
+    assert_eq!(cells["cells"][2]["cell"], "B2");
+    assert_eq!(cells["cells"][2]["formula"], "=SUM(A1:A2)*'Hoja 2'!A1");
+
+    let found = find_cells::handle(json!({
+        "path": path,
+        "query": "SUM",
+        "look_in": ["formulas"],
+        "formula_syntax": "excel"
+    }))
+    .expect("find");
+    assert_eq!(found["matches"][0]["formula"], "=SUM(A1:A2)*'Hoja 2'!A1");
+}
+
+#[test]
+fn set_cell_formula_stores_uncomputable_formulas_without_a_result() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "formulas.ods", CONTENT);
+
+    let out = set_cell_formula::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "cell": "C1",
+        "formula": "of:=COM.MICROSOFT.TEXTJOIN(\";\";1;[.A1:.A2])",
+        "formula_syntax": "odf"
+    }))
+    .expect("unsupported");
+    assert_eq!(out["value"], json!(null));
+    assert_eq!(out["unsupported"], json!(["COM.MICROSOFT.TEXTJOIN"]));
+
+    let out = set_cell_formula::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "cell": "B1",
+        "formula": "=B1+1"
+    }))
+    .expect("circular");
+    assert_eq!(out["value"], json!(null));
+
+    let content = OdsFile::read_content_xml(Path::new(&path)).expect("read");
+    assert!(content.contains(r#"table:formula="of:=[.B1]+1""#));
+    assert!(content
+        .contains(r#"table:formula="of:=COM.MICROSOFT.TEXTJOIN(&quot;;&quot;;1;[.A1:.A2])""#));
+}
+
+#[test]
+fn set_cell_formula_rejects_invalid_formula_without_writing() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "formulas.ods", CONTENT);
+    let before = std::fs::read(&path).expect("read");
+
+    let err = set_cell_formula::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "cell": "B1",
+        "formula": "=SUM(A1"
+    }))
+    .expect_err("invalid");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+    assert_eq!(std::fs::read(&path).expect("read"), before);
+}
@@ -1,5 +1,5 @@
 use mcp_ods::tools::{create_ods, get_cell_value, set_cell_value};
-use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::ods::ods_file::{OdsFile, SaveOptions};
 use serde_json::json;
 use tempfile::tempdir;
 
@@ -176,7 +176,11 @@ fn set_cell_value_propagates_resolve_anchor_xml_errors() {
 
     create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
         .expect("create");
-    OdsFile::write_content_xml(&path, "<broken").expect("write broken xml");
+    let unchecked = SaveOptions {
+        validate: false,
+        ..SaveOptions::default()
+    };
+    OdsFile::write_content_xml_with(&path, "<broken", &unchecked).expect("write broken xml");
 
     let err = set_cell_value::handle(json!({
         "path": path.to_string_lossy(),
@@ -1,5 +1,5 @@
 use mcp_ods::tools::{add_sheet, create_ods, get_sheet_content, set_range_values};
-use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::ods::ods_file::{OdsFile, SaveOptions};
 use serde_json::json;
 use tempfile::tempdir;
 
@@ -142,7 +142,11 @@ fn set_range_values_propagates_resolve_anchor_xml_errors() {
 
     create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
         .expect("create");
-    OdsFile::write_content_xml(&path, "<broken").expect("write broken xml");
+    let unchecked = SaveOptions {
+        validate: false,
+        ..SaveOptions::default()
+    };
+    OdsFile::write_content_xml_with(&path, "<broken", &unchecked).expect("write broken xml");
 
     let err = set_range_values::handle(json!({
         "path": path.to_string_lossy(),
@@ -0,0 +1,132 @@
+use mcp_ods::tools::{
+    add_sheet, create_ods, duplicate_sheet, get_sheets, set_sheet_protection, set_sheet_tab_color,
+    set_sheet_visibility,
+};
+use serde_json::json;
+use tempfile::tempdir;
+
+#[test]
+fn set_sheet_visibility_hides_only_the_target_sheet() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("visibility.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    duplicate_sheet::handle(json!({
+        "path": path.to_string_lossy(),
+        "source_sheet": { "index": 0 },
+        "new_sheet_name": "Aux"
+    }))
+    .expect("duplicate shares the table style");
+
+    let out = set_sheet_visibility::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "Aux" },
+        "visible": false
+    }))
+    .expect("hide");
+    assert_eq!(out["visible"], false);
+
+    let sheets = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("sheets");
+    assert_eq!(sheets["details"][0]["visible"], true);
+    assert_eq!(sheets["details"][1]["visible"], false);
+
+    set_sheet_visibility::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "Aux" },
+        "visible": true
+    }))
+    .expect("show");
+    let sheets = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("sheets");
+    assert_eq!(sheets["details"][1]["visible"], true);
+}
+
+#[test]
+fn set_sheet_visibility_keeps_at_least_one_visible_sheet() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("visibility_last.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let err = set_sheet_visibility::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "visible": false
+    }))
+    .expect_err("last visible");
+    assert!(err.to_string().contains("at least one sheet"));
+}
+
+#[test]
+fn set_sheet_tab_color_sets_normalizes_and_clears() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("tab_color.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+    add_sheet::handle(json!({ "path": path.to_string_lossy(), "sheet_name": "Nueva" }))
+        .expect("add sheet without style");
+
+    let out = set_sheet_tab_color::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "Nueva" },
+        "color": "FF8000"
+    }))
+    .expect("color");
+    assert_eq!(out["tab_color"], "#ff8000");
+
+    let sheets = get_sheets::handle(json!({ "path": path.to_string_lossy() })).expect("sheets");
+    assert_eq!(sheets["details"][0]["tab_color"], json!(null));
+    assert_eq!(sheets["details"][1]["tab_color"], "#ff8000");
+
+    let out = set_sheet_tab_color::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "Nueva" },
+        "color": null
+    }))
+    .expect("clear");
+    assert_eq!(out["tab_color"], json!(null));
+
+    let err = set_sheet_tab_color::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "name": "Nueva" },
+        "color": "red"
+    }))
+    .expect_err("invalid color");
+    assert!(err.to_string().contains("invalid color"));
+}
+
+#[test]
+fn set_sheet_protection_with_password_and_unprotect() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("protection.ods");
+    create_ods::handle(json!({ "path": path.to_string_lossy(), "overwrite": true }))
+        .expect("create");
+
+    let out = set_sheet_protection::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "protected": true,
+        "password": "secreto"
+    }))
+    .expect("protect");
+    assert_eq!(out["protected"], true);
+    assert_eq!(out["has_password"], true);
+
+    let out = set_sheet_protection::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "protected": false
+    }))
+    .expect("unprotect");
+    assert_eq!(out["protected"], false);
+    assert_eq!(out["has_password"], false);
+
+    let err = set_sheet_protection::handle(json!({
+        "path": path.to_string_lossy(),
+        "sheet": { "index": 0 },
+        "protected": true,
+        "password": "a",
+        "password_hash": "b"
+    }))
+    .expect_err("both");
+    assert!(err.to_string().contains("either password or password_hash"));
+}
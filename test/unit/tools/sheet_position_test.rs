@@ -0,0 +1,77 @@
+use mcp_ods::tools::sheet_position::SheetPosition;
+use mcp_ods::tools::sheet_ref::SheetRef;
+use serde_json::json;
+
+fn names() -> Vec<String> {
+    vec!["A".to_string(), "B".to_string(), "C".to_string()]
+}
+
+#[test]
+fn sheet_position_parses_all_supported_shapes() {
+    let cases = vec![
+        (json!("start"), SheetPosition::Start),
+        (json!("END"), SheetPosition::End),
+        (json!(2), SheetPosition::Index(2)),
+        (json!("1"), SheetPosition::Index(1)),
+        (json!({ "index": 0 }), SheetPosition::Index(0)),
+        (
+            json!({ "before": { "name": "B" } }),
+            SheetPosition::Before(SheetRef::Name {
+                name: "B".to_string(),
+            }),
+        ),
+        (
+            json!({ "after": "C" }),
+            SheetPosition::After(SheetRef::Name {
+                name: "C".to_string(),
+            }),
+        ),
+        (
+            json!("{\"after\":{\"index\":0}}"),
+            SheetPosition::After(SheetRef::Index { index: 0 }),
+        ),
+    ];
+
+    for (value, expected) in cases {
+        let parsed: SheetPosition = serde_json::from_value(value.clone()).expect("parse");
+        assert_eq!(parsed, expected, "input {value}");
+    }
+}
+
+#[test]
+fn sheet_position_rejects_unknown_strings_and_objects() {
+    let err = serde_json::from_value::<SheetPosition>(json!("middle")).expect_err("unknown");
+    assert!(err.to_string().contains("unknown sheet position"));
+
+    let err = serde_json::from_value::<SheetPosition>(json!({ "near": "A" })).expect_err("obj");
+    assert!(err.to_string().contains("index, before or after"));
+}
+
+#[test]
+fn sheet_position_resolves_insert_index() {
+    let names = names();
+    assert_eq!(
+        SheetPosition::Start.resolve_insert_index(&names).unwrap(),
+        0
+    );
+    assert_eq!(SheetPosition::End.resolve_insert_index(&names).unwrap(), 3);
+    assert_eq!(
+        SheetPosition::Index(3)
+            .resolve_insert_index(&names)
+            .unwrap(),
+        3
+    );
+    let before_b = SheetPosition::Before(SheetRef::Name {
+        name: "B".to_string(),
+    });
+    assert_eq!(before_b.resolve_insert_index(&names).unwrap(), 1);
+    let after_b = SheetPosition::After(SheetRef::Name {
+        name: "B".to_string(),
+    });
+    assert_eq!(after_b.resolve_insert_index(&names).unwrap(), 2);
+
+    let err = SheetPosition::Index(4)
+        .resolve_insert_index(&names)
+        .expect_err("out of range");
+    assert!(err.to_string().contains("out of range"));
+}
@@ -0,0 +1,45 @@
+use mcp_ods::ods::content_xml::MergedRange;
+use mcp_ods::tools::sheet_render::RenderFormat;
+
+fn grid(rows: &[&[&str]]) -> Vec<Vec<String>> {
+    rows.iter()
+        .map(|r| r.iter().map(|v| v.to_string()).collect())
+        .collect()
+}
+
+#[test]
+fn sheet_render_markdown_uses_first_row_as_header_and_escapes_pipes() {
+    let data = grid(&[&["name", "note"], &["a|b", "line1\nline2"]]);
+    let out = RenderFormat::Markdown.render(&data, &[]);
+    assert_eq!(
+        out,
+        "| name | note |\n| --- | --- |\n| a\\|b | line1<br>line2 |"
+    );
+    assert_eq!(RenderFormat::Markdown.render(&[], &[]), "");
+}
+
+#[test]
+fn sheet_render_csv_quotes_only_when_needed() {
+    let data = grid(&[&["plain", "with,comma"], &["say \"hi\"", "two\nlines"]]);
+    let out = RenderFormat::Csv.render(&data, &[]);
+    assert_eq!(
+        out,
+        "plain,\"with,comma\"\n\"say \"\"hi\"\"\",\"two\nlines\""
+    );
+}
+
+#[test]
+fn sheet_render_html_emits_spans_and_skips_covered_cells() {
+    let data = grid(&[&["<b>", "", "x"], &["", "", "y & z"]]);
+    let merges = [MergedRange {
+        row: 0,
+        col: 0,
+        rows: 2,
+        cols: 2,
+    }];
+    let out = RenderFormat::Html.render(&data, &merges);
+    assert_eq!(
+        out,
+        "<table>\n  <tr><td rowspan=\"2\" colspan=\"2\">&lt;b&gt;</td><td>x</td></tr>\n  <tr><td>y &amp; z</td></tr>\n</table>"
+    );
+}
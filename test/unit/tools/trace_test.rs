@@ -0,0 +1,181 @@
+use crate::common::ods_with_content;
+use mcp_ods::common::errors::AppError;
+use mcp_ods::tools::{trace_dependents, trace_precedents};
+use serde_json::json;
+use tempfile::tempdir;
+
+const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+  <office:body><office:spreadsheet>
+    <table:table table:name="Resumen">
+      <table:table-row>
+        <table:table-cell table:formula="of:=[.B1]*[$Ventas.A1]" office:value-type="float" office:value="60"><text:p>60</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=SUM([$Ventas.A2:.A3])+[$Viejo.A1]" office:value-type="float" office:value="3"><text:p>3</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.D1]" office:value-type="float" office:value="0"><text:p>0</text:p></table:table-cell>
+        <table:table-cell table:formula="of:=[.C1]" office:value-type="float" office:value="0"><text:p>0</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+    <table:table table:name="Ventas">
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="20"><text:p>20</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+      </table:table-row>
+      <table:table-row>
+        <table:table-cell table:formula="of:=[.A2]+1" office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+      </table:table-row>
+    </table:table>
+  </office:spreadsheet></office:body>
+</office:document-content>"#;
+
+#[test]
+fn trace_precedents_follows_formulas_through_ranges_and_sheets() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "trace.ods", CONTENT);
+
+    let out = trace_precedents::handle(json!({
+        "path": path,
+        "sheet": { "name": "Resumen" },
+        "cell": "a1",
+        "formula_syntax": "excel"
+    }))
+    .expect("trace");
+    assert_eq!(out["cell"], "A1");
+    assert_eq!(out["nodes"], 7);
+    assert_eq!(out["truncated"], false);
+    assert_eq!(
+        out["tree"],
+        json!({
+            "sheet": "Resumen",
+            "ref": "A1",
+            "formula": "=B1*Ventas!A1",
+            "value": { "type": "number", "data": 60.0 },
+            "children": [
+                {
+                    "sheet": "Resumen",
+                    "ref": "B1",
+                    "formula": "=SUM(Ventas!A2:A3)+Viejo!A1",
+                    "value": { "type": "number", "data": 3.0 },
+                    "children": [
+                        {
+                            "sheet": "Ventas",
+                            "ref": "A2:A3",
+                            "children": [
+                                {
+                                    "sheet": "Ventas",
+                                    "ref": "A3",
+                                    "formula": "=A2+1",
+                                    "value": { "type": "number", "data": 2.0 },
+                                    "children": [
+                                        {
+                                            "sheet": "Ventas",
+                                            "ref": "A2",
+                                            "value": { "type": "number", "data": 1.0 }
+                                        }
+                                    ]
+                                }
+                            ]
+                        },
+                        { "sheet": "Viejo", "ref": "A1", "missing_sheet": true }
+                    ]
+                },
+                {
+                    "sheet": "Ventas",
+                    "ref": "A1",
+                    "value": { "type": "number", "data": 20.0 }
+                }
+            ]
+        })
+    );
+}
+
+#[test]
+fn trace_precedents_honours_depth_and_node_limits_and_marks_cycles() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "trace.ods", CONTENT);
+
+    let shallow = trace_precedents::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "cell": "A1",
+        "max_depth": 1
+    }))
+    .expect("shallow");
+    assert_eq!(shallow["tree"]["children"][0]["depth_limited"], true);
+    assert!(shallow["tree"]["children"][0].get("children").is_none());
+
+    let capped = trace_precedents::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "cell": "A1",
+        "max_nodes": 2
+    }))
+    .expect("capped");
+    assert_eq!(capped["nodes"], 2);
+    assert_eq!(capped["truncated"], true);
+
+    let cycle = trace_precedents::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "cell": "C1"
+    }))
+    .expect("cycle");
+    let back = &cycle["tree"]["children"][0]["children"][0];
+    assert_eq!(back["ref"], "C1");
+    assert_eq!(back["circular"], true);
+}
+
+#[test]
+fn trace_dependents_walks_up_to_every_reader() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "trace.ods", CONTENT);
+
+    let out = trace_dependents::handle(json!({
+        "path": path,
+        "sheet": { "name": "Ventas" },
+        "cell": "A2"
+    }))
+    .expect("trace");
+    let tree = &out["tree"];
+    assert_eq!(tree["ref"], "A2");
+    let refs: Vec<_> = tree["children"]
+        .as_array()
+        .expect("children")
+        .iter()
+        .map(|c| {
+            format!(
+                "{}!{}",
+                c["sheet"].as_str().unwrap(),
+                c["ref"].as_str().unwrap()
+            )
+        })
+        .collect();
+    assert_eq!(refs, vec!["Resumen!B1", "Ventas!A3"]);
+    assert_eq!(tree["children"][0]["children"][0]["ref"], "A1");
+    assert_eq!(tree["children"][1]["children"][0]["ref"], "B1");
+    assert_eq!(out["nodes"], 6);
+}
+
+#[test]
+fn trace_rejects_unknown_sheet_and_zero_node_budget() {
+    let dir = tempdir().expect("tempdir");
+    let path = ods_with_content(&dir, "trace.ods", CONTENT);
+
+    let err = trace_dependents::handle(json!({
+        "path": path,
+        "sheet": { "name": "Nope" },
+        "cell": "A1"
+    }))
+    .expect_err("sheet");
+    assert!(matches!(err, AppError::SheetNotFound(_)));
+
+    let err = trace_precedents::handle(json!({
+        "path": path,
+        "sheet": { "index": 0 },
+        "cell": "A1",
+        "max_nodes": 0
+    }))
+    .expect_err("budget");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+}
@@ -0,0 +1 @@
+mod xlsx_file_test;
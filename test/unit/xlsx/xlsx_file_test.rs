@@ -0,0 +1,243 @@
+use mcp_ods::common::errors::AppError;
+use mcp_ods::ods::ods_file::OdsFile;
+use mcp_ods::ods::sheet_model::{CellStyle, CellValue, MergedRange, Workbook};
+use mcp_ods::xlsx::xlsx_file::XlsxFile;
+use std::io::Write;
+use tempfile::tempdir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+fn write_zip(path: &std::path::Path, parts: &[(&str, &str)]) {
+    let mut zip = ZipWriter::new(std::fs::File::create(path).expect("create"));
+    for (name, data) in parts {
+        zip.start_file(*name, SimpleFileOptions::default())
+            .expect("start");
+        zip.write_all(data.as_bytes()).expect("write");
+    }
+    zip.finish().expect("finish");
+}
+
+#[test]
+fn xlsx_file_reads_shared_strings_dates_formulas_merges_and_styles() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("in.xlsx");
+    write_zip(
+        &path,
+        &[
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Ventas" sheetId="1" r:id="rId7"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId7" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="/xl/worksheets/data.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/sharedStrings.xml",
+                r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><si><t>plain</t></si><si><r><t>ri</t></r><r><rPr><b/></rPr><t>ch</t></r><rPh><t>x</t></rPh></si></sst>"#,
+            ),
+            (
+                "xl/styles.xml",
+                r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="1"><numFmt numFmtId="164" formatCode="dd/mm/yyyy"/></numFmts><fonts count="2"><font><sz val="11"/></font><font><b/><color rgb="FFFF0000"/></font></fonts><fills count="3"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill><fill><patternFill patternType="solid"><fgColor rgb="FFFFFF00"/></patternFill></fill></fills><cellXfs count="3"><xf numFmtId="0" fontId="0" fillId="0"/><xf numFmtId="164" fontId="0" fillId="0"/><xf numFmtId="0" fontId="1" fillId="2"><alignment horizontal="center"/></xf></cellXfs></styleSheet>"#,
+            ),
+            (
+                "xl/worksheets/data.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>
+<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s" s="2"><v>1</v></c><c r="C1" t="inlineStr"><is><t>inline</t></is></c></row>
+<row r="2"><c r="A2" s="1"><v>45351</v></c><c r="B2" t="b"><v>1</v></c><c r="C2"><f t="shared" ref="C2:C3" si="0">A2+B$1</f><v>3</v></c></row>
+<row r="3"><c r="A3"><v>2.5</v></c><c r="C3"><f t="shared" si="0"/><v>4</v></c></row>
+</sheetData><mergeCells count="1"><mergeCell ref="D1:E2"/></mergeCells></worksheet>"#,
+            ),
+        ],
+    );
+
+    let workbook = XlsxFile::read_workbook(&path).expect("read");
+    let sheet = &workbook.sheets[0];
+    assert_eq!(sheet.name, "Ventas");
+    let value = |r: usize, c: usize| sheet.get_cell(r, c).expect("cell").value.clone();
+    assert_eq!(value(0, 0), CellValue::String("plain".to_string()));
+    assert_eq!(value(0, 1), CellValue::String("rich".to_string()));
+    assert_eq!(value(0, 2), CellValue::String("inline".to_string()));
+    assert_eq!(value(1, 0), CellValue::Date("2024-02-29".to_string()));
+    assert_eq!(value(1, 1), CellValue::Boolean(true));
+    assert_eq!(value(2, 0), CellValue::Number(2.5));
+    assert_eq!(
+        sheet.get_cell(1, 2).expect("c2").formula.as_deref(),
+        Some("of:=[.A2]+[.B$1]")
+    );
+    assert_eq!(
+        sheet.get_cell(2, 2).expect("c3").formula.as_deref(),
+        Some("of:=[.A3]+[.B$1]")
+    );
+    assert_eq!(
+        sheet.get_cell(0, 1).expect("b1").style,
+        CellStyle {
+            bold: true,
+            font_color: Some("#ff0000".to_string()),
+            background_color: Some("#ffff00".to_string()),
+            horizontal_align: Some("center".to_string()),
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        sheet.merges,
+        vec![MergedRange {
+            row: 0,
+            col: 3,
+            rows: 2,
+            cols: 2
+        }]
+    );
+    assert!(sheet.get_cell(1, 4).is_some());
+}
+
+#[test]
+fn xlsx_file_write_then_read_preserves_the_model() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("out.xlsx");
+    let mut workbook = Workbook::new("Datos & más".to_string());
+    let sheet = &mut workbook.sheets[0];
+    sheet.ensure_cell_mut(0, 0).value = CellValue::String(" <texto> ".to_string());
+    sheet.ensure_cell_mut(0, 1).value = CellValue::Number(-1.25);
+    sheet.ensure_cell_mut(1, 0).value = CellValue::Date("1900-01-15".to_string());
+    sheet.ensure_cell_mut(1, 1).value = CellValue::Date("2024-02-29T13:45:00".to_string());
+    let cell = sheet.ensure_cell_mut(2, 0);
+    cell.value = CellValue::Number(3.0);
+    cell.formula = Some("of:=SUM([.B1:.B2])".to_string());
+    cell.style.italic = true;
+    cell.style.underline = true;
+    sheet.merges.push(MergedRange {
+        row: 3,
+        col: 0,
+        rows: 1,
+        cols: 2,
+    });
+
+    XlsxFile::write_workbook(&path, &workbook).expect("write");
+    let read = XlsxFile::read_workbook(&path).expect("read");
+    let sheet = &read.sheets[0];
+    assert_eq!(sheet.name, "Datos & más");
+    let value = |r: usize, c: usize| sheet.get_cell(r, c).expect("cell").value.clone();
+    assert_eq!(value(0, 0), CellValue::String(" <texto> ".to_string()));
+    assert_eq!(value(0, 1), CellValue::Number(-1.25));
+    assert_eq!(value(1, 0), CellValue::Date("1900-01-15".to_string()));
+    assert_eq!(
+        value(1, 1),
+        CellValue::Date("2024-02-29T13:45:00".to_string())
+    );
+    let formula_cell = sheet.get_cell(2, 0).expect("a3");
+    assert_eq!(formula_cell.formula.as_deref(), Some("of:=SUM([.B1:.B2])"));
+    assert_eq!(formula_cell.value, CellValue::Number(3.0));
+    assert!(formula_cell.style.italic && formula_cell.style.underline);
+    assert_eq!(sheet.merges, workbook.sheets[0].merges);
+}
+
+#[test]
+fn xlsx_file_excel_serials_account_for_the_1900_leap_bug() {
+    assert_eq!(
+        XlsxFile::iso8601_from_excel_serial(1.0, false),
+        "1900-01-01"
+    );
+    assert_eq!(
+        XlsxFile::iso8601_from_excel_serial(61.0, false),
+        "1900-03-01"
+    );
+    assert_eq!(XlsxFile::iso8601_from_excel_serial(0.0, true), "1904-01-01");
+    assert_eq!(XlsxFile::excel_serial_from_iso8601("1900-01-01"), Some(1.0));
+    assert_eq!(
+        XlsxFile::excel_serial_from_iso8601("2024-02-29"),
+        Some(45351.0)
+    );
+}
+
+#[test]
+fn xlsx_file_rejects_sheet_names_excel_cannot_store() {
+    for name in ["", "a/b", "[x]", "'quoted'", &"x".repeat(32)] {
+        assert!(matches!(
+            XlsxFile::validate_sheet_name(name),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+    assert!(XlsxFile::validate_sheet_name("Ventas 2024").is_ok());
+}
+
+#[test]
+fn xlsx_file_read_fails_for_zip_without_workbook() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("empty.xlsx");
+    write_zip(&path, &[("readme.txt", "hello")]);
+    let err = XlsxFile::read_workbook(&path).expect_err("must fail");
+    assert!(matches!(err, AppError::InvalidInput(_)));
+}
+
+#[test]
+fn xlsx_file_ignores_non_ascii_colours() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("colour.xlsx");
+    write_zip(
+        &path,
+        &[
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Hoja1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/styles.xml",
+                r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="1"><font><color rgb="a€bcde"/></font></fonts><cellXfs count="1"><xf numFmtId="0" fontId="0" fillId="0"/></cellXfs></styleSheet>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1" s="0"><v>1</v></c></row></sheetData></worksheet>"#,
+            ),
+        ],
+    );
+
+    let workbook = XlsxFile::read_workbook(&path).expect("read");
+    let cell = workbook.sheets[0].get_cell(0, 0).expect("a1");
+    assert_eq!(cell.value, CellValue::Number(1.0));
+    assert_eq!(cell.style.font_color, None);
+}
+
+#[test]
+fn xlsx_file_sparse_sheet_converts_to_compact_content_xml() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("sparse.xlsx");
+    write_zip(
+        &path,
+        &[
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Hoja1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1"><v>1</v></c></row><row r="200000"><c r="C200000"><v>2</v></c></row></sheetData></worksheet>"#,
+            ),
+        ],
+    );
+
+    let workbook = XlsxFile::read_workbook(&path).expect("read");
+    let ods = dir.path().join("sparse.ods");
+    OdsFile::create(&ods, "Hoja1".to_string()).expect("create");
+    OdsFile::write_workbook(&ods, &workbook).expect("write ods");
+
+    let content = OdsFile::read_content_xml(&ods).expect("content");
+    assert!(
+        content.len() < 10_000,
+        "content.xml is {} bytes",
+        content.len()
+    );
+    let read = OdsFile::read_workbook(&ods).expect("read ods");
+    assert_eq!(
+        read.sheets[0].get_cell(199_999, 2).expect("c200000").value,
+        CellValue::Number(2.0)
+    );
+}